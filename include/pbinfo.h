@@ -0,0 +1,54 @@
+/* C interface of the pbinfo crate (feature `ffi`). Strings are UTF-8 and NUL-terminated. */
+
+#ifndef PBINFO_H
+#define PBINFO_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define PBINFO_OK 0
+#define PBINFO_ERR_INVALID_ARGUMENT 1
+#define PBINFO_ERR_UNKNOWN_ID 2
+#define PBINFO_ERR_NETWORK 3
+#define PBINFO_ERR_RATE_LIMITED 4
+#define PBINFO_ERR_SITE_UNAVAILABLE 5
+#define PBINFO_ERR_PARSE 6
+#define PBINFO_ERR_OTHER 99
+
+typedef struct PbInfoCExample {
+    char *input;
+    char *output;
+} PbInfoCExample;
+
+/* Optional strings are NULL when missing; input_file/output_file are NULL for stdin/stdout. */
+typedef struct PbInfoCProblem {
+    size_t id;
+    char *name;
+    size_t grade;
+    char *input_file;
+    char *output_file;
+    char *time_limit;
+    char *memory_limit;
+    char *statement;
+    char *statement_html;
+    PbInfoCExample *examples; /* NULL if there are none */
+    size_t examples_len;
+} PbInfoCProblem;
+
+/* Fetches the problem `id` into `out`. Returns PBINFO_OK or an error code. */
+int pbinfo_fetch_problem(size_t id, PbInfoCProblem *out);
+
+/* Releases a problem filled in by pbinfo_fetch_problem. */
+void pbinfo_free_problem(PbInfoCProblem *problem);
+
+/* Message of the last error of the calling thread, or NULL. Valid until the next call. */
+const char *pbinfo_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
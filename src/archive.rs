@@ -0,0 +1,119 @@
+use crate::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+impl ContestSource {
+    /// Returns `true` if the source names `contest` (compared by `normalize_text`, so `oni`
+    /// matches `ONI`) in `year`.
+    pub fn matches(&self, contest: &str, year: u16) -> bool {
+        self.year == Some(year) && normalize_text(&self.contest) == normalize_text(contest)
+    }
+}
+
+/// The problems given at a contest in a year, as found in PbInfo's archive.
+#[derive(Debug, Clone)]
+pub struct ContestArchive {
+    /// Name of the contest (e.g. "ONI")
+    pub contest: String,
+    /// Year of the contest
+    pub year: u16,
+    /// The problems, in increasing order of id
+    pub problems: Vec<PbInfoProblem>,
+    /// Ids that could not be fetched, with the error; their sources are unknown, so some of them
+    /// might belong to the archive
+    pub failed: Vec<(ProblemId, PbInfoError)>,
+}
+
+/// Returns the name of the directory of a division: its words in lowercase, without
+/// diacritics, separated by dashes (`toate` for the problems without a division).
+fn division_dir(division: Option<&str>) -> String {
+    let words = normalize_text(division.unwrap_or_default())
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    match words.is_empty() {
+        true => "toate".to_owned(),
+        false => words.join("-"),
+    }
+}
+
+impl ContestArchive {
+    /// Groups the ids of the problems by division (e.g. `clasa a 9-a`), as named by their
+    /// sources. Problems whose source does not name a division are under `None`.
+    pub fn divisions(&self) -> BTreeMap<Option<String>, Vec<ProblemId>> {
+        let mut divisions: BTreeMap<Option<String>, Vec<ProblemId>> = BTreeMap::new();
+        for problem in &self.problems {
+            let division = problem.contest_source().and_then(|source| source.division);
+            divisions.entry(division).or_default().push(problem.id);
+        }
+        divisions
+    }
+
+    /// Writes every problem as a bundle (see `bundle::save`) to
+    /// `dir/{division}/{name}.pbinfo`, where `division` is the division in lowercase, with
+    /// dashes between its words (`toate` for the problems without one). Returns the paths of
+    /// the bundles, in the order of the problems.
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        let mut paths = Vec::new();
+        for problem in &self.problems {
+            let division = problem.contest_source().and_then(|source| source.division);
+            let division_dir = dir.join(division_dir(division.as_deref()));
+            std::fs::create_dir_all(&division_dir).map_err(|err| {
+                PbInfoError::IOError(format!(
+                    "Could not create `{}`: {}",
+                    division_dir.display(),
+                    err
+                ))
+            })?;
+            let path = division_dir.join(format!("{}.pbinfo", problem.name));
+            bundle::save(problem, &path)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+/// Returns the archive of `contest` in `year` from already fetched `problems`: those whose
+/// source names the contest and the year (see `ContestSource::matches`).
+pub fn contest_archive(problems: &[PbInfoProblem], contest: &str, year: u16) -> ContestArchive {
+    let mut problems = problems
+        .iter()
+        .filter(|problem| {
+            problem
+                .contest_source()
+                .is_some_and(|source| source.matches(contest, year))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    problems.sort_by_key(|problem| problem.id);
+    ContestArchive {
+        contest: contest.to_owned(),
+        year,
+        problems,
+        failed: Vec::new(),
+    }
+}
+
+/// Returns the problems given at `contest` (e.g. "ONI") in `year`, using the shared client.
+#[cfg(feature = "blocking")]
+pub fn fetch_contest_archive(contest: &str, year: u16) -> Result<ContestArchive> {
+    PbInfoClient::shared().fetch_contest_archive(contest, year)
+}
+
+#[cfg(feature = "blocking")]
+impl PbInfoClient {
+    /// Returns the problems given at `contest` (e.g. "ONI") in `year`, as attributed by their
+    /// sources. PbInfo cannot be searched by source, so every problem is fetched (see
+    /// `fetch_many`); the ids that fail are reported in `ContestArchive::failed` instead of
+    /// aborting the archive. Use `contest_archive` on an already fetched catalog when recreating
+    /// several contests.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_contest_archive(&self, contest: &str, year: u16) -> Result<ContestArchive> {
+        let report = self.fetch_many(&self.discover_problem_ids()?);
+        let mut archive = contest_archive(&report.succeeded, contest, year);
+        archive.failed = report.failed;
+        Ok(archive)
+    }
+}
@@ -0,0 +1,93 @@
+use crate::links::extract_links;
+use crate::*;
+
+/// A theory article (lesson) of PbInfo, such as `/articole/18/sortarea-prin-selectie`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Article {
+    /// Unique id of article
+    pub id: usize,
+    /// Title of article
+    pub title: String,
+    /// Html of the body of the article, without its title
+    pub html: String,
+    /// Text of every code sample (`<pre>` block), in the order in which they appear
+    pub code_samples: Vec<String>,
+    /// Ids of the problems linked from the article, in the order of their first link
+    pub problems: Vec<ProblemId>,
+}
+
+impl Article {
+    /// Parses the page of the article `id`. The body is the contents of the `<article>`
+    /// element, and the title its first `<h1>` (or the title of the page).
+    pub fn from_html(id: usize, string: &str) -> Result<Self> {
+        let article_regex = &selectors::ARTICLE;
+        let heading_regex = &selectors::H1;
+        let title_regex = &selectors::TITLE_TEXT;
+        let code_regex = &selectors::PRE;
+
+        let body = match article_regex.captures(string) {
+            Some(caps) => caps[1].to_owned(),
+            None => {
+                return Err(PbInfoError::RegexError(
+                    "Failed to locate the article in the HTML".to_owned(),
+                ))
+            }
+        };
+        let (title, html) = match heading_regex.captures(&body) {
+            Some(caps) => (
+                html_to_text(&caps[1]),
+                body[caps.get(0).unwrap().end()..].trim().to_owned(),
+            ),
+            None => (
+                title_regex
+                    .captures(string)
+                    .map(|caps| html_to_text(&caps[1]))
+                    .unwrap_or_default(),
+                body.trim().to_owned(),
+            ),
+        };
+
+        Ok(Article {
+            id,
+            title: title.trim().to_owned(),
+            code_samples: code_regex
+                .captures_iter(&html)
+                .map(|caps| html_to_text(&caps[1]))
+                .collect(),
+            problems: extract_links(&html)
+                .into_iter()
+                .filter_map(|target| match target {
+                    LinkTarget::Problem(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            html,
+        })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl PbInfoClient {
+    /// Returns the article (lesson) with the id `id`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_article(&self, id: usize) -> Result<Article> {
+        let page = self.get_page(&self.url(&format!("/articole/{}", id)))?;
+
+        match page.status() {
+            reqwest::StatusCode::OK => {
+                let text = self.read_text(page)?;
+                Article::from_html(id, &text).or_else(|err| {
+                    check_site_available(&text)?;
+                    Err(err)
+                })
+            }
+            reqwest::StatusCode::NOT_FOUND => {
+                Err(PbInfoError::Error(format!("Unknown article {}", id)))
+            }
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to fetch the article. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use crate::*;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A file in an `AssetStore`, and what refers to it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Asset {
+    /// Name of the file in the store: the SHA-256 of its contents (in hex) and its extension
+    pub file: String,
+    /// Size of the file in bytes
+    pub size: u64,
+    /// The references to the file: their owner (e.g. `problem 1`) and the name the owner gave
+    /// it (e.g. `desen.png`)
+    pub refs: Vec<(String, String)>,
+}
+
+/// A directory of files (images, attachments) stored once per content, whatever their names,
+/// so that the problems of a mirror that share an image or an attachment share its copy. Every
+/// asset counts the references to it, and is deleted once the last one is released. The
+/// references are kept in `assets.json`, inside the directory.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AssetStore {
+    dir: PathBuf,
+    /// Assets by the hash of their contents
+    assets: BTreeMap<String, Asset>,
+}
+
+/// Returns the extension of `name` (lowercase, only if it is short and alphanumeric), with its
+/// dot.
+fn extension(name: &str) -> String {
+    match Path::new(name).extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.len() <= 8 && ext.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            format!(".{}", ext.to_ascii_lowercase())
+        }
+        _ => String::new(),
+    }
+}
+
+impl AssetStore {
+    /// Opens the store in `dir`, reading the references saved by a previous run. The directory
+    /// is created when the first asset is added.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        let path = dir.join("assets.json");
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(AssetStore {
+                    dir,
+                    assets: BTreeMap::new(),
+                })
+            }
+            Err(err) => {
+                return Err(PbInfoError::IOError(format!(
+                    "Could not read `{}`: {}",
+                    path.display(),
+                    err
+                )))
+            }
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&text).map_err(|err| PbInfoError::JSONError(err.to_string()))?;
+        let assets = json
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(hash, asset)| {
+                let refs = asset["refs"]
+                    .as_array()?
+                    .iter()
+                    .filter_map(|reference| {
+                        Some((
+                            reference["owner"].as_str()?.to_owned(),
+                            reference["name"].as_str()?.to_owned(),
+                        ))
+                    })
+                    .collect();
+                Some((
+                    hash.clone(),
+                    Asset {
+                        file: asset["file"].as_str()?.to_owned(),
+                        size: asset["size"].as_u64()?,
+                        refs,
+                    },
+                ))
+            })
+            .collect();
+        Ok(AssetStore { dir, assets })
+    }
+
+    /// Returns the directory of the store.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the assets, by the hash of their contents.
+    pub fn assets(&self) -> &BTreeMap<String, Asset> {
+        &self.assets
+    }
+
+    /// Returns the total size of the stored files, each counted once.
+    pub fn disk_usage(&self) -> u64 {
+        self.assets.values().map(|asset| asset.size).sum()
+    }
+
+    /// Returns the assets referred to by `owner`, as the names it gave them and the files.
+    pub fn assets_of(&self, owner: &str) -> Vec<(String, String)> {
+        self.assets
+            .values()
+            .flat_map(|asset| {
+                asset
+                    .refs
+                    .iter()
+                    .filter(|(other, _)| other == owner)
+                    .map(|(_, name)| (name.clone(), asset.file.clone()))
+            })
+            .collect()
+    }
+
+    /// Adds the file at `path` (e.g. a finished download) as `name` of `owner`, moving it into
+    /// the store. If the store already has a file with the same contents, `path` is deleted and
+    /// only the reference is added. Returns the name of the file in the store.
+    pub fn add_file(&mut self, path: impl AsRef<Path>, name: &str, owner: &str) -> Result<String> {
+        let path = path.as_ref();
+        let io_error = |action: &str, path: &Path, err: std::io::Error| {
+            PbInfoError::IOError(format!(
+                "Could not {} `{}`: {}",
+                action,
+                path.display(),
+                err
+            ))
+        };
+        let mut file = std::fs::File::open(path).map_err(|err| io_error("read", path, err))?;
+        let mut hasher = Sha256::new();
+        let size =
+            std::io::copy(&mut file, &mut hasher).map_err(|err| io_error("read", path, err))?;
+        drop(file);
+        let hash = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+
+        let stored = self
+            .assets
+            .get(&hash)
+            .is_some_and(|asset| self.dir.join(&asset.file).exists());
+        let file = format!("{}{}", hash, extension(name));
+        if stored {
+            std::fs::remove_file(path).map_err(|err| io_error("remove", path, err))?;
+        } else {
+            std::fs::create_dir_all(&self.dir).map_err(|err| io_error("create", &self.dir, err))?;
+            let target = self.dir.join(&file);
+            std::fs::rename(path, &target).map_err(|err| io_error("write", &target, err))?;
+        }
+        let asset = self.assets.entry(hash).or_insert_with(|| Asset {
+            file: String::new(),
+            size,
+            refs: Vec::new(),
+        });
+        if !stored {
+            asset.file = file;
+        }
+        let reference = (owner.to_owned(), name.to_owned());
+        if !asset.refs.contains(&reference) {
+            asset.refs.push(reference);
+        }
+        Ok(asset.file.clone())
+    }
+
+    /// Adds `contents` as `name` of `owner`. Returns the name of the file in the store.
+    pub fn add(&mut self, contents: &[u8], name: &str, owner: &str) -> Result<String> {
+        std::fs::create_dir_all(&self.dir).map_err(|err| {
+            PbInfoError::IOError(format!(
+                "Could not create `{}`: {}",
+                self.dir.display(),
+                err
+            ))
+        })?;
+        let path = self.dir.join(format!(".{}.partial", std::process::id()));
+        std::fs::write(&path, contents).map_err(|err| {
+            PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+        })?;
+        self.add_file(path, name, owner)
+    }
+
+    /// Removes the references of `owner`, deleting the files no longer referred to. Returns the
+    /// number of bytes freed.
+    pub fn release(&mut self, owner: &str) -> Result<u64> {
+        self.release_except(owner, &[])
+    }
+
+    /// Removes the references of `owner` to every file but `kept` (names of files in the store),
+    /// deleting the files no longer referred to. Returns the number of bytes freed. Used once
+    /// the new assets of an owner are added, so that its old ones are only deleted if they were
+    /// replaced.
+    pub fn release_except(&mut self, owner: &str, kept: &[String]) -> Result<u64> {
+        let mut freed = 0;
+        for asset in self.assets.values_mut() {
+            if !kept.contains(&asset.file) {
+                asset.refs.retain(|(other, _)| other != owner);
+            }
+        }
+        let mut unused = Vec::new();
+        self.assets.retain(|_, asset| {
+            if asset.refs.is_empty() {
+                unused.push((asset.file.clone(), asset.size));
+            }
+            !asset.refs.is_empty()
+        });
+        for (file, size) in unused {
+            let path = self.dir.join(file);
+            match std::fs::remove_file(&path) {
+                Ok(()) => freed += size,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    return Err(PbInfoError::IOError(format!(
+                        "Could not remove `{}`: {}",
+                        path.display(),
+                        err
+                    )))
+                }
+            }
+        }
+        Ok(freed)
+    }
+
+    /// Writes the references to `assets.json`.
+    pub fn save(&self) -> Result<()> {
+        let json = self
+            .assets
+            .iter()
+            .map(|(hash, asset)| {
+                let refs = asset
+                    .refs
+                    .iter()
+                    .map(|(owner, name)| serde_json::json!({ "owner": owner, "name": name }))
+                    .collect::<Vec<_>>();
+                (
+                    hash.clone(),
+                    serde_json::json!({ "file": asset.file, "size": asset.size, "refs": refs }),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+        std::fs::create_dir_all(&self.dir).map_err(|err| {
+            PbInfoError::IOError(format!(
+                "Could not create `{}`: {}",
+                self.dir.display(),
+                err
+            ))
+        })?;
+        let path = self.dir.join("assets.json");
+        std::fs::write(&path, serde_json::Value::Object(json).to_string()).map_err(|err| {
+            PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+        })
+    }
+}
@@ -0,0 +1,75 @@
+use crate::*;
+use std::collections::BTreeMap;
+
+/// Normalizes an author name for comparisons: lowercase, without diacritics and with the words
+/// separated by single spaces.
+pub fn normalize_author(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'ă' | 'â' => 'a',
+            'î' => 'i',
+            'ș' | 'ş' => 's',
+            'ț' | 'ţ' => 't',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns `true` if `author` (the author field of a problem) names `name`. Every word of
+/// `name` has to appear in `author`, in any order, so "Ion Popescu" matches "prof. Popescu Ion".
+pub fn author_matches(author: &str, name: &str) -> bool {
+    let author = normalize_author(author);
+    let author = author.split(' ').collect::<Vec<_>>();
+    let name = normalize_author(name);
+    !name.is_empty() && name.split(' ').all(|word| author.contains(&word))
+}
+
+/// Groups the ids of `problems` by author, keyed by the author as displayed by PbInfo.
+/// Problems without an author are left out.
+pub fn author_index(problems: &[PbInfoProblem]) -> BTreeMap<String, Vec<ProblemId>> {
+    let mut index: BTreeMap<String, Vec<ProblemId>> = BTreeMap::new();
+    for problem in problems {
+        if let Some(author) = &problem.author {
+            index.entry(author.clone()).or_default().push(problem.id);
+        }
+    }
+    index
+}
+
+/// Returns every problem attributed to the author `name`, in increasing order of id.
+#[cfg(feature = "blocking")]
+pub fn fetch_problems_by_author(name: &str) -> Result<Vec<PbInfoProblem>> {
+    PbInfoClient::shared().fetch_problems_by_author(name)
+}
+
+#[cfg(feature = "blocking")]
+impl PbInfoClient {
+    /// Returns every problem attributed to the author `name`, in increasing order of id.
+    /// PbInfo cannot be searched by author, so every problem is fetched; use `author_index`
+    /// on an already fetched catalog when making several queries.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_problems_by_author(&self, name: &str) -> Result<Vec<PbInfoProblem>> {
+        let mut problems = Vec::new();
+        for id in self.discover_problem_ids()? {
+            let problem = match self.fetch_problem_by_id(id) {
+                Ok(problem) => problem,
+                // Deleted between the discovery and the fetch
+                Err(PbInfoError::UnknownId(_)) => continue,
+                Err(err) => return Err(err),
+            };
+            if problem
+                .author
+                .as_deref()
+                .is_some_and(|author| author_matches(author, name))
+            {
+                problems.push(problem);
+            }
+        }
+        Ok(problems)
+    }
+}
@@ -0,0 +1,241 @@
+use crate::*;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// The outcome of fetching many problems: what was fetched, what failed and what does not
+/// exist. Failed ids can be fetched again with `retry_failed`, so a long crawl can be resumed
+/// without starting over.
+#[derive(Debug, Clone, Default)]
+pub struct BatchReport {
+    /// The fetched problems, in the order of their ids in the batch (sorted by id once
+    /// `retry_failed` has merged the retried ones)
+    pub succeeded: Vec<PbInfoProblem>,
+    /// Ids that could not be fetched, with the error
+    pub failed: Vec<(ProblemId, PbInfoError)>,
+    /// Ids of problems that do not exist, in the same order as `succeeded`
+    pub skipped: Vec<ProblemId>,
+}
+
+impl BatchReport {
+    /// Returns `true` if no id failed.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Returns the ids that failed, in order.
+    pub fn failed_ids(&self) -> Vec<ProblemId> {
+        self.failed.iter().map(|(id, _)| *id).collect()
+    }
+
+    /// Fetches the failed ids again using `client`, moving them to `succeeded` or `skipped` if
+    /// they are fetched this time, and sorting them by id. Returns the number of ids that still
+    /// fail.
+    pub fn retry_failed(&mut self, client: &PbInfoClient) -> usize {
+        let retried = client.fetch_many(&self.failed_ids());
+        self.succeeded.extend(retried.succeeded);
+        self.succeeded.sort_by_key(|problem| problem.id);
+        self.skipped.extend(retried.skipped);
+        self.skipped.sort();
+        self.failed = retried.failed;
+        self.failed.len()
+    }
+
+    /// Records the result of fetching `id`.
+    pub(crate) fn push(&mut self, id: ProblemId, result: Result<PbInfoProblem>) {
+        match result {
+            Ok(problem) => self.succeeded.push(problem),
+            Err(PbInfoError::UnknownId(_)) => self.skipped.push(id),
+            Err(err) => self.failed.push((id, err)),
+        }
+    }
+}
+
+/// How `PbInfoClient::fetch_many_with` spreads its requests. The number of concurrent requests
+/// is adjusted like TCP congestion control (AIMD): it grows by one after a full round of fast,
+/// successful requests, and is multiplied by `decrease_factor` when a request is slow or
+/// throttled, so the batch stays just under the rate PbInfo tolerates.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FetchOptions {
+    /// Concurrent requests at the start of the batch
+    pub initial_concurrency: usize,
+    /// The most concurrent requests ever made
+    pub max_concurrency: usize,
+    /// The fewest concurrent requests (at least 1)
+    pub min_concurrency: usize,
+    /// Requests that take longer than this reduce the concurrency
+    pub target_latency: Duration,
+    /// Factor (between 0 and 1) by which the concurrency is reduced
+    pub decrease_factor: f64,
+}
+
+impl Default for FetchOptions {
+    /// Starts with 2 concurrent requests and goes up to 8, reducing them by half when a request
+    /// takes more than 2 seconds or fails because of the network or throttling.
+    fn default() -> Self {
+        FetchOptions {
+            initial_concurrency: 2,
+            max_concurrency: 8,
+            min_concurrency: 1,
+            target_latency: Duration::from_secs(2),
+            decrease_factor: 0.5,
+        }
+    }
+}
+
+impl FetchOptions {
+    /// Options that make one request at a time.
+    pub fn sequential() -> Self {
+        FetchOptions {
+            initial_concurrency: 1,
+            max_concurrency: 1,
+            ..FetchOptions::default()
+        }
+    }
+}
+
+/// The AIMD concurrency limit of a batch.
+#[derive(Debug, Clone)]
+pub(crate) struct AdaptiveLimit {
+    limit: f64,
+    min: f64,
+    max: f64,
+    target_latency: Duration,
+    decrease_factor: f64,
+}
+
+impl AdaptiveLimit {
+    pub(crate) fn new(options: &FetchOptions) -> Self {
+        let min = options.min_concurrency.max(1) as f64;
+        let max = (options.max_concurrency as f64).max(min);
+        AdaptiveLimit {
+            limit: (options.initial_concurrency as f64).clamp(min, max),
+            min,
+            max,
+            target_latency: options.target_latency,
+            decrease_factor: options.decrease_factor.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Returns the number of requests that may be in flight.
+    pub(crate) fn current(&self) -> usize {
+        self.limit as usize
+    }
+
+    /// Updates the limit after a request that took `latency`. Errors that are caused by the
+    /// load on PbInfo (network errors, throttling, outages, an open circuit) count as
+    /// congestion; other errors (e.g. a problem that does not exist) do not change the limit.
+    pub(crate) fn record<T>(&mut self, latency: Duration, result: &Result<T>) {
+        let congested = match result {
+            Err(PbInfoError::NetworkError(_))
+            | Err(PbInfoError::RateLimited { .. })
+            | Err(PbInfoError::SiteUnavailable(_))
+            | Err(PbInfoError::CircuitOpen { .. }) => true,
+            Err(_) => return,
+            Ok(_) => latency > self.target_latency,
+        };
+        self.limit = match congested {
+            true => (self.limit * self.decrease_factor).max(self.min),
+            // One more request after `limit` fast requests
+            false => (self.limit + 1.0 / self.limit).min(self.max),
+        };
+    }
+}
+
+/// Fetches the problems with the given ids (see `PbInfoClient::fetch_many`).
+pub fn fetch_many(ids: &[ProblemId]) -> BatchReport {
+    PbInfoClient::shared().fetch_many(ids)
+}
+
+/// Fetches the problems with the given ids concurrently (see `PbInfoClient::fetch_many_with`).
+pub fn fetch_many_with(ids: &[ProblemId], options: &FetchOptions) -> BatchReport {
+    PbInfoClient::shared().fetch_many_with(ids, options)
+}
+
+impl PbInfoClient {
+    /// Fetches the problems with the given ids, one after the other. A failure does not stop the
+    /// batch, except when PbInfo is unavailable or the circuit breaker of the client opens (see
+    /// `PbInfoClientBuilder::circuit_breaker`): the remaining ids are then reported as failed
+    /// with the same error, without making any more requests.
+    pub fn fetch_many(&self, ids: &[ProblemId]) -> BatchReport {
+        self.fetch_many_with(ids, &FetchOptions::sequential())
+    }
+
+    /// Fetches the problems with the given ids, making several requests at a time as allowed by
+    /// `options`. The problems are reported in the order of `ids`, and failures are handled as
+    /// by `fetch_many`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, ids)))]
+    pub fn fetch_many_with(&self, ids: &[ProblemId], options: &FetchOptions) -> BatchReport {
+        /// State shared by the workers
+        struct Shared {
+            next: usize,
+            in_flight: usize,
+            limit: AdaptiveLimit,
+            /// The error that stopped the batch (`SiteUnavailable` or `CircuitOpen`)
+            halted: Option<PbInfoError>,
+            results: Vec<(usize, Result<PbInfoProblem>)>,
+        }
+
+        let limit = AdaptiveLimit::new(options);
+        let workers = (limit.max as usize).min(ids.len());
+        let shared = Mutex::new(Shared {
+            next: 0,
+            in_flight: 0,
+            limit,
+            halted: None,
+            results: Vec::new(),
+        });
+        let turn = Condvar::new();
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let mut state = shared.lock().unwrap();
+                    while state.next < ids.len()
+                        && state.halted.is_none()
+                        && state.in_flight >= state.limit.current()
+                    {
+                        state = turn.wait(state).unwrap();
+                    }
+                    if state.next == ids.len() {
+                        return;
+                    }
+                    let index = state.next;
+                    state.next += 1;
+                    if let Some(err) = &state.halted {
+                        let err = err.clone();
+                        state.results.push((index, Err(err)));
+                        continue;
+                    }
+                    state.in_flight += 1;
+                    drop(state);
+
+                    let start = Instant::now();
+                    let result = self.fetch_problem_by_id(ids[index]);
+                    let latency = start.elapsed();
+
+                    let mut state = shared.lock().unwrap();
+                    state.in_flight -= 1;
+                    state.limit.record(latency, &result);
+                    if let Err(
+                        err @ (PbInfoError::SiteUnavailable(_) | PbInfoError::CircuitOpen { .. }),
+                    ) = &result
+                    {
+                        if state.halted.is_none() {
+                            state.halted = Some(err.clone());
+                        }
+                    }
+                    state.results.push((index, result));
+                    turn.notify_all();
+                });
+            }
+        });
+
+        let mut results = shared.into_inner().unwrap().results;
+        results.sort_by_key(|(index, _)| *index);
+        let mut report = BatchReport::default();
+        for (index, result) in results {
+            report.push(ids[index], result);
+        }
+        report
+    }
+}
@@ -0,0 +1,72 @@
+use crate::*;
+use std::time::{Duration, Instant};
+
+/// Stops the requests of a client after too many consecutive failures, so that a batch job does
+/// not keep hammering PbInfo while it is down. Once the cooldown passes, the circuit is half-open:
+/// a single probe request is let through while the others are still rejected, and the circuit
+/// closes if the probe succeeds or opens again if it fails.
+#[derive(Debug, Clone)]
+pub(crate) struct CircuitBreaker {
+    /// Consecutive failures after which the circuit opens
+    threshold: u32,
+    /// How long the circuit stays open
+    cooldown: Duration,
+    /// Consecutive failures so far
+    failures: u32,
+    /// Until when the requests are short-circuited
+    open_until: Option<Instant>,
+    /// Whether the probe of the half-open circuit is in flight
+    probing: bool,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed circuit breaker.
+    pub(crate) fn new(threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            threshold: threshold.max(1),
+            cooldown,
+            failures: 0,
+            open_until: None,
+            probing: false,
+        }
+    }
+
+    /// Fails with `PbInfoError::CircuitOpen` if a request may not be made at `now`. After the
+    /// cooldown, the first request to be checked becomes the probe, and the others fail until
+    /// its outcome is recorded.
+    pub(crate) fn check(&mut self, now: Instant) -> Result<()> {
+        match self.open_until {
+            Some(until) if now < until => Err(PbInfoError::CircuitOpen {
+                retry_after: until - now,
+            }),
+            Some(_) if self.probing => Err(PbInfoError::CircuitOpen {
+                retry_after: self.cooldown,
+            }),
+            Some(_) => {
+                self.probing = true;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Records the outcome of a request that finished at `now`.
+    pub(crate) fn record(&mut self, success: bool, now: Instant) {
+        let probe = std::mem::take(&mut self.probing);
+        if success {
+            self.failures = 0;
+            self.open_until = None;
+        } else {
+            self.failures += 1;
+            if probe || self.failures >= self.threshold {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    failures = self.failures,
+                    cooldown_ms = self.cooldown.as_millis() as u64,
+                    "circuit opened"
+                );
+                self.open_until = Some(now + self.cooldown);
+            }
+        }
+    }
+}
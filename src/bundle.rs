@@ -0,0 +1,182 @@
+//! A single-file format for sharing problems offline: a zip archive (usually with the
+//! `.pbinfo` extension) containing
+//!
+//! - `manifest.json`: the problem, as fetched from PbInfo
+//! - `statement.md`: the statement rendered as Markdown
+//! - `examples/{n}.in`, `examples/{n}.ok`: the examples from the statement
+//! - `tests/{n}.in`, `tests/{n}.ok`: additional tests (optional)
+
+use crate::*;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+/// Version of the bundle format, stored in the manifest.
+pub const FORMAT_VERSION: u64 = 1;
+
+/// The contents of a bundle.
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    /// The problem
+    pub problem: PbInfoProblem,
+    /// Tests other than the examples from the statement, in order
+    pub tests: Vec<Example>,
+}
+
+/// Writes `problem` to the bundle `path`, without any additional tests.
+pub fn save(problem: &PbInfoProblem, path: impl AsRef<Path>) -> Result<()> {
+    save_with_tests(problem, &[], path)
+}
+
+/// Writes `problem` and `tests` to the bundle `path`, replacing it if it exists.
+pub fn save_with_tests(
+    problem: &PbInfoProblem,
+    tests: &[Example],
+    path: impl AsRef<Path>,
+) -> Result<()> {
+    let path = path.as_ref();
+    let io_error = |err: std::io::Error| {
+        PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+    };
+    let file = std::fs::File::create(path).map_err(io_error)?;
+    write_bundle(problem, tests, file).map_err(|err| match err {
+        zip::result::ZipError::Io(err) => io_error(err),
+        err => PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err)),
+    })
+}
+
+/// Writes the entries of a bundle to `writer`.
+fn write_bundle<W: Write + Seek>(
+    problem: &PbInfoProblem,
+    tests: &[Example],
+    writer: W,
+) -> zip::result::ZipResult<()> {
+    let manifest = serde_json::json!({
+        "format_version": FORMAT_VERSION,
+        "id": problem.id.0,
+        "name": problem.name.as_str(),
+        "problem_text": problem.problem_text,
+        "meta_text": problem.meta_text,
+        "topics": problem.topics,
+        "attachments": problem
+            .attachments
+            .iter()
+            .map(|attachment| serde_json::json!({
+                "name": attachment.name,
+                "url": attachment.url,
+                "size": attachment.size,
+            }))
+            .collect::<Vec<_>>(),
+        "etag": problem.etag,
+        "last_modified": problem.last_modified,
+        "tests": tests.len(),
+    });
+
+    let mut zip = zip::ZipWriter::new(writer);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut add = |name: &str, contents: &[u8]| -> zip::result::ZipResult<()> {
+        zip.start_file(name, options)?;
+        zip.write_all(contents)?;
+        Ok(())
+    };
+
+    add(
+        "manifest.json",
+        serde_json::to_string_pretty(&manifest).unwrap().as_bytes(),
+    )?;
+    add("statement.md", problem.to_markdown().as_bytes())?;
+    for (dir, examples) in [("examples", problem.examples()), ("tests", tests.to_vec())] {
+        for (i, example) in examples.iter().enumerate() {
+            add(&format!("{}/{}.in", dir, i + 1), example.input.as_bytes())?;
+            add(&format!("{}/{}.ok", dir, i + 1), example.output.as_bytes())?;
+        }
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Reads the bundle `path`. The problem is parsed again from the html stored in the manifest,
+/// so bundles stay usable when PbInfoProblem gains new fields.
+pub fn load(path: impl AsRef<Path>) -> Result<Bundle> {
+    let path = path.as_ref();
+    let invalid = |message: String| {
+        PbInfoError::IOError(format!(
+            "`{}` is not a valid bundle: {}",
+            path.display(),
+            message
+        ))
+    };
+    let file = std::fs::File::open(path).map_err(|err| {
+        PbInfoError::IOError(format!("Could not open `{}`: {}", path.display(), err))
+    })?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|err| invalid(err.to_string()))?;
+    let mut read = |name: &str| -> Result<String> {
+        let mut entry = archive
+            .by_name(name)
+            .map_err(|_| invalid(format!("`{}` is missing", name)))?;
+        let mut contents = String::new();
+        entry
+            .read_to_string(&mut contents)
+            .map_err(|err| invalid(format!("`{}` could not be read: {}", name, err)))?;
+        Ok(contents)
+    };
+
+    let manifest: serde_json::Value = serde_json::from_str(&read("manifest.json")?)
+        .map_err(|err| invalid(format!("the manifest is not valid JSON: {}", err)))?;
+    let version = manifest["format_version"].as_u64().unwrap_or(0);
+    if version == 0 || version > FORMAT_VERSION {
+        return Err(invalid(format!("unsupported format version {}", version)));
+    }
+    let text = |key: &str| -> Result<String> {
+        manifest[key]
+            .as_str()
+            .map(|value| value.to_owned())
+            .ok_or_else(|| invalid(format!("the manifest has no `{}`", key)))
+    };
+    let optional = |key: &str| manifest[key].as_str().map(|value| value.to_owned());
+
+    let id = manifest["id"]
+        .as_u64()
+        .ok_or_else(|| invalid("the manifest has no `id`".to_owned()))? as usize;
+    let mut problem = PbInfoProblem::from_parts(
+        ProblemId(id),
+        text("name")?,
+        text("problem_text")?,
+        text("meta_text")?,
+    )?;
+    problem.topics = manifest["topics"]
+        .as_array()
+        .map(|topics| {
+            topics
+                .iter()
+                .filter_map(|topic| topic.as_str().map(|topic| topic.to_owned()))
+                .collect()
+        })
+        .unwrap_or_default();
+    problem.attachments = manifest["attachments"]
+        .as_array()
+        .map(|attachments| {
+            attachments
+                .iter()
+                .filter_map(|attachment| {
+                    Some(Attachment {
+                        name: attachment["name"].as_str()?.to_owned(),
+                        url: attachment["url"].as_str()?.to_owned(),
+                        size: attachment["size"].as_u64(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    problem.etag = optional("etag");
+    problem.last_modified = optional("last_modified");
+
+    let mut tests = Vec::new();
+    for i in 1..=manifest["tests"].as_u64().unwrap_or(0) {
+        tests.push(Example {
+            input: read(&format!("tests/{}.in", i))?,
+            output: read(&format!("tests/{}.ok", i))?,
+        });
+    }
+    Ok(Bundle { problem, tests })
+}
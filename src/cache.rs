@@ -0,0 +1,93 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// Hit and miss counts of a cache.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct CacheStats {
+    /// Lookups that were answered from the cache
+    pub hits: u64,
+    /// Lookups that were not in the cache
+    pub misses: u64,
+    /// Number of cached entries
+    pub len: usize,
+    /// Maximum number of cached entries
+    pub capacity: usize,
+}
+
+/// A size-bounded map that evicts the least recently used entry when it is full.
+#[derive(Debug)]
+pub(crate) struct LruCache<K, V> {
+    /// The entries, together with the tick of their last use
+    entries: HashMap<K, (V, u64)>,
+    /// Keys of the entries, ordered by the tick of their last use
+    order: BTreeMap<u64, K>,
+    /// Incremented on every use
+    tick: u64,
+    capacity: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LruCache<K, V> {
+    /// Creates a cache holding at most `capacity` entries.
+    pub(crate) fn new(capacity: usize) -> Self {
+        LruCache {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            capacity,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns a copy of the value of `key`, marking it as the most recently used.
+    pub(crate) fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        match self.entries.get_mut(key) {
+            Some((value, tick)) => {
+                self.order.remove(tick);
+                *tick = self.tick;
+                self.order.insert(self.tick, key.clone());
+                self.hits += 1;
+                Some(value.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Inserts `value`, evicting the least recently used entry if the cache is full.
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, tick)) = self.entries.remove(&key) {
+            self.order.remove(&tick);
+        } else if self.entries.len() == self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, (value, self.tick));
+    }
+
+    /// Returns the cached values, without marking them as used.
+    pub(crate) fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.values().map(|(value, _)| value)
+    }
+
+    /// Returns the hit and miss counts.
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            len: self.entries.len(),
+            capacity: self.capacity,
+        }
+    }
+}
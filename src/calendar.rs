@@ -0,0 +1,179 @@
+//! iCalendar (`.ics`) files with deadlines, such as the ones of homework sets or of problems
+//! assigned to a group, so that they show up in the calendars of the students.
+//!
+//! Times are PbInfo's local times (see `parse_datetime`), so they are written in the
+//! `Europe/Bucharest` time zone, which is defined in the file.
+
+use crate::*;
+use std::time::Duration;
+
+/// Definition of the `Europe/Bucharest` time zone (EET, with EEST in the summer).
+const BUCHAREST: &str = "BEGIN:VTIMEZONE\r\n\
+TZID:Europe/Bucharest\r\n\
+BEGIN:DAYLIGHT\r\n\
+TZOFFSETFROM:+0200\r\n\
+TZOFFSETTO:+0300\r\n\
+TZNAME:EEST\r\n\
+DTSTART:19700329T030000\r\n\
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r\n\
+END:DAYLIGHT\r\n\
+BEGIN:STANDARD\r\n\
+TZOFFSETFROM:+0300\r\n\
+TZOFFSETTO:+0200\r\n\
+TZNAME:EET\r\n\
+DTSTART:19701025T040000\r\n\
+RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r\n\
+END:STANDARD\r\n\
+END:VTIMEZONE\r\n";
+
+/// A deadline in a calendar.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CalendarEvent {
+    /// Unique and stable id of the event, so that importing the calendar again updates it
+    pub uid: String,
+    /// Title of the event
+    pub summary: String,
+    /// Longer description
+    pub description: String,
+    /// Link to the page of the event
+    pub url: Option<String>,
+    /// The deadline (see `parse_datetime`)
+    pub at: u64,
+    /// How long before the deadline to show a reminder
+    pub reminder: Option<Duration>,
+}
+
+impl CalendarEvent {
+    /// Returns the event of the deadline of a problem assigned to `group`, or `None` if the
+    /// problem has no deadline (or it can not be read).
+    #[cfg(feature = "blocking")]
+    pub fn from_assigned_problem(group: &Group, problem: &AssignedProblem) -> Option<Self> {
+        let at = parse_datetime(problem.deadline.as_deref()?)?;
+        Some(CalendarEvent {
+            uid: format!("group-{}-problem-{}@pbinfo.ro", group.id, problem.id),
+            summary: format!("{}: {}", group.name, problem.name),
+            description: format!("Termen pentru problema #{} {}", problem.id, problem.name),
+            url: Some(format!(
+                "https://www.pbinfo.ro/probleme/{}/{}",
+                problem.id, problem.name
+            )),
+            at,
+            reminder: Some(Duration::from_secs(24 * 60 * 60)),
+        })
+    }
+}
+
+/// A calendar of deadlines.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Calendar {
+    /// Name shown by calendar applications
+    pub name: String,
+    /// The events
+    pub events: Vec<CalendarEvent>,
+}
+
+/// Escapes `text` for a text value of iCalendar.
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Formats a timestamp as an iCalendar date and time (`20261020T235900`).
+fn format_ics(timestamp: u64) -> String {
+    let (year, month, day, hour, minute, second) = datetime_parts(timestamp);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}",
+        year, month, day, hour, minute, second
+    )
+}
+
+/// Appends the content line `line` to `output`, folded into lines of at most 75 bytes.
+fn push_line(output: &mut String, line: &str) {
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            output.push_str("\r\n ");
+            length = 1;
+        }
+        output.push(c);
+        length += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+impl Calendar {
+    /// Creates an empty calendar.
+    pub fn new(name: &str) -> Self {
+        Calendar {
+            name: name.to_owned(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Writes the calendar as an `.ics` file. `stamp` is the time at which it is generated, as
+    /// a Unix timestamp (see `progress::timestamp`).
+    pub fn to_ics(&self, stamp: u64) -> String {
+        let mut output = String::new();
+        for line in [
+            "BEGIN:VCALENDAR",
+            "VERSION:2.0",
+            "PRODID:-//pbinfo//pbinfo//RO",
+            "CALSCALE:GREGORIAN",
+        ] {
+            push_line(&mut output, line);
+        }
+        push_line(
+            &mut output,
+            &format!("X-WR-CALNAME:{}", escape_text(&self.name)),
+        );
+        output.push_str(BUCHAREST);
+
+        for event in &self.events {
+            push_line(&mut output, "BEGIN:VEVENT");
+            push_line(&mut output, &format!("UID:{}", escape_text(&event.uid)));
+            push_line(&mut output, &format!("DTSTAMP:{}Z", format_ics(stamp)));
+            for property in ["DTSTART", "DTEND"] {
+                push_line(
+                    &mut output,
+                    &format!(
+                        "{};TZID=Europe/Bucharest:{}",
+                        property,
+                        format_ics(event.at)
+                    ),
+                );
+            }
+            push_line(
+                &mut output,
+                &format!("SUMMARY:{}", escape_text(&event.summary)),
+            );
+            if !event.description.is_empty() {
+                push_line(
+                    &mut output,
+                    &format!("DESCRIPTION:{}", escape_text(&event.description)),
+                );
+            }
+            if let Some(url) = &event.url {
+                push_line(&mut output, &format!("URL:{}", url));
+            }
+            if let Some(reminder) = event.reminder {
+                push_line(&mut output, "BEGIN:VALARM");
+                push_line(&mut output, "ACTION:DISPLAY");
+                push_line(
+                    &mut output,
+                    &format!("DESCRIPTION:{}", escape_text(&event.summary)),
+                );
+                push_line(
+                    &mut output,
+                    &format!("TRIGGER:-PT{}M", reminder.as_secs() / 60),
+                );
+                push_line(&mut output, "END:VALARM");
+            }
+            push_line(&mut output, "END:VEVENT");
+        }
+        push_line(&mut output, "END:VCALENDAR");
+        output
+    }
+}
@@ -0,0 +1,1053 @@
+use crate::breaker::CircuitBreaker;
+use crate::cache::LruCache;
+use crate::robots::{robots_agent, RobotsRules};
+use crate::session::SessionJar;
+use crate::*;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// The client used by `PbInfoProblem::fetch_problem_by_id` and the other free-standing fetch
+/// functions. Initialized on first use.
+static SHARED_CLIENT: OnceLock<PbInfoClient> = OnceLock::new();
+
+/// A client for making requests to PbInfo. It keeps a pool of open connections, so reusing one
+/// client for many requests is considerably faster than creating a new one for each of them.
+#[derive(Debug, Clone)]
+pub struct PbInfoClient {
+    /// The underlying http client
+    http: reqwest::blocking::Client,
+    /// Cookies of the login session
+    session: Arc<SessionJar>,
+    /// Whether the cookies are managed by an http client given by the user, so that `session`
+    /// stays empty
+    external_cookies: bool,
+    /// How many times a throttled request is retried
+    max_retries: u32,
+    /// The longest wait before retrying a throttled request
+    max_retry_delay: Duration,
+    /// Address of PbInfo, without a trailing slash
+    base_url: String,
+    /// Recently fetched problems, shared by the clones of the client
+    cache: Arc<Mutex<LruCache<ProblemId, PbInfoProblem>>>,
+    /// Counters of the requests, shared by the clones of the client
+    metrics: Arc<Mutex<Metrics>>,
+    /// Whether responses are recorded or replayed
+    record_mode: Option<RecordMode>,
+    /// Shortest time between the starts of two requests
+    min_request_interval: Duration,
+    /// When the last request was started, shared by the clones of the client
+    last_request: Arc<Mutex<Option<Instant>>>,
+    /// Product token of the User-Agent, used to pick the robots.txt rules
+    robots_agent: String,
+    /// The robots.txt rules (fetched on the first request), if the client respects them
+    robots: Option<Arc<Mutex<Option<RobotsRules>>>>,
+    /// Where the responses to GET requests are cached
+    cache_backend: Option<Arc<dyn CacheBackend>>,
+    /// Stops the requests while PbInfo is down, shared by the clones of the client
+    breaker: Option<Arc<Mutex<CircuitBreaker>>>,
+}
+
+/// Configures the requests made by a PbInfoClient.
+#[derive(Debug, Clone)]
+pub struct PbInfoClientBuilder {
+    /// Value of the User-Agent header
+    user_agent: String,
+    /// Headers sent with every request
+    headers: Vec<(String, String)>,
+    /// How many times a throttled request is retried
+    max_retries: u32,
+    /// The longest wait before retrying a throttled request
+    max_retry_delay: Duration,
+    /// Address of PbInfo, without a trailing slash
+    base_url: String,
+    /// Maximum number of problems kept in memory
+    cache_capacity: usize,
+    /// Whether responses are recorded or replayed
+    record_mode: Option<RecordMode>,
+    /// Shortest time between the starts of two requests
+    min_request_interval: Duration,
+    /// Whether robots.txt is respected
+    respect_robots_txt: bool,
+    /// Where the responses to GET requests are cached
+    cache_backend: Option<Arc<dyn CacheBackend>>,
+    /// Consecutive failures and cooldown of the circuit breaker
+    circuit_breaker: Option<(u32, Duration)>,
+}
+
+impl Default for PbInfoClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PbInfoClientBuilder {
+    /// Creates a builder with the default User-Agent (`DEFAULT_USER_AGENT`), no extra headers and
+    /// the base url from the `PBINFO_BASE_URL` environment variable (or `DEFAULT_BASE_URL`).
+    pub fn new() -> Self {
+        PbInfoClientBuilder {
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            headers: Vec::new(),
+            max_retries: 3,
+            max_retry_delay: Duration::from_secs(60),
+            base_url: base_url_from_env(),
+            cache_capacity: 0,
+            record_mode: None,
+            min_request_interval: Duration::ZERO,
+            respect_robots_txt: false,
+            cache_backend: None,
+            circuit_breaker: None,
+        }
+    }
+
+    /// Sets the User-Agent sent with every request.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_owned();
+        self
+    }
+
+    /// Adds a header that is sent with every request.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Sets how many times a request is retried when PbInfo answers with HTTP 429 or 503
+    /// (3 by default). Use 0 to fail immediately with `PbInfoError::RateLimited`.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the longest wait before retrying a throttled request (60 seconds by default). If
+    /// PbInfo asks for a longer wait, `PbInfoError::RateLimited` is returned right away.
+    pub fn max_retry_delay(mut self, max_retry_delay: Duration) -> Self {
+        self.max_retry_delay = max_retry_delay;
+        self
+    }
+
+    /// Sets the address of PbInfo (e.g. `http://localhost:8080` for a test server, or the url of
+    /// a mirror). Every request is made to this address.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// Keeps up to `capacity` fetched problems in memory, so fetching them again does not make
+    /// any requests. The least recently used problem is evicted first. Disabled (0) by default.
+    pub fn cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    /// Records every response to a directory, or serves the requests from one (see
+    /// `RecordMode`). Recorded pages make parsing problems reproducible.
+    pub fn record_mode(mut self, mode: RecordMode) -> Self {
+        self.record_mode = Some(mode);
+        self
+    }
+
+    /// Waits at least `interval` between the starts of two requests made by the client (and its
+    /// clones), so that a single account stays under a request rate. Disabled by default.
+    pub fn min_request_interval(mut self, interval: Duration) -> Self {
+        self.min_request_interval = interval;
+        self
+    }
+
+    /// Makes the client fetch PbInfo's robots.txt before its first request and respect it: the
+    /// requests to disallowed paths fail with `PbInfoError::RobotsDisallowed`, and the requests
+    /// are spaced by the crawl delay (if it is longer than `min_request_interval`). Disabled by
+    /// default.
+    pub fn respect_robots_txt(mut self, respect: bool) -> Self {
+        self.respect_robots_txt = respect;
+        self
+    }
+
+    /// Caches the successful responses to GET requests in `backend` (e.g. `MemoryCache`,
+    /// `FileCache` or a shared store), and serves the cached ones without making a request.
+    /// Responses are only cached while the client is not logged in, since they may contain
+    /// data of the account. Disabled by default.
+    pub fn cache_backend(mut self, backend: impl CacheBackend + 'static) -> Self {
+        self.cache_backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Stops making requests for `cooldown` after `threshold` consecutive requests fail because
+    /// of the network, throttling or a server error, failing right away with
+    /// `PbInfoError::CircuitOpen` instead. This keeps batch jobs from hammering PbInfo while it
+    /// is down. Disabled by default.
+    pub fn circuit_breaker(mut self, threshold: u32, cooldown: Duration) -> Self {
+        self.circuit_breaker = Some((threshold, cooldown));
+        self
+    }
+
+    /// Creates the client. Fails if one of the headers is not valid.
+    pub fn build(self) -> Result<PbInfoClient> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in self.headers.iter() {
+            let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| PbInfoError::Error(format!("Invalid header name `{}`", name)))?;
+            let value = reqwest::header::HeaderValue::from_str(value)
+                .map_err(|_| PbInfoError::Error(format!("Invalid value for header `{}`", name)))?;
+            headers.append(name, value);
+        }
+
+        let session = Arc::new(SessionJar::default());
+        let http = reqwest::blocking::Client::builder()
+            .gzip(true)
+            .tcp_keepalive(Duration::from_secs(60))
+            .pool_idle_timeout(Duration::from_secs(90))
+            .cookie_provider(session.clone())
+            .user_agent(self.user_agent.as_str())
+            .default_headers(headers)
+            .build()
+            .map_err(|err| {
+                PbInfoError::NetworkError(format!("Could not initialize the http client: {}", err))
+            })?;
+        Ok(PbInfoClient {
+            http,
+            session,
+            external_cookies: false,
+            max_retries: self.max_retries,
+            max_retry_delay: self.max_retry_delay,
+            base_url: self.base_url,
+            cache: Arc::new(Mutex::new(LruCache::new(self.cache_capacity))),
+            metrics: Arc::default(),
+            record_mode: self.record_mode,
+            min_request_interval: self.min_request_interval,
+            last_request: Arc::default(),
+            robots_agent: robots_agent(&self.user_agent),
+            robots: self.respect_robots_txt.then(Arc::default),
+            cache_backend: self.cache_backend,
+            breaker: self.circuit_breaker.map(|(threshold, cooldown)| {
+                Arc::new(Mutex::new(CircuitBreaker::new(threshold, cooldown)))
+            }),
+        })
+    }
+}
+
+impl Default for PbInfoClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PbInfoClient {
+    /// Creates a client with keep-alive, connection pooling, gzip compression and the default
+    /// User-Agent.
+    pub fn new() -> Self {
+        PbInfoClientBuilder::new()
+            .build()
+            .expect("Could not initialize the http client")
+    }
+
+    /// Returns a builder, for configuring the User-Agent and other request headers.
+    pub fn builder() -> PbInfoClientBuilder {
+        PbInfoClientBuilder::new()
+    }
+
+    /// Creates a client that makes its requests through `http`, for full control over the
+    /// connection settings (proxies, timeouts, certificates etc.). Cookies are managed by
+    /// `http`, so such a client cannot save or load login sessions, and whether it is logged in
+    /// is only known by asking PbInfo.
+    pub fn with_http_client(http: reqwest::blocking::Client) -> Self {
+        let defaults = PbInfoClientBuilder::new();
+        PbInfoClient {
+            http,
+            session: Arc::new(SessionJar::default()),
+            external_cookies: true,
+            max_retries: defaults.max_retries,
+            max_retry_delay: defaults.max_retry_delay,
+            base_url: defaults.base_url,
+            cache: Arc::new(Mutex::new(LruCache::new(defaults.cache_capacity))),
+            metrics: Arc::default(),
+            record_mode: None,
+            min_request_interval: defaults.min_request_interval,
+            last_request: Arc::default(),
+            robots_agent: robots_agent(&defaults.user_agent),
+            robots: None,
+            cache_backend: None,
+            breaker: None,
+        }
+    }
+
+    /// Returns the underlying http client.
+    pub fn http(&self) -> &reqwest::blocking::Client {
+        &self.http
+    }
+
+    /// Returns the address of PbInfo used by the client.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns the hit and miss counts of the in-memory problem cache.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.lock().unwrap().stats()
+    }
+
+    /// Returns the problems of the in-memory problem cache that match `filter`, in increasing
+    /// order of id. No requests are made.
+    pub fn cached_problems(&self, filter: &ProblemFilter) -> Vec<PbInfoProblem> {
+        let cache = self.cache.lock().unwrap();
+        let mut problems = filter
+            .apply(cache.values())
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        problems.sort_by_key(|problem| problem.id);
+        problems
+    }
+
+    /// Returns the counters of the requests made by the client (and its clones) so far.
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            cache_hits: self.cache_stats().hits,
+            ..*self.metrics.lock().unwrap()
+        }
+    }
+
+    /// Adds `bytes` to the downloaded bytes counter.
+    pub(crate) fn record_download(&self, bytes: u64) {
+        self.metrics.lock().unwrap().bytes_downloaded += bytes;
+    }
+
+    /// Returns the url of `path` (which starts with a slash) on PbInfo.
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Returns the client shared by the free-standing fetch functions.
+    pub fn shared() -> &'static PbInfoClient {
+        SHARED_CLIENT.get_or_init(PbInfoClient::new)
+    }
+
+    /// Replaces the shared client. This only works before the shared client is first used.
+    pub fn set_shared(client: PbInfoClient) -> Result<()> {
+        SHARED_CLIENT.set(client).map_err(|_| {
+            PbInfoError::Error("The shared client has already been initialized".to_owned())
+        })
+    }
+
+    /// Logs into PbInfo. The session cookies are kept by the client (and can be saved to disk
+    /// using `save_session`), so every following request is authenticated. If PbInfo asks for
+    /// a captcha or a verification code, `PbInfoError::LoginChallenge` is returned and the login
+    /// can be finished using `continue_login`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, password), err(Debug))
+    )]
+    pub fn login(&self, username: &str, password: &str) -> Result<()> {
+        self.post_login(&[("user", username), ("parola", password), ("form_token", "")])?;
+        self.check_login(username)
+    }
+
+    /// Finishes a login that was interrupted by `challenge`, sending `answer` (the text in the
+    /// captcha image or the verification code).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, password, challenge, answer), err(Debug))
+    )]
+    pub fn continue_login(
+        &self,
+        username: &str,
+        password: &str,
+        challenge: &LoginChallenge,
+        answer: &str,
+    ) -> Result<()> {
+        let answer_field = match challenge.kind {
+            ChallengeKind::Captcha => "captcha",
+            ChallengeKind::ReCaptcha(_) => "g-recaptcha-response",
+            ChallengeKind::Verification => "cod",
+        };
+        self.post_login(&[
+            ("user", username),
+            ("parola", password),
+            ("form_token", &challenge.token),
+            (answer_field, answer),
+        ])?;
+        self.check_login(username)
+    }
+
+    /// Posts `form` to the login endpoint, returning any challenge found in the response.
+    fn post_login(&self, form: &[(&str, &str)]) -> Result<()> {
+        let url = self.url("/ajx-module/php-login.php");
+        let page = self.send(self.http.post(url).form(form))?;
+
+        if !page.status().is_success() {
+            return Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to log in. HTTP status code {}",
+                page.status()
+            )));
+        }
+
+        let text = self.read_text(page)?;
+        match extract_login_challenge(&text) {
+            Some(challenge) => Err(PbInfoError::LoginChallenge(challenge)),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that the login of `username` succeeded.
+    fn check_login(&self, username: &str) -> Result<()> {
+        match self.is_logged_in()? {
+            true => Ok(()),
+            false => Err(PbInfoError::LoginError(format!(
+                "Could not log in as `{}`. Check the username and password",
+                username
+            ))),
+        }
+    }
+
+    /// Returns `true` if the client has a valid login session.
+    pub fn is_logged_in(&self) -> Result<bool> {
+        if !self.external_cookies && self.session.is_empty() {
+            return Ok(false);
+        }
+
+        let page = self.get_page(&self.url("/"))?;
+        let text = self.read_text(page)?;
+        Ok(text.contains("/php/logout.php") || text.contains("Deconectare"))
+    }
+
+    /// Returns `true` if the client has the cookies of a login session (which may have expired).
+    /// The cookies of a client made by `with_http_client` are not known, so it might have them.
+    pub(crate) fn has_session(&self) -> bool {
+        self.external_cookies || !self.session.is_empty()
+    }
+
+    /// Returns the cookies of the login session.
+    #[cfg_attr(not(feature = "keyring"), allow(dead_code))]
+    pub(crate) fn session_jar(&self) -> &SessionJar {
+        &self.session
+    }
+
+    /// Forgets the login session.
+    pub fn logout(&self) {
+        self.session.clear();
+    }
+
+    /// Writes the cookies of the login session to `path`. On Unix, the file is only readable
+    /// by its owner.
+    pub fn save_session(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.session.to_text()).map_err(|err| {
+            PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+        })?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).map_err(
+                |err| {
+                    PbInfoError::IOError(format!(
+                        "Could not restrict the permissions of `{}`: {}",
+                        path.display(),
+                        err
+                    ))
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the login session with one previously written by `save_session`.
+    pub fn load_session(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            PbInfoError::IOError(format!("Could not read `{}`: {}", path.display(), err))
+        })?;
+        self.session.load_text(&text)
+    }
+
+    /// Sends `request`, converting connection errors into `PbInfoError::NetworkError`. When
+    /// PbInfo answers with HTTP 429 or 503, the request is retried after the delay from the
+    /// `Retry-After` header (or an exponential backoff), up to `max_retries` times. If the client
+    /// has a circuit breaker, the outcome is recorded, and the request fails with
+    /// `PbInfoError::CircuitOpen` without being made while the circuit is open.
+    pub(crate) fn send(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> Result<reqwest::blocking::Response> {
+        let breaker = match &self.breaker {
+            Some(breaker) => breaker,
+            None => return self.send_with_retries(request),
+        };
+        breaker.lock().unwrap().check(Instant::now())?;
+        let result = self.send_with_retries(request);
+        let success = match &result {
+            Ok(page) => !page.status().is_server_error(),
+            Err(PbInfoError::NetworkError(_)) | Err(PbInfoError::RateLimited { .. }) => false,
+            Err(_) => true,
+        };
+        breaker.lock().unwrap().record(success, Instant::now());
+        result
+    }
+
+    /// Sends `request`, retrying it while PbInfo throttles it (see `send`).
+    fn send_with_retries(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> Result<reqwest::blocking::Response> {
+        let mut request = request.build().map_err(|err| {
+            PbInfoError::NetworkError(format!("Could not build the request: {}", err))
+        })?;
+
+        let mut attempt = 0;
+        loop {
+            // Requests with streaming bodies cannot be cloned, so they are not retried
+            let retry = request.try_clone();
+            let page = self.execute(request)?;
+
+            let status = page.status();
+            if status != reqwest::StatusCode::TOO_MANY_REQUESTS
+                && status != reqwest::StatusCode::SERVICE_UNAVAILABLE
+            {
+                return Ok(page);
+            }
+
+            let retry_after = page
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, std::time::SystemTime::now()));
+            // A 503 without Retry-After is an outage rather than throttling
+            if status == reqwest::StatusCode::SERVICE_UNAVAILABLE && retry_after.is_none() {
+                return Ok(page);
+            }
+
+            let delay = retry_after.unwrap_or(Duration::from_secs(1 << attempt.min(16)));
+            match retry {
+                Some(retry) if attempt < self.max_retries && delay <= self.max_retry_delay => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(status = %status, delay_ms = delay.as_millis() as u64, "throttled, retrying");
+                    std::thread::sleep(delay);
+                    request = retry;
+                    attempt += 1;
+                }
+                _ => return Err(PbInfoError::RateLimited { retry_after }),
+            }
+        }
+    }
+
+    /// Executes a single request, recording or replaying it if the client has a `RecordMode`.
+    fn execute(&self, request: reqwest::blocking::Request) -> Result<reqwest::blocking::Response> {
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!("request", url = %request.url()).entered();
+
+        let url = request.url().to_string();
+        let recording = match &self.record_mode {
+            Some(RecordMode::Record(dir)) | Some(RecordMode::Replay(dir)) => Some(
+                crate::recording::recording_path(dir, request.method().as_str(), &url),
+            ),
+            None => None,
+        };
+        if let (Some(RecordMode::Replay(_)), Some(path)) = (&self.record_mode, &recording) {
+            if !path.exists() {
+                return Err(PbInfoError::NetworkError(format!(
+                    "No response to `{}` was recorded",
+                    url
+                )));
+            }
+            return crate::recording::load_response(path);
+        }
+
+        // Conditional requests revalidate a page, so they are not answered from the cache
+        let cache = self.cache_backend.as_ref().filter(|_| {
+            request.method() == reqwest::Method::GET
+                && !request
+                    .headers()
+                    .contains_key(reqwest::header::IF_NONE_MATCH)
+                && !request
+                    .headers()
+                    .contains_key(reqwest::header::IF_MODIFIED_SINCE)
+                && self.session.is_empty()
+        });
+        if let Some(cached) = cache.and_then(|cache| cache.get(&url)) {
+            return cached.to_response();
+        }
+
+        let crawl_delay = self.check_robots(request.url())?;
+        self.wait_for_turn(self.min_request_interval.max(crawl_delay));
+        let start = Instant::now();
+
+        let page = self.http.execute(request);
+        {
+            let mut metrics = self.metrics.lock().unwrap();
+            metrics.requests += 1;
+            metrics.total_latency += start.elapsed();
+        }
+        let page = page.map_err(|err| {
+            PbInfoError::NetworkError(format!(
+                "Encountered an error while making a request to pbinfo.ro: {}",
+                err
+            ))
+        })?;
+
+        #[cfg(feature = "tracing")]
+        {
+            tracing::debug!(
+                status = %page.status(),
+                duration_ms = start.elapsed().as_millis() as u64,
+                "received response"
+            );
+            span.exit();
+        }
+        let page = match recording {
+            Some(path) => crate::recording::record_response(&path, &url, page)?,
+            None => page,
+        };
+        match cache {
+            Some(cache) if page.status() == reqwest::StatusCode::OK => {
+                let response = CachedResponse::read(page)?;
+                cache.put(&url, response.clone());
+                response.to_response()
+            }
+            _ => Ok(page),
+        }
+    }
+
+    /// Sleeps until `interval` has passed since the start of the last request.
+    fn wait_for_turn(&self, interval: Duration) {
+        let mut last_request = self.last_request.lock().unwrap();
+        if let Some(last) = *last_request {
+            let elapsed = last.elapsed();
+            if elapsed < interval {
+                std::thread::sleep(interval - elapsed);
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+
+    /// Checks `url` against robots.txt (if the client respects it), fetching the rules on the
+    /// first call. Returns the crawl delay asked for by PbInfo (zero if there is none).
+    fn check_robots(&self, url: &reqwest::Url) -> Result<Duration> {
+        let robots = match &self.robots {
+            Some(robots) => robots,
+            None => return Ok(Duration::ZERO),
+        };
+        // Other hosts (e.g. the ones serving attachments) have their own rules
+        let path = match url.as_str().strip_prefix(&self.base_url) {
+            Some(path) => path,
+            None => return Ok(Duration::ZERO),
+        };
+
+        let mut robots = robots.lock().unwrap();
+        if robots.is_none() {
+            *robots = Some(self.fetch_robots()?);
+        }
+        let rules = robots.as_ref().unwrap();
+        if !rules.is_allowed(if path.is_empty() { "/" } else { path }) {
+            return Err(PbInfoError::RobotsDisallowed(url.to_string()));
+        }
+        Ok(rules.crawl_delay().unwrap_or_default())
+    }
+
+    /// Downloads and parses PbInfo's robots.txt. A missing robots.txt allows everything.
+    fn fetch_robots(&self) -> Result<RobotsRules> {
+        let page = self
+            .http
+            .get(self.url("/robots.txt"))
+            .send()
+            .map_err(|err| {
+                PbInfoError::NetworkError(format!("Could not fetch robots.txt: {}", err))
+            })?;
+        if !page.status().is_success() {
+            return Ok(RobotsRules::default());
+        }
+        let text = self.read_text(page)?;
+        Ok(RobotsRules::parse(&text, &self.robots_agent))
+    }
+
+    /// Reads the body of `page`, counting its size in the metrics.
+    fn read_bytes(&self, mut page: reqwest::blocking::Response) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        std::io::Read::read_to_end(&mut page, &mut bytes).map_err(|err| {
+            PbInfoError::NetworkError(format!("Could not read the response body: {}", err))
+        })?;
+        self.record_download(bytes.len() as u64);
+        Ok(bytes)
+    }
+
+    /// Reads the body of `page` as text, decoding it with `decode_html`.
+    pub(crate) fn read_text(&self, page: reqwest::blocking::Response) -> Result<String> {
+        let content_type = header_value(&page, reqwest::header::CONTENT_TYPE);
+        let bytes = self.read_bytes(page)?;
+        Ok(decode_html(&bytes, content_type.as_deref()))
+    }
+
+    /// Makes a get request to `url`
+    pub(crate) fn get_page(&self, url: &str) -> Result<reqwest::blocking::Response> {
+        self.send(self.http.get(url))
+    }
+
+    /// Makes a conditional get request to `url`, sending the validators of a previously fetched page.
+    fn get_page_conditional(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> Result<reqwest::blocking::Response> {
+        let mut request = self.http.get(url);
+        if let Some(etag) = etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+        self.send(request)
+    }
+
+    /// Construct PbInfoProblem from id. If the client has a cache, problems fetched before are
+    /// returned without making any requests.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_problem_by_id(&self, id: ProblemId) -> Result<PbInfoProblem> {
+        let cached = self.cache.lock().unwrap().get(&id);
+        if let Some(problem) = cached {
+            return Ok(problem);
+        }
+
+        let problem = self.download_problem(id)?;
+        self.cache.lock().unwrap().insert(id, problem.clone());
+        Ok(problem)
+    }
+
+    /// Downloads and parses the page of the problem with the given id.
+    fn download_problem(&self, id: ProblemId) -> Result<PbInfoProblem> {
+        let page = self.get_page(&self.url(&format!("/probleme/{}", id)))?;
+
+        match page.status() {
+            reqwest::StatusCode::OK => {
+                let etag = header_value(&page, reqwest::header::ETAG);
+                let last_modified = header_value(&page, reqwest::header::LAST_MODIFIED);
+                let text = self.read_text(page)?;
+
+                let mut problem = PbInfoProblem::from_html(id, &text)?;
+                problem.etag = etag;
+                problem.last_modified = last_modified;
+                Ok(problem)
+            }
+            reqwest::StatusCode::NOT_FOUND => Err(PbInfoError::UnknownId(id)), // If the page does not exist, it means the id is wrong
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to fetch the problem. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+
+    /// Refetch a previously fetched PbInfoProblem. A conditional request is made using the
+    /// cached `etag`/`last_modified` validators, and the page is only reparsed if its statement
+    /// or metadata differ from the cached ones.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, cached), fields(id = %cached.id), err(Debug))
+    )]
+    pub fn fetch_problem_refresh(
+        &self,
+        cached: &PbInfoProblem,
+    ) -> Result<(PbInfoProblem, Freshness)> {
+        let page = self.get_page_conditional(
+            &self.url(&format!("/probleme/{}", cached.id)),
+            cached.etag.as_deref(),
+            cached.last_modified.as_deref(),
+        )?;
+
+        match page.status() {
+            reqwest::StatusCode::NOT_MODIFIED => Ok((cached.clone(), Freshness::Unchanged)),
+            reqwest::StatusCode::OK => {
+                let etag = header_value(&page, reqwest::header::ETAG);
+                let last_modified = header_value(&page, reqwest::header::LAST_MODIFIED);
+                let text = self.read_text(page)?;
+
+                let problem_text = match extract_problem_text(&text) {
+                    Ok(problem_text) => problem_text,
+                    Err(err) => {
+                        check_site_available(&text)?;
+                        return Err(err);
+                    }
+                };
+                let metadata = extract_metadata(&text)?;
+
+                let (mut problem, freshness) =
+                    if problem_text == cached.problem_text && metadata == cached.meta_text {
+                        (cached.clone(), Freshness::Unchanged)
+                    } else {
+                        let problem = PbInfoProblem::from_parts(
+                            cached.id,
+                            extract_name(&text)?,
+                            problem_text,
+                            metadata,
+                        )?;
+                        (problem, Freshness::Changed)
+                    };
+                problem.topics = extract_topics(&text);
+                problem.attachments = extract_attachments(&text);
+                problem.etag = etag;
+                problem.last_modified = last_modified;
+                self.cache
+                    .lock()
+                    .unwrap()
+                    .insert(problem.id, problem.clone());
+                Ok((problem, freshness))
+            }
+            reqwest::StatusCode::NOT_FOUND => Err(PbInfoError::UnknownId(cached.id)),
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to fetch the problem. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+
+    /// Construct PbInfoProblem from name.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_problem_by_name(&self, name: &str) -> Result<PbInfoProblem> {
+        // `name` is converted to lowercase, without diacritics
+        let name = ProblemName::new(name)?;
+        let name = name.as_str();
+
+        let mut suggestions: Vec<Suggestion> = Vec::new();
+        for query in name_search_queries(name) {
+            let search_json = self.search_json(&query)?;
+            match extract_id_from_search(name, &search_json) {
+                Ok(id) => return self.fetch_problem_by_id(ProblemId(id)),
+                Err(PbInfoError::UnknownName(_, found)) => {
+                    merge_suggestions(&mut suggestions, found)
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(PbInfoError::UnknownName(name.to_owned(), suggestions))
+    }
+
+    /// Construct PbInfoProblem from a problem url (e.g. `https://www.pbinfo.ro/probleme/1691/arbore1?tab=1`).
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_problem_by_url(&self, url: &str) -> Result<PbInfoProblem> {
+        self.fetch_problem_by_id(ProblemId(extract_id_from_url(url)?))
+    }
+
+    /// Returns the source code of one of the logged in user's submissions.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_submission_source(&self, submission_id: usize) -> Result<String> {
+        let page = self.get_page(&self.url(&format!("/detalii-evaluare/{}", submission_id)))?;
+
+        match page.status() {
+            reqwest::StatusCode::OK => {
+                let text = self.read_text(page)?;
+                match extract_submission_source(&text) {
+                    Ok(source) => Ok(source),
+                    Err(_) if !self.is_logged_in()? => Err(PbInfoError::LoginError(
+                        "The source code of a submission is only visible after logging in"
+                            .to_owned(),
+                    )),
+                    Err(err) => Err(err),
+                }
+            }
+            reqwest::StatusCode::NOT_FOUND => Err(PbInfoError::Error(format!(
+                "Unknown submission {}",
+                submission_id
+            ))),
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to fetch the submission. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+
+    /// Returns the submission statistics of the problem with the given id.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_problem_stats(&self, id: ProblemId) -> Result<ProblemStats> {
+        let page = self.get_page(&self.url(&format!("/probleme/{}", id)))?;
+
+        match page.status() {
+            reqwest::StatusCode::OK => extract_problem_stats(&self.read_text(page)?),
+            reqwest::StatusCode::NOT_FOUND => Err(PbInfoError::UnknownId(id)),
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to fetch the problem statistics. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+
+    /// Returns the submissions that match `filter`, newest first, following the pagination
+    /// until the history (or `filter.max_entries`) is exhausted. PbInfo lists the newest
+    /// submissions first, so no page older than `filter.since` is fetched.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_submissions(&self, filter: &SubmissionFilter) -> Result<Vec<Submission>> {
+        let url = self.url("/solutii");
+        let mut submissions: Vec<Submission> = Vec::new();
+        let mut start = 0;
+
+        loop {
+            let mut query = vec![("start", start.to_string())];
+            if let Some(username) = &filter.username {
+                query.push(("user", username.clone()));
+            }
+            if let Some(problem) = filter.problem {
+                query.push(("problema", problem.to_string()));
+            }
+
+            let page = self.send(self.http.get(&url).query(&query))?;
+            if !page.status().is_success() {
+                return Err(PbInfoError::NetworkError(format!(
+                    "Encountered an error when trying to fetch the submissions. HTTP status code {}",
+                    page.status()
+                )));
+            }
+
+            // Pages past the end are either empty or repeat the last page
+            let found = extract_submissions(&self.read_text(page)?);
+            let new = found
+                .into_iter()
+                .filter(|found| submissions.iter().all(|known| known.id != found.id))
+                .collect::<Vec<_>>();
+            if new.is_empty() {
+                break;
+            }
+            start += new.len();
+            let older = new.iter().any(|submission| {
+                submission
+                    .submitted_at
+                    .zip(filter.since)
+                    .is_some_and(|(at, since)| at < since)
+            });
+            submissions.extend(new);
+            let matching = submissions
+                .iter()
+                .filter(|submission| filter.matches_time(submission))
+                .count();
+            if older || filter.max_entries.is_some_and(|max| matching >= max) {
+                break;
+            }
+        }
+
+        let mut submissions = submissions
+            .into_iter()
+            .filter(|submission| filter.matches_time(submission))
+            .collect::<Vec<_>>();
+        if let Some(max) = filter.max_entries {
+            submissions.truncate(max);
+        }
+        Ok(submissions)
+    }
+
+    /// Returns the ranking ("clasament") of the users that match `filter`, following the
+    /// pagination until the ranking (or `filter.max_entries`) is exhausted.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_rankings(&self, filter: &RankingFilter) -> Result<Vec<RankEntry>> {
+        let url = self.url("/clasament");
+        let mut entries: Vec<RankEntry> = Vec::new();
+
+        loop {
+            let mut query = vec![("start", entries.len().to_string())];
+            if let Some(county) = &filter.county {
+                query.push(("judet", county.clone()));
+            }
+            if let Some(grade) = filter.grade {
+                query.push(("clasa", grade.to_string()));
+            }
+
+            let page = self.send(self.http.get(&url).query(&query))?;
+            if !page.status().is_success() {
+                return Err(PbInfoError::NetworkError(format!(
+                    "Encountered an error when trying to fetch the ranking. HTTP status code {}",
+                    page.status()
+                )));
+            }
+
+            // Pages past the end are either empty or repeat the last page
+            let found = extract_rankings(&self.read_text(page)?);
+            let last_rank = entries.last().map(|entry| entry.rank).unwrap_or(0);
+            let new = found
+                .into_iter()
+                .filter(|entry| entry.rank > last_rank)
+                .collect::<Vec<_>>();
+            if new.is_empty() {
+                break;
+            }
+            entries.extend(new);
+
+            if let Some(max_entries) = filter.max_entries {
+                if entries.len() >= max_entries {
+                    entries.truncate(max_entries);
+                    break;
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Returns the problems listed on the public profile of `username`. Does not require logging
+    /// in, so it only sees what PbInfo shows to anonymous visitors.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_public_solves(&self, username: &str) -> Result<Vec<PublicSolve>> {
+        let page = self.get_page(&self.url(&format!("/profil/{}", username.trim())))?;
+
+        match page.status() {
+            reqwest::StatusCode::OK => {
+                let text = self.read_text(page)?;
+                let solves = extract_public_solves(&text);
+                if solves.is_empty() {
+                    check_site_available(&text)?;
+                }
+                Ok(solves)
+            }
+            reqwest::StatusCode::NOT_FOUND => {
+                Err(PbInfoError::Error(format!("Unknown user {}", username)))
+            }
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to fetch the profile. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+
+    /// Returns the raw JSON returned by the search endpoint for `query`.
+    fn search_json(&self, query: &str) -> Result<Vec<HashMap<String, String>>> {
+        let url = self.url("/php/ajax-search.php");
+        let page = self.send(self.http.get(url).query(&[("term", query)]))?;
+
+        let bytes = self.read_bytes(page)?;
+        match serde_json::from_slice::<Vec<HashMap<String, String>>>(&bytes) {
+            Ok(res) => Ok(res),
+            Err(_) => Err(PbInfoError::JSONError(
+                "Could not parse JSON response".to_owned(),
+            )),
+        }
+    }
+
+    /// Returns the problems whose names (partially) match `query`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn search(&self, query: &str) -> Result<Vec<SearchResult>> {
+        extract_search_results(&self.search_json(query)?)
+    }
+
+    /// Searches for every query in `queries`, returning a map from each query to its results.
+    /// Queries that only differ in case, diacritics or surrounding whitespace are searched once.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn search_many(&self, queries: &[&str]) -> Result<HashMap<String, Vec<SearchResult>>> {
+        // Results of the searches that were already made, keyed by the normalized query
+        let mut searched: HashMap<String, Vec<SearchResult>> = HashMap::new();
+        let mut results = HashMap::new();
+        for query in queries {
+            let normalized = normalize_text(query);
+            if !searched.contains_key(&normalized) {
+                let found = self.search(&normalized)?;
+                searched.insert(normalized.clone(), found);
+            }
+            results.insert(query.to_string(), searched[&normalized].clone());
+        }
+        Ok(results)
+    }
+}
+
+/// Returns the value of the header `name` (if it exists and is valid text).
+fn header_value(
+    page: &reqwest::blocking::Response,
+    name: reqwest::header::HeaderName,
+) -> Option<String> {
+    page.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_owned())
+}
@@ -0,0 +1,115 @@
+use crate::judge::Language;
+use crate::*;
+
+/// A block of code from a statement or an article (a `<pre>` block or an element with the
+/// `cod-sursa` class).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CodeBlock {
+    /// Language of the code, if it could be recognized (pseudocode is not)
+    pub language: Option<Language>,
+    /// The code, as plain text
+    pub text: String,
+}
+
+impl CodeBlock {
+    /// Creates a block, detecting its language (see `detect_language`).
+    pub fn new(text: &str) -> Self {
+        CodeBlock {
+            language: detect_language(text),
+            text: text.to_owned(),
+        }
+    }
+
+    /// Renders the block as a fenced Markdown code block, tagged with its language.
+    pub fn to_markdown(&self) -> String {
+        format!(
+            "```{}\n{}\n```",
+            self.language.map_or("", |language| language.extension()),
+            self.text.trim_matches('\n')
+        )
+    }
+}
+
+/// Returns the language named by the class of a code element (`language-cpp`, `lang-py`,
+/// `brush: c`...), if any.
+fn language_from_class(tag: &str) -> Option<Language> {
+    let class = &selectors::CODE_LANGUAGE_CLASS;
+    class.captures(tag).and_then(|caps| caps[1].parse().ok())
+}
+
+/// Guesses the language of `code` from the features of C, C++ and Python it uses. Returns
+/// `None` if it does not look like any of them (e.g. input data or pseudocode).
+pub fn detect_language(code: &str) -> Option<Language> {
+    let count = |features: &[selectors::Selector]| {
+        features
+            .iter()
+            .filter(|feature| feature.is_match(code))
+            .count()
+    };
+    let cpp = count(&selectors::CPP_FEATURES);
+    let c = count(&selectors::C_FEATURES);
+    let c_family = count(&selectors::C_FAMILY_FEATURES);
+    let python = count(&selectors::PYTHON_FEATURES);
+
+    // C++ programs often use C functions, so C only wins without any C++ feature
+    if cpp > 0 && cpp + c_family >= python {
+        Some(Language::Cpp)
+    } else if c > 0 && c + c_family >= python {
+        Some(Language::C)
+    } else if python >= 2 && python > c_family {
+        Some(Language::Python)
+    } else if c_family >= 2 {
+        // Plain C-like code (e.g. a function) is most likely C++ on PbInfo
+        Some(Language::Cpp)
+    } else {
+        None
+    }
+}
+
+/// Extracts the code blocks (`<pre>` blocks and elements with the `cod-sursa` class) of a
+/// fragment of html, in order. The language is taken from the class of the element if it names
+/// one, and detected from the code otherwise.
+pub fn extract_code_blocks(html: &str) -> Vec<CodeBlock> {
+    let block = &selectors::CODE_BLOCK;
+    let tags = &selectors::TAG;
+
+    block
+        .captures_iter(html)
+        .filter_map(|caps| {
+            let (tag, body) = match (caps.get(1), caps.get(3)) {
+                (Some(tag), _) => (tag.as_str(), &caps[2]),
+                (None, Some(tag)) => (tag.as_str(), &caps[4]),
+                (None, None) => return None,
+            };
+            // `<br>` separates lines in some code blocks
+            let body = selectors::BR.replace_all(body, "\n");
+            let text = decode_entities(&tags.replace_all(&body, ""));
+            let text = text.trim_matches('\n').trim_end();
+            if text.trim().is_empty() {
+                return None;
+            }
+            Some(CodeBlock {
+                language: language_from_class(tag).or_else(|| detect_language(text)),
+                text: text.to_owned(),
+            })
+        })
+        .collect()
+}
+
+impl PbInfoProblem {
+    /// Returns the code blocks of the statement, in order, except for the examples.
+    pub fn code_blocks(&self) -> Vec<CodeBlock> {
+        self.sections()
+            .into_iter()
+            .filter(|section| !section.title.to_lowercase().starts_with("exempl"))
+            .flat_map(|section| extract_code_blocks(&section.html))
+            .collect()
+    }
+}
+
+impl Article {
+    /// Returns the code blocks of the article, in order, with their languages.
+    pub fn code_blocks(&self) -> Vec<CodeBlock> {
+        extract_code_blocks(&self.html)
+    }
+}
@@ -0,0 +1,109 @@
+//! Generated code for reading the input and writing the output of a problem, such as
+//! `ifstream fin("suma.in");` or `cin`, used by the solution templates and by editor plugins.
+
+use crate::judge::Language;
+use crate::*;
+
+/// The code a solution needs to read its input and write its output. Every snippet is made of
+/// whole lines, without indentation (see `indent`).
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct IoBoilerplate {
+    /// Includes or imports needed by the other snippets
+    pub header: String,
+    /// Statements that open the input and the output, at the start of `main`
+    pub setup: String,
+    /// Statements that close the files, at the end of `main`
+    pub cleanup: String,
+    /// The stream the input is read from (`fin`, `cin`, `stdin`...)
+    pub input: String,
+    /// The stream the output is written to (`fout`, `cout`, `stdout`...)
+    pub output: String,
+}
+
+/// Returns `text` with `prefix` added before every line that is not empty.
+pub fn indent(text: &str, prefix: &str) -> String {
+    text.split_inclusive('\n')
+        .map(|line| match line.trim().is_empty() {
+            true => line.to_owned(),
+            false => format!("{}{}", prefix, line),
+        })
+        .collect()
+}
+
+/// Returns the code that opens the input and output of `problem` in `language`: the files
+/// named by `input_source` and `output_source`, or stdin and stdout. The input and the output
+/// are handled separately, since some problems read from a file and write to stdout.
+pub fn io_boilerplate(problem: &PbInfoProblem, language: Language) -> IoBoilerplate {
+    let input = match &problem.input_source {
+        IOSource::File(name) => Some(name.as_str()),
+        IOSource::Std => None,
+    };
+    let output = match &problem.output_source {
+        IOSource::File(name) => Some(name.as_str()),
+        IOSource::Std => None,
+    };
+    let mut code = IoBoilerplate::default();
+    let push = |snippet: &mut String, line: String| {
+        snippet.push_str(&line);
+        snippet.push('\n');
+    };
+
+    match language {
+        Language::Cpp => {
+            if input.is_some() || output.is_some() {
+                push(&mut code.header, "#include <fstream>".to_owned());
+            }
+            if input.is_none() || output.is_none() {
+                push(&mut code.header, "#include <iostream>".to_owned());
+                push(&mut code.setup, "ios::sync_with_stdio(false);".to_owned());
+            }
+            match input {
+                Some(name) => push(&mut code.setup, format!("ifstream fin(\"{}\");", name)),
+                None => push(&mut code.setup, "cin.tie(nullptr);".to_owned()),
+            }
+            if let Some(name) = output {
+                push(&mut code.setup, format!("ofstream fout(\"{}\");", name));
+            }
+            code.input = input.map_or("cin", |_| "fin").to_owned();
+            code.output = output.map_or("cout", |_| "fout").to_owned();
+        }
+        Language::C => {
+            push(&mut code.header, "#include <stdio.h>".to_owned());
+            if let Some(name) = input {
+                push(
+                    &mut code.setup,
+                    format!("FILE *fin = fopen(\"{}\", \"r\");", name),
+                );
+                push(&mut code.cleanup, "fclose(fin);".to_owned());
+            }
+            if let Some(name) = output {
+                push(
+                    &mut code.setup,
+                    format!("FILE *fout = fopen(\"{}\", \"w\");", name),
+                );
+                push(&mut code.cleanup, "fclose(fout);".to_owned());
+            }
+            code.input = input.map_or("stdin", |_| "fin").to_owned();
+            code.output = output.map_or("stdout", |_| "fout").to_owned();
+        }
+        Language::Python => {
+            if input.is_none() || output.is_none() {
+                push(&mut code.header, "import sys".to_owned());
+            }
+            match input {
+                Some(name) => {
+                    push(&mut code.setup, format!("fin = open(\"{}\")", name));
+                    push(&mut code.cleanup, "fin.close()".to_owned());
+                }
+                None => push(&mut code.setup, "input = sys.stdin.readline".to_owned()),
+            }
+            if let Some(name) = output {
+                push(&mut code.setup, format!("fout = open(\"{}\", \"w\")", name));
+                push(&mut code.cleanup, "fout.close()".to_owned());
+            }
+            code.input = input.map_or("sys.stdin", |_| "fin").to_owned();
+            code.output = output.map_or("sys.stdout", |_| "fout").to_owned();
+        }
+    }
+    code
+}
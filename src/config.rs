@@ -0,0 +1,217 @@
+//! Configuration shared by the `pbinfo` command and programs using the library, read in layers:
+//! the file (`~/.config/pbinfo.toml`), then the environment variables, then the flags of the
+//! command, each overriding the values set by the previous ones.
+//!
+//! ```toml
+//! username = "ana"
+//! # Name of the environment variable holding the password, which is never stored in the file
+//! password_env = "PBINFO_PASSWORD"
+//! cache_dir = "~/.cache/pbinfo"
+//! language = "cpp"
+//! templates = "~/.config/pbinfo/templates"
+//!
+//! [rate_limit]
+//! min_interval_ms = 500
+//! max_retries = 3
+//! ```
+
+use crate::judge::Language;
+use crate::*;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Settings of the client and of the command. Unset values keep their defaults.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Config {
+    /// Username to log in with
+    pub username: Option<String>,
+    /// Name of the environment variable holding the password (see `password`)
+    pub password_env: Option<String>,
+    /// Directory where the responses from PbInfo are cached (see `FileCache`)
+    pub cache_dir: Option<PathBuf>,
+    /// Language of new solutions
+    pub language: Option<Language>,
+    /// Directory with the solution templates (see `Templates::load_dir`)
+    pub templates: Option<PathBuf>,
+    /// Address of PbInfo (see `PbInfoClientBuilder::base_url`)
+    pub base_url: Option<String>,
+    /// Shortest time between two requests (see `PbInfoClientBuilder::min_request_interval`)
+    pub min_request_interval: Option<Duration>,
+    /// Retries of throttled requests (see `PbInfoClientBuilder::max_retries`)
+    pub max_retries: Option<u32>,
+}
+
+/// Returns the error for an invalid setting.
+fn invalid(setting: &str, message: &str) -> PbInfoError {
+    PbInfoError::Error(format!("Invalid configuration: `{}` {}", setting, message))
+}
+
+/// Replaces a leading `~` in `path` with the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+impl Config {
+    /// Parses the configuration file.
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let table = text
+            .parse::<toml::Table>()
+            .map_err(|err| PbInfoError::Error(format!("Invalid configuration: {}", err)))?;
+        let string = |table: &toml::Table, name: &str| match table.get(name) {
+            None => Ok(None),
+            Some(toml::Value::String(value)) => Ok(Some(value.clone())),
+            Some(_) => Err(invalid(name, "has to be a string")),
+        };
+        let integer = |table: &toml::Table, name: &str| match table.get(name) {
+            None => Ok(None),
+            Some(toml::Value::Integer(value)) if *value >= 0 => Ok(Some(*value as u64)),
+            Some(_) => Err(invalid(name, "has to be a positive integer")),
+        };
+        let empty = toml::Table::new();
+        let rate_limit = match table.get("rate_limit") {
+            None => &empty,
+            Some(toml::Value::Table(rate_limit)) => rate_limit,
+            Some(_) => return Err(invalid("rate_limit", "has to be a table")),
+        };
+
+        Ok(Config {
+            username: string(&table, "username")?,
+            password_env: string(&table, "password_env")?,
+            cache_dir: string(&table, "cache_dir")?.map(|dir| expand_home(&dir)),
+            language: string(&table, "language")?
+                .map(|language| language.parse())
+                .transpose()?,
+            templates: string(&table, "templates")?.map(|dir| expand_home(&dir)),
+            base_url: string(&table, "base_url")?,
+            min_request_interval: integer(rate_limit, "min_interval_ms")?
+                .map(Duration::from_millis),
+            max_retries: integer(rate_limit, "max_retries")?.map(|retries| retries as u32),
+        })
+    }
+
+    /// Reads the configuration file at `path`.
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            PbInfoError::IOError(format!("Could not read `{}`: {}", path.display(), err))
+        })?;
+        Config::from_toml(&text)
+    }
+
+    /// Returns the path of the configuration file: `$PBINFO_CONFIG`, or `pbinfo.toml` in
+    /// `$XDG_CONFIG_HOME` (`~/.config` by default).
+    pub fn default_path() -> Option<PathBuf> {
+        let var = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+        if let Some(path) = var("PBINFO_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+        let dir = var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(dir.join("pbinfo.toml"))
+    }
+
+    /// Reads the settings from the environment variables `PBINFO_USERNAME`,
+    /// `PBINFO_PASSWORD_ENV`, `PBINFO_CACHE_DIR`, `PBINFO_LANGUAGE`, `PBINFO_TEMPLATES`,
+    /// `PBINFO_BASE_URL`, `PBINFO_MIN_INTERVAL_MS` and `PBINFO_MAX_RETRIES`.
+    pub fn from_env() -> Result<Self> {
+        Config::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Reads the settings from the variables returned by `var`.
+    pub(crate) fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+        let number = |name: &str| {
+            var(name)
+                .map(|value| {
+                    value
+                        .trim()
+                        .parse::<u64>()
+                        .map_err(|_| invalid(name, "has to be a positive integer"))
+                })
+                .transpose()
+        };
+        Ok(Config {
+            username: var("PBINFO_USERNAME"),
+            password_env: var("PBINFO_PASSWORD_ENV"),
+            cache_dir: var("PBINFO_CACHE_DIR").map(|dir| expand_home(&dir)),
+            language: var("PBINFO_LANGUAGE")
+                .map(|language| language.parse())
+                .transpose()?,
+            templates: var("PBINFO_TEMPLATES").map(|dir| expand_home(&dir)),
+            base_url: var("PBINFO_BASE_URL"),
+            min_request_interval: number("PBINFO_MIN_INTERVAL_MS")?.map(Duration::from_millis),
+            max_retries: number("PBINFO_MAX_RETRIES")?.map(|retries| retries as u32),
+        })
+    }
+
+    /// Returns the configuration with the values set in `layer` replacing its own.
+    pub fn merge(self, layer: Config) -> Config {
+        Config {
+            username: layer.username.or(self.username),
+            password_env: layer.password_env.or(self.password_env),
+            cache_dir: layer.cache_dir.or(self.cache_dir),
+            language: layer.language.or(self.language),
+            templates: layer.templates.or(self.templates),
+            base_url: layer.base_url.or(self.base_url),
+            min_request_interval: layer.min_request_interval.or(self.min_request_interval),
+            max_retries: layer.max_retries.or(self.max_retries),
+        }
+    }
+
+    /// Reads the configuration file (if there is one) and the environment variables, which
+    /// override it.
+    pub fn load() -> Result<Self> {
+        let file = match Config::default_path().filter(|path| path.exists()) {
+            Some(path) => Config::load_file(path)?,
+            None => Config::default(),
+        };
+        Ok(file.merge(Config::from_env()?))
+    }
+
+    /// Returns the password, read from the environment variable named by `password_env`.
+    pub fn password(&self) -> Option<String> {
+        std::env::var(self.password_env.as_ref()?).ok()
+    }
+}
+
+impl PbInfoClientBuilder {
+    /// Applies the address, rate limits and cache directory of `config`, keeping the current
+    /// settings for the values it does not set.
+    pub fn config(mut self, config: &Config) -> Self {
+        if let Some(base_url) = &config.base_url {
+            self = self.base_url(base_url);
+        }
+        if let Some(interval) = config.min_request_interval {
+            self = self.min_request_interval(interval);
+        }
+        if let Some(retries) = config.max_retries {
+            self = self.max_retries(retries);
+        }
+        if let Some(dir) = &config.cache_dir {
+            self = self.cache_backend(FileCache::new(dir));
+        }
+        self
+    }
+}
+
+impl PbInfoClient {
+    /// Creates a client with the address, rate limits and cache directory of `config`. If the
+    /// configuration has a username and a password, the client also logs in.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let client = PbInfoClient::builder().config(config).build()?;
+        client.login_with_config(config)?;
+        Ok(client)
+    }
+
+    /// Logs in with the username and the password of `config`, if it has both.
+    pub fn login_with_config(&self, config: &Config) -> Result<()> {
+        match (&config.username, config.password()) {
+            (Some(username), Some(password)) => self.login(username, &password),
+            _ => Ok(()),
+        }
+    }
+}
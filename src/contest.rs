@@ -0,0 +1,438 @@
+//! Mock contests with PbInfo problems: the solution skeletons of every problem are written to a
+//! directory, a timer starts, and the solutions are judged locally, either as they are submitted
+//! (ICPC) or once the time is up (OJI). The contest ends with a scoreboard.
+//!
+//! ```no_run
+//! use pbinfo::{contest, ProblemId};
+//! use std::time::Duration;
+//!
+//! let mut contest =
+//!     contest::simulate(&[ProblemId(1691), ProblemId(489)], Duration::from_secs(3 * 3600))?;
+//! // ... solve the problems in `contest/` ...
+//! println!("{}", contest.finish());
+//! # Ok::<(), pbinfo::PbInfoError>(())
+//! ```
+
+use crate::judge::{self, Language, Verdict};
+use crate::template::{scaffold, Templates};
+use crate::*;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Penalty of a rejected submission to a problem that is solved later (ICPC).
+const ICPC_REJECTION_PENALTY: Duration = Duration::from_secs(20 * 60);
+
+/// How a contest is scored.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ScoringStyle {
+    /// Every problem is solved or not; ties are broken by the penalty time (the minutes until
+    /// each solve, plus 20 for every rejected submission before it). Submissions are judged
+    /// right away.
+    Icpc,
+    /// Every problem is worth 100 points, split evenly between its tests. Only the last version
+    /// of every solution counts, and it is judged once the time is up.
+    Oji,
+}
+
+/// How a contest is set up.
+#[derive(Debug, Clone)]
+pub struct ContestOptions {
+    /// Directory the solutions are written to
+    pub dir: PathBuf,
+    /// Language of the solutions
+    pub language: Language,
+    /// Templates of the solution skeletons
+    pub templates: Templates,
+    /// How the contest is scored
+    pub style: ScoringStyle,
+}
+
+impl Default for ContestOptions {
+    fn default() -> Self {
+        ContestOptions {
+            dir: PathBuf::from("contest"),
+            language: Language::Cpp,
+            templates: Templates::new(),
+            style: ScoringStyle::Oji,
+        }
+    }
+}
+
+/// A problem of a contest.
+#[derive(Debug, Clone)]
+pub struct ContestProblem {
+    /// The problem
+    pub problem: PbInfoProblem,
+    /// Path of the solution
+    pub solution: PathBuf,
+    /// Tests the solution is judged on (the examples, unless replaced with e.g. the official
+    /// tests)
+    pub tests: Vec<Example>,
+}
+
+/// A solution judged during a contest.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ContestSubmission {
+    /// The problem
+    pub problem: ProblemId,
+    /// Time since the start of the contest
+    pub at: Duration,
+    /// The verdicts of the tests (empty if the solution does not compile)
+    pub verdicts: Vec<Verdict>,
+    /// Points out of 100: the share of the tests that passed
+    pub score: usize,
+    /// The compiler errors, if the solution does not compile
+    pub compilation_error: Option<String>,
+}
+
+impl ContestSubmission {
+    /// Returns `true` if every test passed.
+    pub fn is_accepted(&self) -> bool {
+        self.compilation_error.is_none()
+            && !self.verdicts.is_empty()
+            && self
+                .verdicts
+                .iter()
+                .all(|verdict| *verdict == Verdict::Accepted)
+    }
+}
+
+/// A contest in progress.
+#[derive(Debug)]
+pub struct Contest {
+    /// The problems, in order
+    pub problems: Vec<ContestProblem>,
+    /// How long the contest lasts
+    pub duration: Duration,
+    /// How the contest is scored
+    pub style: ScoringStyle,
+    language: Language,
+    started: Instant,
+    submissions: Vec<ContestSubmission>,
+}
+
+impl Contest {
+    /// Writes the solution skeletons of `problems` to the directory of `options` (keeping the
+    /// solutions that already exist) and starts the timer.
+    pub fn start(
+        problems: Vec<PbInfoProblem>,
+        duration: Duration,
+        options: &ContestOptions,
+    ) -> Result<Self> {
+        let problems = problems
+            .into_iter()
+            .map(|problem| {
+                let solution =
+                    scaffold(&problem, options.language, &options.templates, &options.dir)?;
+                Ok(ContestProblem {
+                    tests: problem.examples(),
+                    problem,
+                    solution,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Contest {
+            problems,
+            duration,
+            style: options.style,
+            language: options.language,
+            started: Instant::now(),
+            submissions: Vec::new(),
+        })
+    }
+
+    /// Returns the time since the start of the contest.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Returns the time left (zero once the contest is over).
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed())
+    }
+
+    /// Returns `true` if the time is up.
+    pub fn is_over(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Returns the submissions so far, in order.
+    pub fn submissions(&self) -> &[ContestSubmission] {
+        &self.submissions
+    }
+
+    /// Judges the current solution of the problem `id` on its tests. Fails once the time is up.
+    pub fn submit(&mut self, id: ProblemId) -> Result<ContestSubmission> {
+        if self.is_over() {
+            return Err(PbInfoError::Error("The contest is over".to_owned()));
+        }
+        self.judge(id, self.elapsed())
+    }
+
+    /// Ends the contest and returns the scoreboard. With `ScoringStyle::Oji`, the last version of
+    /// every solution is judged now; a solution that can not be judged (e.g. because it was
+    /// deleted) gets 0 points, with the error on its row.
+    pub fn finish(mut self) -> Scoreboard {
+        let mut failures = Vec::new();
+        if self.style == ScoringStyle::Oji {
+            let at = self.elapsed().min(self.duration);
+            let ids = self
+                .problems
+                .iter()
+                .map(|problem| problem.problem.id)
+                .collect::<Vec<_>>();
+            for id in ids {
+                if let Err(err) = self.judge(id, at) {
+                    failures.push((id, err));
+                }
+            }
+        }
+        let mut scoreboard = self.scoreboard();
+        for (id, err) in failures {
+            if let Some(row) = scoreboard.rows.iter_mut().find(|row| row.id == id) {
+                row.score = 0;
+                row.error = Some(format!("{:?}", err));
+            }
+        }
+        scoreboard
+    }
+
+    /// Returns the scoreboard of the submissions so far.
+    pub fn scoreboard(&self) -> Scoreboard {
+        let problems = self
+            .problems
+            .iter()
+            .map(|problem| (problem.problem.id, problem.problem.name.to_string()))
+            .collect::<Vec<_>>();
+        Scoreboard::new(self.style, &problems, &self.submissions)
+    }
+
+    /// Judges the solution of the problem `id` as submitted at `at`.
+    fn judge(&mut self, id: ProblemId, at: Duration) -> Result<ContestSubmission> {
+        let problem = self
+            .problems
+            .iter()
+            .find(|problem| problem.problem.id == id)
+            .ok_or(PbInfoError::UnknownId(id))?;
+        let source = std::fs::read_to_string(&problem.solution).map_err(|err| {
+            PbInfoError::IOError(format!(
+                "Could not read `{}`: {}",
+                problem.solution.display(),
+                err
+            ))
+        })?;
+
+        let mut submission = ContestSubmission {
+            problem: id,
+            at,
+            verdicts: Vec::new(),
+            score: 0,
+            compilation_error: None,
+        };
+        match judge::compile(&source, self.language) {
+            Ok(program) => {
+                submission.verdicts = judge::run_tests(&program, &problem.problem, &problem.tests)?
+                    .into_iter()
+                    .map(|result| result.verdict)
+                    .collect();
+                let passed = submission
+                    .verdicts
+                    .iter()
+                    .filter(|verdict| **verdict == Verdict::Accepted)
+                    .count();
+                submission.score = (passed * 100)
+                    .checked_div(submission.verdicts.len())
+                    .unwrap_or_default();
+            }
+            Err(PbInfoError::CompilationError(diagnostics)) => {
+                submission.compilation_error = Some(diagnostics)
+            }
+            Err(err) => return Err(err),
+        }
+        self.submissions.push(submission.clone());
+        Ok(submission)
+    }
+}
+
+/// Simulates a contest with the problems `problem_ids`, lasting `duration`, using the shared
+/// client and the default options (C++ solutions in `contest/`, scored like OJI).
+pub fn simulate(problem_ids: &[ProblemId], duration: Duration) -> Result<Contest> {
+    PbInfoClient::shared().simulate_contest(problem_ids, duration, &ContestOptions::default())
+}
+
+impl PbInfoClient {
+    /// Fetches the problems `problem_ids` and starts a contest with them (see `Contest::start`).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, options), err(Debug))
+    )]
+    pub fn simulate_contest(
+        &self,
+        problem_ids: &[ProblemId],
+        duration: Duration,
+        options: &ContestOptions,
+    ) -> Result<Contest> {
+        let problems = problem_ids
+            .iter()
+            .map(|&id| self.fetch_problem_by_id(id))
+            .collect::<Result<Vec<_>>>()?;
+        Contest::start(problems, duration, options)
+    }
+}
+
+/// How a problem went in a contest.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ScoreboardRow {
+    /// The problem
+    pub id: ProblemId,
+    /// Name of the problem
+    pub name: String,
+    /// Points out of 100 (100 or 0 with `ScoringStyle::Icpc`)
+    pub score: usize,
+    /// Submissions that count: all of them with `ScoringStyle::Oji`, those until the first
+    /// accepted one (without the ones that do not compile) with `ScoringStyle::Icpc`
+    pub attempts: usize,
+    /// When the problem was first solved
+    pub solved_at: Option<Duration>,
+    /// Why the last solution could not be judged at the end of the contest
+    pub error: Option<String>,
+}
+
+/// The results of a contest.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Scoreboard {
+    /// How the contest was scored
+    pub style: ScoringStyle,
+    /// The problems, in order
+    pub rows: Vec<ScoreboardRow>,
+}
+
+impl Scoreboard {
+    /// Builds the scoreboard of `problems` (ids and names) from `submissions`, in order.
+    pub fn new(
+        style: ScoringStyle,
+        problems: &[(ProblemId, String)],
+        submissions: &[ContestSubmission],
+    ) -> Self {
+        let rows = problems
+            .iter()
+            .map(|(id, name)| {
+                let submissions = submissions
+                    .iter()
+                    .filter(|submission| submission.problem == *id)
+                    .collect::<Vec<_>>();
+                let solved_at = submissions
+                    .iter()
+                    .find(|submission| submission.is_accepted())
+                    .map(|submission| submission.at);
+                let (score, attempts) = match style {
+                    ScoringStyle::Oji => (
+                        submissions.last().map_or(0, |submission| submission.score),
+                        submissions.len(),
+                    ),
+                    ScoringStyle::Icpc => (
+                        if solved_at.is_some() { 100 } else { 0 },
+                        submissions
+                            .iter()
+                            .filter(|submission| submission.compilation_error.is_none())
+                            .filter(|submission| solved_at.is_none_or(|at| submission.at <= at))
+                            .count(),
+                    ),
+                };
+                ScoreboardRow {
+                    id: *id,
+                    name: name.clone(),
+                    score,
+                    attempts,
+                    solved_at,
+                    error: None,
+                }
+            })
+            .collect();
+        Scoreboard { style, rows }
+    }
+
+    /// Returns the total score.
+    pub fn total_score(&self) -> usize {
+        self.rows.iter().map(|row| row.score).sum()
+    }
+
+    /// Returns the number of problems solved with 100 points.
+    pub fn solved(&self) -> usize {
+        self.rows.iter().filter(|row| row.score == 100).count()
+    }
+
+    /// Returns the ICPC penalty time: the time until every solve, plus 20 minutes for every
+    /// rejected attempt before it.
+    pub fn penalty(&self) -> Duration {
+        self.rows
+            .iter()
+            .filter_map(|row| {
+                let rejected = row.attempts.saturating_sub(1) as u32;
+                row.solved_at
+                    .map(|at| at + ICPC_REJECTION_PENALTY * rejected)
+            })
+            .sum()
+    }
+}
+
+/// Formats a duration as `h:mm:ss`.
+fn format_clock(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+impl fmt::Display for Scoreboard {
+    /// Formats the scoreboard as a table, with the total and then the errors of the solutions
+    /// that could not be judged.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name_width = self
+            .rows
+            .iter()
+            .map(|row| row.name.chars().count() + row.id.to_string().len() + 2)
+            .max()
+            .unwrap_or_default()
+            .max("Problem".len());
+        writeln!(
+            f,
+            "{:name_width$}  {:>5}  {:>8}  {:>8}",
+            "Problem", "Score", "Attempts", "Solved"
+        )?;
+        for row in &self.rows {
+            writeln!(
+                f,
+                "{:name_width$}  {:>5}  {:>8}  {:>8}",
+                format!("#{} {}", row.id, row.name),
+                row.score,
+                row.attempts,
+                row.solved_at
+                    .map(format_clock)
+                    .unwrap_or_else(|| "-".to_owned())
+            )?;
+        }
+        match self.style {
+            ScoringStyle::Oji => writeln!(f, "{:name_width$}  {:>5}", "Total", self.total_score())?,
+            ScoringStyle::Icpc => writeln!(
+                f,
+                "{:name_width$}  {:>5}  {:>8}  {:>8}",
+                "Total",
+                self.solved(),
+                "",
+                format_clock(self.penalty())
+            )?,
+        }
+        for row in &self.rows {
+            if let Some(error) = &row.error {
+                writeln!(f, "#{} {}: {}", row.id, row.name, error)?;
+            }
+        }
+        Ok(())
+    }
+}
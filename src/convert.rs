@@ -0,0 +1,116 @@
+use crate::*;
+use std::str::FromStr;
+
+/// Returns the error for a string that could not be converted into `what`.
+fn invalid(what: &str, string: &str) -> PbInfoError {
+    PbInfoError::Error(format!("`{}` is not a valid {}", string, what))
+}
+
+impl FromStr for IOSource {
+    type Err = PbInfoError;
+
+    /// Parses one side of PbInfo's "input / output" column: `tastatură` and `ecran` (or
+    /// `stdin`/`stdout`) are the standard streams, anything else is a file name.
+    fn from_str(string: &str) -> Result<Self> {
+        let string = string.trim();
+        match normalize_text(string).as_str() {
+            "" => Err(invalid("input/output source", string)),
+            "tastatura" | "ecran" | "stdin" | "stdout" | "std" => Ok(IOSource::Std),
+            _ if string.contains(char::is_whitespace) => {
+                Err(invalid("input/output source", string))
+            }
+            _ => Ok(IOSource::File(string.to_owned())),
+        }
+    }
+}
+
+impl IOSource {
+    /// Parses PbInfo's "input / output" column (e.g. `tastatură / ecran` or
+    /// `numere8.in / numere8.out`) into the input and the output source.
+    pub fn parse_pair(string: &str) -> Result<(IOSource, IOSource)> {
+        match string.split_once('/') {
+            Some((input, output)) => Ok((input.parse()?, output.parse()?)),
+            None => Err(invalid("input/output pair", string)),
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = PbInfoError;
+
+    /// Accepts the Romanian labels shown by PbInfo (`ușor`, `mediu`, `dificil`, `concurs`, with
+    /// or without diacritics) and their English names.
+    fn from_str(string: &str) -> Result<Self> {
+        match normalize_text(string).as_str() {
+            "usor" | "usoara" | "easy" => Ok(Difficulty::Easy),
+            "mediu" | "medie" | "medium" => Ok(Difficulty::Medium),
+            "dificil" | "dificila" | "difficult" | "hard" => Ok(Difficulty::Difficult),
+            "concurs" | "contest" => Ok(Difficulty::Contest),
+            _ => Err(invalid("difficulty", string)),
+        }
+    }
+}
+
+/// Parses a roman numeral between I and XII.
+fn parse_roman(string: &str) -> Option<usize> {
+    const NUMERALS: [&str; 12] = [
+        "i", "ii", "iii", "iv", "v", "vi", "vii", "viii", "ix", "x", "xi", "xii",
+    ];
+    NUMERALS
+        .iter()
+        .position(|numeral| *numeral == string)
+        .map(|i| i + 1)
+}
+
+impl FromStr for Grade {
+    type Err = PbInfoError;
+
+    /// Accepts a number (`9`), a roman numeral (`IX`) or the way PbInfo names the grades
+    /// (`a IX-a`, `Clasa a IX-a`).
+    fn from_str(string: &str) -> Result<Self> {
+        let normalized = normalize_text(string);
+        let grade = normalized
+            .trim_start_matches("clasa")
+            .trim()
+            .trim_start_matches("a ")
+            .trim_end_matches("-a")
+            .trim();
+        grade
+            .parse::<usize>()
+            .ok()
+            .or_else(|| parse_roman(grade))
+            .filter(|grade| (1..=12).contains(grade))
+            .map(Grade)
+            .ok_or_else(|| invalid("grade", string))
+    }
+}
+
+impl TryFrom<&str> for ProblemId {
+    type Error = PbInfoError;
+
+    /// Accepts an id (`1691`), an id prefixed by `#` (`#1691`) or the url of a problem.
+    fn try_from(string: &str) -> Result<Self> {
+        let trimmed = string.trim();
+        match trimmed.trim_start_matches('#').parse::<usize>() {
+            Ok(id) => Ok(ProblemId(id)),
+            Err(_) if trimmed.contains("probleme/") => extract_id_from_url(trimmed).map(ProblemId),
+            Err(_) => Err(invalid("problem id", string)),
+        }
+    }
+}
+
+impl FromStr for ProblemId {
+    type Err = PbInfoError;
+
+    fn from_str(string: &str) -> Result<Self> {
+        ProblemId::try_from(string)
+    }
+}
+
+impl FromStr for ProblemName {
+    type Err = PbInfoError;
+
+    fn from_str(string: &str) -> Result<Self> {
+        ProblemName::new(string)
+    }
+}
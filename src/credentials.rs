@@ -0,0 +1,146 @@
+//! Credentials and login sessions kept in the keyring of the operating system (the macOS
+//! Keychain, the Windows Credential Manager or the Linux kernel keyring) instead of in plain
+//! files, so that a shared computer does not leak the password of a student.
+
+use crate::*;
+use std::fmt;
+
+/// Name of the keyring service the credentials are stored under.
+const KEYRING_SERVICE: &str = "pbinfo";
+/// Name of the keyring entry holding the credentials.
+const KEYRING_USER: &str = "credentials";
+
+/// Returns the error for a failure of the keyring.
+fn keyring_error(err: keyring::Error) -> PbInfoError {
+    PbInfoError::IOError(format!("Could not access the keyring: {}", err))
+}
+
+/// Returns the keyring entry of the credentials.
+fn entry() -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).map_err(keyring_error)
+}
+
+/// How a client logs in: the username with the password and/or the cookies of a previous login
+/// session. The password and the session are never shown by `Debug`.
+#[derive(PartialEq, Eq, Clone)]
+pub struct ClientAuth {
+    /// Username of the account
+    pub username: String,
+    password: Option<String>,
+    session: Option<String>,
+}
+
+impl fmt::Debug for ClientAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redacted = |secret: &Option<String>| secret.as_ref().map(|_| "***");
+        f.debug_struct("ClientAuth")
+            .field("username", &self.username)
+            .field("password", &redacted(&self.password))
+            .field("session", &redacted(&self.session))
+            .finish()
+    }
+}
+
+impl ClientAuth {
+    /// Creates the credentials of an account.
+    pub fn new(username: &str, password: &str) -> Self {
+        ClientAuth {
+            username: username.to_owned(),
+            password: Some(password.to_owned()),
+            session: None,
+        }
+    }
+
+    /// Returns a copy without the password, keeping only the login session. Use it with
+    /// `save_to_keyring` to keep the account logged in without storing its password.
+    pub fn without_password(&self) -> Self {
+        ClientAuth {
+            password: None,
+            ..self.clone()
+        }
+    }
+
+    /// Serializes the credentials for the keyring.
+    pub(crate) fn to_secret(&self) -> String {
+        serde_json::json!({
+            "username": self.username,
+            "password": self.password,
+            "session": self.session,
+        })
+        .to_string()
+    }
+
+    /// Reads the credentials serialized by `to_secret`.
+    pub(crate) fn from_secret(secret: &str) -> Result<Self> {
+        let invalid = || PbInfoError::Error("Invalid credentials in the keyring".to_owned());
+        let value = serde_json::from_str::<serde_json::Value>(secret).map_err(|_| invalid())?;
+        let optional = |name: &str| value[name].as_str().map(str::to_owned);
+        Ok(ClientAuth {
+            username: optional("username").ok_or_else(invalid)?,
+            password: optional("password"),
+            session: optional("session"),
+        })
+    }
+
+    /// Reads the credentials saved by `save_to_keyring`. Fails with `PbInfoError::LoginError`
+    /// if there are none.
+    pub fn from_keyring() -> Result<Self> {
+        match entry()?.get_password() {
+            Ok(secret) => ClientAuth::from_secret(&secret),
+            Err(keyring::Error::NoEntry) => Err(PbInfoError::LoginError(
+                "No credentials are stored in the keyring".to_owned(),
+            )),
+            Err(err) => Err(keyring_error(err)),
+        }
+    }
+
+    /// Stores the credentials in the keyring, replacing the previous ones.
+    pub fn save_to_keyring(&self) -> Result<()> {
+        entry()?
+            .set_password(&self.to_secret())
+            .map_err(keyring_error)
+    }
+
+    /// Removes the credentials from the keyring, if there are any.
+    pub fn delete_from_keyring() -> Result<()> {
+        match entry()?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(err) => Err(keyring_error(err)),
+        }
+    }
+
+    /// Logs `client` in: the saved session is used if it is still valid, otherwise the client
+    /// logs in with the password. The new session is kept in the credentials, so that saving
+    /// them again avoids logging in next time.
+    pub fn login(&mut self, client: &PbInfoClient) -> Result<()> {
+        if let Some(session) = &self.session {
+            client.session_jar().load_text(session)?;
+            if client.is_logged_in()? {
+                return Ok(());
+            }
+        }
+        let password = self.password.as_ref().ok_or_else(|| {
+            PbInfoError::LoginError(format!(
+                "The session of `{}` expired and no password is stored",
+                self.username
+            ))
+        })?;
+        client.login(&self.username, password)?;
+        self.session = Some(client.session_jar().to_text());
+        Ok(())
+    }
+}
+
+impl PbInfoClient {
+    /// Logs in with the credentials from the keyring (see `ClientAuth::from_keyring`), and
+    /// stores the session back if the client had to log in again.
+    pub fn login_from_keyring(&self) -> Result<()> {
+        let mut auth = ClientAuth::from_keyring()?;
+        let before = auth.clone();
+        auth.login(self)?;
+        if auth != before {
+            auth.save_to_keyring()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+//! A long-running process for editor plugins, answering JSON-RPC 2.0 requests over stdin and
+//! stdout, so that the problems fetched and the compiled toolchains stay warm between commands.
+//! Messages are framed like in the Language Server Protocol, with a `Content-Length` header.
+//!
+//! Methods:
+//!
+//! - `fetch` (`{"id": 1}` or `{"name": "suma"}`): the problem, in the public JSON schema
+//! - `search` (`{"query": "suma"}`): the problems found, as `[{"id": ..., "name": ...}]`
+//! - `examples` (`{"id": 1}`): the examples, as `[{"input": ..., "output": ...}]`
+//! - `judge` (`{"id": 1, "source": ..., "language": "cpp"}`): the issues found by
+//!   `validate_source` and the results of running the source on the examples
+//! - `shutdown`, followed by the `exit` notification, to stop the daemon
+
+use crate::judge::{self, Language, Verdict};
+use crate::*;
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+/// Invalid JSON was received.
+const PARSE_ERROR: i64 = -32700;
+/// The message is not a valid request.
+const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// The parameters of the method are missing or invalid.
+const INVALID_PARAMS: i64 = -32602;
+/// The problem does not exist.
+const UNKNOWN_PROBLEM: i64 = -32001;
+/// The source could not be compiled.
+const COMPILATION_ERROR: i64 = -32002;
+/// Any other error of the library.
+const PBINFO_ERROR: i64 = -32000;
+
+/// Largest message accepted, so that a bogus `Content-Length` can not exhaust the memory.
+const MAX_MESSAGE_LENGTH: usize = 16 * 1024 * 1024;
+
+/// A JSON-RPC error: its code, message and optional data.
+type RpcError = (i64, String, Option<Value>);
+
+/// Returns the JSON-RPC error for `err`.
+fn rpc_error(err: PbInfoError) -> RpcError {
+    match err {
+        PbInfoError::UnknownName(_, ref suggestions) => {
+            let suggestions = suggestions
+                .iter()
+                .map(|suggestion| {
+                    json!({
+                        "id": suggestion.id.0,
+                        "name": suggestion.name,
+                        "score": suggestion.score,
+                    })
+                })
+                .collect();
+            (UNKNOWN_PROBLEM, format!("{:?}", err), Some(suggestions))
+        }
+        PbInfoError::UnknownId(_) => (UNKNOWN_PROBLEM, format!("{:?}", err), None),
+        PbInfoError::CompilationError(diagnostics) => (
+            COMPILATION_ERROR,
+            "Compilation error".to_owned(),
+            Some(Value::String(diagnostics)),
+        ),
+        err => (PBINFO_ERROR, format!("{:?}", err), None),
+    }
+}
+
+/// Returns the error for a missing or invalid parameter.
+fn invalid_param(name: &str) -> RpcError {
+    (
+        INVALID_PARAMS,
+        format!("Missing or invalid parameter `{}`", name),
+        None,
+    )
+}
+
+/// Returns the name used for `verdict` in the responses.
+fn verdict_name(verdict: &Verdict) -> &'static str {
+    match verdict {
+        Verdict::Accepted => "accepted",
+        Verdict::WrongAnswer => "wrong_answer",
+        Verdict::RuntimeError(_) => "runtime_error",
+        Verdict::TimeLimitExceeded => "time_limit_exceeded",
+        Verdict::MemoryLimitExceeded => "memory_limit_exceeded",
+    }
+}
+
+/// The daemon, answering requests with `client`.
+#[derive(Debug)]
+pub struct Daemon {
+    client: PbInfoClient,
+    shutting_down: bool,
+}
+
+impl Daemon {
+    /// Creates the daemon. `client` should cache its responses, since the same problems are
+    /// requested again and again while they are being solved.
+    pub fn new(client: PbInfoClient) -> Self {
+        Daemon {
+            client,
+            shutting_down: false,
+        }
+    }
+
+    /// Returns the problem named by the `id` or the `name` parameter.
+    fn problem(&self, params: &Value) -> std::result::Result<PbInfoProblem, RpcError> {
+        let result = match (params["id"].as_u64(), params["name"].as_str()) {
+            (Some(id), _) => self.client.fetch_problem_by_id(ProblemId(id as usize)),
+            (None, Some(name)) => self.client.fetch_problem_by_name(name),
+            (None, None) => return Err(invalid_param("id")),
+        };
+        result.map_err(rpc_error)
+    }
+
+    /// Runs `method` with `params`.
+    fn call(&mut self, method: &str, params: &Value) -> std::result::Result<Value, RpcError> {
+        if self.shutting_down {
+            return Err((
+                INVALID_REQUEST,
+                "The daemon is shutting down".to_owned(),
+                None,
+            ));
+        }
+        match method {
+            "fetch" => Ok(self.problem(params)?.to_json_v1()),
+            "search" => {
+                let query = params["query"]
+                    .as_str()
+                    .ok_or_else(|| invalid_param("query"))?;
+                let results = self.client.search(query).map_err(rpc_error)?;
+                Ok(results
+                    .iter()
+                    .map(|result| json!({ "id": result.id.0, "name": result.name }))
+                    .collect())
+            }
+            "examples" => Ok(self
+                .problem(params)?
+                .examples()
+                .iter()
+                .map(|example| json!({ "input": example.input, "output": example.output }))
+                .collect()),
+            "judge" => {
+                let source = params["source"]
+                    .as_str()
+                    .ok_or_else(|| invalid_param("source"))?;
+                let language = params["language"]
+                    .as_str()
+                    .and_then(|language| language.parse::<Language>().ok())
+                    .ok_or_else(|| invalid_param("language"))?;
+                let problem = self.problem(params)?;
+                let issues = validate_source(&problem, source, language)
+                    .iter()
+                    .map(|issue| issue.to_string())
+                    .collect::<Vec<_>>();
+                let results =
+                    judge::check_examples(&problem, source, language).map_err(rpc_error)?;
+                let tests = results
+                    .iter()
+                    .map(|result| {
+                        json!({
+                            "verdict": verdict_name(&result.verdict),
+                            "time_ms": result.duration.as_millis() as u64,
+                            "memory": result.memory,
+                            "output": result.output,
+                            "stderr": result.stderr,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                Ok(json!({ "issues": issues, "tests": tests }))
+            }
+            "shutdown" => {
+                self.shutting_down = true;
+                Ok(Value::Null)
+            }
+            _ => Err((
+                METHOD_NOT_FOUND,
+                format!("Unknown method `{}`", method),
+                None,
+            )),
+        }
+    }
+
+    /// Answers one JSON-RPC message. Returns `None` for notifications (requests without an
+    /// `id`), which get no response.
+    pub fn handle(&mut self, message: &Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let result = match message["method"].as_str() {
+            Some(method) if message["jsonrpc"] == "2.0" => {
+                self.call(method, message.get("params").unwrap_or(&Value::Null))
+            }
+            _ => Err((INVALID_REQUEST, "Invalid request".to_owned(), None)),
+        };
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message, data)) => {
+                let mut error = json!({ "code": code, "message": message });
+                if let Some(data) = data {
+                    error["data"] = data;
+                }
+                json!({ "jsonrpc": "2.0", "id": id, "error": error })
+            }
+        })
+    }
+
+    /// Answers the messages read from `input`, writing the responses to `output`, until the
+    /// `exit` notification or the end of the input.
+    pub fn serve(&mut self, mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+        while let Some(body) = read_message(&mut input)? {
+            let response = match serde_json::from_str::<Value>(&body) {
+                Ok(message) if message["method"] == "exit" => break,
+                Ok(message) => self.handle(&message),
+                Err(err) => Some(json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": PARSE_ERROR, "message": err.to_string() },
+                })),
+            };
+            if let Some(response) = response {
+                write_message(&mut output, &response)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the error for a failure to read or write a message.
+fn io_error(err: std::io::Error) -> PbInfoError {
+    PbInfoError::IOError(format!(
+        "Could not exchange a message with the editor: {}",
+        err
+    ))
+}
+
+/// Reads the body of the next message, or `None` at the end of the input.
+pub(crate) fn read_message(input: &mut impl BufRead) -> Result<Option<String>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line).map_err(io_error)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            // Blank lines between messages are tolerated
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let length = length.unwrap_or_default();
+    if length > MAX_MESSAGE_LENGTH {
+        return Err(PbInfoError::Error(format!(
+            "The message of {} bytes is larger than the maximum of {} bytes",
+            length, MAX_MESSAGE_LENGTH
+        )));
+    }
+    let mut body = vec![0; length];
+    input.read_exact(&mut body).map_err(io_error)?;
+    Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+}
+
+/// Writes `message` with its `Content-Length` header.
+pub(crate) fn write_message(output: &mut impl Write, message: &Value) -> Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)
+        .and_then(|_| output.flush())
+        .map_err(io_error)
+}
+
+/// Runs the daemon on stdin and stdout, until the editor stops it.
+pub fn run() -> Result<()> {
+    let client = PbInfoClient::builder()
+        .cache_backend(MemoryCache::new(1000))
+        .build()?;
+    let stdin = std::io::stdin();
+    Daemon::new(client).serve(stdin.lock(), std::io::stdout().lock())
+}
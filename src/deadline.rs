@@ -0,0 +1,191 @@
+//! Composite operations with an overall deadline, so that an interactive UI stays responsive
+//! even when one of the requests they make stalls.
+
+use crate::*;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// What a composite operation does when some of its parts are not ready by the deadline (or
+/// fail).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PartialPolicy {
+    /// Return the parts that are ready, listing the others as missing
+    ReturnPartial,
+    /// Fail with the error of the first missing part
+    Fail,
+}
+
+/// The time by which a composite operation has to finish, and what it does if it cannot.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Deadline {
+    /// When the operation has to finish
+    pub at: Instant,
+    /// What the operation does with the parts that are not ready by then
+    pub policy: PartialPolicy,
+}
+
+impl Deadline {
+    /// Creates a deadline `timeout` from now.
+    pub fn after(timeout: Duration, policy: PartialPolicy) -> Self {
+        Deadline {
+            at: Instant::now() + timeout,
+            policy,
+        }
+    }
+
+    /// Returns the time left until the deadline (zero once it has passed).
+    pub fn remaining(&self) -> Duration {
+        self.at.saturating_duration_since(Instant::now())
+    }
+
+    /// Returns `true` if the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+}
+
+/// A part of a `ProblemOverview`, each fetched by its own request.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OverviewPart {
+    /// The statement (with the examples)
+    Problem,
+    /// The submission statistics
+    Stats,
+    /// The tests archive
+    Tests,
+}
+
+impl OverviewPart {
+    /// Returns the name of the part, as used in errors.
+    fn name(self) -> &'static str {
+        match self {
+            OverviewPart::Problem => "problem",
+            OverviewPart::Stats => "stats",
+            OverviewPart::Tests => "tests",
+        }
+    }
+}
+
+/// What `PbInfoClient::fetch_overview` could fetch before the deadline.
+#[derive(Debug, Clone, Default)]
+pub struct ProblemOverview {
+    /// The problem
+    pub problem: Option<PbInfoProblem>,
+    /// The examples of the statement (empty without the problem)
+    pub examples: Vec<Example>,
+    /// The submission statistics
+    pub stats: Option<ProblemStats>,
+    /// Size of the downloaded tests archive
+    pub tests: Option<u64>,
+    /// The parts that are missing, with the reason (`PbInfoError::DeadlineExceeded` for the
+    /// ones still being fetched at the deadline)
+    pub missing: Vec<(OverviewPart, PbInfoError)>,
+}
+
+impl ProblemOverview {
+    /// Returns `true` if no part is missing.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// The result of fetching one part of an overview.
+enum PartResult {
+    Problem(Box<Result<PbInfoProblem>>),
+    Stats(Result<ProblemStats>),
+    Tests(Result<u64>),
+}
+
+impl PbInfoClient {
+    /// Fetches the problem `id` with its examples and statistics, and downloads its tests to
+    /// `tests` if a path is given, all at the same time. The parts that are not ready by
+    /// `deadline` are handled according to its policy: they are either listed in `missing` or
+    /// make the whole operation fail with `PbInfoError::DeadlineExceeded`. The requests of the
+    /// missing parts are not cancelled, but their results are discarded.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_overview(
+        &self,
+        id: ProblemId,
+        tests: Option<&Path>,
+        deadline: Deadline,
+    ) -> Result<ProblemOverview> {
+        let (sender, receiver) = mpsc::channel();
+        let mut pending = vec![OverviewPart::Problem, OverviewPart::Stats];
+        let spawn = |fetch: Box<dyn FnOnce(PbInfoClient) -> PartResult + Send>| {
+            let (client, sender) = (self.clone(), sender.clone());
+            std::thread::spawn(move || {
+                // The receiver is gone if the deadline passed
+                let _ = sender.send(fetch(client));
+            });
+        };
+        spawn(Box::new(move |client| {
+            PartResult::Problem(Box::new(client.fetch_problem_by_id(id)))
+        }));
+        spawn(Box::new(move |client| {
+            PartResult::Stats(client.fetch_problem_stats(id))
+        }));
+        if let Some(path) = tests {
+            let path = PathBuf::from(path);
+            pending.push(OverviewPart::Tests);
+            spawn(Box::new(move |client| {
+                PartResult::Tests(client.download_tests(id, path, |_| {}))
+            }));
+        }
+        drop(sender);
+
+        let mut overview = ProblemOverview::default();
+        let mut missing_parts = Vec::new();
+        let mut missing = |part: OverviewPart, err: PbInfoError| match deadline.policy {
+            PartialPolicy::ReturnPartial => {
+                missing_parts.push((part, err));
+                Ok(())
+            }
+            PartialPolicy::Fail => Err(err),
+        };
+        while !pending.is_empty() {
+            let result = match receiver.recv_timeout(deadline.remaining()) {
+                Ok(result) => result,
+                Err(_) => {
+                    let names = pending.iter().map(|part| part.name()).collect::<Vec<_>>();
+                    let err = PbInfoError::DeadlineExceeded(names.join(", "));
+                    for part in pending.drain(..) {
+                        missing(part, err.clone())?;
+                    }
+                    break;
+                }
+            };
+            let part = match result {
+                PartResult::Problem(result) => {
+                    match *result {
+                        Ok(problem) => {
+                            overview.examples = problem.examples();
+                            overview.problem = Some(problem);
+                        }
+                        Err(err) => missing(OverviewPart::Problem, err)?,
+                    }
+                    OverviewPart::Problem
+                }
+                PartResult::Stats(Ok(stats)) => {
+                    overview.stats = Some(stats);
+                    OverviewPart::Stats
+                }
+                PartResult::Tests(Ok(size)) => {
+                    overview.tests = Some(size);
+                    OverviewPart::Tests
+                }
+                PartResult::Stats(Err(err)) => {
+                    missing(OverviewPart::Stats, err)?;
+                    OverviewPart::Stats
+                }
+                PartResult::Tests(Err(err)) => {
+                    missing(OverviewPart::Tests, err)?;
+                    OverviewPart::Tests
+                }
+            };
+            pending.retain(|pending| *pending != part);
+        }
+        overview.missing = missing_parts;
+        Ok(overview)
+    }
+}
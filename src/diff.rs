@@ -0,0 +1,104 @@
+use crate::*;
+
+/// A part of a PbInfoProblem statement that can be compared between two versions.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StatementSection {
+    /// Task (Cerința)
+    Task,
+    /// Input description (Date de intrare)
+    Input,
+    /// Output description (Date de ieșire)
+    Output,
+    /// Restrictions (Restricții și precizări)
+    Restrictions,
+    /// Examples and their explanations (Exemplu, Explicație)
+    Examples,
+    /// Time limit
+    TimeLimit,
+    /// Memory limit
+    MemoryLimit,
+}
+
+/// A single changed section, storing the old and the new html (`None` if the section is missing).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SectionChange {
+    /// The section that changed
+    pub section: StatementSection,
+    /// Html of the section in the old statement
+    pub old: Option<String>,
+    /// Html of the section in the new statement
+    pub new: Option<String>,
+}
+
+/// The sections that differ between two versions of a statement.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct StatementDiff {
+    /// Changed sections, in statement order
+    pub changes: Vec<SectionChange>,
+}
+
+impl StatementDiff {
+    /// Returns `true` if the two statements are identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns `true` if `section` changed.
+    pub fn changed(&self, section: StatementSection) -> bool {
+        self.changes.iter().any(|change| change.section == section)
+    }
+}
+
+/// Returns the html of the section of `problem` that corresponds to `section`.
+fn section_html(problem: &PbInfoProblem, section: StatementSection) -> Option<String> {
+    let prefixes: &[&str] = match section {
+        StatementSection::Task => &["cerin"],
+        StatementSection::Input => &["date de intrare"],
+        StatementSection::Output => &["date de ieșire", "date de iesire"],
+        StatementSection::Restrictions => &["restricții", "restrictii"],
+        StatementSection::Examples => &["exempl", "explica"],
+        StatementSection::TimeLimit => return problem.time_limit.clone(),
+        StatementSection::MemoryLimit => return problem.memory_limit.clone(),
+    };
+
+    let html = extract_sections(&problem.problem_text)
+        .into_iter()
+        .filter(|(title, _)| {
+            let title = title.to_lowercase();
+            prefixes.iter().any(|prefix| title.starts_with(prefix))
+        })
+        .map(|(_, html)| html)
+        .collect::<Vec<_>>();
+
+    match html.is_empty() {
+        true => None,
+        false => Some(html.join("\n")),
+    }
+}
+
+/// Compares two versions of a statement, section by section.
+pub fn diff_statements(old: &PbInfoProblem, new: &PbInfoProblem) -> StatementDiff {
+    let sections = [
+        StatementSection::Task,
+        StatementSection::Input,
+        StatementSection::Output,
+        StatementSection::Restrictions,
+        StatementSection::Examples,
+        StatementSection::TimeLimit,
+        StatementSection::MemoryLimit,
+    ];
+
+    let mut diff = StatementDiff::default();
+    for section in sections {
+        let old_html = section_html(old, section);
+        let new_html = section_html(new, section);
+        if old_html != new_html {
+            diff.changes.push(SectionChange {
+                section,
+                old: old_html,
+                new: new_html,
+            });
+        }
+    }
+    diff
+}
@@ -0,0 +1,85 @@
+use crate::*;
+
+/// Number of consecutive missing ids after which no more problems are expected. Deleted
+/// problems leave gaps in the id space, but never this long.
+pub(crate) const MAX_ID_GAP: usize = 32;
+
+/// Returns the largest id for which `exists` is true, assuming that no more than
+/// `max_gap - 1` consecutive ids are missing below it. Makes a number of probes that is
+/// logarithmic in the largest id.
+pub(crate) fn find_last_id(
+    mut exists: impl FnMut(usize) -> Result<bool>,
+    max_gap: usize,
+) -> Result<usize> {
+    // Returns the first existing id of the window of `max_gap` ids starting at `start`
+    let mut first_in_window = |start: usize| -> Result<Option<usize>> {
+        for id in start..start + max_gap {
+            if exists(id)? {
+                return Ok(Some(id));
+            }
+        }
+        Ok(None)
+    };
+
+    // Gallop until a window without problems is found
+    let mut last = 0;
+    let mut step = 1;
+    let mut missing = loop {
+        match first_in_window(last + step)? {
+            Some(id) => {
+                last = id;
+                step *= 2;
+            }
+            None => break last + step,
+        }
+    };
+
+    // Every id in the window starting at `missing` is missing, so the answer is in `last..missing`
+    while missing - last > 1 {
+        let middle = last + (missing - last) / 2;
+        match first_in_window(middle)? {
+            Some(id) if id < missing => last = id,
+            _ => missing = middle,
+        }
+    }
+    Ok(last)
+}
+
+/// Returns the ids of every problem on PbInfo, in increasing order.
+pub fn discover_problem_ids() -> Result<Vec<ProblemId>> {
+    PbInfoClient::shared().discover_problem_ids()
+}
+
+impl PbInfoClient {
+    /// Returns the ids of every problem on PbInfo, in increasing order. The largest id is
+    /// found with a few probes, then every id below it is checked with a HEAD request.
+    /// Throttling is handled by backing off, so this can take a while.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn discover_problem_ids(&self) -> Result<Vec<ProblemId>> {
+        let last = find_last_id(|id| self.problem_exists(ProblemId(id)), MAX_ID_GAP)?;
+
+        let mut ids = Vec::new();
+        for id in (1..=last).map(ProblemId) {
+            if self.problem_exists(id)? {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Checks whether the problem with the given id exists. Only a HEAD request is made, so
+    /// this is much cheaper than fetching the problem.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn problem_exists(&self, id: ProblemId) -> Result<bool> {
+        let page = self.send(self.http().head(self.url(&format!("/probleme/{}", id))))?;
+
+        match page.status() {
+            reqwest::StatusCode::OK => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to check the problem. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+}
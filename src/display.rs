@@ -0,0 +1,173 @@
+use crate::judge::Limits;
+use crate::*;
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Language of the labels generated by the `Display` implementations.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum Locale {
+    /// English labels (e.g. "grade 9, medium")
+    #[default]
+    English,
+    /// Romanian labels, as used by PbInfo (e.g. "clasa a 9-a, mediu")
+    Romanian,
+}
+
+/// The locale used by `Display`, stored as `Locale as u8`.
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::English as u8);
+
+/// Sets the locale used by the `Display` implementations of the crate's types. English by
+/// default; use `Localize::localized` to format a single value in another locale.
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+/// Returns the locale used by the `Display` implementations.
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        value if value == Locale::Romanian as u8 => Locale::Romanian,
+        _ => Locale::English,
+    }
+}
+
+/// Types whose `Display` output depends on the locale.
+pub trait Localize {
+    /// Formats `self` with the labels of `locale`.
+    fn fmt_localized(&self, f: &mut fmt::Formatter<'_>, locale: Locale) -> fmt::Result;
+
+    /// Returns a value that displays `self` in `locale`, regardless of the global locale.
+    fn localized(&self, locale: Locale) -> Localized<'_, Self> {
+        Localized {
+            value: self,
+            locale,
+        }
+    }
+}
+
+/// A value displayed in a fixed locale, created by `Localize::localized`.
+#[derive(Debug, Clone, Copy)]
+pub struct Localized<'a, T: ?Sized> {
+    value: &'a T,
+    locale: Locale,
+}
+
+impl<T: Localize + ?Sized> fmt::Display for Localized<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt_localized(f, self.locale)
+    }
+}
+
+/// Picks the English or the Romanian label.
+pub(crate) fn label(locale: Locale, english: &'static str, romanian: &'static str) -> &'static str {
+    match locale {
+        Locale::English => english,
+        Locale::Romanian => romanian,
+    }
+}
+
+/// Formats a size in bytes using the largest unit that divides it.
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        bytes if bytes >= 1 << 30 && bytes % (1 << 30) == 0 => format!("{} GB", bytes >> 30),
+        bytes if bytes >= 1 << 20 && bytes % (1 << 20) == 0 => format!("{} MB", bytes >> 20),
+        bytes if bytes >= 1 << 10 && bytes % (1 << 10) == 0 => format!("{} KB", bytes >> 10),
+        bytes => format!("{} B", bytes),
+    }
+}
+
+impl Localize for IOSource {
+    fn fmt_localized(&self, f: &mut fmt::Formatter<'_>, locale: Locale) -> fmt::Result {
+        match self {
+            IOSource::File(name) => f.write_str(name),
+            IOSource::Std => f.write_str(label(locale, "standard input/output", "tastatură/ecran")),
+        }
+    }
+}
+
+impl Localize for Difficulty {
+    fn fmt_localized(&self, f: &mut fmt::Formatter<'_>, locale: Locale) -> fmt::Result {
+        f.write_str(match self {
+            Difficulty::Easy => label(locale, "easy", "ușor"),
+            Difficulty::Medium => label(locale, "medium", "mediu"),
+            Difficulty::Difficult => label(locale, "difficult", "dificil"),
+            Difficulty::Contest => label(locale, "contest", "concurs"),
+        })
+    }
+}
+
+impl Localize for Limits {
+    /// Lists the known limits (e.g. "time 0.5 s, memory 64 MB, stack 8 MB").
+    fn fmt_localized(&self, f: &mut fmt::Formatter<'_>, locale: Locale) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(time) = self.time {
+            parts.push(format!(
+                "{} {} {}",
+                label(locale, "time", "timp"),
+                time.as_secs_f64(),
+                label(locale, "s", "secunde")
+            ));
+        }
+        if let Some(memory) = self.memory {
+            parts.push(format!(
+                "{} {}",
+                label(locale, "memory", "memorie"),
+                format_size(memory)
+            ));
+        }
+        if let Some(stack) = self.stack {
+            parts.push(format!(
+                "{} {}",
+                label(locale, "stack", "stivă"),
+                format_size(stack)
+            ));
+        }
+        match parts.is_empty() {
+            true => f.write_str(label(locale, "no limits", "fără limite")),
+            false => f.write_str(&parts.join(", ")),
+        }
+    }
+}
+
+impl Localize for PbInfoProblem {
+    /// A summary line, e.g. `#1691 arbore1 (grade 9, medium, arbore1.in / arbore1.out, time
+    /// 0.1 s, memory 64 MB, stack 8 MB)`.
+    fn fmt_localized(&self, f: &mut fmt::Formatter<'_>, locale: Locale) -> fmt::Result {
+        write!(f, "#{} {} (", self.id, self.name)?;
+        match locale {
+            Locale::English => write!(f, "grade {}", self.grade)?,
+            Locale::Romanian => write!(f, "clasa a {}-a", self.grade)?,
+        }
+        if let Some(difficulty) = &self.difficulty {
+            write!(f, ", {}", difficulty.localized(locale))?;
+        }
+        match (&self.input_source, &self.output_source) {
+            (IOSource::Std, IOSource::Std) => write!(f, ", {}", IOSource::Std.localized(locale))?,
+            (input, output) => write!(
+                f,
+                ", {} / {}",
+                input.localized(locale),
+                output.localized(locale)
+            )?,
+        }
+        let limits = Limits::from_problem(self);
+        if limits != Limits::default() {
+            write!(f, ", {}", limits.localized(locale))?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// Implements `Display` using the global locale.
+macro_rules! display_with_locale {
+    ($($type:ty),*) => {
+        $(
+            impl fmt::Display for $type {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    self.fmt_localized(f, locale())
+                }
+            }
+        )*
+    };
+}
+
+display_with_locale!(IOSource, Difficulty, Limits, PbInfoProblem);
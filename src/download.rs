@@ -0,0 +1,185 @@
+use crate::*;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// How much of a download has been written to disk.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DownloadProgress {
+    /// Bytes on disk (including the ones from a resumed partial download)
+    pub downloaded: u64,
+    /// Size of the whole file (if the server reported it)
+    pub total: Option<u64>,
+}
+
+/// Checks that `path` is a complete zip archive, reading every entry so that its CRC is verified.
+pub fn verify_zip(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path).map_err(|err| {
+        PbInfoError::IOError(format!("Could not open `{}`: {}", path.display(), err))
+    })?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|err| {
+        PbInfoError::IOError(format!(
+            "`{}` is not a valid zip archive: {}",
+            path.display(),
+            err
+        ))
+    })?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|err| {
+            PbInfoError::IOError(format!("Invalid entry in `{}`: {}", path.display(), err))
+        })?;
+        std::io::copy(&mut entry, &mut std::io::sink()).map_err(|err| {
+            PbInfoError::IOError(format!(
+                "Entry `{}` of `{}` is corrupted: {}",
+                entry.name(),
+                path.display(),
+                err
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+impl PbInfoClient {
+    /// Downloads the tests archive of the problem `id` to `path`, streaming it to disk and
+    /// calling `progress` after every chunk. If `path` already holds the beginning of the
+    /// archive (e.g. from an interrupted download), only the rest of it is requested. Once the
+    /// download finishes, the size and the CRC of every entry are verified. Returns the size of
+    /// the archive.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, path, progress), err(Debug))
+    )]
+    pub fn download_tests(
+        &self,
+        id: ProblemId,
+        path: impl AsRef<Path>,
+        mut progress: impl FnMut(DownloadProgress),
+    ) -> Result<u64> {
+        let path = path.as_ref();
+        let io_error = |err: std::io::Error| {
+            PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+        };
+
+        let existing = std::fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+        let mut request = self
+            .http()
+            .get(self.url(&format!("/php/descarca-teste.php?id={}", id)));
+        if existing > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+        }
+        let mut page = self.send(request)?;
+
+        let (mut file, mut downloaded, total) = match page.status() {
+            reqwest::StatusCode::PARTIAL_CONTENT => {
+                let file = std::fs::OpenOptions::new()
+                    .append(true)
+                    .open(path)
+                    .map_err(io_error)?;
+                let total = page
+                    .headers()
+                    .get(reqwest::header::CONTENT_RANGE)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.rsplit('/').next())
+                    .and_then(|total| total.parse::<u64>().ok());
+                (file, existing, total)
+            }
+            // The archive was already complete
+            reqwest::StatusCode::RANGE_NOT_SATISFIABLE => {
+                verify_zip(path)?;
+                return Ok(existing);
+            }
+            reqwest::StatusCode::OK => {
+                let file = std::fs::File::create(path).map_err(io_error)?;
+                (file, 0, page.content_length())
+            }
+            reqwest::StatusCode::NOT_FOUND => return Err(PbInfoError::UnknownId(id)),
+            reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::UNAUTHORIZED => {
+                return Err(PbInfoError::LoginError(
+                    "The tests can only be downloaded by users with access to them".to_owned(),
+                ))
+            }
+            s => {
+                return Err(PbInfoError::NetworkError(format!(
+                    "Encountered an error when trying to download the tests. HTTP status code {}",
+                    s
+                )))
+            }
+        };
+
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let read = page.read(&mut buffer).map_err(|err| {
+                PbInfoError::NetworkError(format!("The download was interrupted: {}", err))
+            })?;
+            if read == 0 {
+                break;
+            }
+            file.write_all(&buffer[..read]).map_err(io_error)?;
+            downloaded += read as u64;
+            self.record_download(read as u64);
+            progress(DownloadProgress { downloaded, total });
+        }
+        file.flush().map_err(io_error)?;
+
+        if let Some(total) = total {
+            if downloaded != total {
+                return Err(PbInfoError::NetworkError(format!(
+                    "Downloaded {} bytes out of {}. Call download_tests again to resume",
+                    downloaded, total
+                )));
+            }
+        }
+        verify_zip(path)?;
+        Ok(downloaded)
+    }
+
+    /// Downloads `attachment` to `path`, replacing it if it exists. Returns the size of the
+    /// file.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, attachment, path), fields(url = %attachment.url), err(Debug))
+    )]
+    pub fn download_attachment(
+        &self,
+        attachment: &Attachment,
+        path: impl AsRef<Path>,
+    ) -> Result<u64> {
+        let path = path.as_ref();
+        // Attachments are extracted with urls on the default address, which may be mirrored
+        let url = match attachment.url.strip_prefix(DEFAULT_BASE_URL) {
+            Some(relative) => self.url(relative),
+            None => attachment.url.clone(),
+        };
+        let mut page = self.get_page(&url)?;
+
+        match page.status() {
+            reqwest::StatusCode::OK => {}
+            reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::UNAUTHORIZED => {
+                return Err(PbInfoError::LoginError(format!(
+                    "The attachment `{}` can only be downloaded after logging in",
+                    attachment.name
+                )))
+            }
+            s => {
+                return Err(PbInfoError::NetworkError(format!(
+                    "Encountered an error when trying to download `{}`. HTTP status code {}",
+                    attachment.name, s
+                )))
+            }
+        }
+
+        let mut file = std::fs::File::create(path).map_err(|err| {
+            PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+        })?;
+        let downloaded = std::io::copy(&mut page, &mut file).map_err(|err| {
+            PbInfoError::NetworkError(format!(
+                "The download of `{}` was interrupted: {}",
+                attachment.name, err
+            ))
+        })?;
+        self.record_download(downloaded);
+        Ok(downloaded)
+    }
+}
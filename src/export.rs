@@ -0,0 +1,297 @@
+use crate::*;
+use std::io::Write;
+
+/// What `export_catalog_jsonl` includes for every problem.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ExportOptions {
+    /// Include the statement as plain text (`"statement"`)
+    pub include_statement: bool,
+    /// Include the statement as html (`"statement_html"`)
+    pub include_html: bool,
+}
+
+/// Returns the name used for `difficulty` in exported data.
+fn difficulty_name(difficulty: &Difficulty) -> &'static str {
+    match difficulty {
+        Difficulty::Easy => "easy",
+        Difficulty::Medium => "medium",
+        Difficulty::Difficult => "difficult",
+        Difficulty::Contest => "contest",
+    }
+}
+
+/// Returns the file name of `source`, or `null` for stdin/stdout.
+fn io_source_json(source: &IOSource) -> serde_json::Value {
+    match source {
+        IOSource::File(name) => serde_json::Value::from(name.as_str()),
+        IOSource::Std => serde_json::Value::Null,
+    }
+}
+
+/// Identifier of the version 1 of the public problem JSON schema (see `to_json_v1`).
+pub const JSON_SCHEMA_V1: &str = "pbinfo/problem/v1";
+
+/// Returns the difficulty named `name` in exported data.
+fn difficulty_from_name(name: &str) -> Option<Difficulty> {
+    match name {
+        "easy" => Some(Difficulty::Easy),
+        "medium" => Some(Difficulty::Medium),
+        "difficult" => Some(Difficulty::Difficult),
+        "contest" => Some(Difficulty::Contest),
+        _ => None,
+    }
+}
+
+impl PbInfoProblem {
+    /// Converts the problem into the version 1 of the public JSON schema, whose shape does not
+    /// change when the fields of PbInfoProblem do. It is an object with
+    ///
+    /// - `schema`: always `"pbinfo/problem/v1"`
+    /// - `id` (number), `name` (string), `grade` (number)
+    /// - `input`, `output`: file names, or `null` for stdin/stdout
+    /// - `time_limit`, `memory_limit`, `source`, `author`: strings or `null`
+    /// - `difficulty`: `"easy"`, `"medium"`, `"difficult"`, `"contest"` or `null`
+    /// - `topics`: list of strings, from the most general
+    /// - `attachments`: list of `{"name", "url", "size"}` objects (`size` may be `null`)
+    /// - `statement_html`, `meta_html`: the html of the statement and of the table above it
+    /// - `etag`, `last_modified`: HTTP validators of the problem page, strings or `null`
+    ///
+    /// Fields may be added to version 1, but never removed or changed, so consumers should
+    /// ignore the fields they do not know.
+    pub fn to_json_v1(&self) -> serde_json::Value {
+        serde_json::json!({
+            "schema": JSON_SCHEMA_V1,
+            "id": self.id.0,
+            "name": self.name.as_str(),
+            "grade": self.grade.0,
+            "input": io_source_json(&self.input_source),
+            "output": io_source_json(&self.output_source),
+            "time_limit": self.time_limit,
+            "memory_limit": self.memory_limit,
+            "source": self.source,
+            "author": self.author,
+            "difficulty": self.difficulty.as_ref().map(difficulty_name),
+            "topics": self.topics,
+            "attachments": self
+                .attachments
+                .iter()
+                .map(|attachment| serde_json::json!({
+                    "name": attachment.name,
+                    "url": attachment.url,
+                    "size": attachment.size,
+                }))
+                .collect::<Vec<_>>(),
+            "statement_html": self.problem_text,
+            "meta_html": self.meta_text,
+            "etag": self.etag,
+            "last_modified": self.last_modified,
+        })
+    }
+
+    /// Reads a problem written by `to_json_v1`. The fields are taken as they are, without
+    /// parsing the html again. Fails if `schema` is not `"pbinfo/problem/v1"` or a required
+    /// field (`id`, `name`, `grade`, `statement_html`, `meta_html`) is missing.
+    pub fn from_json_v1(json: &serde_json::Value) -> Result<Self> {
+        let invalid =
+            |message: &str| PbInfoError::JSONError(format!("Invalid problem (v1): {}", message));
+        if json["schema"] != JSON_SCHEMA_V1 {
+            return Err(invalid(&format!(
+                "unsupported schema {}, expected \"{}\"",
+                json["schema"], JSON_SCHEMA_V1
+            )));
+        }
+        let number = |key: &str| {
+            json[key]
+                .as_u64()
+                .map(|value| value as usize)
+                .ok_or_else(|| invalid(&format!("`{}` has to be a number", key)))
+        };
+        let text = |key: &str| {
+            json[key]
+                .as_str()
+                .map(str::to_owned)
+                .ok_or_else(|| invalid(&format!("`{}` has to be a string", key)))
+        };
+        let optional = |key: &str| json[key].as_str().map(str::to_owned);
+        let io_source = |key: &str| match json[key].as_str() {
+            Some(name) => IOSource::File(name.to_owned()),
+            None => IOSource::Std,
+        };
+
+        Ok(PbInfoProblem {
+            id: ProblemId(number("id")?),
+            name: ProblemName::new(&text("name")?)?,
+            meta_text: text("meta_html")?,
+            problem_text: text("statement_html")?,
+            input_source: io_source("input"),
+            output_source: io_source("output"),
+            grade: Grade(number("grade")?),
+            time_limit: optional("time_limit"),
+            memory_limit: optional("memory_limit"),
+            source: optional("source"),
+            author: optional("author"),
+            difficulty: json["difficulty"].as_str().and_then(difficulty_from_name),
+            topics: json["topics"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|topic| topic.as_str().map(str::to_owned))
+                .collect(),
+            attachments: json["attachments"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|attachment| {
+                    Some(Attachment {
+                        name: attachment["name"].as_str()?.to_owned(),
+                        url: attachment["url"].as_str()?.to_owned(),
+                        size: attachment["size"].as_u64(),
+                    })
+                })
+                .collect(),
+            etag: optional("etag"),
+            last_modified: optional("last_modified"),
+        })
+    }
+}
+
+/// Converts `problem` into the JSON object written by `export_catalog_jsonl`.
+pub fn problem_to_json(problem: &PbInfoProblem, options: &ExportOptions) -> serde_json::Value {
+    let mut object = serde_json::json!({
+        "id": problem.id.0,
+        "name": problem.name.as_str(),
+        "grade": problem.grade.0,
+        "input": io_source_json(&problem.input_source),
+        "output": io_source_json(&problem.output_source),
+        "time_limit": problem.time_limit,
+        "memory_limit": problem.memory_limit,
+        "source": problem.source,
+        "author": problem.author,
+        "difficulty": problem.difficulty.as_ref().map(difficulty_name),
+        "topics": problem.topics,
+        "examples": problem.examples().len(),
+        "subtasks": problem.subtasks().len(),
+    });
+
+    if options.include_statement {
+        object["statement"] = serde_json::Value::from(html_to_text(&problem.problem_text));
+    }
+    if options.include_html {
+        object["statement_html"] = serde_json::Value::from(problem.problem_text.as_str());
+    }
+    object
+}
+
+/// Writes every problem to `writer` as one JSON object per line (JSON Lines), without holding
+/// more than one of them in memory. Returns the number of problems written.
+pub fn export_catalog_jsonl<'a, W: Write>(
+    mut writer: W,
+    problems: impl IntoIterator<Item = &'a PbInfoProblem>,
+    options: &ExportOptions,
+) -> Result<usize> {
+    let mut count = 0;
+    for problem in problems {
+        let line = problem_to_json(problem, options).to_string();
+        writeln!(writer, "{}", line).map_err(|err| {
+            PbInfoError::IOError(format!("Could not write problem {}: {}", problem.id, err))
+        })?;
+        count += 1;
+    }
+    writer
+        .flush()
+        .map_err(|err| PbInfoError::IOError(format!("Could not write the catalog: {}", err)))?;
+    Ok(count)
+}
+
+/// Identifier of the version 1 of the research dataset schema (see `research_record`).
+pub const RESEARCH_SCHEMA_V1: &str = "pbinfo/research/v1";
+
+/// Removes personal data from the plain text of a statement: links to user profiles become
+/// `[user]`, email addresses `[email]`, and the name of the author `[author]`.
+fn anonymize_statement(problem: &PbInfoProblem) -> String {
+    let profile = &selectors::PROFILE_LINK;
+    let html = profile.replace_all(&problem.problem_text, "[user]");
+    let email = &selectors::EMAIL;
+    let mut text = email
+        .replace_all(&html_to_text(&html), "[email]")
+        .into_owned();
+    if let Some(author) = problem.author.as_deref().map(str::trim) {
+        if !author.is_empty() {
+            text = text.replace(author, "[author]");
+        }
+    }
+    text
+}
+
+/// Converts `problem` (and its submission statistics, if known) into a record of the research
+/// dataset, without any personal data: the author is left out, and the usernames, email
+/// addresses and the name of the author are removed from the statement. It is an object with
+///
+/// - `schema`: always `"pbinfo/research/v1"`
+/// - `id` (number), `name` (string), `grade` (number)
+/// - `difficulty`: `"easy"`, `"medium"`, `"difficult"`, `"contest"` or `null`
+/// - `topics`: list of strings, from the most general
+/// - `source`: the contest the problem comes from, or `null`
+/// - `io`: `"file"` or `"std"`, how the solutions read their input
+/// - `time_limit_ms`, `memory_limit_bytes`: numbers or `null`
+/// - `statement`: the statement as plain text
+/// - `examples`, `subtasks`: numbers of examples and of subtasks
+/// - `stats`: `{"attempts", "solved", "average_score", "success_rate", "language_breakdown"}`
+///   or `null` (`average_score` may be `null`, `success_rate` is a percentage and
+///   `language_breakdown` maps languages to their numbers of correct solutions)
+///
+/// Fields may be added to version 1, but never removed or changed.
+pub fn research_record(problem: &PbInfoProblem, stats: Option<&ProblemStats>) -> serde_json::Value {
+    serde_json::json!({
+        "schema": RESEARCH_SCHEMA_V1,
+        "id": problem.id.0,
+        "name": problem.name.as_str(),
+        "grade": problem.grade.0,
+        "difficulty": problem.difficulty.as_ref().map(difficulty_name),
+        "topics": problem.topics,
+        "source": problem.source,
+        "io": match problem.input_source {
+            IOSource::File(_) => "file",
+            IOSource::Std => "std",
+        },
+        "time_limit_ms": problem
+            .time_limit
+            .as_deref()
+            .and_then(judge::parse_time)
+            .map(|time| time.as_millis() as u64),
+        "memory_limit_bytes": problem
+            .memory_limit
+            .as_deref()
+            .and_then(|limit| judge::parse_size(limit.split('/').next().unwrap_or_default())),
+        "statement": anonymize_statement(problem),
+        "examples": problem.examples().len(),
+        "subtasks": problem.subtasks().len(),
+        "stats": stats.map(|stats| serde_json::json!({
+            "attempts": stats.attempts,
+            "solved": stats.solved,
+            "average_score": stats.average_score,
+            "success_rate": stats.success_rate,
+            "language_breakdown": stats.language_breakdown,
+        })),
+    })
+}
+
+/// Writes the research dataset of `problems` (with their statistics, if known) to `writer`, as
+/// one `research_record` per line (JSON Lines). Returns the number of records written.
+pub fn export_research_dataset<'a, W: Write>(
+    mut writer: W,
+    problems: impl IntoIterator<Item = (&'a PbInfoProblem, Option<&'a ProblemStats>)>,
+) -> Result<usize> {
+    let mut count = 0;
+    for (problem, stats) in problems {
+        let line = research_record(problem, stats).to_string();
+        writeln!(writer, "{}", line).map_err(|err| {
+            PbInfoError::IOError(format!("Could not write problem {}: {}", problem.id, err))
+        })?;
+        count += 1;
+    }
+    writer
+        .flush()
+        .map_err(|err| PbInfoError::IOError(format!("Could not write the dataset: {}", err)))?;
+    Ok(count)
+}
@@ -2,6 +2,164 @@ use crate::*;
 
 type Result<T> = std::result::Result<T, PbInfoError>;
 
+/// A way of locating one part of a problem page. Each part has a chain of strategies, from the
+/// current layout of PbInfo to older or more tolerant ones, so that a redesign of the pages
+/// degrades the extraction instead of breaking it.
+pub(crate) struct Strategy {
+    /// Name reported when the strategy succeeds
+    pub(crate) name: &'static str,
+    /// Returns the located part, if the page matches the strategy
+    find: fn(&str) -> Option<String>,
+}
+
+/// Returns the first capture of `selector` in `string`.
+fn capture(selector: &selectors::Selector, string: &str) -> Option<String> {
+    selector.captures(string).map(|caps| caps[1].to_owned())
+}
+
+/// Strategies for the problem name, which is returned in lowercase.
+pub(crate) const NAME_STRATEGIES: &[Strategy] = &[
+    Strategy {
+        name: "title",
+        find: |string| capture(&selectors::NAME_TITLE, string).map(|name| name.to_lowercase()),
+    },
+    Strategy {
+        name: "og:title",
+        find: |string| capture(&selectors::NAME_OG_TITLE, string).map(|name| name.to_lowercase()),
+    },
+    Strategy {
+        name: "title (legacy)",
+        find: |string| {
+            capture(&selectors::NAME_LEGACY_TITLE, string).map(|name| name.to_lowercase())
+        },
+    },
+];
+
+/// Strategies for the problem text, which starts at the "Cerința" heading.
+pub(crate) const PROBLEM_TEXT_STRATEGIES: &[Strategy] = &[
+    Strategy {
+        name: "article",
+        find: |string| capture(&selectors::TEXT_ARTICLE, string),
+    },
+    Strategy {
+        name: "article (attributes)",
+        find: |string| capture(&selectors::TEXT_ARTICLE_ATTRIBUTES, string),
+    },
+    Strategy {
+        name: "main",
+        find: |string| capture(&selectors::TEXT_MAIN, string),
+    },
+];
+
+/// Strategies for the metadata table (input source, output source etc.).
+pub(crate) const METADATA_STRATEGIES: &[Strategy] = &[
+    Strategy {
+        name: "table.table-bordered",
+        find: |string| capture(&selectors::METADATA_TABLE, string),
+    },
+    Strategy {
+        name: "table with Clasa",
+        find: |string| {
+            let regex = &selectors::TABLE;
+            let table = regex
+                .captures_iter(string)
+                .map(|caps| caps[1].to_owned())
+                .find(|table| table.contains("Clasa") && table.contains("<th"));
+            table
+        },
+    },
+];
+
+/// Returns the result of the first strategy that succeeds, together with its name.
+pub(crate) fn extract_with(
+    string: &str,
+    strategies: &[Strategy],
+) -> Option<(String, &'static str)> {
+    let found = strategies
+        .iter()
+        .find_map(|strategy| (strategy.find)(string).map(|found| (found, strategy.name)));
+
+    #[cfg(feature = "tracing")]
+    if let Some((_, strategy)) = &found {
+        tracing::debug!(strategy, "extracted");
+    }
+    found
+}
+
+/// The strategies that located each part of a problem page (`None` if every strategy failed).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ExtractionReport {
+    /// Strategy that located the name
+    pub name: Option<&'static str>,
+    /// Strategy that located the problem text
+    pub problem_text: Option<&'static str>,
+    /// Strategy that located the metadata table
+    pub metadata: Option<&'static str>,
+}
+
+/// Reports which strategy locates each part of the problem page `string`. Anything other than
+/// the first strategy of each chain means that PbInfo's layout changed.
+pub fn extraction_report(string: &str) -> ExtractionReport {
+    let strategy = |strategies| extract_with(string, strategies).map(|(_, name)| name);
+    ExtractionReport {
+        name: strategy(NAME_STRATEGIES),
+        problem_text: strategy(PROBLEM_TEXT_STRATEGIES),
+        metadata: strategy(METADATA_STRATEGIES),
+    }
+}
+
+/// Extracts the (lowercase) problem name from the page title.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(string), fields(selector = "title"), err(Debug))
+)]
+pub fn extract_name(string: &str) -> Result<String> {
+    match extract_with(string, NAME_STRATEGIES) {
+        Some((name, _)) => Ok(name),
+        None => Err(PbInfoError::RegexError(
+            "Failed to locate the problem name in the HTML".to_owned(),
+        )),
+    }
+}
+
+/// Extracts the problem text (task, examples etc.) from the page.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(string),
+        fields(selector = "article"),
+        err(Debug)
+    )
+)]
+pub fn extract_problem_text(string: &str) -> Result<String> {
+    match extract_with(string, PROBLEM_TEXT_STRATEGIES) {
+        Some((problem_text, _)) => Ok(problem_text),
+        None => Err(PbInfoError::RegexError(
+            "Failed to locate the problem text in the HTML".to_owned(),
+        )),
+    }
+}
+
+/// Extracts the metadata table (input source, output source etc.) from the page.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(string),
+        fields(selector = "table.table-bordered"),
+        err(Debug)
+    )
+)]
+pub fn extract_metadata(string: &str) -> Result<String> {
+    match extract_with(string, METADATA_STRATEGIES) {
+        Some((metadata, _)) => Ok(metadata),
+        None => Err(PbInfoError::RegexError(
+            "Failed to locate the problem metadata in the HTML".to_owned(),
+        )),
+    }
+}
+
 /// Extracts the problem id from the JSON "label" attribute. The "label" attribute is of the form `"label": "Problema #{id}: <strong>{name}</strong>`
 pub fn extract_id_from_json(string: &str) -> Result<usize> {
     let error = PbInfoError::JSONError(
@@ -28,16 +186,22 @@ pub fn extract_id_from_json(string: &str) -> Result<usize> {
 
     match id_string.parse::<usize>() {
         Ok(res) => Ok(res),
-        Err(_) => return Err(error),
+        Err(_) => Err(error),
     }
 }
 
 /// Extracts the input source (stdin or a file name) from the metadata text.
-pub fn extract_input_source(string: &str) -> Result<IOSource> {
-    let regex = regex::Regex::new(
-        r#"<span style="background: url\(.*?>\s*([\w\.ă]+) / ([\w\.ă]+)\s*</span>"#,
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(string),
+        fields(selector = "td > span[style]"),
+        err(Debug)
     )
-    .unwrap();
+)]
+pub fn extract_input_source(string: &str) -> Result<IOSource> {
+    let regex = &selectors::IO_SOURCES;
 
     let input_text = match regex.captures(string) {
         Some(res) => res[1].to_owned(),
@@ -56,11 +220,17 @@ pub fn extract_input_source(string: &str) -> Result<IOSource> {
 }
 
 /// Extracts the output source (stdout or a file name) from the metadata text.
-pub fn extract_output_source(string: &str) -> Result<IOSource> {
-    let regex = regex::Regex::new(
-        r#"<span style="background: url\(.*?>\s*([\w\.ă]+) / ([\w\.ă]+)\s*</span>"#,
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(string),
+        fields(selector = "td > span[style]"),
+        err(Debug)
     )
-    .unwrap();
+)]
+pub fn extract_output_source(string: &str) -> Result<IOSource> {
+    let regex = &selectors::IO_SOURCES;
 
     let output_text = match regex.captures(string) {
         Some(res) => res[2].to_owned(),
@@ -78,12 +248,18 @@ pub fn extract_output_source(string: &str) -> Result<IOSource> {
     }
 }
 
-/// Each \s*?<td[ \S]*?>([\s\S]*?)</td> represents a <td> tag.
-const const_reg: &str = r#"<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>"#;
-
 /// Extracts the grade (from 9 to 11) of the problem.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(string),
+        fields(selector = "td:nth-child(2)"),
+        err(Debug)
+    )
+)]
 pub fn extract_grade(string: &str) -> Result<usize> {
-    let regex = regex::Regex::new(const_reg).unwrap();
+    let regex = &selectors::METADATA_ROW;
 
     let grade_str = match regex.captures(string) {
         Some(res) => res[2].to_owned(),
@@ -104,8 +280,17 @@ pub fn extract_grade(string: &str) -> Result<usize> {
 }
 
 /// Extracts the time limit of the problem (if it exists).
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(string),
+        fields(selector = "td:nth-child(4)"),
+        err(Debug)
+    )
+)]
 pub fn extract_time_limit(string: &str) -> Result<Option<String>> {
-    let regex = regex::Regex::new(const_reg).unwrap();
+    let regex = &selectors::METADATA_ROW;
 
     let time_str = match regex.captures(string) {
         Some(res) => res[4].to_owned(),
@@ -123,8 +308,17 @@ pub fn extract_time_limit(string: &str) -> Result<Option<String>> {
 }
 
 /// Extracts the memory limit of the problem (if it exists).
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(string),
+        fields(selector = "td:nth-child(5)"),
+        err(Debug)
+    )
+)]
 pub fn extract_memory_limit(string: &str) -> Result<Option<String>> {
-    let regex = regex::Regex::new(const_reg).unwrap();
+    let regex = &selectors::METADATA_ROW;
 
     let memory_str = match regex.captures(string) {
         Some(res) => res[5].to_owned(),
@@ -135,7 +329,7 @@ pub fn extract_memory_limit(string: &str) -> Result<Option<String>> {
         }
     };
 
-    let memory_regex = regex::Regex::new(r">([\w -]*)<").unwrap();
+    let memory_regex = &selectors::MEMORY_PART;
     let memory_caps = memory_regex.captures_iter(&memory_str).collect::<Vec<_>>();
 
     match memory_caps.len() {
@@ -150,8 +344,17 @@ pub fn extract_memory_limit(string: &str) -> Result<Option<String>> {
 }
 
 /// Extracts the source of the problem (if it exists).
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(string),
+        fields(selector = "td:nth-child(6)"),
+        err(Debug)
+    )
+)]
 pub fn extract_source(string: &str) -> Result<Option<String>> {
-    let regex = regex::Regex::new(const_reg).unwrap();
+    let regex = &selectors::METADATA_ROW;
 
     let source_str = match regex.captures(string) {
         Some(res) => res[6].to_owned(),
@@ -169,8 +372,17 @@ pub fn extract_source(string: &str) -> Result<Option<String>> {
 }
 
 /// Extracts the author of the problem (if it exists).
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(string),
+        fields(selector = "td:nth-child(7)"),
+        err(Debug)
+    )
+)]
 pub fn extract_author(string: &str) -> Result<Option<String>> {
-    let regex = regex::Regex::new(const_reg).unwrap();
+    let regex = &selectors::METADATA_ROW;
 
     let author_str = match regex.captures(string) {
         Some(res) => res[7].to_owned(),
@@ -188,8 +400,17 @@ pub fn extract_author(string: &str) -> Result<Option<String>> {
 }
 
 /// Extracts the difficulty of the problem (if it exists).
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(string),
+        fields(selector = "td:nth-child(8)"),
+        err(Debug)
+    )
+)]
 pub fn extract_difficulty(string: &str) -> Result<Option<Difficulty>> {
-    let regex = regex::Regex::new(const_reg).unwrap();
+    let regex = &selectors::METADATA_ROW;
 
     let difficulty_str = match regex.captures(string) {
         Some(res) => res[8].to_owned(),
@@ -209,13 +430,917 @@ pub fn extract_difficulty(string: &str) -> Result<Option<Difficulty>> {
     }
 }
 
-#[allow(unused_variables)]
-pub fn get_task(problem_text: &str) -> String {
-    let content_regex = regex::Regex::new(r"<h1.*>Cerința</h1>[\s\S]*<p>(?P<task>[\s\S]+)</p>[\s\S]*<h1.*>Date de intrare</h1>[\s\S]*<p>(?P<input>[\s\S]+)</p>[\s\S]*<h1.*>Date de ieșire</h1>[\s\S]*<p>(?P<output>[\s\S]+)</p>[\s\S]*<h1.*>Restricții și precizări</h1>").unwrap();
-
-    let caps = content_regex.captures(problem_text).unwrap();
+#[allow(unused_variables, dead_code)]
+pub fn get_task(problem_text: &str) -> Result<String> {
+    let caps = selectors::TASK.captures_or(problem_text, "the task")?;
     let task = &caps["task"];
     let input = &caps["input"];
     let output = &caps["output"];
-    String::new()
+    Ok(String::new())
+}
+
+/// Splits the problem text into `(title, html)` pairs, one for each `<h1>` heading.
+pub fn extract_sections(problem_text: &str) -> Vec<(String, String)> {
+    let regex = &selectors::H1;
+
+    let headings = regex.captures_iter(problem_text).collect::<Vec<_>>();
+    let mut sections = Vec::new();
+    for (i, caps) in headings.iter().enumerate() {
+        let start = caps.get(0).unwrap().end();
+        let end = match headings.get(i + 1) {
+            Some(next) => next.get(0).unwrap().start(),
+            None => problem_text.len(),
+        };
+        sections.push((
+            caps[1].trim().to_owned(),
+            problem_text[start..end].trim().to_owned(),
+        ));
+    }
+    sections
+}
+
+/// Returns the queries under which the problem called `name` (a `ProblemName`) is searched.
+/// PbInfo only finds names that contain the query, so the name is searched both without
+/// separators (`sum_cif` as `sumcif`) and with them as spaces (`SumCif` stays `sumcif`).
+pub fn name_search_queries(name: &str) -> Vec<String> {
+    let mut queries = vec![name_key(name)];
+    let spaced = name.replace(['_', '-', '.'], " ");
+    if !queries.contains(&spaced) {
+        queries.push(spaced);
+    }
+    queries
+}
+
+/// Adds the suggestions `found` by one of the `name_search_queries` to the ones of the previous
+/// queries, skipping the problems that were already suggested, most similar first.
+pub fn merge_suggestions(suggestions: &mut Vec<Suggestion>, found: Vec<Suggestion>) {
+    for suggestion in found {
+        if !suggestions.iter().any(|other| other.id == suggestion.id) {
+            suggestions.push(suggestion);
+        }
+    }
+    suggestions.sort_by(|first, second| second.score.total_cmp(&first.score));
+}
+
+/// Finds the id of the problem called `name` in the JSON returned by the search endpoint. Names
+/// are compared by their `name_key`, so case, diacritics and separators do not matter. If there
+/// is no such problem, the problems that were found are returned as suggestions, most similar to
+/// `name` first (see `name_similarity`).
+pub fn extract_id_from_search(
+    name: &str,
+    search_json: &[std::collections::HashMap<String, String>],
+) -> Result<usize> {
+    // A list of suggested problems; used only in case we do not find a matching name
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    let key = name_key(name);
+    for map in search_json.iter() {
+        let possible_name = match map.get("value") {
+            Some(res) => res,
+            None => {
+                return Err(PbInfoError::JSONError(
+                    "JSON should contain the 'value' attribute".to_owned(),
+                ))
+            }
+        };
+
+        if name_key(possible_name) == key {
+            let label = match map.get("label") {
+                Some(res) => res,
+                None => {
+                    return Err(PbInfoError::JSONError(
+                        "JSON should contain the 'label' attribute".to_owned(),
+                    ))
+                }
+            };
+
+            // Try to get the id from the JSON
+            return extract_id_from_json(label);
+        } else if let Some(id) = map
+            .get("label")
+            .and_then(|label| extract_id_from_json(label).ok())
+        {
+            // If we do not get a match, we add the problem to the list of suggestions
+            suggestions.push(Suggestion {
+                id: ProblemId(id),
+                name: possible_name.clone(),
+                score: name_similarity(name, possible_name),
+            });
+        }
+    }
+
+    // The sort is stable, so equally similar problems keep the order of the search
+    suggestions.sort_by(|first, second| second.score.total_cmp(&first.score));
+    Err(PbInfoError::UnknownName(name.to_owned(), suggestions))
+}
+
+/// Decodes the html entities that appear in statements (`&lt;`, `&amp;`, `&#8804;` etc.).
+pub fn decode_entities(string: &str) -> String {
+    let regex = &selectors::ENTITY;
+
+    regex
+        .replace_all(string, |caps: &regex::Captures| {
+            let entity = &caps[1];
+            let decoded = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "le" => Some('≤'),
+                "ge" => Some('≥'),
+                "ne" => Some('≠'),
+                "times" => Some('×'),
+                "middot" => Some('·'),
+                _ if entity.starts_with("#x") => u32::from_str_radix(&entity[2..], 16)
+                    .ok()
+                    .and_then(char::from_u32),
+                _ if entity.starts_with('#') => {
+                    entity[1..].parse::<u32>().ok().and_then(char::from_u32)
+                }
+                _ => None,
+            };
+            match decoded {
+                Some(c) => c.to_string(),
+                None => caps[0].to_owned(),
+            }
+        })
+        .into_owned()
+}
+
+/// Converts a fragment of html into plain text: block elements become line breaks, all other
+/// tags are removed and entities are decoded.
+pub fn html_to_text(html: &str) -> String {
+    let breaks = &selectors::TEXT_BREAK;
+    let tags = &selectors::TAG;
+    let blank_lines = &selectors::BLANK_LINES;
+
+    // Tables are laid out in columns, so they are set aside until the end
+    let mut tables = Vec::new();
+    let html = replace_tables(html, |table| {
+        tables.push(table.to_plain_text());
+        format!("\n\n\u{0}{}\u{0}\n\n", tables.len() - 1)
+    });
+
+    let text = breaks.replace_all(&html, "\n");
+    let text = tags.replace_all(&text, "");
+    let text = decode_entities(&text);
+    let text = text
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut text = blank_lines.replace_all(&text, "\n\n").trim().to_owned();
+    for (i, table) in tables.iter().enumerate() {
+        text = text.replace(&format!("\u{0}{}\u{0}", i), table);
+    }
+    text
+}
+
+/// Parses the contents of a `<table>` element.
+fn parse_table(html: &str) -> Table {
+    let row = &selectors::LOOSE_TABLE_ROW;
+    let cell = &selectors::LOOSE_TABLE_CELL;
+    let colspan = &selectors::COLSPAN;
+
+    let mut table = Table::default();
+    for (i, row) in row.captures_iter(html).enumerate() {
+        let mut cells = Vec::new();
+        let mut header = true;
+        for cell in cell.captures_iter(&row[1]) {
+            header &= cell[1].eq_ignore_ascii_case("h");
+            let text = html_to_text(&math_to_latex(&cell[3]))
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            cells.push(text);
+            // A cell spanning several columns is followed by empty ones
+            let span = colspan
+                .captures(&cell[2])
+                .and_then(|span| span[1].parse::<usize>().ok())
+                .unwrap_or(1);
+            cells.extend(std::iter::repeat_n(String::new(), span.clamp(1, 100) - 1));
+        }
+        match (i, header && !cells.is_empty()) {
+            (0, true) => table.headers = cells,
+            _ if !cells.is_empty() => table.rows.push(cells),
+            _ => {}
+        }
+    }
+
+    // Every row gets one cell per column
+    let columns = table
+        .rows
+        .iter()
+        .map(Vec::len)
+        .chain([table.headers.len()])
+        .max()
+        .unwrap_or_default();
+    for row in table.rows.iter_mut() {
+        row.resize(columns, String::new());
+    }
+    if !table.headers.is_empty() {
+        table.headers.resize(columns, String::new());
+    }
+    table
+}
+
+/// Replaces every table of `html` with the result of `replace` (tables in tables are not
+/// supported).
+pub(crate) fn replace_tables(html: &str, mut replace: impl FnMut(&Table) -> String) -> String {
+    if !html.contains("<table") && !html.contains("<TABLE") {
+        return html.to_owned();
+    }
+    let table = &selectors::LOOSE_TABLE;
+    table
+        .replace_all(html, |caps: &regex::Captures| {
+            replace(&parse_table(&caps[1]))
+        })
+        .into_owned()
+}
+
+/// Extracts the tables of a fragment of html, in order. The first row is the header if all of
+/// its cells are `<th>`, and formulas in the cells are written as LaTeX.
+pub fn extract_tables(html: &str) -> Vec<Table> {
+    let mut tables = Vec::new();
+    replace_tables(html, |table| {
+        tables.push(table.clone());
+        String::new()
+    });
+    tables
+}
+
+/// Extracts the subtasks (partial scores and their constraints) described in the plain text of
+/// the restrictions. Lines such as "Pentru 30 de puncte, n ≤ 1000", "Pentru 20% din teste,
+/// n ≤ 100" or "Subtask 2 (40 de puncte): n ≤ 10^5" are recognized.
+pub fn extract_subtasks(text: &str) -> Vec<Subtask> {
+    let regexes = [&selectors::SUBTASK_POINTS, &selectors::SUBTASK_HEADING];
+
+    let mut subtasks = Vec::new();
+    for line in text.lines() {
+        for regex in regexes.iter() {
+            if let Some(caps) = regex.captures(line) {
+                let constraints = caps[2].trim().trim_end_matches(['.', ';']).trim();
+                if let (Ok(points), false) = (caps[1].parse::<usize>(), constraints.is_empty()) {
+                    subtasks.push(Subtask {
+                        points,
+                        constraints: constraints.to_owned(),
+                    });
+                    break;
+                }
+            }
+        }
+    }
+    subtasks
+}
+
+/// Returns `true` if `title` is the heading of an interaction protocol section.
+pub fn is_interaction_title(title: &str) -> bool {
+    let regex = &selectors::INTERACTION_TITLE;
+    regex.is_match(title)
+}
+
+/// Returns `true` if `text` calls the problem interactive or mentions an interactor.
+pub fn mentions_interaction(text: &str) -> bool {
+    let regex = &selectors::INTERACTION_MENTION;
+    regex.is_match(text)
+}
+
+/// Returns `true` if `text` asks for the output to be flushed (e.g. `fflush(stdout)`,
+/// `cout.flush()`, `cout << endl` or `sys.stdout.flush()`).
+pub fn mentions_flush(text: &str) -> bool {
+    let regex = &selectors::FLUSH_MENTION;
+    regex.is_match(text)
+}
+
+/// Extracts the problem id from a problem url. Accepts `/probleme/{id}`, `/probleme/{id}/{slug}`,
+/// with or without the scheme and host, and with any query parameters or fragment.
+pub fn extract_id_from_url(url: &str) -> Result<usize> {
+    let regex = &selectors::PROBLEM_URL;
+
+    match regex.captures(url.trim()) {
+        Some(res) => match res[1].parse::<usize>() {
+            Ok(id) => Ok(id),
+            Err(_) => Err(PbInfoError::Error(format!(
+                "Invalid problem id in `{}`",
+                url
+            ))),
+        },
+        None => Err(PbInfoError::Error(format!(
+            "`{}` is not a PbInfo problem url",
+            url
+        ))),
+    }
+}
+
+/// Pairs every `<th>` of the metadata table with the `<td>` in the same column, converting the
+/// cell contents to plain text.
+pub fn extract_metadata_map(string: &str) -> std::collections::BTreeMap<String, String> {
+    let header_regex = &selectors::TH;
+    let cell_regex = &selectors::TD;
+
+    let headers = header_regex
+        .captures_iter(string)
+        .map(|caps| html_to_text(&caps[1]));
+    let cells = cell_regex
+        .captures_iter(string)
+        .map(|caps| html_to_text(&caps[1]));
+
+    headers.zip(cells).collect()
+}
+
+/// Converts the JSON returned by the search endpoint into a list of SearchResults.
+pub fn extract_search_results(
+    search_json: &[std::collections::HashMap<String, String>],
+) -> Result<Vec<SearchResult>> {
+    let mut results = Vec::new();
+    for map in search_json.iter() {
+        let name = match map.get("value") {
+            Some(res) => res,
+            None => {
+                return Err(PbInfoError::JSONError(
+                    "JSON should contain the 'value' attribute".to_owned(),
+                ))
+            }
+        };
+        let label = match map.get("label") {
+            Some(res) => res,
+            None => {
+                return Err(PbInfoError::JSONError(
+                    "JSON should contain the 'label' attribute".to_owned(),
+                ))
+            }
+        };
+
+        results.push(SearchResult {
+            id: ProblemId(extract_id_from_json(label)?),
+            name: name.to_owned(),
+        });
+    }
+    Ok(results)
+}
+
+/// Detects a captcha or an extra verification step in the response to a login attempt.
+pub fn extract_login_challenge(string: &str) -> Option<LoginChallenge> {
+    let image_regex = &selectors::CAPTCHA_IMAGE;
+    let recaptcha_regex = &selectors::RECAPTCHA_SITE_KEY;
+    let token_regex = &selectors::CHALLENGE_TOKEN;
+
+    let verification_regex = &selectors::VERIFICATION_CODE;
+
+    let (kind, image_url) = if let Some(caps) = image_regex.captures(string) {
+        let url = match caps[1].starts_with("http") {
+            true => caps[1].to_owned(),
+            false => format!("https://www.pbinfo.ro/{}", caps[1].trim_start_matches('/')),
+        };
+        (ChallengeKind::Captcha, Some(url))
+    } else if let Some(caps) = recaptcha_regex.captures(string) {
+        (ChallengeKind::ReCaptcha(caps[1].to_owned()), None)
+    } else if verification_regex.is_match(string) {
+        (ChallengeKind::Verification, None)
+    } else {
+        return None;
+    };
+
+    Some(LoginChallenge {
+        kind,
+        image_url,
+        token: token_regex
+            .captures(string)
+            .map(|caps| caps[1].to_owned())
+            .unwrap_or_default(),
+    })
+}
+
+/// Extracts the source code from the page of a submission (`/detalii-evaluare/{id}`).
+pub fn extract_submission_source(string: &str) -> Result<String> {
+    let regexes = [&selectors::SOURCE_TEXTAREA, &selectors::SOURCE_PRE];
+    let tags = &selectors::TAG;
+
+    for regex in regexes.iter() {
+        if let Some(caps) = regex.captures(string) {
+            // The source might be highlighted, so any markup is removed
+            return Ok(decode_entities(&tags.replace_all(&caps[1], "")));
+        }
+    }
+
+    Err(PbInfoError::RegexError(
+        "Failed to locate the source code in the HTML".to_owned(),
+    ))
+}
+
+/// Extracts the evaluation status from the page of a submission (`/detalii-evaluare/{id}`).
+/// While the submission is evaluated, the tests that already have a verdict are counted.
+pub fn extract_submission_status(string: &str) -> Result<SubmissionStatus> {
+    let compilation_regex = &selectors::COMPILATION_ERROR;
+    let messages_regex = &selectors::COMPILER_MESSAGES;
+    let queued_regex = &selectors::QUEUED;
+    let evaluating_regex = &selectors::EVALUATING;
+    let score_regex = &selectors::TOTAL_SCORE;
+    let row_regex = &selectors::TABLE_ROW;
+    let tag_regex = &selectors::TAG;
+    let verdict_regex = &selectors::TEST_VERDICT;
+
+    let text = html_to_text(string);
+    if compilation_regex.is_match(&text) {
+        let messages = messages_regex
+            .captures(string)
+            .map(|caps| decode_entities(&caps[1]).trim().to_owned())
+            .unwrap_or_default();
+        return Ok(SubmissionStatus::CompilationError(messages));
+    }
+    if queued_regex.is_match(&text) {
+        return Ok(SubmissionStatus::Queued);
+    }
+    if evaluating_regex.is_match(&text) {
+        let evaluated = row_regex
+            .captures_iter(string)
+            .filter(|row| verdict_regex.is_match(&tag_regex.replace_all(&row[1], " ")))
+            .count();
+        return Ok(SubmissionStatus::Evaluating(evaluated));
+    }
+    match score_regex
+        .captures(&text)
+        .and_then(|caps| caps[1].parse().ok())
+    {
+        Some(score) => Ok(SubmissionStatus::Finished(score)),
+        None => Err(PbInfoError::RegexError(
+            "The submission page should show its status or its score".to_owned(),
+        )),
+    }
+}
+
+/// Extracts the rows of a ranking table ("clasament"). Each row has the rank, a link to the
+/// profile of the user and then the number of solved problems and the score.
+pub fn extract_rankings(string: &str) -> Vec<RankEntry> {
+    let row_regex = &selectors::TABLE_ROW;
+    let cell_regex = &selectors::TD;
+    let user_regex = &selectors::PROFILE_HREF;
+
+    let mut entries = Vec::new();
+    for row in row_regex.captures_iter(string) {
+        let cells = cell_regex
+            .captures_iter(&row[1])
+            .map(|caps| caps[1].to_owned())
+            .collect::<Vec<_>>();
+        let (user_cell, username) = match cells
+            .iter()
+            .enumerate()
+            .find_map(|(pos, cell)| Some((pos, user_regex.captures(cell)?[1].to_owned())))
+        {
+            Some(user) => user,
+            None => continue,
+        };
+
+        let number = |cell: &String| {
+            html_to_text(cell)
+                .trim_end_matches('.')
+                .replace(['.', ' '], "")
+                .parse::<usize>()
+                .ok()
+        };
+        let numbers_after = cells[user_cell + 1..]
+            .iter()
+            .filter_map(number)
+            .collect::<Vec<_>>();
+
+        if let (Some(rank), [solved, score, ..]) = (
+            cells[..user_cell].iter().find_map(number),
+            &numbers_after[..],
+        ) {
+            entries.push(RankEntry {
+                rank,
+                username,
+                solved: *solved,
+                score: *score,
+            });
+        }
+    }
+    entries
+}
+
+/// Returns the number of days between 1970-01-01 and the given date of the Gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    // Howard Hinnant's algorithm, with years starting in March
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Splits a Unix timestamp into the date and time of the Gregorian calendar (UTC), as
+/// `(year, month, day, hour, minute, second)`.
+pub(crate) fn datetime_parts(timestamp: u64) -> (i64, u32, u32, u32, u32, u32) {
+    let days = (timestamp / 86400) as i64;
+    let seconds = (timestamp % 86400) as u32;
+    // Inverse of `days_from_civil`
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+    )
+}
+
+/// Formats a Unix timestamp as an RFC 3339 date and time in UTC (`2026-10-20T23:59:00Z`).
+pub(crate) fn format_rfc3339(timestamp: u64) -> String {
+    let (year, month, day, hour, minute, second) = datetime_parts(timestamp);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, hour, minute, second
+    )
+}
+
+/// Parses a date and time as written by PbInfo (`20.10.2026 23:59:05`, `20.10.2026 23:59`) or
+/// in ISO 8601 (`2026-10-20 23:59`, `2026-10-20T23:59:05`), or a date alone (midnight).
+/// Returns the Unix timestamp of that time read as UTC; no time zone is applied, so
+/// times should only be compared to other times parsed the same way.
+pub fn parse_datetime(string: &str) -> Option<u64> {
+    let regex = &selectors::DATETIME;
+
+    let caps = regex.captures(string.trim())?;
+    let number = |i: usize| {
+        caps.get(i)
+            .map(|value| value.as_str().parse::<u32>().unwrap())
+    };
+    let (day, month, year) = match number(3) {
+        Some(year) => (number(1)?, number(2)?, year),
+        None => (number(6)?, number(5)?, number(4)?),
+    };
+    let (hour, minute, second) = (
+        number(7).unwrap_or(0),
+        number(8).unwrap_or(0),
+        number(9).unwrap_or(0),
+    );
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+    let days = days_from_civil(year as i64, month, day);
+    let seconds = days * 86400 + (hour * 3600 + minute * 60 + second) as i64;
+    u64::try_from(seconds).ok()
+}
+
+/// Extracts the rows of a submission history. Each row links to the evaluation
+/// (`/detalii-evaluare/{id}`), the problem and the profile of the author, and has the time of
+/// the submission and a cell with the score.
+pub fn extract_submissions(string: &str) -> Vec<Submission> {
+    let row_regex = &selectors::TABLE_ROW;
+    let cell_regex = &selectors::TD;
+    let evaluation_regex = &selectors::EVALUATION_LINK_ID;
+    let problem_regex = &selectors::PROBLEM_LINK_ID;
+    let user_regex = &selectors::PROFILE_LINK_USERNAME;
+    let datetime_regex = &selectors::DATETIME_TEXT;
+
+    let mut submissions = Vec::new();
+    for row in row_regex.captures_iter(string) {
+        let row = &row[1];
+        let (id, problem, username) = match (
+            evaluation_regex.captures(row),
+            problem_regex.captures(row),
+            user_regex.captures(row),
+        ) {
+            (Some(id), Some(problem), Some(user)) => (id, problem, user),
+            _ => continue,
+        };
+        let (id, problem) = match (id[1].parse::<usize>(), problem[1].parse::<usize>()) {
+            (Ok(id), Ok(problem)) => (id, ProblemId(problem)),
+            _ => continue,
+        };
+        let score = cell_regex
+            .captures_iter(row)
+            .filter(|cell| !cell[1].contains("<a"))
+            .find_map(|cell| {
+                html_to_text(&cell[1])
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|score| *score <= 100)
+            });
+        let submitted_at = datetime_regex
+            .find(&html_to_text(row))
+            .and_then(|datetime| parse_datetime(datetime.as_str()));
+
+        submissions.push(Submission {
+            id,
+            problem,
+            username: username[1].to_owned(),
+            score,
+            submitted_at,
+        });
+    }
+    submissions
+}
+
+/// Extracts the problems listed on a public profile (`/profil/{username}`). Each problem is a
+/// link to its page inside a table row or a list item, optionally followed by the score of the
+/// user. Problems that appear several times are only kept once, with their best score.
+pub fn extract_public_solves(string: &str) -> Vec<PublicSolve> {
+    let item_regex = &selectors::ROW_OR_ITEM;
+    let link_regex = &selectors::PROBLEM_LINK;
+    let tag_regex = &selectors::TAG;
+    let score_regex = &selectors::SCORE;
+
+    let mut solves: Vec<PublicSolve> = Vec::new();
+    for item in item_regex.captures_iter(string) {
+        let item = &item[2];
+        let link = match link_regex.captures(item) {
+            Some(link) => link,
+            None => continue,
+        };
+        let id = match link[1].parse::<usize>() {
+            Ok(id) => ProblemId(id),
+            Err(_) => continue,
+        };
+        let name = html_to_text(&link[2]).trim().to_owned();
+        // Tags are replaced by spaces, so that numbers from adjacent cells are not joined
+        let rest = tag_regex.replace_all(&item[link.get(0).unwrap().end()..], " ");
+        let score = score_regex
+            .captures_iter(&rest)
+            .filter_map(|caps| caps[1].parse::<usize>().ok())
+            .find(|score| *score <= 100);
+
+        match solves.iter_mut().find(|solve| solve.id == id) {
+            Some(solve) => solve.score = solve.score.max(score),
+            None => solves.push(PublicSolve { id, name, score }),
+        }
+    }
+    solves
+}
+
+/// Extracts the examples from the html of the example sections. Each example consists of two
+/// consecutive `<pre>` blocks: the input, then the expected output.
+pub fn extract_examples(string: &str) -> Vec<Example> {
+    let regex = &selectors::PRE;
+    let tags = &selectors::TAG;
+
+    let blocks = regex
+        .captures_iter(string)
+        .map(|caps| {
+            let text = decode_entities(&tags.replace_all(&caps[1], ""));
+            let text = text.trim_matches('\n').trim_end();
+            format!("{}\n", text)
+        })
+        .collect::<Vec<_>>();
+
+    blocks
+        .chunks_exact(2)
+        .map(|pair| Example {
+            input: pair[0].clone(),
+            output: pair[1].clone(),
+        })
+        .collect()
+}
+
+/// Parses the value of a `Retry-After` header, which is either a number of seconds or an
+/// HTTP date (e.g. `Wed, 21 Oct 2015 07:28:00 GMT`). Dates are converted to the time left
+/// until them, counting from `now`.
+pub fn parse_retry_after(value: &str, now: std::time::SystemTime) -> Option<std::time::Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(seconds));
+    }
+
+    let regex = &selectors::HTTP_DATE;
+    let caps = regex.captures(value)?;
+    let number = |i: usize| caps[i].parse::<i64>().unwrap();
+
+    let months = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let month = months.iter().position(|m| *m == &caps[2])? as u32 + 1;
+    let days = days_from_civil(number(3), month, number(1) as u32);
+
+    let timestamp = days * 86400 + number(4) * 3600 + number(5) * 60 + number(6);
+    let date = std::time::UNIX_EPOCH + std::time::Duration::from_secs(timestamp.max(0) as u64);
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+/// Extracts the submission statistics of a problem. PbInfo shows them as labeled numbers
+/// (e.g. `Soluții trimise: 1.234`, `Soluții corecte: 567`, `Scor mediu: 73,5`); the success
+/// rate is computed from the counts when it is not shown.
+pub fn extract_problem_stats(string: &str) -> Result<ProblemStats> {
+    let text = html_to_text(string);
+    let find =
+        |selector: &selectors::Selector| selector.captures(&text).map(|caps| caps[1].to_owned());
+    // Counts use `.` as a thousands separator, scores and percentages use `,` as the decimal point
+    let count = |value: String| value.replace('.', "").parse::<usize>().ok();
+    let decimal = |value: String| value.replace(',', ".").parse::<f64>().ok();
+
+    let (attempts, solved) = match (
+        find(&selectors::STATS_ATTEMPTS).and_then(count),
+        find(&selectors::STATS_SOLVED).and_then(count),
+    ) {
+        (Some(attempts), Some(solved)) => (attempts, solved),
+        _ => {
+            return Err(PbInfoError::RegexError(
+                "Failed to locate the problem statistics in the HTML".to_owned(),
+            ))
+        }
+    };
+
+    let success_rate = find(&selectors::STATS_SUCCESS_RATE)
+        .and_then(decimal)
+        .unwrap_or(if attempts == 0 {
+            0.0
+        } else {
+            solved as f64 * 100.0 / attempts as f64
+        });
+
+    Ok(ProblemStats {
+        attempts,
+        solved,
+        average_score: find(&selectors::STATS_AVERAGE_SCORE).and_then(decimal),
+        success_rate,
+        language_breakdown: extract_language_breakdown(&text),
+    })
+}
+
+/// Extracts the number of correct solutions in every language from the plain text of the
+/// statistics, which list them after a heading or a column mentioning the language ("Limbaj",
+/// "Soluții corecte pe limbaje"). Returns an empty map if there is no such list.
+fn extract_language_breakdown(text: &str) -> std::collections::BTreeMap<String, usize> {
+    let mut breakdown = std::collections::BTreeMap::new();
+    let start = match selectors::LANGUAGE_LABEL.find(text) {
+        Some(heading) => heading.end(),
+        None => return breakdown,
+    };
+    let language = &selectors::LANGUAGE_COUNT;
+    for caps in language.captures_iter(&text[start..]) {
+        let lowercase = caps[1].to_lowercase();
+        let name = match lowercase.as_str() {
+            "c++" => "C++",
+            "c#" => "C#",
+            "c" => "C",
+            "pascal" => "Pascal",
+            "java" => "Java",
+            python if python.starts_with("python") => "Python",
+            // Languages that are not known are kept as they are written
+            _ => caps[1].trim(),
+        };
+        if let Ok(count) = caps[2].replace('.', "").parse::<usize>() {
+            *breakdown.entry(name.to_owned()).or_default() += count;
+        }
+    }
+    breakdown
+}
+
+/// Extracts the chapters of a problem from the breadcrumbs of its page (e.g. `Probleme >
+/// Clasa a IX-a > Tablouri unidimensionale > numere8`). Only the linked items are kept, without
+/// the generic ones at the start, so the problem itself is left out.
+pub fn extract_topics(string: &str) -> Vec<String> {
+    let regex = &selectors::BREADCRUMB;
+    let link_regex = &selectors::LINK_TEXT;
+
+    let breadcrumbs = match regex.captures(string) {
+        Some(caps) => caps[2].to_owned(),
+        None => return Vec::new(),
+    };
+    link_regex
+        .captures_iter(&breadcrumbs)
+        .map(|caps| html_to_text(&caps[1]).trim().to_owned())
+        .filter(|topic| {
+            !topic.is_empty()
+                && !["acasă", "acasa", "probleme", "home"].contains(&topic.to_lowercase().as_str())
+        })
+        .collect()
+}
+
+/// Extracts the files listed under the "Atașamente" heading of a problem page. Each file is a
+/// link, optionally followed by its size (e.g. `enunt.pdf (120 KB)`). Relative urls are
+/// resolved against `DEFAULT_BASE_URL`.
+pub fn extract_attachments(string: &str) -> Vec<Attachment> {
+    let heading_regex = &selectors::ATTACHMENTS_HEADING;
+    let end_regex = &selectors::ATTACHMENTS_END;
+    let link_regex = &selectors::LINK;
+    let size_regex = &selectors::FILE_SIZE;
+
+    let start = match heading_regex.find(string) {
+        Some(heading) => heading.end(),
+        None => return Vec::new(),
+    };
+    let end = end_regex
+        .find(&string[start..])
+        .map_or(string.len(), |end| start + end.start());
+    let section = &string[start..end];
+
+    let links = link_regex.captures_iter(section).collect::<Vec<_>>();
+    links
+        .iter()
+        .enumerate()
+        .map(|(i, link)| {
+            // The size is between this link and the next one
+            let after = link.get(0).unwrap().end();
+            let before_next = links
+                .get(i + 1)
+                .map_or(section.len(), |next| next.get(0).unwrap().start());
+            let url = decode_entities(&link[1]);
+            Attachment {
+                name: html_to_text(&link[2]).trim().to_owned(),
+                url: match url.starts_with('/') {
+                    true => format!("{}{}", DEFAULT_BASE_URL, url),
+                    false => url,
+                },
+                size: size_regex
+                    .find(&html_to_text(&section[after..before_next]))
+                    .and_then(|size| crate::judge::parse_size(size.as_str())),
+            }
+        })
+        .filter(|attachment| !attachment.name.is_empty())
+        .collect()
+}
+
+/// Parses a problem source such as `ONI 2016, clasele XI-XII` or `OJI 2019` into the contest
+/// name, the year and the division. Returns `None` if the source does not start with a name.
+pub fn extract_contest_source(source: &str) -> Option<ContestSource> {
+    let year_regex = &selectors::YEAR;
+    let separators: &[char] = &[' ', ',', ';', '(', ')', '-'];
+
+    // The contest name ends at the year or, without one, at the first separator
+    let (contest, year, rest) = match year_regex.find(source) {
+        Some(year) => (
+            &source[..year.start()],
+            Some(year.as_str().parse().unwrap()),
+            &source[year.end()..],
+        ),
+        None => match source.find([',', ';', '(']) {
+            Some(pos) => (&source[..pos], None, &source[pos..]),
+            None => (source, None, ""),
+        },
+    };
+
+    let contest = contest.trim_matches(separators);
+    if contest.is_empty() {
+        return None;
+    }
+    let division = rest.trim_matches(separators);
+    Some(ContestSource {
+        contest: contest.to_owned(),
+        year,
+        division: (!division.is_empty()).then(|| division.to_owned()),
+        raw: source.to_owned(),
+    })
+}
+
+/// Returns `PbInfoError::SiteUnavailable` if `string` is PbInfo's maintenance or holiday page,
+/// with the title of the page (or its first heading) as the notice.
+pub fn check_site_available(string: &str) -> Result<()> {
+    let marker_regex = &selectors::MAINTENANCE;
+    let title_regex = &selectors::NOTICE_TITLE;
+
+    if !marker_regex.is_match(&html_to_text(string)) {
+        return Ok(());
+    }
+    let notice = title_regex
+        .captures_iter(string)
+        .map(|caps| html_to_text(&caps[1]).trim().to_owned())
+        .find(|notice| !notice.is_empty())
+        .unwrap_or_else(|| "PbInfo is temporarily unavailable".to_owned());
+    Err(PbInfoError::SiteUnavailable(notice))
+}
+
+/// Decodes the body of a page using the charset declared in `content_type` or in a `<meta>`
+/// tag. Without a declared charset, UTF-8 is assumed, falling back to Windows-1250 (used by
+/// older Romanian pages) when the body is not valid UTF-8. Invalid sequences are replaced
+/// instead of failing.
+pub fn decode_html(bytes: &[u8], content_type: Option<&str>) -> String {
+    let charset_regex = &selectors::CONTENT_TYPE_CHARSET;
+    let meta_regex = &selectors::META_CHARSET;
+
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(2048)]);
+    let declared = content_type
+        .and_then(|content_type| charset_regex.captures(content_type))
+        .or_else(|| meta_regex.captures(&head))
+        .and_then(|caps| encoding_rs::Encoding::for_label(caps[1].as_bytes()));
+
+    let encoding = match declared {
+        Some(encoding) => encoding,
+        None if std::str::from_utf8(bytes).is_ok() => encoding_rs::UTF_8,
+        None => encoding_rs::WINDOWS_1250,
+    };
+    encoding.decode(bytes).0.into_owned()
 }
@@ -0,0 +1,111 @@
+//! Atom feeds of problems, so that feed readers can follow the problems added to PbInfo (or to
+//! a grade or a chapter of it) without a custom client.
+
+use crate::*;
+
+/// What `generate` puts in a feed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FeedOptions {
+    /// Title of the feed
+    pub title: String,
+    /// Url of the feed itself, also used as its id
+    pub url: String,
+    /// When the feed was last updated, as a Unix timestamp (also the date of the entries, since
+    /// PbInfo does not show when a problem was published)
+    pub updated: u64,
+    /// Only the problems that match this filter are included
+    pub filter: ProblemFilter,
+    /// Most entries in the feed
+    pub max_entries: usize,
+}
+
+impl FeedOptions {
+    /// A feed at `url` with the 50 newest problems, updated at `updated`.
+    pub fn new(title: &str, url: &str, updated: u64) -> Self {
+        FeedOptions {
+            title: title.to_owned(),
+            url: url.to_owned(),
+            updated,
+            filter: ProblemFilter::new(),
+            max_entries: 50,
+        }
+    }
+}
+
+/// Escapes the characters of `text` that have a meaning in XML.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Generates an Atom feed of the newest problems of `problems` that match `options.filter`.
+/// Problems are ordered by id, newest (largest) first, since ids are given in order of
+/// publication. Every entry links to the problem, has its chapters as categories and the
+/// beginning of the statement as summary.
+pub fn generate<'a>(
+    problems: impl IntoIterator<Item = &'a PbInfoProblem>,
+    options: &FeedOptions,
+) -> String {
+    let mut problems = options.filter.apply(problems);
+    problems.sort_by_key(|problem| std::cmp::Reverse(problem.id));
+    problems.dedup_by_key(|problem| problem.id);
+    problems.truncate(options.max_entries);
+    let updated = format_rfc3339(options.updated);
+
+    let mut feed = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <feed xmlns=\"http://www.w3.org/2005/Atom\">\n\
+         <title>{}</title>\n\
+         <id>{}</id>\n\
+         <link rel=\"self\" href=\"{}\"/>\n\
+         <updated>{}</updated>\n\
+         <author><name>PbInfo</name></author>\n",
+        escape_xml(&options.title),
+        escape_xml(&options.url),
+        escape_xml(&options.url),
+        updated
+    );
+    for problem in problems {
+        let url = format!(
+            "https://www.pbinfo.ro/probleme/{}/{}",
+            problem.id, problem.name
+        );
+        let summary = html_to_text(&problem.problem_text);
+        let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+        let summary = match summary.char_indices().nth(300) {
+            Some((end, _)) => format!("{}…", &summary[..end]),
+            None => summary,
+        };
+
+        feed.push_str(&format!(
+            "<entry>\n<title>#{} {}</title>\n<id>{}</id>\n<link href=\"{}\"/>\n<updated>{}</updated>\n",
+            problem.id,
+            escape_xml(problem.name.as_str()),
+            escape_xml(&url),
+            escape_xml(&url),
+            updated
+        ));
+        if let Some(author) = &problem.author {
+            feed.push_str(&format!(
+                "<author><name>{}</name></author>\n",
+                escape_xml(author)
+            ));
+        }
+        feed.push_str(&format!(
+            "<category term=\"clasa-{}\" label=\"Clasa a {}-a\"/>\n",
+            problem.grade, problem.grade
+        ));
+        for topic in &problem.topics {
+            feed.push_str(&format!("<category term=\"{}\"/>\n", escape_xml(topic)));
+        }
+        feed.push_str(&format!(
+            "<summary>{}</summary>\n</entry>\n",
+            escape_xml(&summary)
+        ));
+    }
+    feed.push_str("</feed>\n");
+    feed
+}
@@ -0,0 +1,55 @@
+use crate::*;
+
+impl PbInfoProblem {
+    /// Construct PbInfoProblem from id.
+    pub fn fetch_problem_by_id(id: ProblemId) -> Result<Self> {
+        PbInfoClient::shared().fetch_problem_by_id(id)
+    }
+
+    /// Refetch a previously fetched PbInfoProblem. A conditional request is made using the
+    /// cached `etag`/`last_modified` validators, and the page is only reparsed if its statement
+    /// or metadata differ from the cached ones.
+    pub fn fetch_problem_refresh(cached: &PbInfoProblem) -> Result<(Self, Freshness)> {
+        PbInfoClient::shared().fetch_problem_refresh(cached)
+    }
+
+    /// Checks whether a problem with the given id exists, without downloading its page.
+    pub fn exists(id: ProblemId) -> Result<bool> {
+        PbInfoClient::shared().problem_exists(id)
+    }
+
+    /// Construct PbInfoProblem from name.
+    pub fn fetch_problem_by_name(name: &str) -> Result<Self> {
+        PbInfoClient::shared().fetch_problem_by_name(name)
+    }
+
+    /// Construct PbInfoProblem from a problem url (e.g. `https://www.pbinfo.ro/probleme/1691/arbore1?tab=1`).
+    pub fn fetch_problem_by_url(url: &str) -> Result<Self> {
+        PbInfoClient::shared().fetch_problem_by_url(url)
+    }
+}
+
+/// Returns the ranking ("clasament") of the users that match `filter`.
+pub fn fetch_rankings(filter: &RankingFilter) -> Result<Vec<RankEntry>> {
+    PbInfoClient::shared().fetch_rankings(filter)
+}
+
+/// Returns the submissions that match `filter`, newest first.
+pub fn fetch_submissions(filter: &SubmissionFilter) -> Result<Vec<Submission>> {
+    PbInfoClient::shared().fetch_submissions(filter)
+}
+
+/// Returns the submission statistics of the problem with the given id.
+pub fn fetch_problem_stats(id: ProblemId) -> Result<ProblemStats> {
+    PbInfoClient::shared().fetch_problem_stats(id)
+}
+
+/// Returns the article (lesson) with the id `id`.
+pub fn fetch_article(id: usize) -> Result<Article> {
+    PbInfoClient::shared().fetch_article(id)
+}
+
+/// Returns the problems listed on the public profile of `username`.
+pub fn fetch_public_solves(username: &str) -> Result<Vec<PublicSolve>> {
+    PbInfoClient::shared().fetch_public_solves(username)
+}
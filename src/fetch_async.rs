@@ -0,0 +1,117 @@
+use crate::*;
+use std::sync::OnceLock;
+
+/// The client used by the asynchronous fetch functions. Initialized on first use.
+static ASYNC_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// Address of PbInfo used by the asynchronous fetch functions. Initialized on first use.
+static ASYNC_BASE_URL: OnceLock<String> = OnceLock::new();
+
+/// Returns the url of `path` (which starts with a slash) on PbInfo.
+fn url(path: &str) -> String {
+    format!("{}{}", ASYNC_BASE_URL.get_or_init(base_url_from_env), path)
+}
+
+/// Makes an asynchronous get request to `url`
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug"))]
+async fn get_page_async(url: &str) -> Result<reqwest::Response> {
+    let client = ASYNC_CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .build()
+            .expect("Could not initialize the http client")
+    });
+    let page = client.get(url).send().await.map_err(|err| {
+        PbInfoError::NetworkError(format!(
+            "Encountered an error while making a request to pbinfo.ro: {}",
+            err
+        ))
+    })?;
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(status = %page.status(), "received response");
+
+    // There is no portable way to sleep here, so throttling is left to the caller. Only the
+    // seconds form of Retry-After is read, as the system clock is not available on wasm
+    if page.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = page
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(std::time::Duration::from_secs);
+        return Err(PbInfoError::RateLimited { retry_after });
+    }
+    Ok(page)
+}
+
+/// Returns the JSON of the search endpoint for `query`, without blocking.
+async fn search_json_async(query: &str) -> Result<Vec<std::collections::HashMap<String, String>>> {
+    let url = reqwest::Url::parse_with_params(&url("/php/ajax-search.php"), &[("term", query)])
+        .map_err(|err| PbInfoError::Error(format!("Invalid search url: {}", err)))?;
+    match get_page_async(url.as_str())
+        .await?
+        .json::<Vec<std::collections::HashMap<String, String>>>()
+        .await
+    {
+        Ok(res) => Ok(res),
+        Err(_) => Err(PbInfoError::JSONError(
+            "Could not parse JSON response".to_owned(),
+        )),
+    }
+}
+
+impl PbInfoProblem {
+    /// Construct PbInfoProblem from id, without blocking. This is the only way to fetch
+    /// problems on `wasm32-unknown-unknown`, where the blocking client is not available.
+    #[cfg_attr(feature = "tracing", tracing::instrument(err(Debug)))]
+    pub async fn fetch_problem_by_id_async(id: ProblemId) -> Result<Self> {
+        let page = get_page_async(&url(&format!("/probleme/{}", id))).await?;
+
+        match page.status() {
+            reqwest::StatusCode::OK => {
+                let content_type = page
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.to_owned());
+                let bytes = page.bytes().await.map_err(|err| {
+                    PbInfoError::NetworkError(format!("Could not read the response body: {}", err))
+                })?;
+                Self::from_html(id, &decode_html(&bytes, content_type.as_deref()))
+            }
+            reqwest::StatusCode::NOT_FOUND => Err(PbInfoError::UnknownId(id)),
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to fetch the problem. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+
+    /// Construct PbInfoProblem from name, without blocking.
+    #[cfg_attr(feature = "tracing", tracing::instrument(err(Debug)))]
+    pub async fn fetch_problem_by_name_async(name: &str) -> Result<Self> {
+        // Searched like `PbInfoClient::fetch_problem_by_name`
+        let name = ProblemName::new(name)?;
+        let name = name.as_str();
+
+        let mut suggestions: Vec<Suggestion> = Vec::new();
+        for query in name_search_queries(name) {
+            let search_json = search_json_async(&query).await?;
+            match extract_id_from_search(name, &search_json) {
+                Ok(id) => return Self::fetch_problem_by_id_async(ProblemId(id)).await,
+                Err(PbInfoError::UnknownName(_, found)) => {
+                    merge_suggestions(&mut suggestions, found)
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(PbInfoError::UnknownName(name.to_owned(), suggestions))
+    }
+
+    /// Construct PbInfoProblem from a problem url, without blocking.
+    #[cfg_attr(feature = "tracing", tracing::instrument(err(Debug)))]
+    pub async fn fetch_problem_by_url_async(url: &str) -> Result<Self> {
+        Self::fetch_problem_by_id_async(ProblemId(extract_id_from_url(url)?)).await
+    }
+}
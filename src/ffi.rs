@@ -0,0 +1,230 @@
+//! A minimal C ABI, for tools written in C, C++ or Python (through `ctypes`). Build the shared
+//! library with
+//!
+//! ```sh
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! ```
+//!
+//! and include `include/pbinfo.h`. Every string is UTF-8 and NUL-terminated, and is owned by
+//! the library: problems have to be released with `pbinfo_free_problem`.
+
+use crate::*;
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::ptr;
+
+/// The call succeeded.
+pub const PBINFO_OK: c_int = 0;
+/// An argument is invalid (e.g. a null pointer).
+pub const PBINFO_ERR_INVALID_ARGUMENT: c_int = 1;
+/// There is no problem with this id.
+pub const PBINFO_ERR_UNKNOWN_ID: c_int = 2;
+/// The request failed, or PbInfo answered with an unexpected status.
+pub const PBINFO_ERR_NETWORK: c_int = 3;
+/// PbInfo throttled the requests.
+pub const PBINFO_ERR_RATE_LIMITED: c_int = 4;
+/// PbInfo is down or under maintenance.
+pub const PBINFO_ERR_SITE_UNAVAILABLE: c_int = 5;
+/// The page could not be parsed.
+pub const PBINFO_ERR_PARSE: c_int = 6;
+/// Any other error (including a panic inside the library).
+pub const PBINFO_ERR_OTHER: c_int = 99;
+
+/// An example of a problem.
+#[repr(C)]
+#[derive(Debug)]
+pub struct PbInfoCExample {
+    /// Contents of the input file (or stdin)
+    pub input: *mut c_char,
+    /// Expected contents of the output file (or stdout)
+    pub output: *mut c_char,
+}
+
+/// A problem, as filled in by `pbinfo_fetch_problem`. Optional strings are null when missing.
+#[repr(C)]
+#[derive(Debug)]
+pub struct PbInfoCProblem {
+    /// Unique id of problem
+    pub id: usize,
+    /// Unique name of problem
+    pub name: *mut c_char,
+    /// Grade (9, 10 or 11)
+    pub grade: usize,
+    /// File name of the input, or null for stdin
+    pub input_file: *mut c_char,
+    /// File name of the output, or null for stdout
+    pub output_file: *mut c_char,
+    /// Time limit (optional)
+    pub time_limit: *mut c_char,
+    /// Memory limit (optional)
+    pub memory_limit: *mut c_char,
+    /// Statement as plain text
+    pub statement: *mut c_char,
+    /// Statement as html
+    pub statement_html: *mut c_char,
+    /// Examples of the statement (null if there are none)
+    pub examples: *mut PbInfoCExample,
+    /// Number of examples
+    pub examples_len: usize,
+}
+
+thread_local! {
+    /// Message of the last error of the current thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Converts `string` into an owned C string, dropping any NUL character.
+fn to_c_string(string: &str) -> *mut c_char {
+    CString::new(string.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Converts an optional string into an owned C string, or null.
+fn to_optional_c_string(string: Option<&str>) -> *mut c_char {
+    string.map(to_c_string).unwrap_or(ptr::null_mut())
+}
+
+/// Releases a string created by `to_c_string`.
+///
+/// # Safety
+///
+/// `string` has to be null or come from `to_c_string`, and must not be used afterwards.
+unsafe fn free_c_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Stores `message` as the last error of the current thread.
+fn set_last_error(message: &str) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(message.replace('\0', "")).ok());
+}
+
+/// Returns the error code of `err`.
+pub(crate) fn error_code(err: &PbInfoError) -> c_int {
+    match err {
+        PbInfoError::UnknownId(_) | PbInfoError::UnknownName(..) => PBINFO_ERR_UNKNOWN_ID,
+        PbInfoError::NetworkError(_) => PBINFO_ERR_NETWORK,
+        PbInfoError::RateLimited { .. } => PBINFO_ERR_RATE_LIMITED,
+        PbInfoError::SiteUnavailable(_) | PbInfoError::CircuitOpen { .. } => {
+            PBINFO_ERR_SITE_UNAVAILABLE
+        }
+        PbInfoError::RegexError(_) | PbInfoError::JSONError(_) => PBINFO_ERR_PARSE,
+        _ => PBINFO_ERR_OTHER,
+    }
+}
+
+/// Converts `problem` into its C representation, which owns copies of its strings.
+pub(crate) fn problem_to_c(problem: &PbInfoProblem) -> PbInfoCProblem {
+    let file = |source: &IOSource| match source {
+        IOSource::File(name) => to_c_string(name),
+        IOSource::Std => ptr::null_mut(),
+    };
+    let examples = problem
+        .examples()
+        .iter()
+        .map(|example| PbInfoCExample {
+            input: to_c_string(&example.input),
+            output: to_c_string(&example.output),
+        })
+        .collect::<Box<[_]>>();
+    let examples_len = examples.len();
+
+    PbInfoCProblem {
+        id: problem.id.0,
+        name: to_c_string(problem.name.as_str()),
+        grade: problem.grade.0,
+        input_file: file(&problem.input_source),
+        output_file: file(&problem.output_source),
+        time_limit: to_optional_c_string(problem.time_limit.as_deref()),
+        memory_limit: to_optional_c_string(problem.memory_limit.as_deref()),
+        statement: to_c_string(&html_to_text(&problem.problem_text)),
+        statement_html: to_c_string(&problem.problem_text),
+        examples: match examples_len {
+            0 => ptr::null_mut(),
+            _ => Box::into_raw(examples) as *mut PbInfoCExample,
+        },
+        examples_len,
+    }
+}
+
+/// Fetches the problem `id` into `out`. Returns `PBINFO_OK`, or an error code whose message
+/// can be read with `pbinfo_last_error`; `out` is only written on success.
+///
+/// # Safety
+///
+/// `out` has to be null or point to memory where a `PbInfoCProblem` can be written.
+#[no_mangle]
+pub unsafe extern "C" fn pbinfo_fetch_problem(id: usize, out: *mut PbInfoCProblem) -> c_int {
+    if out.is_null() {
+        set_last_error("`out` is null");
+        return PBINFO_ERR_INVALID_ARGUMENT;
+    }
+    let result = std::panic::catch_unwind(|| PbInfoProblem::fetch_problem_by_id(ProblemId(id)));
+    match result {
+        Ok(Ok(problem)) => {
+            out.write(problem_to_c(&problem));
+            PBINFO_OK
+        }
+        Ok(Err(err)) => {
+            set_last_error(&format!("{:?}", err));
+            error_code(&err)
+        }
+        Err(_) => {
+            set_last_error("The library panicked");
+            PBINFO_ERR_OTHER
+        }
+    }
+}
+
+/// Releases the strings and examples of a problem filled in by `pbinfo_fetch_problem`, and sets
+/// its pointers to null. Does nothing if `problem` is null.
+///
+/// # Safety
+///
+/// `problem` has to be null or point to a problem filled in by `pbinfo_fetch_problem` that was
+/// not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn pbinfo_free_problem(problem: *mut PbInfoCProblem) {
+    let problem = match problem.as_mut() {
+        Some(problem) => problem,
+        None => return,
+    };
+    for string in [
+        &mut problem.name,
+        &mut problem.input_file,
+        &mut problem.output_file,
+        &mut problem.time_limit,
+        &mut problem.memory_limit,
+        &mut problem.statement,
+        &mut problem.statement_html,
+    ] {
+        free_c_string(*string);
+        *string = ptr::null_mut();
+    }
+    if !problem.examples.is_null() {
+        let examples = Box::from_raw(ptr::slice_from_raw_parts_mut(
+            problem.examples,
+            problem.examples_len,
+        ));
+        for example in examples.iter() {
+            free_c_string(example.input);
+            free_c_string(example.output);
+        }
+        problem.examples = ptr::null_mut();
+        problem.examples_len = 0;
+    }
+}
+
+/// Returns the message of the last error of the current thread, or null if there was none. The
+/// string stays valid until the next call into the library from the same thread.
+#[no_mangle]
+pub extern "C" fn pbinfo_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_deref()
+            .map(CStr::as_ptr)
+            .unwrap_or(ptr::null())
+    })
+}
@@ -0,0 +1,165 @@
+use crate::*;
+use std::collections::BTreeSet;
+
+/// Selects problems by their metadata. Every condition that is set has to hold; a filter
+/// without conditions matches every problem.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ProblemFilter {
+    /// Smallest and largest grade
+    grades: Option<(Grade, Grade)>,
+    /// Allowed difficulties (any difficulty if empty)
+    difficulties: Vec<Difficulty>,
+    /// Whether the statement has to have (or not have) examples
+    has_tests: Option<bool>,
+    /// Ids of the solved problems, and whether the problems have to be solved or unsolved
+    solved: Option<(BTreeSet<ProblemId>, bool)>,
+    /// Normalized text that has to appear in one of the topics
+    topic: Option<String>,
+    /// Name of the author
+    author: Option<String>,
+}
+
+impl ProblemFilter {
+    /// Creates a filter that matches every problem.
+    pub fn new() -> Self {
+        ProblemFilter::default()
+    }
+
+    /// Keeps the problems for the grades between `min` and `max` (inclusive).
+    pub fn grades(mut self, min: Grade, max: Grade) -> Self {
+        self.grades = Some((min, max));
+        self
+    }
+
+    /// Keeps the problems for `grade`.
+    pub fn grade(self, grade: Grade) -> Self {
+        self.grades(grade, grade)
+    }
+
+    /// Adds `difficulty` to the allowed difficulties. Problems without a difficulty do not match
+    /// a filter with allowed difficulties.
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        if !self.difficulties.contains(&difficulty) {
+            self.difficulties.push(difficulty);
+        }
+        self
+    }
+
+    /// Keeps the problems whose statement has examples that can be used as tests (or, if
+    /// `has_tests` is false, the ones without any).
+    pub fn has_tests(mut self, has_tests: bool) -> Self {
+        self.has_tests = Some(has_tests);
+        self
+    }
+
+    /// Keeps the problems in `solved` (the ids of the problems solved by a user).
+    pub fn solved(mut self, solved: impl IntoIterator<Item = ProblemId>) -> Self {
+        self.solved = Some((solved.into_iter().collect(), true));
+        self
+    }
+
+    /// Keeps the problems that are not in `solved` (the ids of the problems solved by a user).
+    pub fn unsolved(mut self, solved: impl IntoIterator<Item = ProblemId>) -> Self {
+        self.solved = Some((solved.into_iter().collect(), false));
+        self
+    }
+
+    /// Keeps the problems with a topic that contains `text`, ignoring case and diacritics.
+    pub fn topic(mut self, text: &str) -> Self {
+        self.topic = Some(normalize_text(text));
+        self
+    }
+
+    /// Keeps the problems attributed to `name` (see `author_matches`).
+    pub fn author(mut self, name: &str) -> Self {
+        self.author = Some(name.to_owned());
+        self
+    }
+
+    /// Returns `true` if `problem` satisfies every condition of the filter.
+    pub fn matches(&self, problem: &PbInfoProblem) -> bool {
+        if let Some((min, max)) = self.grades {
+            if problem.grade < min || problem.grade > max {
+                return false;
+            }
+        }
+        if !self.difficulties.is_empty()
+            && !problem
+                .difficulty
+                .as_ref()
+                .is_some_and(|difficulty| self.difficulties.contains(difficulty))
+        {
+            return false;
+        }
+        if let Some((solved, wanted)) = &self.solved {
+            if solved.contains(&problem.id) != *wanted {
+                return false;
+            }
+        }
+        if let Some(topic) = &self.topic {
+            if !problem
+                .topics
+                .iter()
+                .any(|name| normalize_text(name).contains(topic.as_str()))
+            {
+                return false;
+            }
+        }
+        if let Some(name) = &self.author {
+            if !problem
+                .author
+                .as_deref()
+                .is_some_and(|author| author_matches(author, name))
+            {
+                return false;
+            }
+        }
+        // Checked last, since it parses the statement
+        match self.has_tests {
+            Some(has_tests) => problem.examples().is_empty() != has_tests,
+            None => true,
+        }
+    }
+
+    /// Returns the problems of `problems` that match the filter, in order.
+    pub fn apply<'a>(
+        &self,
+        problems: impl IntoIterator<Item = &'a PbInfoProblem>,
+    ) -> Vec<&'a PbInfoProblem> {
+        problems
+            .into_iter()
+            .filter(|problem| self.matches(problem))
+            .collect()
+    }
+
+    /// Picks one of the problems that match the filter, using `seed` to choose (the same seed
+    /// picks the same problem from the same list). Returns `None` if no problem matches.
+    pub fn pick<'a>(
+        &self,
+        problems: impl IntoIterator<Item = &'a PbInfoProblem>,
+        seed: u64,
+    ) -> Option<&'a PbInfoProblem> {
+        let matching = self.apply(problems);
+        if matching.is_empty() {
+            return None;
+        }
+        // SplitMix64, so that close seeds pick unrelated problems
+        let mut x = seed.wrapping_add(0x9e3779b97f4a7c15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+        x ^= x >> 31;
+        Some(matching[(x % matching.len() as u64) as usize])
+    }
+
+    /// Picks a random problem among the ones that match the filter.
+    pub fn pick_random<'a>(
+        &self,
+        problems: impl IntoIterator<Item = &'a PbInfoProblem>,
+    ) -> Option<&'a PbInfoProblem> {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default();
+        self.pick(problems, seed)
+    }
+}
@@ -0,0 +1,303 @@
+//! Golden-file tests for the parser: a directory of captured problem pages (`{id}.html`), each
+//! with the extraction expected from it (`{id}.json`, see `golden_record`). Any change to the
+//! parser that changes what is extracted from a page shows up as a mismatch, which is either a
+//! bug or, once reviewed, a reason to update the golden file.
+//!
+//! ```no_run
+//! // In a test: fails with every mismatch, or rewrites the golden files when the
+//! // `PBINFO_UPDATE_GOLDEN` environment variable is set
+//! pbinfo::golden::assert_fixtures("fixtures/problems");
+//! ```
+
+use crate::*;
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that makes `assert_fixtures` rewrite the golden files instead of
+/// comparing them.
+pub const UPDATE_ENV: &str = "PBINFO_UPDATE_GOLDEN";
+
+/// A captured problem page and its golden file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Fixture {
+    /// Id of the problem
+    pub id: ProblemId,
+    /// Path of the page (`{id}.html`)
+    pub html: PathBuf,
+    /// Path of the golden file (`{id}.json`, which may not exist yet)
+    pub golden: PathBuf,
+}
+
+/// A value extracted from a fixture that differs from its golden file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GoldenMismatch {
+    /// The problem
+    pub id: ProblemId,
+    /// Where the value is in the record, as a JSON pointer (e.g. `/problem/time_limit`)
+    pub pointer: String,
+    /// The value in the golden file (`null` if it is missing)
+    pub expected: Value,
+    /// The value extracted now (`null` if it is missing)
+    pub actual: Value,
+}
+
+impl fmt::Display for GoldenMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{} {}: expected {}, got {}",
+            self.id, self.pointer, self.expected, self.actual
+        )
+    }
+}
+
+/// Returns everything extracted from the page of the problem `id`, as stored in golden files:
+/// an object with
+///
+/// - `problem`: the problem in the public JSON schema (see `PbInfoProblem::to_json_v1`)
+/// - `sections`: the titles of the sections of the statement
+/// - `examples`: list of `{"input", "output"}` objects
+/// - `subtasks`: list of `{"points", "constraints"}` objects
+/// - `extraction`: the strategy that located each part of the page (see `extraction_report`)
+///
+/// Pages that cannot be parsed (e.g. maintenance pages) are recorded as `{"error": ...}`, so
+/// that they keep failing the same way.
+pub fn golden_record(id: ProblemId, html: &str) -> Value {
+    let problem = match PbInfoProblem::from_html(id, html) {
+        Ok(problem) => problem,
+        Err(err) => return serde_json::json!({ "error": format!("{:?}", err) }),
+    };
+    let report = extraction_report(html);
+    serde_json::json!({
+        "problem": problem.to_json_v1(),
+        "sections": problem
+            .sections()
+            .into_iter()
+            .map(|section| section.title)
+            .collect::<Vec<_>>(),
+        "examples": problem
+            .examples()
+            .into_iter()
+            .map(|example| serde_json::json!({ "input": example.input, "output": example.output }))
+            .collect::<Vec<_>>(),
+        "subtasks": problem
+            .subtasks()
+            .into_iter()
+            .map(|subtask| {
+                serde_json::json!({ "points": subtask.points, "constraints": subtask.constraints })
+            })
+            .collect::<Vec<_>>(),
+        "extraction": {
+            "name": report.name,
+            "problem_text": report.problem_text,
+            "metadata": report.metadata,
+        },
+    })
+}
+
+/// Returns the fixtures in `dir` (every `{id}.html`), in increasing order of id.
+pub fn fixtures(dir: impl AsRef<Path>) -> Result<Vec<Fixture>> {
+    let dir = dir.as_ref();
+    let entries = std::fs::read_dir(dir).map_err(|err| {
+        PbInfoError::IOError(format!("Could not read `{}`: {}", dir.display(), err))
+    })?;
+    let mut fixtures = entries
+        .filter_map(|entry| {
+            let html = entry.ok()?.path();
+            if html.extension()? != "html" {
+                return None;
+            }
+            let id = html.file_stem()?.to_str()?.parse().ok()?;
+            Some(Fixture {
+                id: ProblemId(id),
+                golden: html.with_extension("json"),
+                html,
+            })
+        })
+        .collect::<Vec<_>>();
+    fixtures.sort_by_key(|fixture| fixture.id);
+    Ok(fixtures)
+}
+
+/// Reads a file of a fixture.
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|err| {
+        PbInfoError::IOError(format!("Could not read `{}`: {}", path.display(), err))
+    })
+}
+
+/// Appends the differences between `expected` and `actual`, found under `pointer`.
+fn compare(
+    id: ProblemId,
+    pointer: &str,
+    expected: &Value,
+    actual: &Value,
+    mismatches: &mut Vec<GoldenMismatch>,
+) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let mut keys = expected.keys().chain(actual.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                // `~` and `/` are escaped in JSON pointers
+                let pointer = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                let (expected, actual) = (
+                    expected.get(key).unwrap_or(&Value::Null),
+                    actual.get(key).unwrap_or(&Value::Null),
+                );
+                compare(id, &pointer, expected, actual, mismatches);
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            for index in 0..expected.len().max(actual.len()) {
+                let (expected, actual) = (
+                    expected.get(index).unwrap_or(&Value::Null),
+                    actual.get(index).unwrap_or(&Value::Null),
+                );
+                compare(
+                    id,
+                    &format!("{}/{}", pointer, index),
+                    expected,
+                    actual,
+                    mismatches,
+                );
+            }
+        }
+        (expected, actual) if expected != actual => mismatches.push(GoldenMismatch {
+            id,
+            pointer: pointer.to_owned(),
+            expected: expected.clone(),
+            actual: actual.clone(),
+        }),
+        _ => {}
+    }
+}
+
+impl Fixture {
+    /// Extracts the page and compares the result with the golden file. Fails if the golden
+    /// file does not exist.
+    pub fn check(&self) -> Result<Vec<GoldenMismatch>> {
+        let actual = golden_record(self.id, &read(&self.html)?);
+        let expected: Value = serde_json::from_str(&read(&self.golden)?).map_err(|err| {
+            PbInfoError::JSONError(format!("`{}`: {}", self.golden.display(), err))
+        })?;
+        let mut mismatches = Vec::new();
+        compare(self.id, "", &expected, &actual, &mut mismatches);
+        Ok(mismatches)
+    }
+
+    /// Writes the golden file from the page, as the parser extracts it now.
+    pub fn update(&self) -> Result<()> {
+        let record = golden_record(self.id, &read(&self.html)?);
+        let text = serde_json::to_string_pretty(&record).unwrap();
+        std::fs::write(&self.golden, text + "\n").map_err(|err| {
+            PbInfoError::IOError(format!(
+                "Could not write `{}`: {}",
+                self.golden.display(),
+                err
+            ))
+        })
+    }
+}
+
+/// Checks every fixture in `dir` against its golden file, returning all the mismatches.
+pub fn check_fixtures(dir: impl AsRef<Path>) -> Result<Vec<GoldenMismatch>> {
+    let mut mismatches = Vec::new();
+    for fixture in fixtures(dir)? {
+        mismatches.extend(fixture.check()?);
+    }
+    Ok(mismatches)
+}
+
+/// Writes the golden file of every fixture in `dir`. Returns the number of fixtures.
+pub fn update_fixtures(dir: impl AsRef<Path>) -> Result<usize> {
+    let fixtures = fixtures(dir)?;
+    for fixture in &fixtures {
+        fixture.update()?;
+    }
+    Ok(fixtures.len())
+}
+
+/// Checks every fixture in `dir` (see `check_fixtures`), panicking with the list of mismatches
+/// if there are any. If the `PBINFO_UPDATE_GOLDEN` environment variable is set, the golden
+/// files are rewritten instead.
+pub fn assert_fixtures(dir: impl AsRef<Path>) {
+    let dir = dir.as_ref();
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        update_fixtures(dir).unwrap();
+        return;
+    }
+    let mismatches = check_fixtures(dir).unwrap();
+    if !mismatches.is_empty() {
+        let lines = mismatches
+            .iter()
+            .map(|mismatch| format!("  {}", mismatch))
+            .collect::<Vec<_>>();
+        panic!(
+            "{} value(s) differ from the golden files in `{}` (set {}=1 to update them):\n{}",
+            mismatches.len(),
+            dir.display(),
+            UPDATE_ENV,
+            lines.join("\n")
+        );
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl PbInfoClient {
+    /// Downloads the page of the problem `id` to `dir/{id}.html` and writes its golden file,
+    /// adding it to the fixtures (or replacing it).
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, dir), err(Debug)))]
+    pub fn capture_fixture(&self, id: ProblemId, dir: impl AsRef<Path>) -> Result<Fixture> {
+        let dir = dir.as_ref();
+        let page = self.get_page(&self.url(&format!("/probleme/{}", id)))?;
+        match page.status() {
+            reqwest::StatusCode::OK => {}
+            reqwest::StatusCode::NOT_FOUND => return Err(PbInfoError::UnknownId(id)),
+            s => {
+                return Err(PbInfoError::NetworkError(format!(
+                    "Encountered an error when trying to fetch the problem. HTTP status code {}",
+                    s
+                )))
+            }
+        }
+        let text = self.read_text(page)?;
+
+        std::fs::create_dir_all(dir).map_err(|err| {
+            PbInfoError::IOError(format!("Could not create `{}`: {}", dir.display(), err))
+        })?;
+        let html = dir.join(format!("{}.html", id));
+        std::fs::write(&html, text).map_err(|err| {
+            PbInfoError::IOError(format!("Could not write `{}`: {}", html.display(), err))
+        })?;
+        let fixture = Fixture {
+            id,
+            golden: html.with_extension("json"),
+            html,
+        };
+        fixture.update()?;
+        Ok(fixture)
+    }
+
+    /// Downloads the pages of every fixture in `dir` again, rewriting the golden files. Returns
+    /// how the extraction changed compared to the old golden files, to be reviewed before the
+    /// new fixtures are committed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, dir), err(Debug)))]
+    pub fn refresh_fixtures(&self, dir: impl AsRef<Path>) -> Result<Vec<GoldenMismatch>> {
+        let dir = dir.as_ref();
+        let mut changes = Vec::new();
+        for fixture in fixtures(dir)? {
+            let old = read(&fixture.golden)
+                .ok()
+                .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+                .unwrap_or(Value::Null);
+            self.capture_fixture(fixture.id, dir)?;
+            let new = serde_json::from_str::<Value>(&read(&fixture.golden)?)
+                .map_err(|err| PbInfoError::JSONError(err.to_string()))?;
+            compare(fixture.id, "", &old, &new, &mut changes);
+        }
+        Ok(changes)
+    }
+}
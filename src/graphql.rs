@@ -0,0 +1,461 @@
+//! A GraphQL schema over the problem model, for dashboards that need more than a few fixed
+//! queries. The resolvers use the client given to `schema`, so its caches are shared by every
+//! query:
+//!
+//! ```graphql
+//! {
+//!   problem(id: 1691) { name grade examples { input output } stats { successRate } }
+//!   user(username: "ana") { solvedCount submissions(limit: 5) { score problem { name } } }
+//!   category(name: "Grafuri") { problems { id name } }
+//! }
+//! ```
+//!
+//! The client makes blocking requests, so queries should be executed with `execute_blocking`
+//! (or on a thread where blocking is allowed, e.g. in `tokio::task::spawn_blocking`).
+//!
+//! A query can not make the client crawl the site: submissions are fetched 20 at a time by
+//! default and at most 100 at a time, and queries are limited in depth and complexity.
+
+use crate::*;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, SimpleObject};
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Poll, Wake, Waker};
+
+/// Number of submissions returned when a `submissions` field has no `limit`.
+const DEFAULT_SUBMISSIONS_LIMIT: u64 = 20;
+/// Largest `limit` of a `submissions` field.
+const MAX_SUBMISSIONS_LIMIT: u64 = 100;
+/// Deepest nesting of fields in a query.
+const MAX_DEPTH: usize = 10;
+/// Largest number of fields in a query.
+const MAX_COMPLEXITY: usize = 200;
+
+/// The schema, with `Query` as its root.
+pub type PbInfoSchema = async_graphql::Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Creates the schema, resolving every field with `client`.
+pub fn schema(client: PbInfoClient) -> PbInfoSchema {
+    async_graphql::Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(client)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Executes `request` on the current thread, blocking until it is resolved.
+pub fn execute_blocking(
+    schema: &PbInfoSchema,
+    request: impl Into<async_graphql::Request>,
+) -> async_graphql::Response {
+    /// Wakes the thread that is executing the request.
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut context = std::task::Context::from_waker(&waker);
+    let mut future = std::pin::pin!(schema.execute(request));
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(response) => return response,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+/// Converts `err` into a GraphQL error.
+fn graphql_error(err: PbInfoError) -> async_graphql::Error {
+    async_graphql::Error::new(format!("{:?}", err))
+}
+
+/// Returns the client of the schema.
+fn client<'a>(ctx: &Context<'a>) -> &'a PbInfoClient {
+    ctx.data_unchecked::<PbInfoClient>()
+}
+
+/// The root of the schema.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// The problem with this id, or null if there is none.
+    async fn problem(&self, ctx: &Context<'_>, id: u64) -> async_graphql::Result<Option<Problem>> {
+        match client(ctx).fetch_problem_by_id(ProblemId(id as usize)) {
+            Ok(problem) => Ok(Some(Problem(problem))),
+            Err(PbInfoError::UnknownId(_)) => Ok(None),
+            Err(err) => Err(graphql_error(err)),
+        }
+    }
+
+    /// The problems whose name matches `query`.
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+    ) -> async_graphql::Result<Vec<SearchHit>> {
+        Ok(client(ctx)
+            .search(&query)
+            .map_err(graphql_error)?
+            .into_iter()
+            .map(|result| SearchHit {
+                id: result.id.0,
+                name: result.name,
+            })
+            .collect())
+    }
+
+    /// The user with this username.
+    async fn user(&self, username: String) -> User {
+        User { username }
+    }
+
+    /// A chapter of the problems (e.g. `Grafuri`).
+    async fn category(&self, name: String) -> Category {
+        Category { name }
+    }
+
+    /// The submissions that match the arguments, newest first (20 unless `limit` is given, at
+    /// most 100).
+    async fn submissions(
+        &self,
+        ctx: &Context<'_>,
+        username: Option<String>,
+        problem_id: Option<u64>,
+        limit: Option<u64>,
+    ) -> async_graphql::Result<Vec<SubmissionObject>> {
+        fetch_submissions_with(client(ctx), username, problem_id, limit)
+    }
+}
+
+/// Returns the number of submissions fetched for the `limit` of a `submissions` field.
+pub(crate) fn submissions_limit(limit: Option<u64>) -> usize {
+    limit
+        .unwrap_or(DEFAULT_SUBMISSIONS_LIMIT)
+        .min(MAX_SUBMISSIONS_LIMIT) as usize
+}
+
+/// Fetches the submissions that match the arguments of a `submissions` field.
+fn fetch_submissions_with(
+    client: &PbInfoClient,
+    username: Option<String>,
+    problem_id: Option<u64>,
+    limit: Option<u64>,
+) -> async_graphql::Result<Vec<SubmissionObject>> {
+    let filter = SubmissionFilter {
+        username,
+        problem: problem_id.map(|id| ProblemId(id as usize)),
+        max_entries: Some(submissions_limit(limit)),
+        ..SubmissionFilter::default()
+    };
+    Ok(client
+        .fetch_submissions(&filter)
+        .map_err(graphql_error)?
+        .into_iter()
+        .map(SubmissionObject)
+        .collect())
+}
+
+/// A problem found by `search`.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    id: usize,
+    name: String,
+}
+
+#[Object]
+impl SearchHit {
+    /// Id of the problem
+    async fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Name of the problem, as displayed by PbInfo
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The problem
+    async fn problem(&self, ctx: &Context<'_>) -> async_graphql::Result<Problem> {
+        client(ctx)
+            .fetch_problem_by_id(ProblemId(self.id))
+            .map(Problem)
+            .map_err(graphql_error)
+    }
+}
+
+/// An example from a statement.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ExampleObject {
+    /// Contents of the input file (or stdin)
+    input: String,
+    /// Expected contents of the output file (or stdout)
+    output: String,
+}
+
+/// Submission statistics of a problem.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct Stats {
+    /// Number of submitted solutions
+    attempts: usize,
+    /// Number of solutions that got 100 points
+    solved: usize,
+    /// Average score, if PbInfo shows it
+    average_score: Option<f64>,
+    /// Percentage of the solutions that got 100 points
+    success_rate: f64,
+}
+
+/// A problem.
+#[derive(Debug, Clone)]
+pub struct Problem(PbInfoProblem);
+
+/// Returns the file name of `source`, or `None` for stdin/stdout.
+fn file_name(source: &IOSource) -> Option<&str> {
+    match source {
+        IOSource::File(name) => Some(name),
+        IOSource::Std => None,
+    }
+}
+
+#[Object]
+impl Problem {
+    /// Unique id
+    async fn id(&self) -> usize {
+        self.0.id.0
+    }
+
+    /// Unique name
+    async fn name(&self) -> &str {
+        self.0.name.as_str()
+    }
+
+    /// Grade (9, 10 or 11)
+    async fn grade(&self) -> usize {
+        self.0.grade.0
+    }
+
+    /// Difficulty label (`Easy`, `Medium`, `Difficult` or `Contest`)
+    async fn difficulty(&self) -> Option<String> {
+        self.0
+            .difficulty
+            .as_ref()
+            .map(|difficulty| format!("{:?}", difficulty))
+    }
+
+    /// File name of the input (null for stdin)
+    async fn input_file(&self) -> Option<&str> {
+        file_name(&self.0.input_source)
+    }
+
+    /// File name of the output (null for stdout)
+    async fn output_file(&self) -> Option<&str> {
+        file_name(&self.0.output_source)
+    }
+
+    /// Time limit
+    async fn time_limit(&self) -> Option<&str> {
+        self.0.time_limit.as_deref()
+    }
+
+    /// Memory limit
+    async fn memory_limit(&self) -> Option<&str> {
+        self.0.memory_limit.as_deref()
+    }
+
+    /// Author
+    async fn author(&self) -> Option<&str> {
+        self.0.author.as_deref()
+    }
+
+    /// Statement as plain text
+    async fn statement(&self) -> String {
+        html_to_text(&self.0.problem_text)
+    }
+
+    /// Statement as html
+    async fn statement_html(&self) -> &str {
+        &self.0.problem_text
+    }
+
+    /// Examples from the statement
+    async fn examples(&self) -> Vec<ExampleObject> {
+        self.0
+            .examples()
+            .into_iter()
+            .map(|example| ExampleObject {
+                input: example.input,
+                output: example.output,
+            })
+            .collect()
+    }
+
+    /// Chapters containing the problem, from the most general
+    async fn categories(&self) -> Vec<Category> {
+        self.0
+            .topics
+            .iter()
+            .map(|name| Category { name: name.clone() })
+            .collect()
+    }
+
+    /// Submission statistics
+    async fn stats(&self, ctx: &Context<'_>) -> async_graphql::Result<Stats> {
+        let stats = client(ctx)
+            .fetch_problem_stats(self.0.id)
+            .map_err(graphql_error)?;
+        Ok(Stats {
+            attempts: stats.attempts,
+            solved: stats.solved,
+            average_score: stats.average_score,
+            success_rate: stats.success_rate,
+        })
+    }
+
+    /// Difficulty estimated from the statistics, from 0 to 10 (see `estimate_difficulty`)
+    async fn estimated_difficulty(&self, ctx: &Context<'_>) -> async_graphql::Result<f32> {
+        let stats = client(ctx)
+            .fetch_problem_stats(self.0.id)
+            .map_err(graphql_error)?;
+        Ok(self.0.estimated_difficulty(&stats))
+    }
+
+    /// Submissions to the problem, newest first (20 unless `limit` is given, at most 100)
+    async fn submissions(
+        &self,
+        ctx: &Context<'_>,
+        username: Option<String>,
+        limit: Option<u64>,
+    ) -> async_graphql::Result<Vec<SubmissionObject>> {
+        fetch_submissions_with(client(ctx), username, Some(self.0.id.0 as u64), limit)
+    }
+}
+
+/// A user of PbInfo.
+#[derive(Debug, Clone)]
+pub struct User {
+    username: String,
+}
+
+/// A problem on the public profile of a user.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct Solve {
+    /// Id of the problem
+    problem_id: usize,
+    /// Name of the problem
+    name: String,
+    /// Best score, if shown
+    score: Option<usize>,
+}
+
+#[Object]
+impl User {
+    /// Username
+    async fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Problems on the public profile, with the best scores
+    async fn solves(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Solve>> {
+        Ok(client(ctx)
+            .fetch_public_solves(&self.username)
+            .map_err(graphql_error)?
+            .into_iter()
+            .map(|solve| Solve {
+                problem_id: solve.id.0,
+                name: solve.name,
+                score: solve.score,
+            })
+            .collect())
+    }
+
+    /// Number of problems solved with 100 points
+    async fn solved_count(&self, ctx: &Context<'_>) -> async_graphql::Result<usize> {
+        Ok(client(ctx)
+            .fetch_public_solves(&self.username)
+            .map_err(graphql_error)?
+            .iter()
+            .filter(|solve| solve.score == Some(100))
+            .count())
+    }
+
+    /// Submissions of the user, newest first (20 unless `limit` is given, at most 100)
+    async fn submissions(
+        &self,
+        ctx: &Context<'_>,
+        problem_id: Option<u64>,
+        limit: Option<u64>,
+    ) -> async_graphql::Result<Vec<SubmissionObject>> {
+        fetch_submissions_with(client(ctx), Some(self.username.clone()), problem_id, limit)
+    }
+}
+
+/// A submission.
+#[derive(Debug, Clone)]
+pub struct SubmissionObject(Submission);
+
+#[Object(name = "Submission")]
+impl SubmissionObject {
+    /// Unique id of the evaluation
+    async fn id(&self) -> usize {
+        self.0.id
+    }
+
+    /// Score (null while it is evaluated)
+    async fn score(&self) -> Option<usize> {
+        self.0.score
+    }
+
+    /// When it was submitted, as a Unix timestamp of PbInfo's local time
+    async fn submitted_at(&self) -> Option<u64> {
+        self.0.submitted_at
+    }
+
+    /// Id of the problem
+    async fn problem_id(&self) -> usize {
+        self.0.problem.0
+    }
+
+    /// The problem
+    async fn problem(&self, ctx: &Context<'_>) -> async_graphql::Result<Problem> {
+        client(ctx)
+            .fetch_problem_by_id(self.0.problem)
+            .map(Problem)
+            .map_err(graphql_error)
+    }
+
+    /// The author
+    async fn user(&self) -> User {
+        User {
+            username: self.0.username.clone(),
+        }
+    }
+}
+
+/// A chapter of the problems.
+#[derive(Debug, Clone)]
+pub struct Category {
+    name: String,
+}
+
+#[Object]
+impl Category {
+    /// Name of the chapter
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The problems of the chapter that the client has cached
+    async fn problems(&self, ctx: &Context<'_>) -> Vec<Problem> {
+        client(ctx)
+            .cached_problems(&ProblemFilter::new().topic(&self.name))
+            .into_iter()
+            .map(Problem)
+            .collect()
+    }
+}
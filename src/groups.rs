@@ -0,0 +1,173 @@
+use crate::*;
+use std::collections::BTreeMap;
+
+/// A teacher-managed group of students.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Group {
+    /// Unique id of group
+    pub id: usize,
+    /// Name of group
+    pub name: String,
+}
+
+/// A problem assigned to a group as homework.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AssignedProblem {
+    /// Unique id of problem
+    pub id: ProblemId,
+    /// Name of problem
+    pub name: String,
+    /// Deadline, as displayed by PbInfo (if it exists)
+    pub deadline: Option<String>,
+}
+
+/// The scores of a student on the problems assigned to a group.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StudentProgress {
+    /// Username of student
+    pub username: String,
+    /// Best score for each assigned problem id (`None` if the problem was not attempted)
+    pub scores: BTreeMap<ProblemId, Option<usize>>,
+}
+
+impl StudentProgress {
+    /// Returns the ids of the problems solved with 100 points.
+    pub fn solved(&self) -> Vec<ProblemId> {
+        self.scores
+            .iter()
+            .filter(|(_, score)| **score == Some(100))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+/// Extracts the groups listed on the groups page of a teacher.
+pub fn extract_groups(string: &str) -> Vec<Group> {
+    let regex = &selectors::GROUP_LINK;
+
+    let mut groups: Vec<Group> = Vec::new();
+    for caps in regex.captures_iter(string) {
+        let id = match caps[1].parse::<usize>() {
+            Ok(id) => id,
+            Err(_) => continue,
+        };
+        let name = html_to_text(&caps[2]);
+        if !name.is_empty() && groups.iter().all(|group| group.id != id) {
+            groups.push(Group { id, name });
+        }
+    }
+    groups
+}
+
+/// Extracts the problems assigned to a group. Each problem is on its own table row, which
+/// may also contain a deadline (`dd.mm.yyyy`, optionally followed by a time).
+pub fn extract_assigned_problems(string: &str) -> Vec<AssignedProblem> {
+    let row_regex = &selectors::TABLE_ROW;
+    let problem_regex = &selectors::PBINFO_PROBLEM_LINK;
+    let deadline_regex = &selectors::DEADLINE;
+
+    let mut problems = Vec::new();
+    for row in row_regex.captures_iter(string) {
+        let caps = match problem_regex.captures(&row[1]) {
+            Some(caps) => caps,
+            None => continue,
+        };
+        if let Ok(id) = caps[1].parse::<usize>() {
+            problems.push(AssignedProblem {
+                id: ProblemId(id),
+                name: html_to_text(&caps[2]),
+                deadline: deadline_regex
+                    .find(&html_to_text(&row[1]))
+                    .map(|deadline| deadline.as_str().to_owned()),
+            });
+        }
+    }
+    problems
+}
+
+/// Extracts the score matrix of a group: the header row links to the assigned problems and
+/// every other row starts with a link to the profile of a student, followed by one cell for
+/// each problem.
+pub fn extract_group_progress(string: &str) -> Vec<StudentProgress> {
+    let row_regex = &selectors::TABLE_ROW;
+    let cell_regex = &selectors::TABLE_CELL;
+    let problem_regex = &selectors::PBINFO_PROBLEM_HREF;
+    let user_regex = &selectors::PROFILE_HREF;
+
+    let mut problem_ids: Vec<Option<ProblemId>> = Vec::new();
+    let mut progress = Vec::new();
+    for row in row_regex.captures_iter(string) {
+        let cells = cell_regex
+            .captures_iter(&row[1])
+            .map(|caps| caps[1].to_owned())
+            .collect::<Vec<_>>();
+
+        let ids = cells
+            .iter()
+            .map(|cell| {
+                problem_regex
+                    .captures(cell)
+                    .and_then(|caps| caps[1].parse::<usize>().ok().map(ProblemId))
+            })
+            .collect::<Vec<_>>();
+        if ids.iter().any(|id| id.is_some()) {
+            problem_ids = ids;
+            continue;
+        }
+
+        let username = match cells.iter().find_map(|cell| user_regex.captures(cell)) {
+            Some(caps) => caps[1].to_owned(),
+            None => continue,
+        };
+        let scores = problem_ids
+            .iter()
+            .zip(cells.iter())
+            .filter_map(|(id, cell)| {
+                id.map(|id| (id, html_to_text(cell).trim().parse::<usize>().ok()))
+            })
+            .collect();
+        progress.push(StudentProgress { username, scores });
+    }
+    progress
+}
+
+impl PbInfoClient {
+    /// Returns the groups of the logged in teacher.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_groups(&self) -> Result<Vec<Group>> {
+        let text = self.fetch_group_page(&self.url("/grupuri"))?;
+        Ok(extract_groups(&text))
+    }
+
+    /// Returns the problems assigned to the group `group_id`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_group_problems(&self, group_id: usize) -> Result<Vec<AssignedProblem>> {
+        let text = self.fetch_group_page(&self.url(&format!("/grupuri/{}/teme", group_id)))?;
+        Ok(extract_assigned_problems(&text))
+    }
+
+    /// Returns the scores of every student of the group `group_id` on its assigned problems.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_group_progress(&self, group_id: usize) -> Result<Vec<StudentProgress>> {
+        let text = self.fetch_group_page(&self.url(&format!("/grupuri/{}/situatie", group_id)))?;
+        Ok(extract_group_progress(&text))
+    }
+
+    /// Fetches a page that is only visible to the teacher of a group.
+    fn fetch_group_page(&self, url: &str) -> Result<String> {
+        let page = self.get_page(url)?;
+        match page.status() {
+            reqwest::StatusCode::OK => self.read_text(page),
+            reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::UNAUTHORIZED => {
+                Err(PbInfoError::LoginError(
+                    "Groups are only visible to their teacher. Log in using a teacher account"
+                        .to_owned(),
+                ))
+            }
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to fetch the group. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Homework sets: a list of problems with a deadline, defined by a teacher in TOML, and checked
+//! against the submission histories of the students.
+//!
+//! ```toml
+//! name = "Tema 3: vectori"
+//! problems = [1691, 489]
+//! deadline = "2026-10-20 23:59"
+//! students = ["ana", "mihai"]
+//!
+//! # Optional: the `pin_hash` of the statements the homework was given for
+//! [pins]
+//! 1691 = "3f6c...e2"
+//! ```
+//!
+//! The deadline is read like the times shown by PbInfo (see `parse_datetime`), so it is compared
+//! to the submission times in the same (Romanian) local time.
+
+use crate::*;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A homework set.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HomeworkSet {
+    /// Name of the homework
+    pub name: String,
+    /// Problems to solve
+    pub problems: Vec<ProblemId>,
+    /// Last moment at which a submission counts (see `parse_datetime`)
+    pub deadline: u64,
+    /// Usernames of the students (may be empty if they are given to `check`)
+    pub students: Vec<String>,
+    /// The `pin_hash` of the statements the homework was given for, by problem (see
+    /// `PbInfoClient::check_homework_pins`)
+    pub pins: BTreeMap<ProblemId, String>,
+}
+
+impl HomeworkSet {
+    /// Parses a homework set from TOML. `problems` and `deadline` are required; the deadline is
+    /// a string (`"20.10.2026 23:59"`, `"2026-10-20 23:59"`) or a TOML date-time without an
+    /// offset.
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let table = text
+            .parse::<toml::Table>()
+            .map_err(|err| PbInfoError::Error(format!("Invalid homework set: {}", err)))?;
+        let invalid = |field: &str| PbInfoError::Error(format!("Invalid homework set: {}", field));
+
+        let problems = table
+            .get("problems")
+            .and_then(|problems| problems.as_array())
+            .ok_or_else(|| invalid("`problems` has to be a list of problem ids"))?
+            .iter()
+            .map(|id| match id.as_integer() {
+                Some(id) if id > 0 => Ok(ProblemId(id as usize)),
+                _ => Err(invalid("`problems` has to be a list of problem ids")),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let deadline = match table.get("deadline") {
+            Some(toml::Value::String(deadline)) => parse_datetime(deadline),
+            Some(toml::Value::Datetime(deadline)) if deadline.offset.is_none() => {
+                parse_datetime(&deadline.to_string())
+            }
+            _ => None,
+        }
+        .ok_or_else(|| invalid("`deadline` has to be a date and time"))?;
+        let students = match table.get("students") {
+            Some(students) => students
+                .as_array()
+                .and_then(|students| {
+                    students
+                        .iter()
+                        .map(|student| student.as_str().map(str::to_owned))
+                        .collect::<Option<Vec<_>>>()
+                })
+                .ok_or_else(|| invalid("`students` has to be a list of usernames"))?,
+            None => Vec::new(),
+        };
+        let pins = match table.get("pins") {
+            Some(pins) => pins
+                .as_table()
+                .and_then(|pins| {
+                    pins.iter()
+                        .map(|(id, hash)| match id.parse() {
+                            Ok(id) if id > 0 => Some((ProblemId(id), hash.as_str()?.to_owned())),
+                            _ => None,
+                        })
+                        .collect::<Option<BTreeMap<_, _>>>()
+                })
+                .ok_or_else(|| invalid("`pins` has to map problem ids to statement hashes"))?,
+            None => BTreeMap::new(),
+        };
+
+        Ok(HomeworkSet {
+            name: table
+                .get("name")
+                .and_then(|name| name.as_str())
+                .unwrap_or_default()
+                .to_owned(),
+            problems,
+            deadline,
+            students,
+            pins,
+        })
+    }
+
+    /// Returns the deadline as an event for `calendar::Calendar`, with a reminder a day before.
+    /// The event is identified by the name of the set (or its problems if it has no name), so a
+    /// calendar that imports it again after the deadline is moved updates the old event.
+    pub fn calendar_event(&self) -> calendar::CalendarEvent {
+        let problems = self
+            .problems
+            .iter()
+            .map(|id| format!("#{}", id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let key = match self.name.is_empty() {
+            true => self
+                .problems
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join("-"),
+            false => normalize_text(&self.name).replace(char::is_whitespace, "-"),
+        };
+        calendar::CalendarEvent {
+            uid: format!("homework-{}@pbinfo.ro", key),
+            summary: match self.name.is_empty() {
+                true => "Temă".to_owned(),
+                false => self.name.clone(),
+            },
+            description: format!("Probleme: {}", problems),
+            url: None,
+            at: self.deadline,
+            reminder: Some(std::time::Duration::from_secs(24 * 60 * 60)),
+        }
+    }
+
+    /// Reads a homework set from a TOML file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            PbInfoError::IOError(format!("Could not read `{}`: {}", path.display(), err))
+        })?;
+        HomeworkSet::from_toml(&text)
+    }
+}
+
+/// How a student did on one problem of a homework set.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct HomeworkProblem {
+    /// The problem
+    pub id: ProblemId,
+    /// Best score submitted until the deadline (`None` if nothing was submitted)
+    pub best_score: Option<usize>,
+    /// When the problem was first solved with 100 points until the deadline
+    pub solved_at: Option<u64>,
+    /// Whether the problem was solved with 100 points only after the deadline
+    pub solved_late: bool,
+}
+
+impl HomeworkProblem {
+    /// Returns `true` if the problem was solved with 100 points until the deadline.
+    pub fn is_solved(&self) -> bool {
+        self.solved_at.is_some()
+    }
+}
+
+/// How a student did on a homework set.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StudentHomework {
+    /// Username of the student
+    pub username: String,
+    /// The problems, in the order of the homework set
+    pub problems: Vec<HomeworkProblem>,
+}
+
+impl StudentHomework {
+    /// Returns the number of problems solved until the deadline.
+    pub fn solved(&self) -> usize {
+        self.problems
+            .iter()
+            .filter(|problem| problem.is_solved())
+            .count()
+    }
+
+    /// Returns `true` if every problem was solved until the deadline.
+    pub fn is_complete(&self) -> bool {
+        self.solved() == self.problems.len()
+    }
+}
+
+/// Checks the submissions of `username` against `set`. Submissions of other users, to other
+/// problems or without a known time are ignored.
+pub fn check_submissions(
+    set: &HomeworkSet,
+    username: &str,
+    submissions: &[Submission],
+) -> StudentHomework {
+    let problems = set
+        .problems
+        .iter()
+        .map(|&id| {
+            let mut problem = HomeworkProblem {
+                id,
+                best_score: None,
+                solved_at: None,
+                solved_late: false,
+            };
+            for submission in submissions {
+                let at = match submission.submitted_at {
+                    Some(at) if submission.problem == id && submission.username == username => at,
+                    _ => continue,
+                };
+                let solved = submission.score == Some(100);
+                if at > set.deadline {
+                    problem.solved_late |= solved;
+                    continue;
+                }
+                problem.best_score = problem.best_score.max(submission.score);
+                if solved && problem.solved_at.is_none_or(|solved_at| at < solved_at) {
+                    problem.solved_at = Some(at);
+                }
+            }
+            if problem.is_solved() {
+                problem.solved_late = false;
+            }
+            problem
+        })
+        .collect();
+
+    StudentHomework {
+        username: username.to_owned(),
+        problems,
+    }
+}
+
+/// Checks `set` for `students` (or, if it is empty, for the students of the set), using the
+/// shared client.
+pub fn check(set: &HomeworkSet, students: &[&str]) -> Result<Vec<StudentHomework>> {
+    PbInfoClient::shared().check_homework(set, students)
+}
+
+impl PbInfoClient {
+    /// Checks `set` for `students` (or, if it is empty, for the students of the set) by fetching
+    /// the submissions of every student to every problem of the set, in the order of the
+    /// students.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, set), err(Debug)))]
+    pub fn check_homework(
+        &self,
+        set: &HomeworkSet,
+        students: &[&str],
+    ) -> Result<Vec<StudentHomework>> {
+        let students = match students.is_empty() {
+            true => set.students.iter().map(String::as_str).collect(),
+            false => students.to_vec(),
+        };
+
+        let mut results = Vec::new();
+        for username in students {
+            let mut submissions = Vec::new();
+            for &id in &set.problems {
+                // Late submissions are fetched too, to report late solves
+                submissions.extend(self.fetch_submissions(&SubmissionFilter {
+                    username: Some(username.to_owned()),
+                    problem: Some(id),
+                    ..SubmissionFilter::default()
+                })?);
+            }
+            results.push(check_submissions(set, username, &submissions));
+        }
+        Ok(results)
+    }
+
+    /// Fetches the pinned problems of `set` and returns the statements that changed since the
+    /// homework was given (see `PbInfoClient::fetch_problem_pinned`).
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, set), err(Debug)))]
+    pub fn check_homework_pins(&self, set: &HomeworkSet) -> Result<Vec<StatementDrift>> {
+        let mut drifts = Vec::new();
+        for (&id, hash) in &set.pins {
+            drifts.extend(self.fetch_problem_pinned(id, hash)?.drift);
+        }
+        Ok(drifts)
+    }
+}
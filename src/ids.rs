@@ -0,0 +1,102 @@
+use crate::*;
+use std::fmt;
+
+/// Unique id of a PbInfo problem (e.g. 1691 for `/probleme/1691/arbore1`). A distinct type, so
+/// that a grade or a score can not be passed where an id is expected.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct ProblemId(pub usize);
+
+impl fmt::Display for ProblemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<ProblemId> for usize {
+    fn from(id: ProblemId) -> Self {
+        id.0
+    }
+}
+
+/// A school grade (e.g. 9 for "Clasa a IX-a").
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Grade(pub usize);
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<Grade> for usize {
+    fn from(grade: Grade) -> Self {
+        grade.0
+    }
+}
+
+/// Name of a PbInfo problem, normalized the way PbInfo writes names in its urls: lowercase,
+/// without diacritics or surrounding whitespace (e.g. `Șir Maxim ` becomes `sir maxim`).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct ProblemName(String);
+
+impl ProblemName {
+    /// Normalizes `name`. Fails if nothing is left of it.
+    pub fn new(name: &str) -> Result<Self> {
+        let name = normalize_text(name);
+        if name.is_empty() {
+            return Err(PbInfoError::Error(
+                "A problem name can not be empty".to_owned(),
+            ));
+        }
+        Ok(ProblemName(name))
+    }
+
+    /// Returns the normalized name.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ProblemName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::ops::Deref for ProblemName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for ProblemName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<std::path::Path> for ProblemName {
+    fn as_ref(&self) -> &std::path::Path {
+        self.0.as_ref()
+    }
+}
+
+impl From<ProblemName> for String {
+    fn from(name: ProblemName) -> Self {
+        name.0
+    }
+}
+
+impl PartialEq<str> for ProblemName {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for ProblemName {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
@@ -0,0 +1,676 @@
+//! A local judge: compiles solutions and runs them on the examples of a problem (or any other
+//! tests), the way PbInfo would.
+
+mod checker;
+mod stress;
+
+pub use self::checker::*;
+pub use self::stress::*;
+use crate::*;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Used to give every build and run directory a unique name.
+static DIRECTORY_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Languages that solutions can be written in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Language {
+    /// C (compiled with gcc)
+    C,
+    /// C++ (compiled with g++)
+    Cpp,
+    /// Python 3
+    Python,
+}
+
+impl Language {
+    /// Extension of source files written in the language.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::Python => "py",
+        }
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = PbInfoError;
+
+    /// Parses a language from its file extension (`cpp`) or its name (`c++`, `python`).
+    fn from_str(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "c" => Ok(Language::C),
+            "cpp" | "c++" => Ok(Language::Cpp),
+            "py" | "python" => Ok(Language::Python),
+            _ => Err(PbInfoError::Error(format!("Unknown language `{}`", name))),
+        }
+    }
+}
+
+/// Paths and flags of the compilers and interpreters used by the judge.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Toolchain {
+    /// C compiler
+    pub gcc: PathBuf,
+    /// C++ compiler
+    pub gxx: PathBuf,
+    /// Python interpreter
+    pub python: PathBuf,
+    /// Flags passed to the C compiler
+    pub c_flags: Vec<String>,
+    /// Flags passed to the C++ compiler
+    pub cpp_flags: Vec<String>,
+}
+
+impl Default for Toolchain {
+    /// The toolchain found in `PATH`, with the flags PbInfo compiles with.
+    fn default() -> Self {
+        Toolchain {
+            gcc: PathBuf::from("gcc"),
+            gxx: PathBuf::from("g++"),
+            python: PathBuf::from("python3"),
+            c_flags: vec!["-O2".to_owned(), "-std=c11".to_owned(), "-lm".to_owned()],
+            cpp_flags: vec!["-O2".to_owned(), "-std=c++17".to_owned()],
+        }
+    }
+}
+
+/// A solution that is ready to be run.
+#[derive(Debug)]
+pub struct CompiledProgram {
+    /// Language of the solution
+    pub language: Language,
+    /// Program to execute (the binary, or the interpreter)
+    pub program: PathBuf,
+    /// Arguments to pass to `program`
+    pub args: Vec<String>,
+    /// Warnings printed by the compiler
+    pub diagnostics: String,
+    /// Directory holding the source and the binary; removed when the program is dropped
+    build_dir: PathBuf,
+}
+
+impl Drop for CompiledProgram {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.build_dir);
+    }
+}
+
+/// Outcome of running a solution on one test.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Verdict {
+    /// The output is correct.
+    Accepted,
+    /// The output is wrong (or the output file is missing).
+    WrongAnswer,
+    /// The program exited with an error, storing the exit code (`None` if it was killed).
+    RuntimeError(Option<i32>),
+    /// The program ran for longer than the time limit and was killed.
+    TimeLimitExceeded,
+    /// The program used more memory than the memory limit.
+    MemoryLimitExceeded,
+}
+
+/// Resources a solution may use on one test.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Limits {
+    /// Time limit; enforced on wall time, and on CPU time on Unix
+    pub time: Option<Duration>,
+    /// Total memory limit, in bytes; enforced on Unix, and checked from the peak usage once the
+    /// solution exits on Windows
+    pub memory: Option<u64>,
+    /// Stack size limit, in bytes (only enforced on Unix)
+    pub stack: Option<u64>,
+}
+
+impl Limits {
+    /// Returns the limits of `problem`, as parsed from its `time_limit` ("0.5 secunde") and
+    /// `memory_limit` ("64 MB / 8 MB").
+    pub fn from_problem(problem: &PbInfoProblem) -> Self {
+        let (memory, stack) = match &problem.memory_limit {
+            Some(limit) => {
+                let mut parts = limit.split('/').map(parse_size);
+                (parts.next().flatten(), parts.next().flatten())
+            }
+            None => (None, None),
+        };
+        Limits {
+            time: problem.time_limit.as_deref().and_then(parse_time),
+            memory,
+            stack,
+        }
+    }
+}
+
+/// Parses a time limit such as "0.5 secunde" or "1,5 secunde".
+pub fn parse_time(string: &str) -> Option<Duration> {
+    let regex = &selectors::DECIMAL;
+    let seconds = regex.captures(string)?[1].replace(',', ".");
+    seconds.parse::<f64>().ok().map(Duration::from_secs_f64)
+}
+
+/// Parses a memory size such as "64 MB" or "512 KB" into bytes.
+pub fn parse_size(string: &str) -> Option<u64> {
+    let regex = &selectors::SIZE;
+    let caps = regex.captures(string)?;
+    let value = caps[1].replace(',', ".").parse::<f64>().ok()?;
+    let unit = match caps[2].to_uppercase().as_str() {
+        "KB" => 1 << 10,
+        "MB" => 1 << 20,
+        "GB" => 1 << 30,
+        _ => 1,
+    };
+    Some((value * unit as f64) as u64)
+}
+
+/// Result of running a solution on one test.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TestResult {
+    /// The verdict of the test
+    pub verdict: Verdict,
+    /// What the program wrote to the output file (or stdout)
+    pub output: String,
+    /// What the program wrote to stderr
+    pub stderr: String,
+    /// Wall time the program ran for
+    pub duration: Duration,
+    /// Peak memory usage, in bytes (only measured on Unix)
+    pub memory: Option<u64>,
+}
+
+/// Creates a new empty directory for the judge.
+pub(crate) fn create_dir(kind: &str) -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!(
+        "pbinfo-{}-{}-{}",
+        kind,
+        std::process::id(),
+        DIRECTORY_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&dir).map_err(|err| {
+        PbInfoError::IOError(format!("Could not create `{}`: {}", dir.display(), err))
+    })?;
+    Ok(dir)
+}
+
+/// Runs `command`, returning its stderr. Fails with `PbInfoError::CompilationError` if the
+/// command does not succeed.
+fn run_compiler(mut command: Command) -> Result<String> {
+    let output = command.output().map_err(|err| {
+        PbInfoError::CompilationError(format!(
+            "Could not run `{:?}`: {}",
+            command.get_program(),
+            err
+        ))
+    })?;
+
+    let diagnostics = String::from_utf8_lossy(&output.stderr).into_owned();
+    match output.status.success() {
+        true => Ok(diagnostics),
+        false => Err(PbInfoError::CompilationError(diagnostics)),
+    }
+}
+
+/// Compiles `source` with the default toolchain.
+pub fn compile(source: &str, language: Language) -> Result<CompiledProgram> {
+    compile_with(source, language, &Toolchain::default())
+}
+
+/// Compiles `source` with `toolchain`. Compiler errors are returned as
+/// `PbInfoError::CompilationError`; Python sources are only checked for syntax errors.
+pub fn compile_with(
+    source: &str,
+    language: Language,
+    toolchain: &Toolchain,
+) -> Result<CompiledProgram> {
+    let build_dir = create_dir("build")?;
+    let source_path = build_dir.join(format!("main.{}", language.extension()));
+    std::fs::write(&source_path, source).map_err(|err| {
+        PbInfoError::IOError(format!(
+            "Could not write `{}`: {}",
+            source_path.display(),
+            err
+        ))
+    })?;
+    let binary = build_dir.join("main");
+
+    let (diagnostics, program, args) = match language {
+        Language::C | Language::Cpp => {
+            let (compiler, flags) = match language {
+                Language::C => (&toolchain.gcc, &toolchain.c_flags),
+                _ => (&toolchain.gxx, &toolchain.cpp_flags),
+            };
+            let mut command = Command::new(compiler);
+            command.arg(&source_path).arg("-o").arg(&binary).args(flags);
+            (run_compiler(command)?, binary, Vec::new())
+        }
+        Language::Python => {
+            let mut command = Command::new(&toolchain.python);
+            command.args(["-m", "py_compile"]).arg(&source_path);
+            let diagnostics = run_compiler(command)?;
+            let args = vec![source_path.to_string_lossy().into_owned()];
+            (diagnostics, toolchain.python.clone(), args)
+        }
+    };
+
+    Ok(CompiledProgram {
+        language,
+        program,
+        args,
+        diagnostics,
+        build_dir,
+    })
+}
+
+/// Returns `true` if `output` matches `expected`, ignoring differences in whitespace.
+pub fn outputs_match(output: &str, expected: &str) -> bool {
+    output.split_whitespace().eq(expected.split_whitespace())
+}
+
+/// How a process that was run under limits ended.
+struct Exit {
+    /// Exit code (`None` if the process was killed by a signal)
+    code: Option<i32>,
+    /// Whether the process ran out of time (and was killed)
+    timed_out: bool,
+    /// Peak memory usage, in bytes
+    memory: Option<u64>,
+}
+
+/// Applies the memory, stack and CPU time limits to the process spawned by `command`.
+#[cfg(unix)]
+fn apply_limits(command: &mut Command, language: Language, limits: &Limits) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    // Interpreters reserve a lot of address space on startup, so only compiled programs are
+    // limited directly; everything else is detected after the fact, from the peak usage.
+    let memory = match language {
+        Language::C | Language::Cpp => limits.memory,
+        Language::Python => None,
+    };
+    let stack = limits.stack.or(limits.memory);
+    let cpu = limits.time.map(|time| time.as_secs() + 1);
+
+    // SAFETY: setrlimit is async-signal-safe, so it may be called between fork and exec.
+    unsafe {
+        command.pre_exec(move || {
+            let set = |resource, value: u64| {
+                let limit = libc::rlimit {
+                    rlim_cur: value as libc::rlim_t,
+                    rlim_max: value as libc::rlim_t,
+                };
+                libc::setrlimit(resource, &limit)
+            };
+            if let Some(memory) = memory {
+                set(libc::RLIMIT_AS, memory);
+            }
+            if let Some(stack) = stack {
+                set(libc::RLIMIT_STACK, stack);
+            }
+            if let Some(cpu) = cpu {
+                set(libc::RLIMIT_CPU, cpu);
+            }
+            Ok(())
+        });
+    }
+    Ok(())
+}
+
+/// Limits can only be applied to the process before it starts on Unix. Elsewhere the time limit
+/// is enforced by `wait_with_limits`, the memory limit is checked after the fact from the peak
+/// usage (see `peak_memory`) and the stack is not limited.
+#[cfg(not(unix))]
+fn apply_limits(_command: &mut Command, _language: Language, _limits: &Limits) -> Result<()> {
+    Ok(())
+}
+
+/// Returns the peak memory usage (working set) of `child`, which has exited but whose handle is
+/// still open.
+#[cfg(windows)]
+fn peak_memory(child: &std::process::Child) -> Option<u64> {
+    use std::os::windows::io::AsRawHandle;
+
+    /// `PROCESS_MEMORY_COUNTERS` of the Windows API
+    #[repr(C)]
+    #[derive(Default)]
+    struct ProcessMemoryCounters {
+        cb: u32,
+        page_fault_count: u32,
+        peak_working_set_size: usize,
+        working_set_size: usize,
+        quota_peak_paged_pool_usage: usize,
+        quota_paged_pool_usage: usize,
+        quota_peak_non_paged_pool_usage: usize,
+        quota_non_paged_pool_usage: usize,
+        pagefile_usage: usize,
+        peak_pagefile_usage: usize,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        #[link_name = "K32GetProcessMemoryInfo"]
+        fn get_process_memory_info(
+            process: std::os::windows::io::RawHandle,
+            counters: *mut ProcessMemoryCounters,
+            cb: u32,
+        ) -> i32;
+    }
+
+    let size = std::mem::size_of::<ProcessMemoryCounters>() as u32;
+    let mut counters = ProcessMemoryCounters {
+        cb: size,
+        ..ProcessMemoryCounters::default()
+    };
+    // SAFETY: the handle of `child` is open, and `counters` is as large as `cb` says.
+    let res = unsafe { get_process_memory_info(child.as_raw_handle(), &mut counters, size) };
+    (res != 0).then_some(counters.peak_working_set_size as u64)
+}
+
+/// The peak memory usage is not known on this platform.
+#[cfg(not(any(unix, windows)))]
+fn peak_memory(_child: &std::process::Child) -> Option<u64> {
+    None
+}
+
+/// Waits for `child`, killing it once `limits.time` passes.
+#[cfg(unix)]
+fn wait_with_limits(
+    child: &mut std::process::Child,
+    limits: &Limits,
+    start: Instant,
+) -> Result<Exit> {
+    let pid = child.id() as libc::pid_t;
+    let mut timed_out = false;
+    loop {
+        let mut status = 0;
+        // SAFETY: rusage is plain data, which wait4 fills in.
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        // SAFETY: `pid` is a child of this process that has not been reaped yet.
+        let res = unsafe { libc::wait4(pid, &mut status, libc::WNOHANG, &mut usage) };
+        if res == pid {
+            let signaled = libc::WIFSIGNALED(status);
+            return Ok(Exit {
+                code: (!signaled).then(|| libc::WEXITSTATUS(status)),
+                timed_out: timed_out || (signaled && libc::WTERMSIG(status) == libc::SIGXCPU),
+                memory: Some(max_rss_bytes(&usage)),
+            });
+        }
+        if res < 0 {
+            return Err(PbInfoError::IOError(format!(
+                "Could not wait for the solution: {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+
+        if let Some(time) = limits.time {
+            if !timed_out && start.elapsed() > time {
+                let _ = child.kill();
+                timed_out = true;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+/// Returns the peak memory usage from `usage`, in bytes.
+#[cfg(unix)]
+fn max_rss_bytes(usage: &libc::rusage) -> u64 {
+    // ru_maxrss is measured in bytes on macOS, and in kilobytes on the other systems
+    #[cfg(target_os = "macos")]
+    let bytes = usage.ru_maxrss as u64;
+    #[cfg(not(target_os = "macos"))]
+    let bytes = usage.ru_maxrss as u64 * 1024;
+    bytes
+}
+
+/// Waits for `child`, killing it once `limits.time` passes.
+#[cfg(not(unix))]
+fn wait_with_limits(
+    child: &mut std::process::Child,
+    limits: &Limits,
+    start: Instant,
+) -> Result<Exit> {
+    let mut timed_out = false;
+    loop {
+        let status = child.try_wait().map_err(|err| {
+            PbInfoError::IOError(format!("Could not wait for the solution: {}", err))
+        })?;
+        if let Some(status) = status {
+            return Ok(Exit {
+                code: status.code(),
+                timed_out,
+                memory: peak_memory(child),
+            });
+        }
+
+        if let Some(time) = limits.time {
+            if !timed_out && start.elapsed() > time {
+                let _ = child.kill();
+                timed_out = true;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+/// Reads everything from `pipe` on a separate thread, so the child never blocks on a full pipe.
+fn read_pipe<R: std::io::Read + Send + 'static>(
+    pipe: Option<R>,
+) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+/// Runs `program` on one test, reading and writing the files described by `problem`.
+pub(crate) fn run_test(
+    program: &CompiledProgram,
+    problem: &PbInfoProblem,
+    test: &Example,
+    limits: &Limits,
+    checker: &dyn Checker,
+) -> Result<TestResult> {
+    let run_dir = create_dir("run")?;
+    let io_error = |path: &Path, err: std::io::Error| {
+        PbInfoError::IOError(format!("Could not access `{}`: {}", path.display(), err))
+    };
+
+    let mut command = Command::new(&program.program);
+    command
+        .args(&program.args)
+        .current_dir(&run_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    match &problem.input_source {
+        IOSource::File(name) => {
+            let path = run_dir.join(name);
+            std::fs::write(&path, &test.input).map_err(|err| io_error(&path, err))?;
+            command.stdin(Stdio::null());
+        }
+        IOSource::Std => {
+            command.stdin(Stdio::piped());
+        }
+    }
+    apply_limits(&mut command, program.language, limits)?;
+
+    let start = Instant::now();
+    let mut child = command
+        .spawn()
+        .map_err(|err| io_error(&program.program, err))?;
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    // The input is written on a separate thread, so that a program that does not read it does
+    // not block the judge on a full pipe before the time limit is enforced
+    let stdin = child.stdin.take().map(|mut stdin| {
+        let input = test.input.clone();
+        std::thread::spawn(move || {
+            // The program might exit without reading its input, so write errors are ignored
+            let _ = stdin.write_all(input.as_bytes());
+        })
+    });
+    let exit = wait_with_limits(&mut child, limits, start)?;
+    if let Some(stdin) = stdin {
+        let _ = stdin.join();
+    }
+    let duration = start.elapsed();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    let output = match &problem.output_source {
+        IOSource::File(name) => std::fs::read_to_string(run_dir.join(name)).ok(),
+        IOSource::Std => Some(String::from_utf8_lossy(&stdout).into_owned()),
+    };
+    let _ = std::fs::remove_dir_all(&run_dir);
+
+    let over_memory = match (exit.memory, limits.memory) {
+        (Some(used), Some(limit)) => used > limit,
+        _ => false,
+    };
+    let accepted = match (&output, exit.code) {
+        (Some(output), Some(0)) if !exit.timed_out && !over_memory => {
+            checker.check(test, output)?
+        }
+        _ => false,
+    };
+    let verdict = match (&output, exit.code) {
+        _ if exit.timed_out => Verdict::TimeLimitExceeded,
+        _ if over_memory => Verdict::MemoryLimitExceeded,
+        // Allocations fail once the address space limit is reached, so a crash of a
+        // memory-limited program is most likely caused by running out of memory
+        (_, code)
+            if code != Some(0) && limits.memory.is_some() && is_allocation_failure(&stderr) =>
+        {
+            Verdict::MemoryLimitExceeded
+        }
+        (_, code) if code != Some(0) => Verdict::RuntimeError(code),
+        _ if accepted => Verdict::Accepted,
+        _ => Verdict::WrongAnswer,
+    };
+    Ok(TestResult {
+        verdict,
+        output: output.unwrap_or_default(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        duration,
+        memory: exit.memory,
+    })
+}
+
+/// Returns `true` if `stderr` shows that an allocation failed.
+fn is_allocation_failure(stderr: &[u8]) -> bool {
+    let stderr = String::from_utf8_lossy(stderr);
+    stderr.contains("bad_alloc") || stderr.contains("MemoryError")
+}
+
+/// Runs `program` on every test, in order, under the limits of `problem`. Every test runs in
+/// its own directory, so solutions that read from and write to files work as they would on
+/// PbInfo.
+pub fn run_tests(
+    program: &CompiledProgram,
+    problem: &PbInfoProblem,
+    tests: &[Example],
+) -> Result<Vec<TestResult>> {
+    run_tests_with_limits(program, problem, tests, &Limits::from_problem(problem))
+}
+
+/// Runs `program` on every test, in order, under `limits`.
+///
+/// The output of an interactive problem (see `PbInfoProblem::is_interactive`) depends on the
+/// interactor's answers, so comparing it with the expected one may reject correct solutions.
+/// Such problems are still judged, but callers that care should check `is_interactive` first.
+pub fn run_tests_with_limits(
+    program: &CompiledProgram,
+    problem: &PbInfoProblem,
+    tests: &[Example],
+    limits: &Limits,
+) -> Result<Vec<TestResult>> {
+    #[cfg(feature = "tracing")]
+    if problem.is_interactive() {
+        tracing::warn!(
+            problem = problem.name.as_str(),
+            "the problem is interactive, so its outputs might not match the expected ones"
+        );
+    }
+    run_tests_checked(program, problem, tests, limits, &TokenChecker)
+}
+
+/// Runs `program` on every test, in order, under `limits`, deciding whether each output is
+/// correct using `checker` (see `Checkers::for_problem`).
+pub fn run_tests_checked(
+    program: &CompiledProgram,
+    problem: &PbInfoProblem,
+    tests: &[Example],
+    limits: &Limits,
+    checker: &dyn Checker,
+) -> Result<Vec<TestResult>> {
+    run_tests_parallel(program, problem, tests, limits, checker, 1)
+}
+
+/// Returns how many tests `run_tests_parallel` should run at the same time: the number of CPUs.
+pub fn default_parallelism() -> usize {
+    std::thread::available_parallelism().map_or(1, |cpus| cpus.get())
+}
+
+/// Runs `program` on every test under `limits`, like `run_tests_checked`, with up to
+/// `parallelism` tests running at the same time (see `default_parallelism`). Every test runs in
+/// its own directory, so solutions that read from and write to files do not interfere with each
+/// other. The results are in the order of the tests. Tests running side by side slow each other
+/// down a little, so solutions close to the time limit are better judged one test at a time.
+pub fn run_tests_parallel(
+    program: &CompiledProgram,
+    problem: &PbInfoProblem,
+    tests: &[Example],
+    limits: &Limits,
+    checker: &dyn Checker,
+    parallelism: usize,
+) -> Result<Vec<TestResult>> {
+    let workers = parallelism.clamp(1, tests.len().max(1));
+    if workers == 1 {
+        return tests
+            .iter()
+            .map(|test| run_test(program, problem, test, limits, checker))
+            .collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results = std::thread::scope(|scope| {
+        let handles = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        match tests.get(index) {
+                            Some(test) => results
+                                .push((index, run_test(program, problem, test, limits, checker))),
+                            None => break results,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Compiles `source` and runs it on the examples of `problem`.
+pub fn check_examples(
+    problem: &PbInfoProblem,
+    source: &str,
+    language: Language,
+) -> Result<Vec<TestResult>> {
+    let program = compile(source, language)?;
+    run_tests(&program, problem, &problem.examples())
+}
@@ -0,0 +1,159 @@
+use crate::judge::{create_dir, outputs_match};
+use crate::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Decides whether the output of a solution is correct.
+pub trait Checker: Send + Sync {
+    /// Returns `true` if `output` is a correct answer to `test`.
+    fn check(&self, test: &Example, output: &str) -> Result<bool>;
+}
+
+/// Accepts only outputs identical to the expected one (ignoring trailing whitespace at the
+/// end of lines and of the file).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExactChecker;
+
+impl Checker for ExactChecker {
+    fn check(&self, test: &Example, output: &str) -> Result<bool> {
+        let lines = |text: &str| {
+            text.trim_end()
+                .lines()
+                .map(|line| line.trim_end().to_owned())
+                .collect::<Vec<_>>()
+        };
+        Ok(lines(output) == lines(&test.output))
+    }
+}
+
+/// Accepts outputs with the same tokens as the expected one, however they are separated.
+/// This is how most PbInfo problems are evaluated.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenChecker;
+
+impl Checker for TokenChecker {
+    fn check(&self, test: &Example, output: &str) -> Result<bool> {
+        Ok(outputs_match(output, &test.output))
+    }
+}
+
+/// Like TokenChecker, but numbers may differ from the expected ones by at most `epsilon`
+/// (absolute or relative).
+#[derive(Debug, Clone, Copy)]
+pub struct FloatChecker {
+    /// Maximum accepted difference
+    pub epsilon: f64,
+}
+
+impl Checker for FloatChecker {
+    fn check(&self, test: &Example, output: &str) -> Result<bool> {
+        let mut output = output.split_whitespace();
+        let mut expected = test.output.split_whitespace();
+        loop {
+            match (output.next(), expected.next()) {
+                (None, None) => return Ok(true),
+                (Some(found), Some(wanted)) => {
+                    let equal = match (found.parse::<f64>(), wanted.parse::<f64>()) {
+                        (Ok(found), Ok(wanted)) => {
+                            let difference = (found - wanted).abs();
+                            difference <= self.epsilon || difference <= self.epsilon * wanted.abs()
+                        }
+                        _ => found == wanted,
+                    };
+                    if !equal {
+                        return Ok(false);
+                    }
+                }
+                _ => return Ok(false),
+            }
+        }
+    }
+}
+
+/// Runs an external checker, with the testlib convention: `program <input> <output> <answer>`,
+/// where the output is accepted if the checker exits with code 0.
+#[derive(Debug, Clone)]
+pub struct ExternalChecker {
+    /// The checker binary
+    pub program: PathBuf,
+    /// Arguments passed before the file names
+    pub args: Vec<String>,
+}
+
+impl Checker for ExternalChecker {
+    fn check(&self, test: &Example, output: &str) -> Result<bool> {
+        let dir = create_dir("checker")?;
+        let write = |name: &str, contents: &str| {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).map_err(|err| {
+                PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+            })?;
+            Ok::<_, PbInfoError>(path)
+        };
+        let input = write("input.txt", &test.input)?;
+        let output = write("output.txt", output)?;
+        let answer = write("answer.txt", &test.output)?;
+
+        let status = Command::new(&self.program)
+            .args(&self.args)
+            .arg(input)
+            .arg(output)
+            .arg(answer)
+            .output()
+            .map_err(|err| {
+                PbInfoError::IOError(format!(
+                    "Could not run the checker `{}`: {}",
+                    self.program.display(),
+                    err
+                ))
+            });
+        let _ = std::fs::remove_dir_all(&dir);
+        Ok(status?.status.success())
+    }
+}
+
+/// Checkers selected per problem, falling back to a default checker (TokenChecker, unless
+/// changed) for the problems without one.
+pub struct Checkers {
+    /// Checker for problems that were not registered
+    default: Box<dyn Checker>,
+    /// Checkers keyed by problem id
+    problems: HashMap<ProblemId, Box<dyn Checker>>,
+}
+
+impl Default for Checkers {
+    fn default() -> Self {
+        Checkers {
+            default: Box::new(TokenChecker),
+            problems: HashMap::new(),
+        }
+    }
+}
+
+impl Checkers {
+    /// Creates a registry where every problem uses TokenChecker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses `checker` for the problems that were not registered.
+    pub fn set_default(&mut self, checker: impl Checker + 'static) -> &mut Self {
+        self.default = Box::new(checker);
+        self
+    }
+
+    /// Uses `checker` for the problem `id`.
+    pub fn register(&mut self, id: ProblemId, checker: impl Checker + 'static) -> &mut Self {
+        self.problems.insert(id, Box::new(checker));
+        self
+    }
+
+    /// Returns the checker that should be used for `problem`.
+    pub fn for_problem(&self, problem: &PbInfoProblem) -> &dyn Checker {
+        match self.problems.get(&problem.id) {
+            Some(checker) => checker.as_ref(),
+            None => self.default.as_ref(),
+        }
+    }
+}
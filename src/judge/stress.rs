@@ -0,0 +1,306 @@
+use crate::judge::*;
+use crate::*;
+
+/// A small, seedable pseudo-random number generator (xorshift64*), so stress tests can be
+/// reproduced from their seed.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Rng {
+            state: (seed ^ 0x9E37_79B9_7F4A_7C15) | 1,
+        }
+    }
+
+    /// Returns the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a random number between `low` and `high`, both included (in either order).
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        let (low, high) = (low.min(high), low.max(high));
+        // The span may not fit in an `i64`, and does not fit in a `u64` for the whole range
+        match (high.wrapping_sub(low) as u64).checked_add(1) {
+            Some(span) => low.wrapping_add((self.next_u64() % span) as i64),
+            None => self.next_u64() as i64,
+        }
+    }
+}
+
+/// Generates random inputs for a problem.
+pub trait Generator {
+    /// Returns an input of (roughly) `size` elements.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String;
+}
+
+impl<F: Fn(&mut Rng, usize) -> String> Generator for F {
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        self(rng, size)
+    }
+}
+
+/// Generates inputs made of `n` on the first line and `n` numbers on the second one, which is
+/// the format of a large part of the PbInfo problems.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ArrayGenerator {
+    /// Smallest value of the numbers
+    pub min_value: i64,
+    /// Largest value of the numbers
+    pub max_value: i64,
+}
+
+impl ArrayGenerator {
+    /// Derives the range of the values from the restrictions of `problem` (the first
+    /// restriction of the form `a ≤ x ≤ b` that is not about `n`).
+    pub fn from_problem(problem: &PbInfoProblem) -> Self {
+        let restrictions = problem
+            .section("restric")
+            .map(|section| section.text)
+            .unwrap_or_default();
+        let bounds = extract_bounds(&restrictions);
+        let (min_value, max_value) = bounds
+            .iter()
+            .find(|(variable, _, _)| variable != "n")
+            .map(|(_, low, high)| (*low, *high))
+            .unwrap_or((1, 1000));
+        ArrayGenerator {
+            min_value,
+            max_value,
+        }
+    }
+}
+
+impl Generator for ArrayGenerator {
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let n = rng.range(1, size.max(1) as i64);
+        let values = (0..n)
+            .map(|_| rng.range(self.min_value, self.max_value).to_string())
+            .collect::<Vec<_>>();
+        format!("{}\n{}\n", n, values.join(" "))
+    }
+}
+
+/// Extracts restrictions of the form `low ≤ variable ≤ high` (`<=` and `10^k` are accepted too).
+pub fn extract_bounds(text: &str) -> Vec<(String, i64, i64)> {
+    let regex = &selectors::BOUNDS;
+    let number = |string: &str| -> Option<i64> {
+        match string.split_once('^') {
+            Some((base, exponent)) => {
+                let base = base.parse::<i64>().ok()?;
+                base.checked_pow(exponent.parse::<u32>().ok()?)
+            }
+            None => string.replace('.', "").parse::<i64>().ok(),
+        }
+    };
+
+    regex
+        .captures_iter(text)
+        .filter_map(|caps| Some((caps[2].to_owned(), number(&caps[1])?, number(&caps[3])?)))
+        .collect()
+}
+
+/// Settings of a stress test.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StressOptions {
+    /// Number of random inputs to try
+    pub iterations: usize,
+    /// Size passed to the generator (sizes grow up to it during the test)
+    pub max_size: usize,
+    /// Seed of the first input
+    pub seed: u64,
+    /// Limits the candidate runs under (the reference runs without any limit)
+    pub limits: Limits,
+}
+
+impl Default for StressOptions {
+    fn default() -> Self {
+        StressOptions {
+            iterations: 1000,
+            max_size: 10,
+            seed: 0,
+            limits: Limits::default(),
+        }
+    }
+}
+
+/// An input on which the candidate fails.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Counterexample {
+    /// The input, together with the output of the reference solution
+    pub test: Example,
+    /// What the candidate did
+    pub result: TestResult,
+    /// Seed that generated the input, before it was shrunk
+    pub seed: u64,
+    /// Size that generated the input, before it was shrunk
+    pub size: usize,
+}
+
+/// Runs `reference` on `input` without any limit, returning its output, or its verdict if it
+/// failed.
+fn reference_output(
+    reference: &CompiledProgram,
+    problem: &PbInfoProblem,
+    input: &str,
+) -> Result<std::result::Result<String, Verdict>> {
+    let test = Example {
+        input: input.to_owned(),
+        output: String::new(),
+    };
+    let unlimited = Limits {
+        time: None,
+        memory: None,
+        stack: None,
+    };
+    let result = run_test(reference, problem, &test, &unlimited, &AcceptAll)?;
+    Ok(match result.verdict {
+        Verdict::Accepted => Ok(result.output),
+        verdict => Err(verdict),
+    })
+}
+
+/// Accepts every output; used to run the reference solution.
+struct AcceptAll;
+
+impl Checker for AcceptAll {
+    fn check(&self, _: &Example, _: &str) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+/// Runs `candidate` on `input`, returning the test and what the candidate did if its output
+/// differs from the one of `reference`. Inputs on which the reference fails are errors, unless
+/// they are `shrunk` ones, which are only skipped.
+fn try_input(
+    problem: &PbInfoProblem,
+    reference: &CompiledProgram,
+    candidate: &CompiledProgram,
+    options: &StressOptions,
+    input: String,
+    shrunk: bool,
+) -> Result<Option<(Example, TestResult)>> {
+    let output = match reference_output(reference, problem, &input)? {
+        Ok(output) => output,
+        Err(_) if shrunk => return Ok(None),
+        Err(verdict) => {
+            return Err(PbInfoError::Error(format!(
+                "The reference solution failed with {:?} on:\n{}",
+                verdict, input
+            )))
+        }
+    };
+    let test = Example { input, output };
+    let result = run_test(candidate, problem, &test, &options.limits, &TokenChecker)?;
+    Ok(match result.verdict {
+        Verdict::Accepted => None,
+        _ => Some((test, result)),
+    })
+}
+
+/// Maximum number of smaller inputs tried when shrinking a counterexample.
+const MAX_SHRINK_ATTEMPTS: usize = 500;
+
+/// Returns the inputs obtained from `input` by removing one of its lines or one of the tokens
+/// of a line. When a token is removed from a line whose length is given by a first line made
+/// of a single number (`n` followed by `n` numbers), that number is decreased too.
+fn shrink_candidates(input: &str) -> Vec<String> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let join = |lines: &[String]| {
+        let mut joined = lines.join("\n");
+        if input.ends_with('\n') {
+            joined.push('\n');
+        }
+        joined
+    };
+    let count = lines
+        .first()
+        .and_then(|line| line.trim().parse::<usize>().ok());
+
+    let mut candidates = Vec::new();
+    for removed in 0..lines.len() {
+        let kept = lines
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != removed)
+            .map(|(_, line)| line.to_string())
+            .collect::<Vec<_>>();
+        candidates.push(join(&kept));
+    }
+    for (index, line) in lines.iter().enumerate() {
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        for removed in 0..tokens.len() {
+            let mut shrunk = lines
+                .iter()
+                .map(|line| line.to_string())
+                .collect::<Vec<_>>();
+            shrunk[index] = tokens
+                .iter()
+                .enumerate()
+                .filter(|(token, _)| *token != removed)
+                .map(|(_, token)| *token)
+                .collect::<Vec<_>>()
+                .join(" ");
+            if index > 0 && count == Some(tokens.len()) {
+                shrunk[0] = (tokens.len() - 1).to_string();
+            }
+            candidates.push(join(&shrunk));
+        }
+    }
+    candidates
+}
+
+/// Compares `candidate` against `reference` on random inputs from `generator` until they
+/// disagree, then shrinks the input by removing lines and tokens from it (see
+/// `shrink_candidates`) while they still disagree. Returns `None` if no counterexample was
+/// found.
+pub fn stress(
+    problem: &PbInfoProblem,
+    reference: &CompiledProgram,
+    candidate: &CompiledProgram,
+    generator: &dyn Generator,
+    options: &StressOptions,
+) -> Result<Option<Counterexample>> {
+    for iteration in 0..options.iterations {
+        // Small inputs come first, as they are easier to debug
+        let size = 1 + iteration * options.max_size.max(1) / options.iterations.max(1);
+        let seed = options.seed.wrapping_add(iteration as u64);
+        let input = generator.generate(&mut Rng::new(seed), size);
+        let (mut test, mut result) =
+            match try_input(problem, reference, candidate, options, input, false)? {
+                Some(found) => found,
+                None => continue,
+            };
+
+        // Keep the first smaller input that still fails, until none of them does
+        let mut attempts = 0;
+        'shrink: while attempts < MAX_SHRINK_ATTEMPTS {
+            for input in shrink_candidates(&test.input) {
+                attempts += 1;
+                if let Some(found) = try_input(problem, reference, candidate, options, input, true)?
+                {
+                    (test, result) = found;
+                    continue 'shrink;
+                }
+                if attempts == MAX_SHRINK_ATTEMPTS {
+                    break;
+                }
+            }
+            break;
+        }
+        return Ok(Some(Counterexample {
+            test,
+            result,
+            seed,
+            size,
+        }));
+    }
+    Ok(None)
+}
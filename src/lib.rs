@@ -1,10 +1,10 @@
 /// A problem from  PbInfo. Can be constructed using an id or a name.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PbInfoProblem {
     /// Unique id of problem
-    pub id: usize,
+    pub id: ProblemId,
     /// Unique name of problem
-    pub name: String,
+    pub name: ProblemName,
     /// Html containing input source, output source etc.
     pub meta_text: String,
     /// Html containing task, examples etc.
@@ -15,7 +15,7 @@ pub struct PbInfoProblem {
     /// File name of stdout
     pub output_source: IOSource,
     /// 9th, 10th or 11th grade
-    pub grade: usize,
+    pub grade: Grade,
 
     /// Time limit (if it exists)
     pub time_limit: Option<String>,
@@ -28,10 +28,20 @@ pub struct PbInfoProblem {
     pub author: Option<String>,
     /// Difficulty (if it exists)
     pub difficulty: Option<Difficulty>,
+    /// Chapters and subchapters containing the problem, from the most general (e.g.
+    /// `["Clasa a IX-a", "Tablouri unidimensionale"]`)
+    pub topics: Vec<String>,
+    /// Files attached to the statement ("Atașamente"), such as PDFs or extra inputs
+    pub attachments: Vec<Attachment>,
+
+    /// ETag header of the problem page (if the server sent one)
+    pub etag: Option<String>,
+    /// Last-Modified header of the problem page (if the server sent one)
+    pub last_modified: Option<String>,
 }
 
 /// Describes the input/output source of a PbInfoProblem.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum IOSource {
     /// The source is a file.
     File(String),
@@ -40,7 +50,7 @@ pub enum IOSource {
 }
 
 /// Difficulty of PbInfoProblem
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Difficulty {
     /// Easy (Ușor)
     Easy,
@@ -52,17 +62,313 @@ pub enum Difficulty {
     Contest,
 }
 
+/// A section of the problem statement, delimited by an `<h1>` heading.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Section {
+    /// Heading of the section (e.g. "Cerința", "Date de intrare", "Interacțiune")
+    pub title: String,
+    /// Html between this heading and the next one
+    pub html: String,
+    /// `html` converted to plain text
+    pub text: String,
+    /// The tables of the section (e.g. value tables or scoring schemes)
+    pub tables: Vec<Table>,
+}
+
+/// A table from a problem statement, as plain text.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Table {
+    /// Cells of the header row (empty if the table has no header)
+    pub headers: Vec<String>,
+    /// The other rows, each with one cell per column
+    pub rows: Vec<Vec<String>>,
+}
+
+/// A file attached to a problem statement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Attachment {
+    /// File name, as displayed by PbInfo
+    pub name: String,
+    /// Absolute url of the file
+    pub url: String,
+    /// Size in bytes (if PbInfo shows it)
+    pub size: Option<u64>,
+}
+
+/// An example from the statement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Example {
+    /// Contents of the input file (or stdin)
+    pub input: String,
+    /// Expected contents of the output file (or stdout)
+    pub output: String,
+}
+
+/// A group of tests worth a part of the score, as described in the restrictions.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Subtask {
+    /// Points awarded for the subtask (percentages of the tests are treated as points out of 100)
+    pub points: usize,
+    /// Constraints of the subtask, as plain text (e.g. "n ≤ 1000")
+    pub constraints: String,
+}
+
+/// How the solution of an interactive problem communicates with the interactor.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InteractionProtocol {
+    /// Html of the statement section describing the interaction
+    pub html: String,
+    /// `html` converted to plain text
+    pub text: String,
+    /// Whether the statement asks for the output to be flushed after every message
+    pub requires_flush: bool,
+}
+
+/// The contest a problem comes from, parsed from its `source` (e.g. "ONI 2016, clasele XI-XII").
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ContestSource {
+    /// Name of the contest (e.g. "ONI")
+    pub contest: String,
+    /// Year of the contest (if it is specified)
+    pub year: Option<u16>,
+    /// Grades or category the problem was given to (e.g. "clasele XI-XII")
+    pub division: Option<String>,
+    /// The unparsed source
+    pub raw: String,
+}
+
+/// A problem found by searching PbInfo.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SearchResult {
+    /// Unique id of problem
+    pub id: ProblemId,
+    /// Name of problem, as displayed by PbInfo
+    pub name: String,
+}
+
+/// A row of the PbInfo ranking ("clasament").
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RankEntry {
+    /// Position in the ranking
+    pub rank: usize,
+    /// Username of the user
+    pub username: String,
+    /// Number of solved problems
+    pub solved: usize,
+    /// Total score
+    pub score: usize,
+}
+
+/// A problem listed on the public profile of a user.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PublicSolve {
+    /// Unique id of problem
+    pub id: ProblemId,
+    /// Name of problem, as displayed by PbInfo
+    pub name: String,
+    /// Best score of the user (if the profile shows it)
+    pub score: Option<usize>,
+}
+
+/// A submission listed in a submission history ("soluții trimise").
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Submission {
+    /// Unique id of submission (evaluation)
+    pub id: usize,
+    /// Id of the problem
+    pub problem: ProblemId,
+    /// Username of the author
+    pub username: String,
+    /// Score (`None` while it is evaluated, or if it is not shown)
+    pub score: Option<usize>,
+    /// When it was submitted, as a Unix timestamp of the time displayed by PbInfo (Romanian
+    /// local time, read as if it were UTC; see `parse_datetime`)
+    pub submitted_at: Option<u64>,
+}
+
+/// Restricts which submissions are listed by `fetch_submissions`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SubmissionFilter {
+    /// Only submissions of this user
+    pub username: Option<String>,
+    /// Only submissions to this problem
+    pub problem: Option<ProblemId>,
+    /// Only submissions made at or after this time (see `Submission::submitted_at`)
+    pub since: Option<u64>,
+    /// Only submissions made at or before this time
+    pub until: Option<u64>,
+    /// Stop after this many submissions (all pages are fetched otherwise)
+    pub max_entries: Option<usize>,
+}
+
+impl SubmissionFilter {
+    /// Returns `true` if `submission` was made between `since` and `until`. Submissions
+    /// without a known time only match filters without dates.
+    pub fn matches_time(&self, submission: &Submission) -> bool {
+        match submission.submitted_at {
+            Some(at) => {
+                self.since.is_none_or(|since| at >= since)
+                    && self.until.is_none_or(|until| at <= until)
+            }
+            None => self.since.is_none() && self.until.is_none(),
+        }
+    }
+}
+
+/// Submission statistics of a problem, as shown on its page.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ProblemStats {
+    /// Number of submitted solutions
+    pub attempts: usize,
+    /// Number of solutions that got 100 points
+    pub solved: usize,
+    /// Average score of the submitted solutions, if PbInfo shows it
+    pub average_score: Option<f64>,
+    /// Percentage (0 to 100) of the submitted solutions that got 100 points
+    pub success_rate: f64,
+    /// Number of solutions that got 100 points in every language (`C++`, `C`, `Pascal`,
+    /// `Python`, `Java`, `C#`), if PbInfo shows it; empty otherwise
+    pub language_breakdown: std::collections::BTreeMap<String, usize>,
+}
+
+impl ProblemStats {
+    /// Returns the fraction (0 to 1) of the solutions with 100 points that are written in
+    /// `language` (e.g. `Python`), or `None` if the breakdown is not known. A problem that
+    /// nobody solved in Python may not be solvable in Python within its time limit.
+    pub fn language_share(&self, language: &str) -> Option<f64> {
+        let total = self.language_breakdown.values().sum::<usize>();
+        if total == 0 {
+            return None;
+        }
+        let solved = self
+            .language_breakdown
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(language))
+            .map(|(_, solved)| solved)
+            .sum::<usize>();
+        Some(solved as f64 / total as f64)
+    }
+}
+
+/// Evaluation status of a submission, as shown on its page (`/detalii-evaluare/{id}`).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SubmissionStatus {
+    /// The submission is waiting to be evaluated.
+    Queued,
+    /// The submission is being evaluated, storing how many tests were already evaluated.
+    Evaluating(usize),
+    /// The evaluation is complete, storing the score.
+    Finished(usize),
+    /// The source did not compile, storing the compiler messages.
+    CompilationError(String),
+}
+
+impl SubmissionStatus {
+    /// Whether the status is final, i.e. it will not change anymore.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            SubmissionStatus::Finished(_) | SubmissionStatus::CompilationError(_)
+        )
+    }
+}
+
+/// Restricts which users appear in the ranking.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct RankingFilter {
+    /// Only users from this county (județ)
+    pub county: Option<String>,
+    /// Only users in this grade
+    pub grade: Option<Grade>,
+    /// Stop after this many entries (all pages are fetched otherwise)
+    pub max_entries: Option<usize>,
+}
+
+/// Result of refreshing a cached PbInfoProblem.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Freshness {
+    /// The statement or metadata changed since the cached copy was fetched.
+    Changed,
+    /// The cached copy is still up to date.
+    Unchanged,
+}
+
+/// An extra step that PbInfo requires before accepting a login.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LoginChallenge {
+    /// What kind of answer is expected
+    pub kind: ChallengeKind,
+    /// Url of the image that has to be solved (for captchas)
+    pub image_url: Option<String>,
+    /// Token that has to be sent back together with the answer
+    pub token: String,
+}
+
+/// Kinds of LoginChallenge.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ChallengeKind {
+    /// An image captcha; the answer is the text in the image.
+    Captcha,
+    /// A Google reCAPTCHA, storing the site key. It can only be solved in a browser.
+    ReCaptcha(String),
+    /// A verification code (e.g. sent by email).
+    Verification,
+}
+
+/// A problem suggested in place of an unknown name.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Suggestion {
+    /// Id of the problem
+    pub id: ProblemId,
+    /// Name of the problem
+    pub name: String,
+    /// How similar the name is to the unknown one, from 0 to 1 (see `name_similarity`)
+    pub score: f32,
+}
+
+// Scores are never NaN
+impl Eq for Suggestion {}
+
 /// Errors that may be encuntered when constructing a PbInfoProblem.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum PbInfoError {
     /// Stores the unknown id.
-    UnknownId(usize),
-    /// Stores the unknown name, as well as a list of potential known names.
-    UnknownName(String, Vec<String>),
+    UnknownId(ProblemId),
+    /// Stores the unknown name, as well as the problems found with similar names, most similar
+    /// first.
+    UnknownName(String, Vec<Suggestion>),
     /// Error message related to networking.
     NetworkError(String),
     /// Error message related to JSON interpretation.
     JSONError(String),
+    /// Error message related to logging in.
+    LoginError(String),
+    /// The login requires solving a challenge, which can be answered using
+    /// `PbInfoClient::continue_login`.
+    LoginChallenge(LoginChallenge),
+    /// Error message related to reading or writing files.
+    IOError(String),
+    /// PbInfo is throttling the requests (HTTP 429 or 503) and kept doing so after backing off.
+    /// Stores how long PbInfo asked to wait, if it did.
+    RateLimited {
+        retry_after: Option<std::time::Duration>,
+    },
+    /// The requests are short-circuited because the previous ones kept failing (see
+    /// `PbInfoClientBuilder::circuit_breaker`). Stores how long until requests are let through
+    /// again.
+    CircuitOpen { retry_after: std::time::Duration },
+    /// PbInfo is serving a maintenance (or holiday) page instead of its content. Stores the
+    /// notice shown on the page.
+    SiteUnavailable(String),
+    /// The request is disallowed by PbInfo's robots.txt, which the client was configured to
+    /// respect. Stores the url of the request.
+    RobotsDisallowed(String),
+    /// A composite operation (e.g. `PbInfoClient::fetch_overview`) or the evaluation of a
+    /// watched submission did not finish before its deadline. Stores the parts that were still
+    /// being fetched.
+    DeadlineExceeded(String),
+    /// Diagnostics of a solution that failed to compile.
+    CompilationError(String),
     /// Error message related to the Html text that should contatin certain regex
     /// patterns.
     RegexError(String),
@@ -71,137 +377,350 @@ pub enum PbInfoError {
 }
 type Result<T> = std::result::Result<T, PbInfoError>;
 
+/// User-Agent sent with every request, unless configured otherwise. It identifies the crate,
+/// so PbInfo knows where the traffic comes from.
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "pbinfo-rs/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/andcov/pbinfo)"
+);
+
+/// Address of PbInfo, used unless the `PBINFO_BASE_URL` environment variable or
+/// `PbInfoClientBuilder::base_url` point somewhere else (e.g. a mirror or a test server).
+pub const DEFAULT_BASE_URL: &str = "https://www.pbinfo.ro";
+
+/// Returns the base url set by the `PBINFO_BASE_URL` environment variable, or
+/// `DEFAULT_BASE_URL`. The trailing slash is removed.
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn base_url_from_env() -> String {
+    std::env::var("PBINFO_BASE_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_BASE_URL.to_owned())
+        .trim()
+        .trim_end_matches('/')
+        .to_owned()
+}
+
+mod archive;
+mod articles;
+mod assets;
+mod authors;
+#[cfg(feature = "blocking")]
+mod batch;
+#[cfg(feature = "blocking")]
+mod breaker;
+pub mod bundle;
+#[cfg_attr(not(feature = "blocking"), allow(dead_code))]
+mod cache;
+pub mod calendar;
+#[cfg(feature = "blocking")]
+mod client;
+mod code;
+pub mod codegen;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "blocking")]
+pub mod contest;
+mod convert;
+#[cfg(feature = "keyring")]
+mod credentials;
+#[cfg(feature = "blocking")]
+pub mod daemon;
+#[cfg(feature = "blocking")]
+mod deadline;
+mod diff;
+#[cfg(feature = "blocking")]
+mod discover;
+mod display;
+#[cfg(feature = "blocking")]
+mod download;
+mod export;
+// Without the blocking client, some of the search helpers are only used by the tests
+#[cfg_attr(not(feature = "blocking"), allow(dead_code))]
 mod extract;
+pub mod feed;
+#[cfg(feature = "blocking")]
+mod fetch;
+#[cfg(feature = "async")]
+mod fetch_async;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod filter;
+pub mod golden;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(feature = "blocking")]
+mod groups;
+#[cfg(feature = "homework")]
+pub mod homework;
+mod ids;
+pub mod judge;
+mod links;
+mod metrics;
+#[cfg(feature = "blocking")]
+pub mod mirror;
+#[cfg(feature = "blocking")]
+pub mod notify;
+mod pin;
+#[cfg(feature = "blocking")]
+mod pool;
+mod preflight;
+pub mod progress;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "blocking")]
+pub mod quiz;
+mod rating;
+#[cfg(feature = "blocking")]
+mod recording;
+mod render;
+#[cfg(feature = "git")]
+pub mod repo;
+#[cfg(feature = "blocking")]
+mod response_cache;
+#[cfg(feature = "blocking")]
+mod robots;
+mod sanitize;
+#[cfg(feature = "blocking")]
+mod search;
+mod selectors;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "blocking")]
+mod session;
+mod similarity;
+#[cfg(feature = "blocking")]
+mod submit;
+pub mod sync;
+pub mod template;
 mod tests;
+mod text;
+mod translate;
+#[cfg(feature = "blocking")]
+mod watch;
+pub use crate::archive::*;
+pub use crate::articles::*;
+pub use crate::assets::*;
+pub use crate::authors::*;
+#[cfg(feature = "blocking")]
+pub use crate::batch::*;
+pub use crate::cache::CacheStats;
+#[cfg(feature = "blocking")]
+pub use crate::client::*;
+pub use crate::code::*;
+#[cfg(feature = "keyring")]
+pub use crate::credentials::*;
+#[cfg(feature = "blocking")]
+pub use crate::deadline::*;
+pub use crate::diff::*;
+#[cfg(feature = "blocking")]
+pub use crate::discover::*;
+pub use crate::display::*;
+#[cfg(feature = "blocking")]
+pub use crate::download::*;
+pub use crate::export::*;
 use crate::extract::*;
-
-/// Makes a get request to `url`
-fn get_page(url: &str) -> reqwest::blocking::Response {
-    reqwest::blocking::get(url).expect("Encountered an error while making a request to pbinfo.ro")
-}
+pub use crate::extract::{extraction_report, parse_datetime, ExtractionReport};
+#[cfg(feature = "blocking")]
+pub use crate::fetch::*;
+pub use crate::filter::*;
+#[cfg(feature = "blocking")]
+pub use crate::groups::*;
+pub use crate::ids::*;
+pub use crate::links::LinkTarget;
+pub use crate::metrics::*;
+pub use crate::pin::*;
+#[cfg(feature = "blocking")]
+pub use crate::pool::*;
+pub use crate::preflight::*;
+pub use crate::rating::*;
+#[cfg(feature = "blocking")]
+pub use crate::recording::RecordMode;
+pub use crate::render::*;
+#[cfg(feature = "blocking")]
+pub use crate::response_cache::*;
+#[cfg(feature = "blocking")]
+pub use crate::robots::RobotsRules;
+pub use crate::sanitize::*;
+#[cfg(feature = "blocking")]
+pub use crate::search::*;
+pub use crate::similarity::*;
+#[cfg(feature = "blocking")]
+pub use crate::submit::*;
+pub use crate::text::*;
+pub use crate::translate::*;
+#[cfg(feature = "blocking")]
+pub use crate::watch::*;
 
 impl PbInfoProblem {
-    /// Construct PbInfoProblem from id.
-    pub fn fetch_problem_by_id(id: usize) -> Result<Self> {
-        let page = get_page(&format!("https://www.pbinfo.ro/probleme/{}", id));
-
-        match page.status() {
-            reqwest::StatusCode::OK => {
-                let text = page.text().unwrap();
-
-                let name_regex =
-                    regex::Regex::new(r"<title>Problema ([\w]+) \| www.pbinfo.ro</title>").unwrap();
-                let name = &name_regex.captures(&text).unwrap()[1];
-                let name = name.to_lowercase();
-                let name = name.as_str();
-
-                let text_regex = regex::Regex::new(r"(<h1>Cerința</h1>[\s\S]*)</article>").unwrap();
-                let problem_text = match text_regex.captures(&text) {
-                    Some(res) => res[1].to_owned(),
-                    None => {
-                        return Err(PbInfoError::RegexError(
-                            "Failed to locate the problem text in the HTML".to_owned(),
-                        ))
-                    }
-                };
-
-                let metadata_regex =
-                    regex::Regex::new(r#"<table class="table table-bordered">([\s\S]*?)</table>"#)
-                        .unwrap();
-                let metadata = match metadata_regex.captures(&text) {
-                    Some(res) => res[1].to_owned(),
-                    None => {
-                        return Err(PbInfoError::RegexError(
-                            "Failed to locate the problem metadata in the HTML".to_owned(),
-                        ))
-                    }
-                };
-
-                Ok(PbInfoProblem {
-                    id,
-                    name: name.to_owned(),
-                    problem_text,
-                    meta_text: metadata.clone(),
-
-                    input_source: extract_input_source(&metadata)?,
-                    output_source: extract_output_source(&metadata)?,
-                    grade: extract_grade(&metadata)?,
-
-                    time_limit: extract_time_limit(&metadata)?,
-                    memory_limit: extract_memory_limit(&metadata)?,
-
-                    source: extract_source(&metadata)?,
-                    author: extract_author(&metadata)?,
-                    difficulty: extract_difficulty(&metadata)?,
-                })
-            }
-            reqwest::StatusCode::NOT_FOUND => Err(PbInfoError::UnknownId(id)), // If the page does not exist, it means the id is wrong
-            s => Err(PbInfoError::NetworkError(format!(
-                "Encountered an error when trying to fetch the problem. HTTP status code {}",
-                s
-            ))),
+    /// Construct PbInfoProblem from the html of its page (e.g. a page that is already open in
+    /// a browser). Does not make any requests.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(text), err(Debug)))]
+    pub fn from_html(id: ProblemId, text: &str) -> Result<Self> {
+        // Maintenance pages are served with HTTP 200, so they are recognized by their content
+        if extract_problem_text(text).is_err() {
+            check_site_available(text)?;
         }
+
+        let mut problem = Self::from_parts(
+            id,
+            extract_name(text)?,
+            extract_problem_text(text)?,
+            extract_metadata(text)?,
+        )?;
+        problem.topics = extract_topics(text);
+        problem.attachments = extract_attachments(text);
+        Ok(problem)
     }
 
-    /// Construct PbInfoProblem from name.
-    pub fn fetch_problem_by_name(name: &str) -> Result<Self> {
-        use std::collections::HashMap;
-
-        // `name` is converted to lowercase
-        let name = name.to_lowercase();
-        let name = name.as_str();
-
-        // Get a list of all of the problems that (partially) match `name`
-        let search_json = match get_page(&format!(
-            "https://www.pbinfo.ro/php/ajax-search.php?term={}",
-            name
-        ))
-        .json::<Vec<HashMap<String, String>>>()
-        {
-            Ok(res) => res,
-            Err(_) => {
-                return Err(PbInfoError::JSONError(
-                    "Could not parse JSON response".to_owned(),
-                ))
-            }
+    /// Construct PbInfoProblem from the already extracted parts of its page.
+    pub(crate) fn from_parts(
+        id: ProblemId,
+        name: String,
+        problem_text: String,
+        metadata: String,
+    ) -> Result<Self> {
+        Ok(PbInfoProblem {
+            id,
+            name: ProblemName::new(&name)?,
+            problem_text,
+
+            input_source: extract_input_source(&metadata)?,
+            output_source: extract_output_source(&metadata)?,
+            grade: Grade(extract_grade(&metadata)?),
+
+            time_limit: extract_time_limit(&metadata)?,
+            memory_limit: extract_memory_limit(&metadata)?,
+
+            source: extract_source(&metadata)?,
+            author: extract_author(&metadata)?,
+            difficulty: extract_difficulty(&metadata)?,
+            topics: Vec::new(),
+            attachments: Vec::new(),
+
+            etag: None,
+            last_modified: None,
+
+            meta_text: metadata,
+        })
+    }
+
+    /// Splits the statement into its sections, in the order in which they appear. Unlike the
+    /// specialised accessors, this keeps every section, including unusual ones such as
+    /// "Interacțiune" or "Subtask-uri".
+    pub fn sections(&self) -> Vec<Section> {
+        extract_sections(&self.problem_text)
+            .into_iter()
+            .map(|(title, html)| Section {
+                text: html_to_text(&html),
+                title: html_to_text(&title),
+                tables: extract_tables(&html),
+                html,
+            })
+            .collect()
+    }
+
+    /// Returns the tables of the statement, in order.
+    pub fn tables(&self) -> Vec<Table> {
+        extract_tables(&self.problem_text)
+    }
+
+    /// Returns the first section whose title starts with `prefix` (case insensitive).
+    pub fn section(&self, prefix: &str) -> Option<Section> {
+        let prefix = prefix.to_lowercase();
+        self.sections()
+            .into_iter()
+            .find(|section| section.title.to_lowercase().starts_with(&prefix))
+    }
+
+    /// Returns the subtasks described in the restrictions (and in any "Subtask" sections). The
+    /// list is empty if the statement does not describe partial scores in a recognizable way.
+    pub fn subtasks(&self) -> Vec<Subtask> {
+        self.sections()
+            .into_iter()
+            .filter(|section| {
+                let title = section.title.to_lowercase();
+                title.starts_with("restric") || title.starts_with("subtask")
+            })
+            .flat_map(|section| extract_subtasks(&section.text))
+            .collect()
+    }
+
+    /// Returns the interaction protocol of an interactive problem: the "Interacțiune" (or
+    /// "Protocol de interacțiune") section, or else the first section that calls the problem
+    /// interactive or mentions an interactor.
+    pub fn interaction_protocol(&self) -> Option<InteractionProtocol> {
+        let sections = self.sections();
+        let section = sections
+            .iter()
+            .find(|section| is_interaction_title(&section.title))
+            .or_else(|| {
+                sections
+                    .iter()
+                    .find(|section| mentions_interaction(&section.text))
+            })?;
+        Some(InteractionProtocol {
+            html: section.html.clone(),
+            text: section.text.clone(),
+            requires_flush: mentions_flush(&section.text),
+        })
+    }
+
+    /// Returns `true` if the solution talks to an interactor instead of reading all of its
+    /// input up front, so its output can not be simply compared with an expected one.
+    pub fn is_interactive(&self) -> bool {
+        self.interaction_protocol().is_some()
+    }
+
+    /// Returns every column of the metadata table as a `header -> value` map (both as plain
+    /// text), including columns that are not modelled by PbInfoProblem's fields.
+    pub fn metadata_raw(&self) -> std::collections::BTreeMap<String, String> {
+        extract_metadata_map(&self.meta_text)
+    }
+
+    /// Returns the contest the problem comes from, if its source names one.
+    pub fn contest_source(&self) -> Option<ContestSource> {
+        self.source.as_deref().and_then(extract_contest_source)
+    }
+
+    /// Returns the examples from the statement, in order.
+    pub fn examples(&self) -> Vec<Example> {
+        self.sections()
+            .into_iter()
+            .filter(|section| section.title.to_lowercase().starts_with("exempl"))
+            .flat_map(|section| extract_examples(&section.html))
+            .collect()
+    }
+
+    /// Writes every example to `dir` as `{name}.{n}.in` / `{name}.{n}.ok` (numbered from 1),
+    /// where `name` is the stem of the input file (or the problem name, for stdin). `dir` is
+    /// created if it does not exist. Returns the paths of the written files.
+    pub fn write_examples(
+        &self,
+        dir: impl AsRef<std::path::Path>,
+    ) -> Result<Vec<std::path::PathBuf>> {
+        let dir = dir.as_ref();
+        let io_error = |path: &std::path::Path, err: std::io::Error| {
+            PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
         };
+        std::fs::create_dir_all(dir).map_err(|err| io_error(dir, err))?;
 
-        // A list of suggested problems; used only in case we do not find a matching name
-        let mut suggested_problems: Vec<String> = Vec::new();
-        for map in search_json.iter() {
-            let possible_name = match map.get("value") {
-                Some(res) => res,
-                None => {
-                    return Err(PbInfoError::JSONError(
-                        "JSON should contain the 'value' attribute".to_owned(),
-                    ))
-                }
-            };
-
-            if possible_name.to_lowercase() == name {
-                let label = match map.get("label") {
-                    Some(res) => res,
-                    None => {
-                        return Err(PbInfoError::JSONError(
-                            "JSON should contain the 'label' attribute".to_owned(),
-                        ))
-                    }
-                };
-
-                // Try to get the id from the JSON
-                let id = extract_id_from_json(&label)?;
-
-                // Try to get the problem associated to `id`
-                return Self::fetch_problem_by_id(id);
-            } else {
-                // If we do not get a match, we add the name to the a list of suggested problems
-                suggested_problems.push(possible_name.clone());
+        let name = match &self.input_source {
+            IOSource::File(file) => file
+                .rsplit_once('.')
+                .map_or(file.as_str(), |(stem, _)| stem),
+            IOSource::Std => &self.name,
+        };
+
+        let mut paths = Vec::new();
+        for (i, example) in self.examples().iter().enumerate() {
+            for (extension, contents) in [("in", &example.input), ("ok", &example.output)] {
+                let path = dir.join(format!("{}.{}.{}", name, i + 1, extension));
+                std::fs::write(&path, contents).map_err(|err| io_error(&path, err))?;
+                paths.push(path);
             }
         }
-
-        return Err(PbInfoError::UnknownName(
-            name.to_owned(),
-            suggested_problems,
-        ));
+        Ok(paths)
     }
 }
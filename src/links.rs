@@ -0,0 +1,65 @@
+use crate::render::absolute_url;
+use crate::*;
+
+/// Where a link of a statement or of an article points to.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum LinkTarget {
+    /// A PbInfo problem (`/probleme/1691/arbore1`)
+    Problem(ProblemId),
+    /// A PbInfo article (`/articole/18/sortarea-prin-selectie`)
+    Article(usize),
+    /// Any other page, as an absolute url
+    External(String),
+}
+
+impl LinkTarget {
+    /// Resolves the `href` of a link. Relative urls point to PbInfo; links that do not point to
+    /// a page (anchors, `mailto:`, `javascript:` etc.) return `None`.
+    pub fn parse(href: &str) -> Option<Self> {
+        let internal_regex = &selectors::PBINFO_URL;
+        let path_regex = &selectors::PBINFO_PATH;
+
+        let url = absolute_url(href)?;
+        let path = internal_regex
+            .captures(&url)
+            .map(|caps| caps.get(1).map_or("/", |path| path.as_str()).to_owned());
+        let target = path.as_deref().and_then(|path| {
+            let caps = path_regex.captures(path)?;
+            let id = caps[2].parse::<usize>().ok()?;
+            Some(match &caps[1] {
+                "probleme" => LinkTarget::Problem(ProblemId(id)),
+                _ => LinkTarget::Article(id),
+            })
+        });
+        Some(target.unwrap_or(LinkTarget::External(url)))
+    }
+}
+
+/// Returns the targets of the links of `html`, in the order of their first link.
+pub(crate) fn extract_links(html: &str) -> Vec<LinkTarget> {
+    let regex = &selectors::HREF;
+
+    let mut links = Vec::new();
+    for caps in regex.captures_iter(html) {
+        if let Some(target) = LinkTarget::parse(&decode_entities(&caps[1])) {
+            if !links.contains(&target) {
+                links.push(target);
+            }
+        }
+    }
+    links
+}
+
+impl PbInfoProblem {
+    /// Returns the targets of the links of the statement, in the order of their first link.
+    pub fn links(&self) -> Vec<LinkTarget> {
+        extract_links(&self.problem_text)
+    }
+}
+
+impl Article {
+    /// Returns the targets of the links of the article, in the order of their first link.
+    pub fn links(&self) -> Vec<LinkTarget> {
+        extract_links(&self.html)
+    }
+}
@@ -1,6 +1,276 @@
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use pbinfo::config::Config;
 use pbinfo::*;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+/// Problems, examples and search results from PbInfo.
+#[derive(Debug, Parser)]
+#[command(name = "pbinfo", version, about)]
+struct Cli {
+    /// How the output is written: `json` for scripts, `table` or `plain` for people
+    #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    /// Configuration file to read instead of `~/.config/pbinfo.toml`
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Directory where the responses from PbInfo are cached
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+    /// Address of PbInfo (e.g. a mirror)
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Format of the output.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+enum Format {
+    /// JSON, for scripts
+    Json,
+    /// Aligned columns with headers
+    Table,
+    /// One row per line, with the columns separated by tabs and without headers (tabs and line
+    /// breaks inside cells are escaped)
+    Plain,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Shows a problem, given its id or its name
+    Problem { problem: String },
+    /// Searches problems by name
+    Search { query: String },
+    /// Shows the examples of a problem, given its id or its name
+    Examples { problem: String },
+    /// Answers editor plugins with JSON-RPC over stdin and stdout
+    Daemon,
+    /// Prints the completions for a shell
+    Completions { shell: clap_complete::Shell },
+}
+
+/// Escapes the backslashes, tabs and line breaks of a cell (as `\\`, `\t`, `\n` and `\r`), so
+/// that every row of the plain format stays on one line with tab separated cells.
+fn escape_plain(cell: &str) -> String {
+    cell.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// The output of a command, as rows for people and as JSON for scripts.
+struct Output {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+    json: Value,
+}
+
+impl Output {
+    /// Writes the output to stdout in `format`.
+    fn print(&self, format: Format) {
+        match format {
+            Format::Json => println!("{:#}", self.json),
+            Format::Plain => {
+                for row in &self.rows {
+                    let cells = row
+                        .iter()
+                        .map(|cell| escape_plain(cell))
+                        .collect::<Vec<_>>();
+                    println!("{}", cells.join("\t"));
+                }
+            }
+            Format::Table => print!("{}", self.table()),
+        }
+    }
+
+    /// Formats the rows as a table. Cells with several lines make the row taller.
+    fn table(&self) -> String {
+        let mut widths = self
+            .headers
+            .iter()
+            .map(|header| header.chars().count())
+            .collect::<Vec<_>>();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                for line in cell.lines() {
+                    *width = (*width).max(line.chars().count());
+                }
+            }
+        }
+
+        let mut table = String::new();
+        let mut push_line = |cells: &[&str]| {
+            let line = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            table.push_str(line.trim_end());
+            table.push('\n');
+        };
+        push_line(&self.headers);
+        let separators = widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>();
+        push_line(&separators.iter().map(String::as_str).collect::<Vec<_>>());
+        for row in &self.rows {
+            let lines = row
+                .iter()
+                .map(|cell| cell.lines().collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let height = lines.iter().map(Vec::len).max().unwrap_or_default().max(1);
+            for i in 0..height {
+                push_line(
+                    &lines
+                        .iter()
+                        .map(|cell| cell.get(i).copied().unwrap_or_default())
+                        .collect::<Vec<_>>(),
+                );
+            }
+        }
+        table
+    }
+}
+
+impl Cli {
+    /// Returns the configuration: the file, overridden by the environment variables and then by
+    /// the flags.
+    fn config(&self) -> Result<Config, PbInfoError> {
+        let config = match &self.config {
+            Some(path) => Config::load_file(path)?.merge(Config::from_env()?),
+            None => Config::load()?,
+        };
+        let flags = Config {
+            cache_dir: self.cache_dir.clone(),
+            base_url: self.base_url.clone(),
+            ..Config::default()
+        };
+        Ok(config.merge(flags))
+    }
+
+    /// Returns a client configured by `config`.
+    fn client(&self) -> Result<PbInfoClient, PbInfoError> {
+        PbInfoClient::from_config(&self.config()?)
+    }
+}
+
+/// Fetches a problem given its id (`1`, `#1`) or its name.
+fn fetch_problem(client: &PbInfoClient, problem: &str) -> Result<PbInfoProblem, PbInfoError> {
+    match problem.trim_start_matches('#').parse::<usize>() {
+        Ok(id) => client.fetch_problem_by_id(ProblemId(id)),
+        Err(_) => client.fetch_problem_by_name(problem),
+    }
+}
+
+/// Runs the command of `cli`, returning what it outputs (`None` for commands that write their
+/// own output).
+fn run(cli: Cli) -> Result<Option<Output>, PbInfoError> {
+    Ok(Some(match &cli.command {
+        Command::Completions { shell } => {
+            clap_complete::generate(
+                *shell,
+                &mut Cli::command(),
+                "pbinfo",
+                &mut std::io::stdout(),
+            );
+            return Ok(None);
+        }
+        Command::Daemon => {
+            let config = cli.config()?;
+            // The daemon keeps the responses in memory unless they are cached on disk
+            let client = PbInfoClient::builder()
+                .cache_backend(MemoryCache::new(1000))
+                .config(&config)
+                .build()?;
+            client.login_with_config(&config)?;
+            daemon::Daemon::new(client).serve(std::io::stdin().lock(), std::io::stdout().lock())?;
+            return Ok(None);
+        }
+        Command::Problem { problem } => {
+            let problem = fetch_problem(&cli.client()?, problem)?;
+            let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+            let fields = [
+                ("id", problem.id.to_string()),
+                ("name", problem.name.to_string()),
+                ("grade", problem.grade.to_string()),
+                (
+                    "difficulty",
+                    problem
+                        .difficulty
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                ),
+                ("input", problem.input_source.to_string()),
+                ("output", problem.output_source.to_string()),
+                ("time limit", optional(&problem.time_limit)),
+                ("memory limit", optional(&problem.memory_limit)),
+                ("author", optional(&problem.author)),
+                ("source", optional(&problem.source)),
+                ("topics", problem.topics.join(", ")),
+            ];
+            Output {
+                headers: vec!["field", "value"],
+                rows: fields
+                    .into_iter()
+                    .map(|(field, value)| vec![field.to_owned(), value])
+                    .collect(),
+                json: problem.to_json_v1(),
+            }
+        }
+        Command::Search { query } => {
+            let results = cli.client()?.search(query)?;
+            Output {
+                headers: vec!["id", "name"],
+                rows: results
+                    .iter()
+                    .map(|result| vec![result.id.to_string(), result.name.clone()])
+                    .collect(),
+                json: results
+                    .iter()
+                    .map(|result| json!({ "id": result.id.0, "name": result.name }))
+                    .collect(),
+            }
+        }
+        Command::Examples { problem } => {
+            let examples = fetch_problem(&cli.client()?, problem)?.examples();
+            Output {
+                headers: vec!["#", "input", "output"],
+                rows: examples
+                    .iter()
+                    .enumerate()
+                    .map(|(i, example)| {
+                        vec![
+                            (i + 1).to_string(),
+                            example.input.clone(),
+                            example.output.clone(),
+                        ]
+                    })
+                    .collect(),
+                json: examples
+                    .iter()
+                    .map(|example| json!({ "input": example.input, "output": example.output }))
+                    .collect(),
+            }
+        }
+    }))
+}
 
 fn main() {
-    let pb = PbInfoProblem::fetch_problem_by_id(877);
-    println!("{:#?}", pb);
+    let cli = Cli::parse();
+    let format = cli.format;
+    match run(cli) {
+        Ok(Some(output)) => output.print(format),
+        Ok(None) => {}
+        Err(err) => {
+            match format {
+                Format::Json => eprintln!("{:#}", json!({ "error": format!("{:?}", err) })),
+                _ => eprintln!("{:?}", err),
+            }
+            std::process::exit(1);
+        }
+    }
 }
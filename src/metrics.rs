@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+/// Counters of the requests made by a client, for reporting and tuning batch jobs.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Metrics {
+    /// Number of requests sent (retries of throttled requests included)
+    pub requests: u64,
+    /// Number of bytes of response bodies read (after decompression)
+    pub bytes_downloaded: u64,
+    /// Number of problems served from the in-memory cache
+    pub cache_hits: u64,
+    /// Time spent waiting for the responses, summed over every request
+    pub total_latency: Duration,
+}
+
+impl Metrics {
+    /// Returns the average time spent waiting for a response (zero if no requests were made).
+    pub fn average_latency(&self) -> Duration {
+        match self.requests {
+            0 => Duration::ZERO,
+            // Dividing by `requests as u32` would truncate past 4 billion requests
+            requests => {
+                let average = self.total_latency.as_nanos() / requests as u128;
+                Duration::from_nanos(u64::try_from(average).unwrap_or(u64::MAX))
+            }
+        }
+    }
+}
@@ -0,0 +1,419 @@
+//! A throttled crawler that copies problems, articles and attachments into a static offline
+//! mirror, for places with an unreliable connection. The mirror directory contains
+//!
+//! - `index.html`: links to every mirrored page
+//! - `problems/{id}.html` (or `problems/{id}.pbinfo` bundles)
+//! - `articles/{id}.html`
+//! - `assets/`: the attachments of the problems and the images of the pages, stored once per
+//!   content however many pages use them (see `AssetStore`)
+//! - `mirror.json`: what has been mirrored so far, so an interrupted run can be resumed
+
+use crate::*;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How the problems are stored in the mirror.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum MirrorFormat {
+    /// A standalone html page per problem, made with `PbInfoProblem::to_clean_html`
+    #[default]
+    Html,
+    /// A bundle per problem (see `bundle`)
+    Bundle,
+}
+
+/// What to mirror and where.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MirrorConfig {
+    /// Directory of the mirror, created if it does not exist
+    pub output: PathBuf,
+    /// How the problems are stored
+    pub format: MirrorFormat,
+    /// Problems to mirror (`None` for every problem on PbInfo)
+    pub problems: Option<Vec<ProblemId>>,
+    /// Articles to mirror, in addition to the ones linked from mirrored pages
+    pub articles: Vec<usize>,
+    /// Whether the articles linked from the mirrored problems and articles are mirrored too
+    pub follow_article_links: bool,
+    /// Whether the attachments of the problems are downloaded
+    pub attachments: bool,
+    /// Whether the images hosted on PbInfo are downloaded, so that the html pages show them
+    /// offline
+    pub images: bool,
+    /// Minimum delay between two requests
+    pub delay: Duration,
+}
+
+impl MirrorConfig {
+    /// Mirrors every problem, with the articles linked from them, their attachments and their
+    /// images, into
+    /// `output`, making at most one request per second.
+    pub fn new(output: impl Into<PathBuf>) -> Self {
+        MirrorConfig {
+            output: output.into(),
+            format: MirrorFormat::Html,
+            problems: None,
+            articles: Vec::new(),
+            follow_article_links: true,
+            attachments: true,
+            images: true,
+            delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// What a mirror run did.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct MirrorReport {
+    /// Number of problems mirrored by this run
+    pub problems: usize,
+    /// Number of articles mirrored by this run
+    pub articles: usize,
+    /// Number of attachments downloaded by this run
+    pub attachments: usize,
+    /// Number of images downloaded by this run
+    pub images: usize,
+    /// Number of pages skipped because they were mirrored by a previous run
+    pub skipped: usize,
+    /// Pages that could not be mirrored (e.g. `problem 1691`), with the error
+    pub failed: Vec<(String, PbInfoError)>,
+}
+
+/// What has been mirrored so far: the titles of the problems and of the articles, by id, and
+/// the linked articles that are still to be mirrored.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+struct MirrorState {
+    problems: BTreeMap<usize, String>,
+    articles: BTreeMap<usize, String>,
+    pending: VecDeque<usize>,
+}
+
+impl MirrorState {
+    /// Reads `mirror.json` from `dir`, or returns an empty state if there is none.
+    fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join("mirror.json");
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(MirrorState::default())
+            }
+            Err(err) => {
+                return Err(PbInfoError::IOError(format!(
+                    "Could not read `{}`: {}",
+                    path.display(),
+                    err
+                )))
+            }
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&text).map_err(|err| PbInfoError::JSONError(err.to_string()))?;
+        let titles = |key: &str| {
+            json[key]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter_map(|(id, title)| {
+                    Some((id.parse::<usize>().ok()?, title.as_str()?.to_owned()))
+                })
+                .collect()
+        };
+        Ok(MirrorState {
+            problems: titles("problems"),
+            articles: titles("articles"),
+            pending: json["pending"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|id| id.as_u64().map(|id| id as usize))
+                .collect(),
+        })
+    }
+
+    /// Writes the state to `mirror.json` and the index of the mirror to `index.html`.
+    fn save(&self, dir: &Path, format: MirrorFormat) -> Result<()> {
+        let titles = |titles: &BTreeMap<usize, String>| {
+            titles
+                .iter()
+                .map(|(id, title)| (id.to_string(), serde_json::Value::from(title.as_str())))
+                .collect::<serde_json::Map<_, _>>()
+        };
+        let json = serde_json::json!({
+            "problems": titles(&self.problems),
+            "articles": titles(&self.articles),
+            "pending": self.pending,
+        });
+        write_file(&dir.join("mirror.json"), json.to_string().as_bytes())?;
+
+        let mut index = String::from("<h1>PbInfo</h1>\n<h2>Probleme</h2>\n<ul>\n");
+        for (id, name) in &self.problems {
+            index.push_str(&format!(
+                "<li><a href=\"{}\">#{} {}</a></li>\n",
+                problem_file(ProblemId(*id), format),
+                id,
+                escape_html(name)
+            ));
+        }
+        index.push_str("</ul>\n<h2>Articole</h2>\n<ul>\n");
+        for (id, title) in &self.articles {
+            index.push_str(&format!(
+                "<li><a href=\"articles/{}.html\">{}</a></li>\n",
+                id,
+                escape_html(title)
+            ));
+        }
+        index.push_str("</ul>\n");
+        write_file(&dir.join("index.html"), page("PbInfo", &index).as_bytes())
+    }
+}
+
+/// Wraps `body` into a standalone html page.
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        body
+    )
+}
+
+/// Path of the file of a problem, relative to the mirror.
+fn problem_file(id: ProblemId, format: MirrorFormat) -> String {
+    match format {
+        MirrorFormat::Html => format!("problems/{}.html", id),
+        MirrorFormat::Bundle => format!("problems/{}.pbinfo", id),
+    }
+}
+
+/// Creates the directory of `path` if it does not exist.
+fn create_parent(path: &Path) -> Result<()> {
+    match path.parent() {
+        Some(dir) => std::fs::create_dir_all(dir).map_err(|err| {
+            PbInfoError::IOError(format!("Could not create `{}`: {}", dir.display(), err))
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Writes `contents` to `path`, creating its directory if needed.
+fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    create_parent(path)?;
+    std::fs::write(path, contents).map_err(|err| {
+        PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+    })
+}
+
+/// Downloads `attachment` into `store`, as an asset of `owner`. Returns the name of the file in
+/// the store.
+fn download_asset(
+    client: &PbInfoClient,
+    store: &mut AssetStore,
+    attachment: &Attachment,
+    owner: &str,
+) -> Result<String> {
+    let path = store.dir().join(".download");
+    create_parent(&path)?;
+    if let Err(err) = client.download_attachment(attachment, &path) {
+        // Leave no partial file behind
+        let _ = std::fs::remove_file(&path);
+        return Err(err);
+    }
+    store.add_file(&path, &attachment.name, owner)
+}
+
+/// Downloads the images of `html` hosted on PbInfo (or on the base url of `client`) into
+/// `store`, as assets of `owner`, adding their files to `kept`, and points them to their copies
+/// (from a page in a subdirectory of the mirror). Images that can
+/// not be downloaded keep pointing to PbInfo.
+fn mirror_images(
+    client: &PbInfoClient,
+    store: &mut AssetStore,
+    html: &str,
+    owner: &str,
+    kept: &mut Vec<String>,
+    report: &mut MirrorReport,
+) -> Result<String> {
+    let image = &selectors::IMAGE_SOURCE;
+    let mut mirrored = String::new();
+    let mut last = 0;
+    for caps in image.captures_iter(html) {
+        let url = caps[2].replace("&amp;", "&");
+        if !url.starts_with(DEFAULT_BASE_URL) && !url.starts_with(client.base_url()) {
+            continue;
+        }
+        let name = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .unwrap_or_default()
+            .to_owned();
+        let attachment = Attachment {
+            name,
+            url,
+            size: None,
+        };
+        match download_asset(client, store, &attachment, owner) {
+            Ok(file) => {
+                let src = caps.get(2).unwrap();
+                mirrored.push_str(&html[last..src.start()]);
+                mirrored.push_str(&format!("../assets/{}", file));
+                last = src.end();
+                kept.push(file);
+                report.images += 1;
+            }
+            Err(err) => report
+                .failed
+                .push((format!("image {} of {}", attachment.name, owner), err)),
+        }
+    }
+    mirrored.push_str(&html[last..]);
+    Ok(mirrored)
+}
+
+/// Mirrors what `config` asks for, using a client that waits `config.delay` between requests
+/// and follows PbInfo's robots.txt.
+pub fn run(config: &MirrorConfig) -> Result<MirrorReport> {
+    let client = PbInfoClient::builder()
+        .min_request_interval(config.delay)
+        .respect_robots_txt(true)
+        .build()?;
+    run_with(&client, config)
+}
+
+/// Mirrors what `config` asks for using `client`, which is responsible for throttling the
+/// requests (see `PbInfoClientBuilder::min_request_interval`). Pages mirrored by a previous run
+/// into the same directory are skipped, and the state is saved after every page, so an
+/// interrupted run can be resumed by running it again. Pages that fail are reported and
+/// skipped; the run only stops early if PbInfo is unavailable or a file can not be written.
+pub fn run_with(client: &PbInfoClient, config: &MirrorConfig) -> Result<MirrorReport> {
+    let dir = config.output.as_path();
+    let mut state = MirrorState::load(dir)?;
+    let mut store = AssetStore::open(dir.join("assets"))?;
+    let mut report = MirrorReport::default();
+    state.pending.extend(config.articles.iter().copied());
+
+    let problems = match &config.problems {
+        Some(problems) => problems.clone(),
+        None => client.discover_problem_ids()?,
+    };
+    for id in problems {
+        if state.problems.contains_key(&id.0) && dir.join(problem_file(id, config.format)).exists()
+        {
+            report.skipped += 1;
+            continue;
+        }
+        let problem = match client.fetch_problem_by_id(id) {
+            Ok(problem) => problem,
+            Err(err @ PbInfoError::SiteUnavailable(_)) => return Err(err),
+            Err(err) => {
+                report.failed.push((format!("problem {}", id), err));
+                continue;
+            }
+        };
+
+        // The assets of a problem mirrored again are replaced, once the new ones are stored
+        let owner = format!("problem {}", id);
+        let mut kept = Vec::new();
+        let mut attachments = String::new();
+        if config.attachments {
+            for attachment in &problem.attachments {
+                match download_asset(client, &mut store, attachment, &owner) {
+                    Ok(file) => {
+                        attachments.push_str(&format!(
+                            "<li><a href=\"../assets/{}\">{}</a></li>\n",
+                            file,
+                            escape_html(&attachment.name)
+                        ));
+                        kept.push(file);
+                        report.attachments += 1;
+                    }
+                    Err(err) => report.failed.push((
+                        format!("attachment {} of problem {}", attachment.name, id),
+                        err,
+                    )),
+                }
+            }
+        }
+
+        let path = dir.join(problem_file(id, config.format));
+        match config.format {
+            MirrorFormat::Html => {
+                let mut body = problem.to_clean_html();
+                if config.images {
+                    body =
+                        mirror_images(client, &mut store, &body, &owner, &mut kept, &mut report)?;
+                }
+                if !attachments.is_empty() {
+                    body.push_str(&format!("<h3>Fișiere</h3>\n<ul>\n{}</ul>\n", attachments));
+                }
+                write_file(&path, page(&problem.name, &body).as_bytes())?
+            }
+            MirrorFormat::Bundle => {
+                create_parent(&path)?;
+                bundle::save(&problem, &path)?;
+            }
+        }
+        if config.follow_article_links {
+            state
+                .pending
+                .extend(problem.links().into_iter().filter_map(|link| match link {
+                    LinkTarget::Article(id) => Some(id),
+                    _ => None,
+                }));
+        }
+        state.problems.insert(id.0, problem.name.to_string());
+        store.release_except(&owner, &kept)?;
+        store.save()?;
+        state.save(dir, config.format)?;
+        report.problems += 1;
+    }
+
+    let mut seen = Vec::new();
+    while let Some(&id) = state.pending.front() {
+        if seen.contains(&id) {
+            state.pending.pop_front();
+            continue;
+        }
+        seen.push(id);
+        let path = dir.join(format!("articles/{}.html", id));
+        if state.articles.contains_key(&id) && path.exists() {
+            state.pending.pop_front();
+            report.skipped += 1;
+            continue;
+        }
+        let article = match client.fetch_article(id) {
+            Ok(article) => article,
+            Err(err @ PbInfoError::SiteUnavailable(_)) => return Err(err),
+            Err(err) => {
+                state.pending.pop_front();
+                report.failed.push((format!("article {}", id), err));
+                continue;
+            }
+        };
+
+        let owner = format!("article {}", id);
+        let mut kept = Vec::new();
+        let mut html = Sanitizer::default().sanitize(&article.html);
+        if config.images {
+            html = mirror_images(client, &mut store, &html, &owner, &mut kept, &mut report)?;
+        }
+        let body = format!("<h1>{}</h1>\n{}\n", escape_html(&article.title), html);
+        write_file(&path, page(&article.title, &body).as_bytes())?;
+        if config.follow_article_links {
+            state
+                .pending
+                .extend(article.links().into_iter().filter_map(|link| match link {
+                    LinkTarget::Article(id) => Some(id),
+                    _ => None,
+                }));
+        }
+        state.pending.pop_front();
+        state.articles.insert(id, article.title);
+        store.release_except(&owner, &kept)?;
+        store.save()?;
+        state.save(dir, config.format)?;
+        report.articles += 1;
+    }
+
+    state.save(dir, config.format)?;
+    Ok(report)
+}
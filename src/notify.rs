@@ -0,0 +1,340 @@
+//! Notifications about new problems and submission verdicts, sent to pluggable sinks: a generic
+//! JSON webhook, a Discord webhook, or (with the `email` feature) an email sent over SMTP. Any
+//! `Fn(&Notification) -> Result<()>` closure is a sink too.
+
+use crate::*;
+
+/// Something worth announcing.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Notification {
+    /// A problem was published.
+    NewProblem {
+        /// Id of the problem
+        id: ProblemId,
+        /// Name of the problem
+        name: String,
+    },
+    /// The evaluation of a submission is complete.
+    Verdict {
+        /// Id of the submission
+        submission_id: usize,
+        /// The final status
+        status: SubmissionStatus,
+    },
+}
+
+impl Notification {
+    /// Returns a short title (e.g. for an email subject).
+    pub fn title(&self) -> String {
+        match self {
+            Notification::NewProblem { id, name } => {
+                format!("Problemă nouă: #{} {}", id, name)
+            }
+            Notification::Verdict { submission_id, .. } => {
+                format!("Evaluarea #{}", submission_id)
+            }
+        }
+    }
+
+    /// Returns the text of the notification, with a link to the page it is about.
+    pub fn message(&self) -> String {
+        match self {
+            Notification::NewProblem { id, name } => format!(
+                "Problemă nouă: #{} {}\nhttps://www.pbinfo.ro/probleme/{}/{}",
+                id, name, id, name
+            ),
+            Notification::Verdict {
+                submission_id,
+                status,
+            } => {
+                let verdict = match status {
+                    SubmissionStatus::Finished(score) => format!("{} puncte", score),
+                    SubmissionStatus::CompilationError(_) => "eroare de compilare".to_owned(),
+                    SubmissionStatus::Queued => "în așteptare".to_owned(),
+                    SubmissionStatus::Evaluating(tests) => {
+                        format!("în evaluare ({} teste)", tests)
+                    }
+                };
+                format!(
+                    "Evaluarea #{}: {}\nhttps://www.pbinfo.ro/detalii-evaluare/{}",
+                    submission_id, verdict, submission_id
+                )
+            }
+        }
+    }
+
+    /// Returns the JSON object posted by `WebhookSink`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = match self {
+            Notification::NewProblem { id, name } => serde_json::json!({
+                "type": "new_problem",
+                "id": id.0,
+                "name": name,
+            }),
+            Notification::Verdict {
+                submission_id,
+                status,
+            } => serde_json::json!({
+                "type": "verdict",
+                "submission_id": submission_id,
+                "score": match status {
+                    SubmissionStatus::Finished(score) => Some(*score),
+                    _ => None,
+                },
+                "compilation_error": matches!(status, SubmissionStatus::CompilationError(_)),
+            }),
+        };
+        json["message"] = serde_json::Value::from(self.message());
+        json
+    }
+}
+
+/// A destination for notifications.
+pub trait Sink: Send + Sync {
+    /// Sends `notification`.
+    fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+impl<F: Fn(&Notification) -> Result<()> + Send + Sync> Sink for F {
+    fn send(&self, notification: &Notification) -> Result<()> {
+        self(notification)
+    }
+}
+
+/// Sends `notification` to every sink, even if some of them fail. Returns the first error.
+pub fn notify_all(sinks: &[&dyn Sink], notification: &Notification) -> Result<()> {
+    match notify_each(sinks, notification).into_iter().next() {
+        Some((_, err)) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Sends `notification` to every sink, returning the errors of the ones that failed, with the
+/// index of the sink in `sinks`.
+pub fn notify_each(sinks: &[&dyn Sink], notification: &Notification) -> Vec<(usize, PbInfoError)> {
+    sinks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, sink)| sink.send(notification).err().map(|err| (index, err)))
+        .collect()
+}
+
+/// The outcome of `PbInfoClient::announce_new_problems`.
+#[derive(Debug, Clone, Default)]
+pub struct Announcement {
+    /// The new problems, in the order of their ids, whether or not every sink announced them
+    pub problems: Vec<PbInfoProblem>,
+    /// The announcements that failed: the problem, the index of the sink in `sinks` and the
+    /// error
+    pub failed: Vec<(ProblemId, usize, PbInfoError)>,
+    /// The error that stopped the search for new problems, if it did not reach its end
+    pub error: Option<PbInfoError>,
+}
+
+/// Posts `body` as JSON to `url`.
+fn post_json(http: &reqwest::blocking::Client, url: &str, body: &serde_json::Value) -> Result<()> {
+    let response = http.post(url).json(body).send().map_err(|err| {
+        PbInfoError::NetworkError(format!("Could not send the notification: {}", err))
+    })?;
+    match response.status().is_success() {
+        true => Ok(()),
+        false => Err(PbInfoError::NetworkError(format!(
+            "Encountered an error when trying to send the notification. HTTP status code {}",
+            response.status()
+        ))),
+    }
+}
+
+/// A sink that posts every notification to a url, as the JSON object of
+/// `Notification::to_json`.
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    url: String,
+    http: reqwest::blocking::Client,
+}
+
+impl WebhookSink {
+    /// Posts the notifications to `url`.
+    pub fn new(url: &str) -> Self {
+        WebhookSink {
+            url: url.to_owned(),
+            http: reqwest::blocking::Client::new(),
+        }
+    }
+}
+
+impl Sink for WebhookSink {
+    fn send(&self, notification: &Notification) -> Result<()> {
+        post_json(&self.http, &self.url, &notification.to_json())
+    }
+}
+
+/// A sink that posts every notification as a message of a Discord webhook.
+#[derive(Debug, Clone)]
+pub struct DiscordSink {
+    url: String,
+    http: reqwest::blocking::Client,
+}
+
+impl DiscordSink {
+    /// Posts the notifications to the Discord webhook `url`
+    /// (`https://discord.com/api/webhooks/...`).
+    pub fn new(url: &str) -> Self {
+        DiscordSink {
+            url: url.to_owned(),
+            http: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// Returns the message posted for `notification`.
+    pub(crate) fn payload(notification: &Notification) -> serde_json::Value {
+        // Discord rejects messages longer than 2000 characters
+        let content = notification
+            .message()
+            .chars()
+            .take(2000)
+            .collect::<String>();
+        serde_json::json!({ "content": content })
+    }
+}
+
+impl Sink for DiscordSink {
+    fn send(&self, notification: &Notification) -> Result<()> {
+        post_json(&self.http, &self.url, &DiscordSink::payload(notification))
+    }
+}
+
+/// A sink that sends every notification by email, over SMTP with STARTTLS.
+#[cfg(feature = "email")]
+#[derive(Clone)]
+pub struct EmailSink {
+    transport: lettre::SmtpTransport,
+    from: lettre::message::Mailbox,
+    to: Vec<lettre::message::Mailbox>,
+}
+
+#[cfg(feature = "email")]
+impl std::fmt::Debug for EmailSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmailSink")
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "email")]
+impl EmailSink {
+    /// Sends the notifications from `from` to every address of `to`, through the SMTP server
+    /// `host`, logging in with `username` and `password`.
+    pub fn new(
+        host: &str,
+        username: &str,
+        password: &str,
+        from: &str,
+        to: &[&str],
+    ) -> Result<Self> {
+        let address = |address: &str| {
+            address.parse::<lettre::message::Mailbox>().map_err(|err| {
+                PbInfoError::Error(format!("Invalid email address `{}`: {}", address, err))
+            })
+        };
+        let transport = lettre::SmtpTransport::starttls_relay(host)
+            .map_err(|err| PbInfoError::Error(format!("Invalid SMTP server `{}`: {}", host, err)))?
+            .credentials(lettre::transport::smtp::authentication::Credentials::new(
+                username.to_owned(),
+                password.to_owned(),
+            ))
+            .build();
+        Ok(EmailSink {
+            transport,
+            from: address(from)?,
+            to: to.iter().map(|to| address(to)).collect::<Result<_>>()?,
+        })
+    }
+}
+
+#[cfg(feature = "email")]
+impl Sink for EmailSink {
+    fn send(&self, notification: &Notification) -> Result<()> {
+        use lettre::Transport;
+
+        let mut message = lettre::Message::builder()
+            .from(self.from.clone())
+            .subject(notification.title());
+        for to in &self.to {
+            message = message.to(to.clone());
+        }
+        let message = message
+            .body(notification.message())
+            .map_err(|err| PbInfoError::Error(format!("Could not write the email: {}", err)))?;
+        self.transport.send(&message).map_err(|err| {
+            PbInfoError::NetworkError(format!("Could not send the email: {}", err))
+        })?;
+        Ok(())
+    }
+}
+
+impl PbInfoClient {
+    /// Watches a submission until its evaluation is complete (see `watch_submission`) and
+    /// announces the verdict to `sinks`. Returns the final status; if a sink fails, the others
+    /// are still notified and the first error is returned.
+    pub fn watch_submission_notify(
+        &self,
+        submission_id: usize,
+        sinks: &[&dyn Sink],
+    ) -> Result<SubmissionStatus> {
+        let mut last = None;
+        for status in self.watch_submission(submission_id) {
+            last = Some(status?);
+        }
+        let status = last.ok_or_else(|| {
+            PbInfoError::Error(format!("No status for the submission {}", submission_id))
+        })?;
+        notify_all(
+            sinks,
+            &Notification::Verdict {
+                submission_id,
+                status: status.clone(),
+            },
+        )?;
+        Ok(status)
+    }
+
+    /// Looks for problems published after `last_known` (the largest id seen so far) and
+    /// announces every one of them to `sinks`. Returns the new problems, so the caller can
+    /// remember the largest id for the next check, even if a sink failed or the search was
+    /// stopped by an error: the problems found until then were already announced.
+    pub fn announce_new_problems(
+        &self,
+        last_known: ProblemId,
+        sinks: &[&dyn Sink],
+    ) -> Announcement {
+        let mut announcement = Announcement::default();
+        let mut missing = 0;
+        let mut id = last_known.0 + 1;
+        // Deleted or hidden ids leave gaps, so stop only after a long run of missing ids
+        while missing < MAX_ID_GAP {
+            match self.fetch_problem_by_id(ProblemId(id)) {
+                Ok(problem) => {
+                    missing = 0;
+                    let notification = Notification::NewProblem {
+                        id: problem.id,
+                        name: problem.name.to_string(),
+                    };
+                    for (sink, err) in notify_each(sinks, &notification) {
+                        announcement.failed.push((problem.id, sink, err));
+                    }
+                    announcement.problems.push(problem);
+                }
+                Err(PbInfoError::UnknownId(_)) => missing += 1,
+                Err(err) => {
+                    announcement.error = Some(err);
+                    break;
+                }
+            }
+            id += 1;
+        }
+        announcement
+    }
+}
@@ -0,0 +1,98 @@
+//! Statements pinned by their `pin_hash`, so that a reference to a problem (e.g. in an
+//! archived homework set) stays tied to the version of the statement it was made for.
+
+use crate::*;
+use sha2::{Digest, Sha256};
+
+/// A statement whose hash differs from the one it was pinned to.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StatementDrift {
+    /// The problem
+    pub id: ProblemId,
+    /// The hash the statement was pinned to
+    pub expected: String,
+    /// The hash of the current statement
+    pub actual: String,
+}
+
+/// A problem fetched by `PbInfoClient::fetch_problem_pinned`.
+#[derive(Debug, Clone)]
+pub struct PinnedProblem {
+    /// The problem, as it is now
+    pub problem: PbInfoProblem,
+    /// How the statement differs from the pinned one (`None` if it matches)
+    pub drift: Option<StatementDrift>,
+}
+
+impl PinnedProblem {
+    /// Returns `true` if the statement is the pinned one.
+    pub fn matches(&self) -> bool {
+        self.drift.is_none()
+    }
+}
+
+impl PbInfoProblem {
+    /// Returns the SHA-256 (in hex) of the statement text (with its symbols, such as `≤` or `+`,
+    /// and its examples) and of the time and memory limits. Unlike `content_hash`, which finds
+    /// reposted problems, any edit changes it; only the html markup and the whitespace do not
+    /// count.
+    pub fn pin_hash(&self) -> String {
+        let statement = html_to_text(&self.problem_text)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut hasher = Sha256::new();
+        for part in [
+            statement.as_str(),
+            self.time_limit.as_deref().unwrap_or_default().trim(),
+            self.memory_limit.as_deref().unwrap_or_default().trim(),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Checks the statement against the `pin_hash` it was pinned to.
+    pub fn check_pin(&self, expected_hash: &str) -> Option<StatementDrift> {
+        let actual = self.pin_hash();
+        match actual.eq_ignore_ascii_case(expected_hash.trim()) {
+            true => None,
+            false => Some(StatementDrift {
+                id: self.id,
+                expected: expected_hash.trim().to_owned(),
+                actual,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl PbInfoClient {
+    /// Fetches the problem `id` and checks its statement against `expected_hash`, a `pin_hash`
+    /// recorded earlier. A statement that was edited since is still returned,
+    /// with the drift, so that the caller decides whether to use it.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_problem_pinned(
+        &self,
+        id: ProblemId,
+        expected_hash: &str,
+    ) -> Result<PinnedProblem> {
+        let problem = self.fetch_problem_by_id(id)?;
+        let drift = problem.check_pin(expected_hash);
+        #[cfg(feature = "tracing")]
+        if let Some(drift) = &drift {
+            tracing::warn!(
+                id = id.0,
+                expected = %drift.expected,
+                actual = %drift.actual,
+                "statement drifted"
+            );
+        }
+        Ok(PinnedProblem { problem, drift })
+    }
+}
@@ -0,0 +1,115 @@
+use crate::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Kinds of requests, used by ClientPool to pick the account that makes them.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Operation {
+    /// Fetching problems, their statistics and tests
+    Fetch,
+    /// Searching problems by name
+    Search,
+    /// Reading submissions and their evaluation status
+    Submissions,
+    /// Fetching rankings and public profiles
+    Rankings,
+    /// Reading groups, their problems and the progress of their members
+    Groups,
+}
+
+/// A pool of clients, each logged into its own account (e.g. a teacher and a few test
+/// accounts). Every operation can be routed to a specific account; operations without a
+/// route are spread over all the accounts in turn.
+///
+/// Each account keeps its own session and its own rate limit, set with
+/// `PbInfoClientBuilder::min_request_interval`.
+#[derive(Debug, Default)]
+pub struct ClientPool {
+    /// The accounts, in the order in which they were added
+    accounts: Vec<(String, PbInfoClient)>,
+    /// operation -> name of the account that makes it
+    routes: HashMap<Operation, String>,
+    /// Index of the account used by the next unrouted operation
+    next: AtomicUsize,
+}
+
+impl ClientPool {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `client` to the pool as the account `name`, replacing any account with the same
+    /// name. The client can already be logged in (or have a loaded session).
+    pub fn add(&mut self, name: &str, client: PbInfoClient) {
+        match self
+            .accounts
+            .iter_mut()
+            .find(|(account, _)| account == name)
+        {
+            Some((_, existing)) => *existing = client,
+            None => self.accounts.push((name.to_owned(), client)),
+        }
+    }
+
+    /// Builds a client using `builder`, logs it in as `username` and adds it to the pool as
+    /// the account `name`.
+    pub fn add_account(
+        &mut self,
+        name: &str,
+        builder: PbInfoClientBuilder,
+        username: &str,
+        password: &str,
+    ) -> Result<()> {
+        let client = builder.build()?;
+        client.login(username, password)?;
+        self.add(name, client);
+        Ok(())
+    }
+
+    /// Makes every `operation` go through the account `name`.
+    pub fn route(&mut self, operation: Operation, name: &str) -> Result<()> {
+        if self.get(name).is_none() {
+            return Err(PbInfoError::Error(format!("Unknown account `{}`", name)));
+        }
+        self.routes.insert(operation, name.to_owned());
+        Ok(())
+    }
+
+    /// Returns the client of the account `name`.
+    pub fn get(&self, name: &str) -> Option<&PbInfoClient> {
+        self.accounts
+            .iter()
+            .find(|(account, _)| account == name)
+            .map(|(_, client)| client)
+    }
+
+    /// Returns the names of the accounts, in the order in which they were added.
+    pub fn names(&self) -> Vec<&str> {
+        self.accounts
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Returns the name of the account that should make `operation`: the routed one, or the
+    /// next account in turn.
+    pub fn account_for(&self, operation: Operation) -> Result<&str> {
+        if let Some(name) = self.routes.get(&operation) {
+            return Ok(name);
+        }
+        if self.accounts.is_empty() {
+            return Err(PbInfoError::Error(
+                "The client pool does not have any accounts".to_owned(),
+            ));
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.accounts.len();
+        Ok(&self.accounts[index].0)
+    }
+
+    /// Returns the client that should make `operation`.
+    pub fn client_for(&self, operation: Operation) -> Result<&PbInfoClient> {
+        let name = self.account_for(operation)?;
+        Ok(self.get(name).unwrap())
+    }
+}
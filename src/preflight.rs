@@ -0,0 +1,197 @@
+use crate::judge::Language;
+use crate::*;
+use std::fmt;
+
+/// A mismatch between a source and the input and output of the problem, which would make every
+/// test fail.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SourceIssue {
+    /// The problem reads from the file, but the source reads from stdin.
+    ReadsStdin(String),
+    /// The problem writes to the file, but the source writes to stdout.
+    WritesStdout(String),
+    /// The source never opens the input file of the problem.
+    MissingInputFile(String),
+    /// The source never opens the output file of the problem.
+    MissingOutputFile(String),
+    /// The source reads from `found` instead of `expected`.
+    WrongInputFile { expected: String, found: String },
+    /// The source writes to `found` instead of `expected`.
+    WrongOutputFile { expected: String, found: String },
+    /// The problem uses stdin/stdout, but the source opens the file.
+    UnexpectedFile(String),
+}
+
+impl fmt::Display for SourceIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceIssue::ReadsStdin(file) => {
+                write!(f, "The input is read from stdin instead of `{}`", file)
+            }
+            SourceIssue::WritesStdout(file) => {
+                write!(f, "The output is written to stdout instead of `{}`", file)
+            }
+            SourceIssue::MissingInputFile(file) => {
+                write!(f, "The input file `{}` is never opened", file)
+            }
+            SourceIssue::MissingOutputFile(file) => {
+                write!(f, "The output file `{}` is never opened", file)
+            }
+            SourceIssue::WrongInputFile { expected, found } => write!(
+                f,
+                "The input is read from `{}` instead of `{}`",
+                found, expected
+            ),
+            SourceIssue::WrongOutputFile { expected, found } => write!(
+                f,
+                "The output is written to `{}` instead of `{}`",
+                found, expected
+            ),
+            SourceIssue::UnexpectedFile(file) => write!(
+                f,
+                "The file `{}` is opened, but the problem uses stdin and stdout",
+                file
+            ),
+        }
+    }
+}
+
+/// What a source does with its input and output.
+#[derive(Debug, Default)]
+struct SourceIO {
+    /// Files opened for reading
+    read_files: Vec<String>,
+    /// Files opened for writing
+    written_files: Vec<String>,
+    /// Every string literal
+    literals: Vec<String>,
+    /// Whether stdin is read without being redirected to a file
+    reads_stdin: bool,
+    /// Whether stdout is written without being redirected to a file
+    writes_stdout: bool,
+}
+
+/// Returns the captures of `selector` in `source` as `(file name, mode)` pairs, where the mode
+/// is the first letter of the mode argument (`r` if there is none).
+fn opened_files(selector: &'static selectors::Selector, source: &str) -> Vec<(String, char)> {
+    selector
+        .captures_iter(source)
+        .map(|captures| {
+            let mode = captures
+                .get(2)
+                .and_then(|mode| mode.as_str().chars().next())
+                .unwrap_or('r');
+            (captures[1].to_owned(), mode)
+        })
+        .collect()
+}
+
+/// Finds the files and the standard streams used by `source`, ignoring comments.
+fn scan(source: &str, language: Language) -> SourceIO {
+    let mut io = SourceIO::default();
+    let opens = match language {
+        Language::C | Language::Cpp => {
+            let source = selectors::C_COMMENT.replace_all(source, "");
+            for (file, mode) in opened_files(&selectors::C_FOPEN, &source) {
+                match mode {
+                    'r' => io.read_files.push(file),
+                    _ => io.written_files.push(file),
+                }
+            }
+            for (file, _) in opened_files(&selectors::CPP_IFSTREAM, &source) {
+                io.read_files.push(file);
+            }
+            for (file, _) in opened_files(&selectors::CPP_OFSTREAM, &source) {
+                io.written_files.push(file);
+            }
+            io.reads_stdin = !selectors::FREOPEN_STDIN.is_match(&source)
+                && selectors::C_READS_STDIN.is_match(&source);
+            io.writes_stdout = !selectors::FREOPEN_STDOUT.is_match(&source)
+                && selectors::C_WRITES_STDOUT.is_match(&source);
+            source.into_owned()
+        }
+        Language::Python => {
+            let source = selectors::PYTHON_COMMENT.replace_all(source, "");
+            for (file, mode) in opened_files(&selectors::PYTHON_OPEN, &source) {
+                match mode {
+                    'r' => io.read_files.push(file),
+                    _ => io.written_files.push(file),
+                }
+            }
+            io.reads_stdin = selectors::PYTHON_READS_STDIN.is_match(&source);
+            // `print(..., file=...)` writes to a file
+            let print = &selectors::PYTHON_PRINT;
+            io.writes_stdout = source
+                .lines()
+                .any(|line| print.is_match(line) && !line.contains("file="))
+                || source.contains("sys.stdout");
+            source.into_owned()
+        }
+    };
+    io.literals = selectors::STRING_LITERAL
+        .captures_iter(&opens)
+        .map(|captures| captures[1].to_owned())
+        .collect();
+    io
+}
+
+/// Checks how `source` reads its input and writes its output against the files of `problem`,
+/// before it is submitted. Reading from stdin when the problem expects `problema.in`, never
+/// writing `problema.out` or misspelling the name of a file make every test fail, so they are
+/// the most common reasons for 0 points.
+///
+/// The source is not parsed, so the checks are heuristics: a file whose name is built at run time
+/// can not be recognized, but a file whose name appears anywhere in the source is assumed to be
+/// used. Returns no issues if nothing looks wrong.
+pub fn validate_source(
+    problem: &PbInfoProblem,
+    source: &str,
+    language: Language,
+) -> Vec<SourceIssue> {
+    let io = scan(source, language);
+    let mut issues = Vec::new();
+
+    match &problem.input_source {
+        IOSource::File(expected) => {
+            // Reading stdin gets nothing even if the file is opened as well
+            if io.reads_stdin {
+                issues.push(SourceIssue::ReadsStdin(expected.clone()));
+            } else if !io.read_files.contains(expected) && !io.literals.contains(expected) {
+                issues.push(match io.read_files.first() {
+                    Some(found) => SourceIssue::WrongInputFile {
+                        expected: expected.clone(),
+                        found: found.clone(),
+                    },
+                    None => SourceIssue::MissingInputFile(expected.clone()),
+                });
+            }
+        }
+        IOSource::Std => issues.extend(
+            io.read_files
+                .iter()
+                .cloned()
+                .map(SourceIssue::UnexpectedFile),
+        ),
+    }
+    match &problem.output_source {
+        IOSource::File(expected) => {
+            if !io.written_files.contains(expected) && !io.literals.contains(expected) {
+                issues.push(match io.written_files.first() {
+                    Some(found) => SourceIssue::WrongOutputFile {
+                        expected: expected.clone(),
+                        found: found.clone(),
+                    },
+                    None if io.writes_stdout => SourceIssue::WritesStdout(expected.clone()),
+                    None => SourceIssue::MissingOutputFile(expected.clone()),
+                });
+            }
+        }
+        IOSource::Std => issues.extend(
+            io.written_files
+                .iter()
+                .cloned()
+                .map(SourceIssue::UnexpectedFile),
+        ),
+    }
+    issues
+}
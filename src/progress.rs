@@ -0,0 +1,681 @@
+//! Local tracking of the problems a user has worked on: best scores, attempts and the days
+//! with activity, stored in a JSON file and merged with the scores scraped from PbInfo. Used to
+//! compute streaks and the coverage of every topic, for "what should I practice next" features,
+//! to schedule reviews of solved problems with spaced repetition (SM-2), and to report the
+//! weekly trend of the scores over a semester.
+//!
+//! Times are Unix timestamps in seconds, and days are counted in UTC.
+
+use crate::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Version of the progress file format.
+pub const FORMAT_VERSION: u64 = 1;
+
+/// Seconds in a day.
+const DAY: u64 = 24 * 60 * 60;
+
+/// Returns `time` as a Unix timestamp in seconds.
+pub fn timestamp(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// An attempt on a problem.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Attempt {
+    /// When the attempt was made (or seen on PbInfo)
+    pub at: u64,
+    /// Score of the attempt (`None` if it is not known)
+    pub score: Option<usize>,
+}
+
+/// What a user has done on one problem.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ProblemProgress {
+    /// Best score so far (`None` if it is not known)
+    pub best_score: Option<usize>,
+    /// Number of attempts recorded locally
+    pub attempts: usize,
+    /// When the problem was first solved with 100 points
+    pub solved_at: Option<u64>,
+    /// When the problem was last attempted (or last seen with a new score on PbInfo)
+    pub last_attempt: Option<u64>,
+    /// The attempts, in the order in which they were recorded
+    pub history: Vec<Attempt>,
+}
+
+impl ProblemProgress {
+    /// Returns `true` if the problem was solved with 100 points.
+    pub fn is_solved(&self) -> bool {
+        self.best_score == Some(100)
+    }
+}
+
+/// The scores of a user as seen by one sync with PbInfo (see `Progress::merge_solves`).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ScoreSync {
+    /// When the scores were seen
+    pub at: u64,
+    /// Number of problems solved with 100 points
+    pub solved: usize,
+    /// Sum of the best scores
+    pub total_score: usize,
+    /// Number of problems with a new or better score
+    pub changed: usize,
+}
+
+/// The progress of a user during one week (see `Progress::trend`).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct WeeklyTrend {
+    /// Start of the week (Monday, 00:00 UTC)
+    pub week_start: u64,
+    /// Problems solved with 100 points for the first time during the week
+    pub solved: usize,
+    /// How much the sum of the best scores changed during the week
+    pub score_delta: i64,
+    /// Sum of the best scores at the end of the week
+    pub total_score: usize,
+}
+
+/// How much of a topic a user has solved.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct TopicCoverage {
+    /// Solved problems of the topic
+    pub solved: usize,
+    /// Problems of the topic
+    pub total: usize,
+}
+
+impl TopicCoverage {
+    /// Returns the solved fraction of the topic, between 0 and 1.
+    pub fn ratio(&self) -> f64 {
+        match self.total {
+            0 => 0.0,
+            total => self.solved as f64 / total as f64,
+        }
+    }
+}
+
+/// The progress of a user.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Progress {
+    /// PbInfo username
+    pub username: String,
+    /// Progress on every problem the user has worked on
+    pub problems: BTreeMap<ProblemId, ProblemProgress>,
+    /// Days (since the Unix epoch) with at least one attempt
+    pub active_days: BTreeSet<u64>,
+    /// The syncs with PbInfo, in the order in which they were made
+    pub syncs: Vec<ScoreSync>,
+}
+
+impl Progress {
+    /// Creates an empty progress for `username`.
+    pub fn new(username: &str) -> Self {
+        Progress {
+            username: username.to_owned(),
+            ..Progress::default()
+        }
+    }
+
+    /// Records an attempt on `id` that got `score` points at `at`.
+    pub fn record(&mut self, id: ProblemId, score: usize, at: u64) {
+        let problem = self.problems.entry(id).or_default();
+        problem.attempts += 1;
+        problem.best_score = problem.best_score.max(Some(score));
+        problem.last_attempt = problem.last_attempt.max(Some(at));
+        if score == 100 && problem.solved_at.is_none() {
+            problem.solved_at = Some(at);
+        }
+        problem.history.push(Attempt {
+            at,
+            score: Some(score),
+        });
+        self.active_days.insert(at / DAY);
+    }
+
+    /// Merges the scores scraped from PbInfo (e.g. by `fetch_public_solves`), seen at `at`.
+    /// Best scores only go up; problems that are new or have a better score count as attempted
+    /// at `at`. The sync is recorded in `syncs`. Returns the number of problems that changed.
+    pub fn merge_solves(&mut self, solves: &[PublicSolve], at: u64) -> usize {
+        let mut changed = 0;
+        for solve in solves {
+            let known = self.problems.contains_key(&solve.id);
+            let problem = self.problems.entry(solve.id).or_default();
+            if known && solve.score <= problem.best_score {
+                continue;
+            }
+            problem.best_score = problem.best_score.max(solve.score);
+            problem.last_attempt = problem.last_attempt.max(Some(at));
+            if problem.is_solved() && problem.solved_at.is_none() {
+                problem.solved_at = Some(at);
+            }
+            problem.history.push(Attempt {
+                at,
+                score: solve.score,
+            });
+            self.active_days.insert(at / DAY);
+            changed += 1;
+        }
+        self.syncs.push(ScoreSync {
+            at,
+            solved: self.solved().len(),
+            total_score: self.total_score_at(u64::MAX),
+            changed,
+        });
+        changed
+    }
+
+    /// Returns the sum of the best scores of the attempts made up to `at`.
+    pub fn total_score_at(&self, at: u64) -> usize {
+        self.problems
+            .values()
+            .filter_map(|problem| {
+                problem
+                    .history
+                    .iter()
+                    .filter(|attempt| attempt.at <= at)
+                    .filter_map(|attempt| attempt.score)
+                    .max()
+            })
+            .sum()
+    }
+
+    /// Returns the problems solved and the change of the total score in every week (starting on
+    /// Monday, in UTC) that overlaps `range`, in order, for plotting the progress over a
+    /// semester. The first week of 1970 starts at the Unix epoch, on a Thursday.
+    pub fn trend(&self, range: Range<u64>) -> Vec<WeeklyTrend> {
+        // The Unix epoch was on a Thursday, 3 days after a Monday
+        let days_since_monday = |at: u64| (at / DAY + 3) % 7;
+        let week_start = |at: u64| (at / DAY).saturating_sub(days_since_monday(at)) * DAY;
+        let next_week = |at: u64| (at / DAY + 7 - days_since_monday(at)).saturating_mul(DAY);
+        if range.is_empty() {
+            return Vec::new();
+        }
+
+        let mut weeks = Vec::new();
+        let mut start = week_start(range.start);
+        while start < range.end {
+            let end = next_week(start);
+            let before = start.checked_sub(1).map_or(0, |at| self.total_score_at(at));
+            let total_score = self.total_score_at(end - 1);
+            weeks.push(WeeklyTrend {
+                week_start: start,
+                solved: self
+                    .problems
+                    .values()
+                    .filter_map(|problem| problem.solved_at)
+                    .filter(|at| (start..end).contains(at))
+                    .count(),
+                score_delta: total_score as i64 - before as i64,
+                total_score,
+            });
+            start = end;
+        }
+        weeks
+    }
+
+    /// Returns the ids of the problems solved with 100 points, in increasing order.
+    pub fn solved(&self) -> Vec<ProblemId> {
+        self.problems
+            .iter()
+            .filter(|(_, problem)| problem.is_solved())
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Returns the ids of the problems attempted but not solved, in increasing order.
+    pub fn unsolved(&self) -> Vec<ProblemId> {
+        self.problems
+            .iter()
+            .filter(|(_, problem)| !problem.is_solved())
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Returns the number of consecutive active days that end on the day of `now`, or on the
+    /// day before it (so a streak is not lost before the end of the day).
+    pub fn current_streak(&self, now: u64) -> usize {
+        let today = now / DAY;
+        let mut day = match (
+            self.active_days.contains(&today),
+            today
+                .checked_sub(1)
+                .filter(|day| self.active_days.contains(day)),
+        ) {
+            (true, _) => today,
+            (false, Some(yesterday)) => yesterday,
+            (false, None) => return 0,
+        };
+        let mut streak = 0;
+        while self.active_days.contains(&day) {
+            streak += 1;
+            match day.checked_sub(1) {
+                Some(previous) => day = previous,
+                None => break,
+            }
+        }
+        streak
+    }
+
+    /// Returns the longest run of consecutive active days.
+    pub fn longest_streak(&self) -> usize {
+        let mut longest = 0;
+        let mut current = 0;
+        let mut previous = None;
+        for day in &self.active_days {
+            current = match previous {
+                Some(previous) if previous + 1 == *day => current + 1,
+                _ => 1,
+            };
+            longest = longest.max(current);
+            previous = Some(*day);
+        }
+        longest
+    }
+
+    /// Returns the coverage of every topic of `problems` (usually a whole catalog), keyed by
+    /// topic.
+    pub fn topic_coverage(&self, problems: &[PbInfoProblem]) -> BTreeMap<String, TopicCoverage> {
+        let mut coverage: BTreeMap<String, TopicCoverage> = BTreeMap::new();
+        for problem in problems {
+            let solved = self
+                .problems
+                .get(&problem.id)
+                .is_some_and(|progress| progress.is_solved());
+            for topic in &problem.topics {
+                let topic = coverage.entry(topic.clone()).or_default();
+                topic.total += 1;
+                topic.solved += solved as usize;
+            }
+        }
+        coverage
+    }
+
+    /// Converts the progress into the JSON object stored by `save`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let problems = self
+            .problems
+            .iter()
+            .map(|(id, problem)| {
+                (
+                    id.to_string(),
+                    serde_json::json!({
+                        "best_score": problem.best_score,
+                        "attempts": problem.attempts,
+                        "solved_at": problem.solved_at,
+                        "last_attempt": problem.last_attempt,
+                        "history": problem
+                            .history
+                            .iter()
+                            .map(|attempt| serde_json::json!({
+                                "at": attempt.at,
+                                "score": attempt.score,
+                            }))
+                            .collect::<Vec<_>>(),
+                    }),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+        serde_json::json!({
+            "format_version": FORMAT_VERSION,
+            "username": self.username,
+            "problems": problems,
+            "active_days": self.active_days,
+            "syncs": self
+                .syncs
+                .iter()
+                .map(|sync| serde_json::json!({
+                    "at": sync.at,
+                    "solved": sync.solved,
+                    "total_score": sync.total_score,
+                    "changed": sync.changed,
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// Reads a progress from the JSON object made by `to_json`.
+    pub fn from_json(json: &serde_json::Value) -> Result<Self> {
+        let version = json["format_version"].as_u64().unwrap_or(0);
+        if version == 0 || version > FORMAT_VERSION {
+            return Err(PbInfoError::JSONError(format!(
+                "Unsupported progress format version {}",
+                version
+            )));
+        }
+        let mut progress = Progress::new(json["username"].as_str().unwrap_or_default());
+        for (id, problem) in json["problems"].as_object().into_iter().flatten() {
+            let id = id
+                .parse::<usize>()
+                .map_err(|_| PbInfoError::JSONError(format!("`{}` is not a problem id", id)))?;
+            progress.problems.insert(
+                ProblemId(id),
+                ProblemProgress {
+                    best_score: problem["best_score"].as_u64().map(|score| score as usize),
+                    attempts: problem["attempts"].as_u64().unwrap_or_default() as usize,
+                    solved_at: problem["solved_at"].as_u64(),
+                    last_attempt: problem["last_attempt"].as_u64(),
+                    history: problem["history"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|attempt| {
+                            Some(Attempt {
+                                at: attempt["at"].as_u64()?,
+                                score: attempt["score"].as_u64().map(|score| score as usize),
+                            })
+                        })
+                        .collect(),
+                },
+            );
+        }
+        progress.active_days = json["active_days"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|day| day.as_u64())
+            .collect();
+        // Progress files written before the syncs were recorded have none
+        progress.syncs = json["syncs"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|sync| {
+                Some(ScoreSync {
+                    at: sync["at"].as_u64()?,
+                    solved: sync["solved"].as_u64()? as usize,
+                    total_score: sync["total_score"].as_u64()? as usize,
+                    changed: sync["changed"].as_u64().unwrap_or_default() as usize,
+                })
+            })
+            .collect();
+        Ok(progress)
+    }
+
+    /// Reads the progress file `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            PbInfoError::IOError(format!("Could not read `{}`: {}", path.display(), err))
+        })?;
+        let json = serde_json::from_str(&text).map_err(|err| {
+            PbInfoError::JSONError(format!("`{}` is not valid JSON: {}", path.display(), err))
+        })?;
+        Progress::from_json(&json)
+    }
+
+    /// Reads the progress file `path`, or returns an empty progress for `username` if the file
+    /// does not exist yet.
+    pub fn load_or_new(path: impl AsRef<Path>, username: &str) -> Result<Self> {
+        match path.as_ref().exists() {
+            true => Progress::load(path),
+            false => Ok(Progress::new(username)),
+        }
+    }
+
+    /// Writes the progress to `path`, replacing it if it exists.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let text = serde_json::to_string_pretty(&self.to_json()).unwrap();
+        std::fs::write(path, text).map_err(|err| {
+            PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+        })
+    }
+}
+
+/// Returns where the progress of `username` is kept by `PbInfoClient::sync_saved_progress`:
+/// `pbinfo/progress/{username}.json` in `$XDG_DATA_HOME` (`~/.local/share` by default).
+pub fn data_path(username: &str) -> Option<PathBuf> {
+    let var = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+    let dir = var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| Path::new(&home).join(".local/share")))?;
+    let name = username
+        .chars()
+        .map(
+            |c| match c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                true => c,
+                false => '_',
+            },
+        )
+        .collect::<String>();
+    Some(
+        dir.join("pbinfo")
+            .join("progress")
+            .join(format!("{}.json", name.trim_start_matches('.'))),
+    )
+}
+
+/// Returns the weekly trend of the progress of `username` kept at `data_path` (see
+/// `Progress::trend`), over `range`.
+pub fn trend(username: &str, range: Range<u64>) -> Result<Vec<WeeklyTrend>> {
+    let path = data_path(username).ok_or_else(|| {
+        PbInfoError::IOError("Could not find the data directory of the user".to_owned())
+    })?;
+    Ok(Progress::load_or_new(path, username)?.trend(range))
+}
+
+/// Parameters of the SM-2 algorithm used by `Progress::reviews`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReviewParameters {
+    /// Days until the first review, and after a failed attempt
+    pub first_interval: f64,
+    /// Days until the second review
+    pub second_interval: f64,
+    /// Ease factor of a new problem, by which the interval grows after a good attempt
+    pub initial_ease: f64,
+    /// Smallest ease factor
+    pub min_ease: f64,
+    /// Lowest quality (0 to 5, one for every 20 points) of an attempt that is not failed
+    pub passing_quality: u8,
+}
+
+impl Default for ReviewParameters {
+    /// The parameters of SuperMemo 2: reviews after 1 and 6 days, then every `ease` times
+    /// longer, starting from an ease of 2.5; attempts under 60 points start over.
+    fn default() -> Self {
+        ReviewParameters {
+            first_interval: 1.0,
+            second_interval: 6.0,
+            initial_ease: 2.5,
+            min_ease: 1.3,
+            passing_quality: 3,
+        }
+    }
+}
+
+/// A problem that should be attempted again.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Review {
+    /// The problem
+    pub id: ProblemId,
+    /// When the review became due
+    pub due: u64,
+    /// Best score so far
+    pub best_score: Option<usize>,
+    /// Days between the last attempt and `due`
+    pub interval: f64,
+}
+
+impl ProblemProgress {
+    /// Replays the attempts with SM-2, returning when the next review is due and the interval
+    /// in days before it (`None` if there are no attempts). Attempts without a known score
+    /// count as barely passing.
+    pub fn next_review(&self, parameters: &ReviewParameters) -> Option<(u64, f64)> {
+        let mut attempts = self.history.clone();
+        attempts.sort_by_key(|attempt| attempt.at);
+
+        let mut ease = parameters.initial_ease;
+        let mut repetitions = 0;
+        let mut interval = 0.0;
+        let mut due = None;
+        for attempt in attempts {
+            let quality = attempt.score.map_or(parameters.passing_quality, |score| {
+                (score.min(100) / 20) as u8
+            });
+            if quality < parameters.passing_quality {
+                repetitions = 0;
+                interval = parameters.first_interval;
+            } else {
+                repetitions += 1;
+                interval = match repetitions {
+                    1 => parameters.first_interval,
+                    2 => parameters.second_interval,
+                    _ => interval * ease,
+                };
+            }
+            let missed = 5.0 - quality as f64;
+            ease = (ease + 0.1 - missed * (0.08 + missed * 0.02)).max(parameters.min_ease);
+            due = Some(attempt.at + (interval * DAY as f64).round() as u64);
+        }
+        due.map(|due| (due, interval))
+    }
+}
+
+impl Progress {
+    /// Returns the problems whose review is due at `now` according to `parameters`, the most
+    /// overdue first (relative to their interval), so that problems solved long ago or with
+    /// less than 100 points come back for practice.
+    pub fn reviews(&self, now: u64, parameters: &ReviewParameters) -> Vec<Review> {
+        let mut reviews = self
+            .problems
+            .iter()
+            .filter_map(|(id, problem)| {
+                let (due, interval) = problem.next_review(parameters)?;
+                (due <= now).then_some(Review {
+                    id: *id,
+                    due,
+                    best_score: problem.best_score,
+                    interval,
+                })
+            })
+            .collect::<Vec<_>>();
+        let overdue = |review: &Review| (now - review.due) as f64 / review.interval.max(1.0);
+        reviews.sort_by(|a, b| {
+            overdue(b)
+                .partial_cmp(&overdue(a))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.id.cmp(&b.id))
+        });
+        reviews
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_owned(),
+    }
+}
+
+/// Writes the score matrix of `students` (usernames with the problems on their profile) as CSV:
+/// a header row with `Username` and a column for every problem (`name (#id)`, in increasing
+/// order of id), then a row per student with their best scores. Problems a student has not
+/// attempted, or whose score is not shown, have an empty cell.
+pub fn write_csv<W: Write>(mut writer: W, students: &[(&str, &[PublicSolve])]) -> Result<()> {
+    let mut problems: BTreeMap<ProblemId, &str> = BTreeMap::new();
+    for (_, solves) in students {
+        for solve in solves.iter() {
+            problems.entry(solve.id).or_insert(&solve.name);
+        }
+    }
+
+    let mut rows = vec![std::iter::once("Username".to_owned())
+        .chain(
+            problems
+                .iter()
+                .map(|(id, name)| format!("{} (#{})", name, id)),
+        )
+        .collect::<Vec<_>>()];
+    for (username, solves) in students {
+        let mut row = vec![username.to_string()];
+        for id in problems.keys() {
+            let score = solves
+                .iter()
+                .filter(|solve| solve.id == *id)
+                .filter_map(|solve| solve.score)
+                .max();
+            row.push(score.map(|score| score.to_string()).unwrap_or_default());
+        }
+        rows.push(row);
+    }
+
+    for row in rows {
+        let line = row
+            .iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        write!(writer, "{}\r\n", line)
+            .map_err(|err| PbInfoError::IOError(format!("Could not write the CSV: {}", err)))?;
+    }
+    writer
+        .flush()
+        .map_err(|err| PbInfoError::IOError(format!("Could not write the CSV: {}", err)))
+}
+
+/// Writes the score matrix of `students` (usernames), scraped from their public profiles, to
+/// `writer` as CSV (see `write_csv`).
+#[cfg(feature = "blocking")]
+pub fn export_csv<W: Write>(writer: W, students: &[&str]) -> Result<()> {
+    PbInfoClient::shared().export_progress_csv(writer, students)
+}
+
+#[cfg(feature = "blocking")]
+impl PbInfoClient {
+    /// Merges the scores on the public profile of `progress.username` into `progress`. Returns
+    /// the number of problems that changed.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, progress), fields(username = %progress.username), err(Debug))
+    )]
+    pub fn sync_progress(&self, progress: &mut Progress) -> Result<usize> {
+        let solves = self.fetch_public_solves(&progress.username)?;
+        Ok(progress.merge_solves(&solves, timestamp(SystemTime::now())))
+    }
+
+    /// Syncs the progress of `username` kept at `data_path` with PbInfo and saves it, recording
+    /// the sync for `trend`. Returns the number of problems that changed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn sync_saved_progress(&self, username: &str) -> Result<usize> {
+        let path = data_path(username).ok_or_else(|| {
+            PbInfoError::IOError("Could not find the data directory of the user".to_owned())
+        })?;
+        let mut progress = Progress::load_or_new(&path, username)?;
+        let changed = self.sync_progress(&mut progress)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| {
+                PbInfoError::IOError(format!("Could not create `{}`: {}", dir.display(), err))
+            })?;
+        }
+        progress.save(&path)?;
+        Ok(changed)
+    }
+
+    /// Writes the score matrix of `students` (usernames), scraped from their public profiles,
+    /// to `writer` as CSV (see `write_csv`).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, writer), err(Debug))
+    )]
+    pub fn export_progress_csv<W: Write>(&self, writer: W, students: &[&str]) -> Result<()> {
+        let mut solves = Vec::new();
+        for student in students {
+            solves.push(self.fetch_public_solves(student)?);
+        }
+        let students = students
+            .iter()
+            .zip(&solves)
+            .map(|(student, solves)| (*student, solves.as_slice()))
+            .collect::<Vec<_>>();
+        write_csv(writer, &students)
+    }
+}
@@ -0,0 +1,203 @@
+//! Python bindings: a `pbinfo` module with `fetch_problem_by_id`, `fetch_problem_by_name` and
+//! `search`. Build it as a shared library and rename it to `pbinfo.so` (`pbinfo.pyd` on
+//! Windows):
+//!
+//! ```sh
+//! cargo rustc --release --lib --features python,pyo3/extension-module --crate-type cdylib
+//! cp target/release/libpbinfo.so pbinfo.so
+//! ```
+//!
+//! ```python
+//! import pbinfo
+//! problem = pbinfo.fetch_problem_by_id(1691)
+//! for example in problem.examples():
+//!     print(example.input, example.output)
+//! ```
+
+use crate::*;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+
+create_exception!(
+    pbinfo,
+    Error,
+    PyException,
+    "An error returned by PbInfo or the library."
+);
+create_exception!(
+    pbinfo,
+    UnknownProblem,
+    Error,
+    "There is no problem with this id or name."
+);
+
+/// Converts `err` into the matching Python exception.
+fn to_py_err(err: PbInfoError) -> PyErr {
+    match err {
+        PbInfoError::UnknownId(_) | PbInfoError::UnknownName(..) => {
+            UnknownProblem::new_err(format!("{:?}", err))
+        }
+        err => Error::new_err(format!("{:?}", err)),
+    }
+}
+
+/// An example of a problem.
+#[pyclass(name = "Example", module = "pbinfo", frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct PyExample {
+    /// Contents of the input file (or stdin)
+    pub input: String,
+    /// Expected contents of the output file (or stdout)
+    pub output: String,
+}
+
+#[pymethods]
+impl PyExample {
+    fn __repr__(&self) -> String {
+        format!("Example(input={:?}, output={:?})", self.input, self.output)
+    }
+}
+
+/// A problem from PbInfo.
+#[pyclass(name = "Problem", module = "pbinfo", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyProblem {
+    pub(crate) problem: PbInfoProblem,
+}
+
+/// Returns the file name of `source`, or `None` for stdin/stdout.
+fn file_name(source: &IOSource) -> Option<String> {
+    match source {
+        IOSource::File(name) => Some(name.clone()),
+        IOSource::Std => None,
+    }
+}
+
+#[pymethods]
+impl PyProblem {
+    /// Unique id of the problem
+    #[getter]
+    fn id(&self) -> usize {
+        self.problem.id.0
+    }
+
+    /// Unique name of the problem
+    #[getter]
+    fn name(&self) -> &str {
+        self.problem.name.as_str()
+    }
+
+    /// Grade (9, 10 or 11)
+    #[getter]
+    fn grade(&self) -> usize {
+        self.problem.grade.0
+    }
+
+    /// File name of the input, or `None` for stdin
+    #[getter]
+    fn input_file(&self) -> Option<String> {
+        file_name(&self.problem.input_source)
+    }
+
+    /// File name of the output, or `None` for stdout
+    #[getter]
+    fn output_file(&self) -> Option<String> {
+        file_name(&self.problem.output_source)
+    }
+
+    /// Time limit, if any
+    #[getter]
+    fn time_limit(&self) -> Option<String> {
+        self.problem.time_limit.clone()
+    }
+
+    /// Memory limit, if any
+    #[getter]
+    fn memory_limit(&self) -> Option<String> {
+        self.problem.memory_limit.clone()
+    }
+
+    /// Chapters containing the problem, from the most general
+    #[getter]
+    fn topics(&self) -> Vec<String> {
+        self.problem.topics.clone()
+    }
+
+    /// Statement as plain text
+    #[getter]
+    fn statement(&self) -> String {
+        html_to_text(&self.problem.problem_text)
+    }
+
+    /// Statement as html
+    #[getter]
+    fn statement_html(&self) -> &str {
+        &self.problem.problem_text
+    }
+
+    /// Returns the examples of the statement.
+    fn examples(&self) -> Vec<PyExample> {
+        self.problem
+            .examples()
+            .into_iter()
+            .map(|example| PyExample {
+                input: example.input,
+                output: example.output,
+            })
+            .collect()
+    }
+
+    /// Returns the problem in the public JSON schema (see `to_json_v1`), as a string.
+    fn to_json(&self) -> String {
+        self.problem.to_json_v1().to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Problem(id={}, name={:?})", self.problem.id, self.name())
+    }
+}
+
+/// Fetches the problem with the given id.
+#[pyfunction]
+fn fetch_problem_by_id(py: Python<'_>, id: usize) -> PyResult<PyProblem> {
+    py.allow_threads(|| PbInfoProblem::fetch_problem_by_id(ProblemId(id)))
+        .map(|problem| PyProblem { problem })
+        .map_err(to_py_err)
+}
+
+/// Fetches the problem with the given name.
+#[pyfunction]
+fn fetch_problem_by_name(py: Python<'_>, name: &str) -> PyResult<PyProblem> {
+    py.allow_threads(|| PbInfoProblem::fetch_problem_by_name(name))
+        .map(|problem| PyProblem { problem })
+        .map_err(to_py_err)
+}
+
+/// Searches problems by name, returning `(id, name)` pairs.
+#[pyfunction]
+#[pyo3(name = "search")]
+fn search_problems(py: Python<'_>, query: &str) -> PyResult<Vec<(usize, String)>> {
+    py.allow_threads(|| crate::search(query))
+        .map(|results| {
+            results
+                .into_iter()
+                .map(|result| (result.id.0, result.name))
+                .collect()
+        })
+        .map_err(to_py_err)
+}
+
+/// The `pbinfo` Python module.
+#[pymodule]
+pub fn pbinfo(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = module.py();
+    module.add("Error", py.get_type::<Error>())?;
+    module.add("UnknownProblem", py.get_type::<UnknownProblem>())?;
+    module.add_class::<PyProblem>()?;
+    module.add_class::<PyExample>()?;
+    module.add_function(wrap_pyfunction!(fetch_problem_by_id, module)?)?;
+    module.add_function(wrap_pyfunction!(fetch_problem_by_name, module)?)?;
+    module.add_function(wrap_pyfunction!(search_problems, module)?)?;
+    Ok(())
+}
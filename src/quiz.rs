@@ -0,0 +1,141 @@
+//! Multiple-choice quizzes ("chestionare"), fetched into structured questions for offline
+//! practice.
+
+use crate::*;
+
+/// A multiple-choice quiz.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Quiz {
+    /// Unique id of quiz
+    pub id: usize,
+    /// Title of quiz
+    pub title: String,
+    /// Questions, in the order in which they appear
+    pub questions: Vec<Question>,
+}
+
+/// A question of a quiz.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Question {
+    /// Text of question
+    pub prompt: String,
+    /// Text of every choice, in the order in which they appear
+    pub choices: Vec<String>,
+    /// Whether more than one choice can be selected (checkboxes instead of radio buttons)
+    pub multiple: bool,
+    /// Indices of the correct choices. Only shown to teachers, so `None` for other users
+    pub answers: Option<Vec<usize>>,
+}
+
+impl Question {
+    /// Returns `true` if `selected` (indices of choices, in any order) is exactly the set of
+    /// correct choices, or `None` if the answers are not known.
+    pub fn check(&self, selected: &[usize]) -> Option<bool> {
+        let answers = self.answers.as_ref()?;
+        let mut selected = selected.to_vec();
+        selected.sort_unstable();
+        selected.dedup();
+        Some(selected == *answers)
+    }
+}
+
+/// Extracts the questions of a quiz page. Every question is in an element whose class contains
+/// `intrebare`; its prompt is the text before the first choice, and every choice is a radio
+/// button or a checkbox followed by its text. The inputs of correct choices are marked with a
+/// `corect` class or attribute on the pages seen by teachers.
+pub fn extract_questions(string: &str) -> Vec<Question> {
+    let question_regex = &selectors::QUIZ_QUESTION;
+    let input_regex = &selectors::QUIZ_INPUT;
+    let correct_regex = &selectors::QUIZ_CORRECT;
+
+    let starts = question_regex
+        .find_iter(string)
+        .map(|question| question.end())
+        .collect::<Vec<_>>();
+    let mut questions = Vec::new();
+    for (i, start) in starts.iter().enumerate() {
+        let block = match starts.get(i + 1) {
+            Some(next) => &string[*start..*next],
+            None => &string[*start..],
+        };
+        let inputs = input_regex.captures_iter(block).collect::<Vec<_>>();
+        let first = match inputs.first() {
+            Some(input) => input.get(0).unwrap().start(),
+            None => continue,
+        };
+
+        let mut choices = Vec::new();
+        let mut answers = Vec::new();
+        for (j, input) in inputs.iter().enumerate() {
+            let tag = input.get(0).unwrap();
+            let end = inputs
+                .get(j + 1)
+                .map(|next| next.get(0).unwrap().start())
+                .unwrap_or(block.len());
+            // The text of the last choice ends with its label or list item
+            let text = &block[tag.end()..end];
+            let text = text
+                .find("</label>")
+                .or_else(|| text.find("</li>"))
+                .map_or(text, |position| &text[..position]);
+            if correct_regex.is_match(tag.as_str()) {
+                answers.push(j);
+            }
+            choices.push(html_to_text(text).trim().to_owned());
+        }
+
+        questions.push(Question {
+            prompt: html_to_text(&block[..first]).trim().to_owned(),
+            choices,
+            multiple: inputs
+                .iter()
+                .any(|input| input[1].eq_ignore_ascii_case("checkbox")),
+            answers: (!answers.is_empty()).then_some(answers),
+        });
+    }
+    questions
+}
+
+/// Extracts the title of a quiz page, the text of its first `<h1>`.
+fn extract_quiz_title(string: &str) -> Option<String> {
+    let regex = &selectors::H1;
+    regex
+        .captures(string)
+        .map(|caps| html_to_text(&caps[1]).trim().to_owned())
+}
+
+/// Returns the quiz with the id `id`.
+pub fn fetch_quiz(id: usize) -> Result<Quiz> {
+    PbInfoClient::shared().fetch_quiz(id)
+}
+
+impl PbInfoClient {
+    /// Returns the quiz with the id `id`. The correct answers are only included when logged in
+    /// using a teacher account.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_quiz(&self, id: usize) -> Result<Quiz> {
+        let page = self.get_page(&self.url(&format!("/chestionare/{}", id)))?;
+
+        match page.status() {
+            reqwest::StatusCode::OK => {
+                let text = self.read_text(page)?;
+                let questions = extract_questions(&text);
+                if questions.is_empty() {
+                    check_site_available(&text)?;
+                }
+                Ok(Quiz {
+                    id,
+                    title: extract_quiz_title(&text).unwrap_or_default(),
+                    questions,
+                })
+            }
+            reqwest::StatusCode::NOT_FOUND => {
+                Err(PbInfoError::Error(format!("Unknown quiz {}", id)))
+            }
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to fetch the quiz. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+}
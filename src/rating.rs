@@ -0,0 +1,36 @@
+use crate::*;
+
+/// Attempts with the average success rate added to the counts of every problem, so that a
+/// problem with a handful of submissions is not rated by them alone.
+const PRIOR_ATTEMPTS: f64 = 10.0;
+
+/// Success rate (0 to 1) assumed for problems without enough submissions.
+const PRIOR_SUCCESS_RATE: f64 = 0.4;
+
+/// Estimates how difficult a problem is, from 0 (trivial) to 10 (hardest), from its submission
+/// statistics and its grade. PbInfo's own labels (`Difficulty`) are coarse and set by the
+/// authors; this rating is based on how the users actually did:
+///
+/// - half of it is the share of submissions that did not get 100 points,
+/// - 30% is the points lost on average (or the failure rate, if no average score is shown),
+/// - 20% is the grade, from the 5th (easiest) to the 11th (hardest).
+pub fn estimate_difficulty(stats: &ProblemStats, grade: Grade) -> f32 {
+    let attempts = stats.attempts as f64;
+    let solved = (stats.solved as f64).min(attempts);
+    let failure_rate =
+        1.0 - (solved + PRIOR_SUCCESS_RATE * PRIOR_ATTEMPTS) / (attempts + PRIOR_ATTEMPTS);
+    let lost_points = match stats.average_score {
+        Some(score) if stats.attempts > 0 => 1.0 - (score / 100.0).clamp(0.0, 1.0),
+        _ => failure_rate,
+    };
+    let grade = (grade.0.clamp(5, 11) - 5) as f64 / 6.0;
+
+    (10.0 * (0.5 * failure_rate + 0.3 * lost_points + 0.2 * grade)) as f32
+}
+
+impl PbInfoProblem {
+    /// Estimates the difficulty of the problem from its statistics (see `estimate_difficulty`).
+    pub fn estimated_difficulty(&self, stats: &ProblemStats) -> f32 {
+        estimate_difficulty(stats, self.grade)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::*;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Makes a client save the responses it receives, or answer requests from saved responses.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RecordMode {
+    /// Every response is saved to the directory (which is created if it does not exist).
+    Record(PathBuf),
+    /// Responses are read from the directory, without making any requests. Requests that
+    /// were not recorded fail with `PbInfoError::NetworkError`.
+    Replay(PathBuf),
+}
+
+/// Returns the file storing the response to `method url` in the recording directory `dir`.
+/// The name is a hash of the request (FNV-1a), so it stays the same across runs and versions.
+pub(crate) fn recording_path(dir: &Path, method: &str, url: &str) -> PathBuf {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in method.bytes().chain([b' ']).chain(url.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    dir.join(format!("{:016x}.http", hash))
+}
+
+/// Saves a response to `path`: the status line, the headers (including the url of the request,
+/// as `X-Recorded-Url`), an empty line and then the body.
+pub(crate) fn save_response(
+    path: &Path,
+    url: &str,
+    status: reqwest::StatusCode,
+    headers: &reqwest::header::HeaderMap,
+    body: &[u8],
+) -> Result<()> {
+    let mut data = format!("HTTP {}\r\nX-Recorded-Url: {}\r\n", status.as_u16(), url);
+    for (name, value) in headers {
+        // The body is saved decompressed, so its encoding and length may differ
+        if name == reqwest::header::CONTENT_ENCODING || name == reqwest::header::CONTENT_LENGTH {
+            continue;
+        }
+        if let Ok(value) = value.to_str() {
+            data.push_str(&format!("{}: {}\r\n", name, value));
+        }
+    }
+    data.push_str("\r\n");
+
+    let mut data = data.into_bytes();
+    data.extend_from_slice(body);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| {
+            PbInfoError::IOError(format!("Could not create `{}`: {}", dir.display(), err))
+        })?;
+    }
+    std::fs::write(path, data).map_err(|err| {
+        PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+    })
+}
+
+/// Loads a response saved by `save_response`.
+pub(crate) fn load_response(path: &Path) -> Result<reqwest::blocking::Response> {
+    let data = std::fs::read(path).map_err(|err| {
+        PbInfoError::IOError(format!("Could not read `{}`: {}", path.display(), err))
+    })?;
+    let invalid = || PbInfoError::Error(format!("`{}` is not a recorded response", path.display()));
+
+    let head_end = data
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(invalid)?;
+    let head = std::str::from_utf8(&data[..head_end]).map_err(|_| invalid())?;
+    let mut lines = head.split("\r\n");
+
+    let status = lines
+        .next()
+        .and_then(|line| line.strip_prefix("HTTP "))
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(invalid)?;
+    let mut response = http::Response::builder().status(status);
+    for line in lines {
+        let (name, value) = line.split_once(": ").ok_or_else(invalid)?;
+        response = response.header(name, value);
+    }
+
+    let response = response
+        .body(data[head_end + 4..].to_vec())
+        .map_err(|_| invalid())?;
+    Ok(reqwest::blocking::Response::from(response))
+}
+
+/// Reads the whole body of `page` and saves the response to `path`, returning an equivalent
+/// response that can still be read by the caller.
+pub(crate) fn record_response(
+    path: &Path,
+    url: &str,
+    mut page: reqwest::blocking::Response,
+) -> Result<reqwest::blocking::Response> {
+    let mut body = Vec::new();
+    page.read_to_end(&mut body).map_err(|err| {
+        PbInfoError::NetworkError(format!("Could not read the response body: {}", err))
+    })?;
+    save_response(path, url, page.status(), page.headers(), &body)?;
+    load_response(path)
+}
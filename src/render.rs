@@ -0,0 +1,760 @@
+use crate::*;
+
+/// Decodes the `%XX` escapes (and `+` as space) of a url component.
+fn percent_decode(string: &str) -> String {
+    let bytes = string.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+            (b'+', _) => decoded.push(b' '),
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Makes a link or image url from PbInfo absolute. Returns `None` for urls that should not be
+/// followed from other pages (`javascript:`, `data:` etc.).
+pub(crate) fn absolute_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let lower = url.to_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        Some(url.to_owned())
+    } else if url.starts_with('/') && !url.starts_with("//") {
+        Some(format!("{}{}", DEFAULT_BASE_URL, url))
+    } else {
+        None
+    }
+}
+
+/// Removes the comments and the elements whose contents are not displayed (scripts, styles,
+/// embedded frames) from a fragment of html.
+pub(crate) fn remove_hidden(html: &str) -> String {
+    let comments = &selectors::HTML_COMMENT;
+
+    let mut html = comments.replace_all(html, "").into_owned();
+    for element in &selectors::HIDDEN_ELEMENTS {
+        html = element.replace_all(&html, "").into_owned();
+    }
+    html
+}
+
+/// An element of a MathML formula.
+enum MathNode {
+    /// Text of a token element (`<mi>`, `<mn>`, `<mo>` etc.)
+    Text(String),
+    /// A layout element and its children
+    Element(String, Vec<MathNode>),
+}
+
+/// Parses the MathML elements of `tokens` until the closing tag of the current element.
+fn parse_mathml<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Vec<MathNode> {
+    let tag = &selectors::MATHML_TAG;
+
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        match tag.captures(token) {
+            Some(caps) if &caps[1] == "/" => break,
+            Some(caps) if &caps[3] == "/" => {
+                nodes.push(MathNode::Element(caps[2].to_owned(), vec![]))
+            }
+            Some(caps) => {
+                let children = parse_mathml(tokens);
+                nodes.push(MathNode::Element(caps[2].to_owned(), children));
+            }
+            None if !token.trim().is_empty() => {
+                nodes.push(MathNode::Text(decode_entities(token.trim())))
+            }
+            None => {}
+        }
+    }
+    nodes
+}
+
+/// Renders MathML nodes as LaTeX.
+fn render_mathml(nodes: &[MathNode]) -> String {
+    let render = |node: Option<&MathNode>| {
+        node.map(|node| render_mathml(std::slice::from_ref(node)))
+            .unwrap_or_default()
+            .trim()
+            .to_owned()
+    };
+    // Exponents and indices need braces unless they are a single character
+    let argument = |node: Option<&MathNode>| {
+        let latex = render(node);
+        match latex.chars().count() {
+            1 => latex,
+            _ => format!("{{{}}}", latex),
+        }
+    };
+
+    nodes
+        .iter()
+        .map(|node| match node {
+            MathNode::Text(text) => match text.as_str() {
+                "≤" => "\\le ".to_owned(),
+                "≥" => "\\ge ".to_owned(),
+                "≠" => "\\ne ".to_owned(),
+                "×" => "\\times ".to_owned(),
+                "·" => "\\cdot ".to_owned(),
+                text => text.to_owned(),
+            },
+            MathNode::Element(name, children) => match name.as_str() {
+                "msup" => format!("{}^{}", render(children.first()), argument(children.get(1))),
+                "msub" => format!("{}_{}", render(children.first()), argument(children.get(1))),
+                "mfrac" => format!(
+                    "\\frac{{{}}}{{{}}}",
+                    render(children.first()),
+                    render(children.get(1))
+                ),
+                "msqrt" => format!("\\sqrt{{{}}}", render_mathml(children).trim()),
+                _ => render_mathml(children),
+            },
+        })
+        .collect()
+}
+
+/// Converts MathML (`<mi>`, `<mn>`, `<mo>`, `<msup>`, `<msub>`, `<mfrac>`, `<msqrt>`, `<mrow>`)
+/// into LaTeX. Other elements are reduced to their contents.
+fn mathml_to_latex(mathml: &str) -> String {
+    let token = &selectors::MATHML_TOKEN;
+    let mut tokens = token.find_iter(mathml).map(|token| token.as_str());
+    render_mathml(&parse_mathml(&mut tokens)).trim().to_owned()
+}
+
+/// Replaces the math in `html` with LaTeX between `$` (or `$$` for displayed formulas):
+/// formula images (using their alt text or the LaTeX in their url), MathJax delimiters,
+/// `math/tex` scripts and MathML. Superscripts and subscripts become `^` and `_`.
+pub fn math_to_latex(html: &str) -> String {
+    let image = &selectors::IMG;
+    let alt = &selectors::ALT;
+    let src = &selectors::SRC;
+    let latex_url = &selectors::LATEX_URL;
+    let script = &selectors::MATH_SCRIPT;
+    let inline = &selectors::INLINE_LATEX;
+    let display = &selectors::DISPLAY_LATEX;
+    let mathml = &selectors::MATHML;
+    let alttext = &selectors::ALTTEXT;
+    let sup = &selectors::SUP;
+    let sub = &selectors::SUB;
+
+    let html = image.replace_all(html, |caps: &regex::Captures| {
+        let tag = &caps[0];
+        let url = src.captures(tag).map(|caps| caps[1].to_owned());
+        let formula = url
+            .as_deref()
+            .and_then(|url| latex_url.captures(url))
+            .map(|caps| percent_decode(&caps[1]))
+            .or_else(|| {
+                alt.captures(tag)
+                    .map(|caps| decode_entities(&caps[1]))
+                    .filter(|alt| alt.contains(['\\', '^', '_', '=']) || tag.contains("latex"))
+            });
+        match formula {
+            Some(formula) => format!("${}$", formula.trim()),
+            None => tag.to_owned(),
+        }
+    });
+    let html = script.replace_all(&html, |caps: &regex::Captures| match caps.get(1) {
+        Some(_) => format!("$${}$$", caps[2].trim()),
+        None => format!("${}$", caps[2].trim()),
+    });
+    let html = display.replace_all(&html, |caps: &regex::Captures| {
+        format!("$${}$$", caps[1].trim())
+    });
+    let html = inline.replace_all(&html, |caps: &regex::Captures| {
+        format!("${}$", caps[1].trim())
+    });
+    let html = mathml.replace_all(&html, |caps: &regex::Captures| {
+        let latex = match alttext.captures(&caps[1]) {
+            Some(alttext) => decode_entities(&alttext[1]),
+            None => mathml_to_latex(&caps[2]),
+        };
+        match caps[1].contains("block") {
+            true => format!("$${}$$", latex),
+            false => format!("${}$", latex),
+        }
+    });
+    let html = sup.replace_all(&html, |caps: &regex::Captures| {
+        match caps[1].chars().count() {
+            1 => format!("^{}", &caps[1]),
+            _ => format!("^({})", &caps[1]),
+        }
+    });
+    let html = sub.replace_all(&html, |caps: &regex::Captures| {
+        match caps[1].chars().count() {
+            1 => format!("_{}", &caps[1]),
+            _ => format!("_({})", &caps[1]),
+        }
+    });
+    html.into_owned()
+}
+
+/// Converts a fragment of html into Markdown: headings, paragraphs, emphasis, code, lists,
+/// links and math (see `math_to_latex`). Other tags are removed.
+pub fn html_to_markdown(html: &str) -> String {
+    let pre = &selectors::MARKUP_PRE;
+    let heading = &selectors::MARKUP_HEADING;
+    let strong = &selectors::MARKUP_STRONG;
+    let emphasis = &selectors::MARKUP_EMPHASIS;
+    let code = &selectors::MARKUP_CODE;
+    let link = &selectors::MARKUP_LINK;
+    let item = &selectors::MARKUP_ITEM;
+    let breaks = &selectors::BR;
+    let blocks = &selectors::MARKUP_BLOCK;
+    let tags = &selectors::TAG;
+    let blank_lines = &selectors::BLANK_LINES;
+
+    let html = math_to_latex(&remove_hidden(html));
+
+    // The contents of code blocks are kept verbatim, so they are set aside first, and so are
+    // the tables, which are laid out at the end
+    let mut code_blocks = Vec::new();
+    let html = pre.replace_all(&html, |caps: &regex::Captures| {
+        code_blocks.push(decode_entities(&tags.replace_all(&caps[1], "")));
+        format!("\n\n\u{0}{}\u{0}\n\n", code_blocks.len() - 1)
+    });
+    let mut tables = Vec::new();
+    let html = replace_tables(&html, |table| {
+        tables.push(table.to_markdown());
+        format!("\n\n\u{1}{}\u{1}\n\n", tables.len() - 1)
+    });
+
+    let html = heading.replace_all(&html, |caps: &regex::Captures| {
+        let level = caps[1].parse::<usize>().unwrap() + 1;
+        format!("\n\n{} {}\n\n", "#".repeat(level.min(6)), caps[2].trim())
+    });
+    let html = strong.replace_all(&html, "**$1**");
+    let html = emphasis.replace_all(&html, "*$1*");
+    let html = code.replace_all(&html, "`$1`");
+    let html = link.replace_all(&html, |caps: &regex::Captures| {
+        match absolute_url(&caps[1]) {
+            Some(url) => format!("[{}]({})", &caps[2], url),
+            None => caps[2].to_owned(),
+        }
+    });
+    let html = item.replace_all(&html, "\n- ");
+    let html = breaks.replace_all(&html, "  \n");
+    let html = blocks.replace_all(&html, "\n\n");
+    let text = decode_entities(&tags.replace_all(&html, ""));
+
+    let text = text
+        .lines()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut text = blank_lines.replace_all(&text, "\n\n").trim().to_owned();
+    for (i, block) in code_blocks.iter().enumerate() {
+        text = text.replace(
+            &format!("\u{0}{}\u{0}", i),
+            &CodeBlock::new(block).to_markdown(),
+        );
+    }
+    for (i, table) in tables.iter().enumerate() {
+        text = text.replace(&format!("\u{1}{}\u{1}", i), table);
+    }
+    text
+}
+
+impl Table {
+    /// Renders the table as a Markdown (GitHub Flavored) table. Tables without a header get an
+    /// empty one, since Markdown tables need it.
+    pub fn to_markdown(&self) -> String {
+        let columns = self
+            .headers
+            .len()
+            .max(self.rows.first().map_or(0, Vec::len));
+        let line = |cells: &[String]| {
+            let cells = (0..columns)
+                .map(|i| {
+                    cells
+                        .get(i)
+                        .map_or(String::new(), |cell| cell.replace('|', "\\|"))
+                })
+                .collect::<Vec<_>>();
+            format!("| {} |", cells.join(" | "))
+        };
+        let mut markdown = vec![line(&self.headers)];
+        markdown.push(format!("|{}", " --- |".repeat(columns)));
+        markdown.extend(self.rows.iter().map(|row| line(row)));
+        markdown.join("\n")
+    }
+
+    /// Renders the table as plain text, with the columns aligned and the header underlined.
+    pub fn to_plain_text(&self) -> String {
+        let mut widths = vec![
+            0;
+            self.headers
+                .len()
+                .max(self.rows.first().map_or(0, Vec::len))
+        ];
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let line = |cells: &[String]| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_owned()
+        };
+        let mut lines = Vec::new();
+        if !self.headers.is_empty() {
+            lines.push(line(&self.headers));
+            lines.push(
+                widths
+                    .iter()
+                    .map(|width| "-".repeat(*width))
+                    .collect::<Vec<_>>()
+                    .join("  "),
+            );
+        }
+        lines.extend(self.rows.iter().map(|row| line(row)));
+        lines.join("\n")
+    }
+}
+
+/// Converts a fragment of html into reStructuredText: paragraphs, emphasis, literals, lists,
+/// links, literal blocks and math (as the `math` role and directive). Other tags are removed.
+pub fn html_to_rst(html: &str) -> String {
+    let pre = &selectors::MARKUP_PRE;
+    let heading = &selectors::MARKUP_HEADING_TEXT;
+    let strong = &selectors::MARKUP_STRONG;
+    let emphasis = &selectors::MARKUP_EMPHASIS;
+    let code = &selectors::MARKUP_CODE;
+    let link = &selectors::MARKUP_LINK;
+    let display_math = &selectors::DISPLAY_DOLLARS;
+    let inline_math = &selectors::INLINE_DOLLARS;
+    let item = &selectors::MARKUP_ITEM;
+    let blocks = &selectors::MARKUP_BLOCK_OR_BREAK;
+    let tags = &selectors::TAG;
+    let blank_lines = &selectors::BLANK_LINES;
+
+    let html = math_to_latex(&remove_hidden(html));
+
+    // Literal and math blocks are indented, so they are set aside until the end
+    let mut literal_blocks = Vec::new();
+    let html = pre.replace_all(&html, |caps: &regex::Captures| {
+        let text = decode_entities(&tags.replace_all(&caps[1], ""));
+        let text = text
+            .trim_matches('\n')
+            .lines()
+            .map(|line| format!("    {}", line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        literal_blocks.push(format!("::\n\n{}", text));
+        format!("\n\n\u{0}{}\u{0}\n\n", literal_blocks.len() - 1)
+    });
+    let html = display_math.replace_all(&html, |caps: &regex::Captures| {
+        literal_blocks.push(format!(".. math::\n\n    {}", caps[1].trim()));
+        format!("\n\n\u{0}{}\u{0}\n\n", literal_blocks.len() - 1)
+    });
+
+    let html = heading.replace_all(&html, "\n\n**$1**\n\n");
+    let html = strong.replace_all(&html, "**$1**");
+    let html = emphasis.replace_all(&html, "*$1*");
+    let html = code.replace_all(&html, "``$1``");
+    // The brackets around the url are added after the tags are removed
+    let html = link.replace_all(&html, |caps: &regex::Captures| {
+        match absolute_url(&caps[1]) {
+            Some(url) => format!("`{} \u{1}{}\u{2}`__", &caps[2], url),
+            None => caps[2].to_owned(),
+        }
+    });
+    let html = inline_math.replace_all(&html, ":math:`$1`");
+    let html = item.replace_all(&html, "\n- ");
+    let html = blocks.replace_all(&html, "\n\n");
+    let text = decode_entities(&tags.replace_all(&html, ""));
+    let text = text.replace('\u{1}', "<").replace('\u{2}', ">");
+
+    let text = text
+        .lines()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut text = blank_lines.replace_all(&text, "\n\n").trim().to_owned();
+    for (i, block) in literal_blocks.iter().enumerate() {
+        text = text.replace(&format!("\u{0}{}\u{0}", i), block);
+    }
+    text
+}
+
+/// Words for the symbols of formulas, read out loud by `html_to_accessible_text`: the ways of
+/// writing the symbol (LaTeX, Unicode, ASCII), and the words in English and in Romanian.
+const SPOKEN_SYMBOLS: &[(&[&str], &str, &str)] = &[
+    (
+        &["\\le", "\\leq", "≤", "<="],
+        "less than or equal to",
+        "mai mic sau egal cu",
+    ),
+    (
+        &["\\ge", "\\geq", "≥", ">="],
+        "greater than or equal to",
+        "mai mare sau egal cu",
+    ),
+    (&["\\ne", "\\neq", "≠", "!="], "not equal to", "diferit de"),
+    (&["\\lt", "<"], "less than", "mai mic decât"),
+    (&["\\gt", ">"], "greater than", "mai mare decât"),
+    (&["="], "equals", "egal cu"),
+    (&["\\pm", "±"], "plus or minus", "plus sau minus"),
+    (&["+"], "plus", "plus"),
+    (&["-", "−"], "minus", "minus"),
+    (&["\\times", "\\cdot", "×", "·", "*"], "times", "ori"),
+    (&["\\div", "÷", "/"], "divided by", "împărțit la"),
+    (
+        &["\\ldots", "\\dots", "\\cdots", "…", "..."],
+        "and so on",
+        "și așa mai departe",
+    ),
+    (&["\\in", "∈"], "in", "din"),
+    (&["\\infty", "∞"], "infinity", "infinit"),
+    (&["\\%", "%"], "percent", "la sută"),
+];
+
+/// Returns the words for `symbol` (see `SPOKEN_SYMBOLS`), if it is a known symbol.
+fn spoken_symbol(symbol: &str, locale: Locale) -> Option<&'static str> {
+    SPOKEN_SYMBOLS
+        .iter()
+        .find(|(symbols, _, _)| symbols.contains(&symbol))
+        .map(|(_, english, romanian)| display::label(locale, english, romanian))
+}
+
+/// Reads the argument of a LaTeX command, exponent or index at `pos`: a group, a command or a
+/// single character.
+fn spell_argument(chars: &[char], pos: &mut usize, locale: Locale) -> String {
+    while chars.get(*pos).is_some_and(|c| c.is_whitespace()) {
+        *pos += 1;
+    }
+    match chars.get(*pos) {
+        Some('{') => {
+            *pos += 1;
+            spell_latex_group(chars, pos, locale)
+        }
+        Some('\\') => {
+            let start = *pos;
+            *pos += 1;
+            while chars.get(*pos).is_some_and(|c| c.is_ascii_alphabetic()) {
+                *pos += 1;
+            }
+            let command = chars[start..*pos].iter().collect::<String>();
+            spell_latex(&command, locale)
+        }
+        Some(c) => {
+            *pos += 1;
+            c.to_string()
+        }
+        None => String::new(),
+    }
+}
+
+/// Reads a LaTeX formula out loud from `pos` to the end of the current group (or of the
+/// formula), leaving `pos` after the closing brace.
+fn spell_latex_group(chars: &[char], pos: &mut usize, locale: Locale) -> String {
+    let mut words = String::new();
+    while let Some(&c) = chars.get(*pos) {
+        *pos += 1;
+        match c {
+            '}' => break,
+            '{' => words.push_str(&format!(" {} ", spell_latex_group(chars, pos, locale))),
+            '^' => words.push_str(&format!(
+                " {} {} ",
+                display::label(locale, "to the power of", "la puterea"),
+                spell_argument(chars, pos, locale)
+            )),
+            '_' => words.push_str(&format!(
+                " {} {} ",
+                display::label(locale, "sub", "indice"),
+                spell_argument(chars, pos, locale)
+            )),
+            '\\' => {
+                let start = *pos;
+                while chars.get(*pos).is_some_and(|c| c.is_ascii_alphabetic()) {
+                    *pos += 1;
+                }
+                // Commands are letters, symbols (`\{`, `\%`, `\,`) are a single character
+                if *pos == start && *pos < chars.len() {
+                    *pos += 1;
+                }
+                let command = chars[start..*pos].iter().collect::<String>();
+                let spelled = match command.as_str() {
+                    "frac" => {
+                        let numerator = spell_argument(chars, pos, locale);
+                        let denominator = spell_argument(chars, pos, locale);
+                        format!(
+                            "{} {} {}",
+                            numerator,
+                            display::label(locale, "over", "supra"),
+                            denominator
+                        )
+                    }
+                    "sqrt" => format!(
+                        "{} {}",
+                        display::label(locale, "the square root of", "radical din"),
+                        spell_argument(chars, pos, locale)
+                    ),
+                    "text" | "mathrm" | "mathbf" | "mathit" | "operatorname" => {
+                        spell_argument(chars, pos, locale)
+                    }
+                    "left" | "right" | "," | ";" | ":" | "!" | " " | "quad" | "qquad" => {
+                        String::new()
+                    }
+                    "{" | "}" => command,
+                    command => spoken_symbol(&format!("\\{}", command), locale)
+                        .map_or_else(|| command.to_owned(), str::to_owned),
+                };
+                words.push_str(&format!(" {} ", spelled));
+            }
+            c => {
+                // Operators of two or three characters (`<=`, `...`) go first
+                let rest = chars[*pos - 1..].iter().take(3).collect::<String>();
+                let operator = [3, 2, 1].into_iter().find_map(|length| {
+                    let operator = rest.chars().take(length).collect::<String>();
+                    (operator.chars().count() == length)
+                        .then(|| spoken_symbol(&operator, locale).map(|words| (length, words)))
+                        .flatten()
+                });
+                match operator {
+                    Some((length, spoken)) => {
+                        *pos += length - 1;
+                        words.push_str(&format!(" {} ", spoken));
+                    }
+                    None => words.push(c),
+                }
+            }
+        }
+    }
+    words.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Reads a LaTeX formula out loud (e.g. `n \le 10^5` is "n less than or equal to 10 to the
+/// power of 5"), in the language of `locale`.
+pub fn spell_latex(latex: &str, locale: Locale) -> String {
+    let chars = latex.chars().collect::<Vec<_>>();
+    spell_latex_group(&chars, &mut 0, locale)
+}
+
+/// Reads a table row by row, each cell preceded by its header (e.g. "Row 1: n: 3, suma: 6.").
+fn linearize_table(table: &Table, locale: Locale) -> String {
+    let count = |count: usize, one: &'static str, many: &'static str| match count {
+        1 => format!("{} {}", count, one),
+        _ => format!("{} {}", count, many),
+    };
+    let columns = table
+        .headers
+        .len()
+        .max(table.rows.first().map_or(0, Vec::len));
+    let mut lines = vec![format!(
+        "{} {}, {}.",
+        display::label(locale, "Table with", "Tabel cu"),
+        count(
+            table.rows.len(),
+            display::label(locale, "row", "rând"),
+            display::label(locale, "rows", "rânduri")
+        ),
+        count(
+            columns,
+            display::label(locale, "column", "coloană"),
+            display::label(locale, "columns", "coloane")
+        )
+    )];
+    for (i, row) in table.rows.iter().enumerate() {
+        let cells = row
+            .iter()
+            .enumerate()
+            .map(|(column, cell)| {
+                let cell = match cell.trim() {
+                    "" => display::label(locale, "empty", "gol"),
+                    cell => cell,
+                };
+                match table.headers.get(column).map(|header| header.trim()) {
+                    Some(header) if !header.is_empty() => format!("{}: {}", header, cell),
+                    _ => cell.to_owned(),
+                }
+            })
+            .collect::<Vec<_>>();
+        lines.push(format!(
+            "{} {}: {}.",
+            display::label(locale, "Row", "Rândul"),
+            i + 1,
+            cells.join(", ")
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Converts a fragment of html into plain text meant to be read by a screen reader or a
+/// text-to-speech engine, in the language of `locale`: formulas are spelled out (see
+/// `spell_latex`), tables are read row by row and images are replaced by their alt text, as
+/// `[figure: ...]`, or by `[figure]`.
+pub fn html_to_accessible_text(html: &str, locale: Locale) -> String {
+    let sup = &selectors::SUP;
+    let sub = &selectors::SUB;
+    let display = &selectors::DISPLAY_DOLLARS;
+    let inline = &selectors::INLINE_DOLLARS;
+    let image = &selectors::IMG;
+    let alt = &selectors::ALT;
+
+    // Superscripts and subscripts are read as formulas
+    let html = remove_hidden(html);
+    let html = sup.replace_all(&html, " $$^{$1}$$");
+    let html = sub.replace_all(&html, " $$_{$1}$$");
+    let html = math_to_latex(&html);
+    let html = display.replace_all(&html, |caps: &regex::Captures| {
+        format!("\n{}\n", spell_latex(&caps[1], locale))
+    });
+    let html = inline.replace_all(&html, |caps: &regex::Captures| {
+        spell_latex(&caps[1], locale)
+    });
+    let figure = display::label(locale, "figure", "figură");
+    let html = image.replace_all(&html, |caps: &regex::Captures| {
+        match alt
+            .captures(&caps[0])
+            .map(|alt| decode_entities(alt[1].trim()))
+        {
+            Some(alt) if !alt.is_empty() => format!(" [{}: {}] ", figure, alt),
+            _ => format!(" [{}] ", figure),
+        }
+    });
+    let html = replace_tables(&html, |table| {
+        format!("\n\n{}\n\n", linearize_table(table, locale))
+    });
+
+    // Symbols written outside formulas (`1 ≤ n`); ASCII ones are left, since they are also
+    // punctuation
+    html_to_text(&html)
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|word| match word.chars().all(|c| !c.is_ascii()) {
+                    true => spoken_symbol(word, locale).unwrap_or(word),
+                    false => word,
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Classes added to some elements by `clean_html`, so they can be styled without affecting the
+/// rest of the page.
+const CLEAN_HTML_CLASSES: &[(&str, &str)] = &[
+    ("pre", "pbinfo-example"),
+    ("table", "pbinfo-table"),
+    ("img", "pbinfo-figure"),
+];
+
+/// Styles of the fragments made by `PbInfoProblem::to_clean_html`, scoped to `.pbinfo-problem`.
+pub const CLEAN_HTML_CSS: &str = ".pbinfo-problem .pbinfo-section-title{font-size:1.2em}\
+.pbinfo-problem .pbinfo-example{background:#f5f5f5;padding:.5em;overflow-x:auto}\
+.pbinfo-problem .pbinfo-table{border-collapse:collapse}\
+.pbinfo-problem .pbinfo-table td,.pbinfo-problem .pbinfo-table th{border:1px solid #ccc;padding:.25em .5em}\
+.pbinfo-problem .pbinfo-figure{max-width:100%}";
+
+/// Escapes the characters of `text` that have a meaning in html.
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Removes everything but basic formatting from a fragment of PbInfo html (see `Sanitizer`),
+/// adding the classes styled by `CLEAN_HTML_CSS`.
+fn clean_html(html: &str) -> String {
+    CLEAN_HTML_CLASSES
+        .iter()
+        .fold(Sanitizer::default(), |sanitizer, (element, class)| {
+            sanitizer.with_class(element, class)
+        })
+        .sanitize(html)
+}
+
+impl PbInfoProblem {
+    /// Renders the statement as Markdown, starting with the name of the problem as a heading.
+    /// Formulas are written as LaTeX (e.g. `$n \le 10^5$`).
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n", self.name);
+        for section in self.sections() {
+            markdown.push_str(&format!(
+                "\n## {}\n\n{}\n",
+                section.title,
+                html_to_markdown(&section.html)
+            ));
+        }
+        markdown
+    }
+
+    /// Renders the statement as a sanitized html fragment that can be embedded in other pages.
+    /// It only contains basic formatting, its styles (`CLEAN_HTML_CSS`) are included and scoped
+    /// to the `pbinfo-problem` class, and its links and images point to PbInfo.
+    pub fn to_clean_html(&self) -> String {
+        let mut html = format!(
+            "<div class=\"pbinfo-problem\">\n<style>{}</style>\n<h2 class=\"pbinfo-title\">{}</h2>\n",
+            CLEAN_HTML_CSS,
+            escape_html(self.name.as_str())
+        );
+        for section in self.sections() {
+            html.push_str(&format!(
+                "<section class=\"pbinfo-section\">\n<h3 class=\"pbinfo-section-title\">{}</h3>\n{}\n</section>\n",
+                escape_html(&section.title),
+                clean_html(section.html.trim())
+            ));
+        }
+        html.push_str("</div>\n");
+        html
+    }
+
+    /// Renders the statement as reStructuredText (e.g. for Sphinx), with the name of the problem
+    /// as the title. Formulas use the `math` role and directive.
+    pub fn to_rst(&self) -> String {
+        let underline = |title: &str, c: char| c.to_string().repeat(title.chars().count());
+        let mut rst = format!("{}\n{}\n", self.name, underline(&self.name, '='));
+        for section in self.sections() {
+            rst.push_str(&format!(
+                "\n{}\n{}\n\n{}\n",
+                section.title,
+                underline(&section.title, '-'),
+                html_to_rst(&section.html)
+            ));
+        }
+        rst
+    }
+
+    /// Renders the statement as plain text. Formulas are written as LaTeX.
+    pub fn to_plain_text(&self) -> String {
+        html_to_text(&math_to_latex(&remove_hidden(&self.problem_text)))
+    }
+
+    /// Renders the statement as plain text for screen readers and text-to-speech (see
+    /// `html_to_accessible_text`), in the language the statement is written in: the name of the
+    /// problem, then every section after its title.
+    pub fn to_accessible_text(&self) -> String {
+        let locale = self.locale().unwrap_or(Locale::Romanian);
+        let mut text = format!("{}.\n", self.name);
+        for section in self.sections() {
+            text.push_str(&format!(
+                "\n{}.\n\n{}\n",
+                section.title,
+                html_to_accessible_text(&section.html, locale)
+            ));
+        }
+        text
+    }
+}
@@ -0,0 +1,126 @@
+//! Solution repositories: solutions are kept in a git repository, organized as
+//! `{grade}/{topic}/{name}/`, and committed with standardized messages containing the problem
+//! id and the score.
+
+use crate::*;
+use std::path::{Path, PathBuf};
+
+/// Returns the error for a failed git operation.
+fn git_error(err: git2::Error) -> PbInfoError {
+    PbInfoError::Error(format!("Git operation failed: {}", err.message()))
+}
+
+/// Makes `name` usable as a single path component.
+fn path_component(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    match name.trim_matches('.') {
+        "" => "_".to_owned(),
+        name => name.to_owned(),
+    }
+}
+
+/// Returns the directory (relative to the root of the repository) holding the solutions of
+/// `problem`: `{grade}/{topic}/{name}`, where `topic` is the most specific topic of the problem
+/// (or `diverse`, if it has none).
+pub fn solution_dir(problem: &PbInfoProblem) -> PathBuf {
+    let topic = problem
+        .topics
+        .last()
+        .map_or("diverse", |topic| topic.as_str());
+    [
+        problem.grade.to_string(),
+        path_component(topic),
+        path_component(&problem.name),
+    ]
+    .iter()
+    .collect()
+}
+
+/// Returns the standardized commit message for a solution of `problem`, e.g.
+/// `numere8 (#1691): 100 points`. The score is left out if it is not known.
+pub fn commit_message(problem: &PbInfoProblem, score: Option<usize>) -> String {
+    match score {
+        Some(score) => format!("{} (#{}): {} points", problem.name, problem.id, score),
+        None => format!("{} (#{})", problem.name, problem.id),
+    }
+}
+
+/// Opens the git repository at `path`, initializing it if there is none.
+pub fn open_or_init(path: impl AsRef<Path>) -> Result<git2::Repository> {
+    let path = path.as_ref();
+    match git2::Repository::open(path) {
+        Ok(repo) => Ok(repo),
+        Err(err) if err.code() == git2::ErrorCode::NotFound => {
+            git2::Repository::init(path).map_err(git_error)
+        }
+        Err(err) => Err(git_error(err)),
+    }
+}
+
+/// Writes `files` (`(file name, contents)` pairs) to the solution directory of `problem` in the
+/// repository at `repo_path` and commits them with `message`. The repository is initialized if
+/// it does not exist. Returns the id of the commit.
+pub fn commit_solution(
+    repo_path: impl AsRef<Path>,
+    problem: &PbInfoProblem,
+    files: &[(&str, &str)],
+    message: &str,
+) -> Result<String> {
+    let repo = open_or_init(repo_path)?;
+    let root = repo.workdir().ok_or_else(|| {
+        PbInfoError::Error("Solutions can not be committed to a bare repository".to_owned())
+    })?;
+    let dir = solution_dir(problem);
+    std::fs::create_dir_all(root.join(&dir)).map_err(|err| {
+        PbInfoError::IOError(format!(
+            "Could not create `{}`: {}",
+            root.join(&dir).display(),
+            err
+        ))
+    })?;
+
+    let mut index = repo.index().map_err(git_error)?;
+    for (name, contents) in files {
+        let relative = dir.join(path_component(name));
+        let path = root.join(&relative);
+        std::fs::write(&path, contents).map_err(|err| {
+            PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+        })?;
+        index.add_path(&relative).map_err(git_error)?;
+    }
+    index.write().map_err(git_error)?;
+    let tree = repo
+        .find_tree(index.write_tree().map_err(git_error)?)
+        .map_err(git_error)?;
+
+    // Fall back to a generic identity if user.name and user.email are not configured
+    let signature = repo
+        .signature()
+        .or_else(|_| git2::Signature::now("pbinfo", "pbinfo@localhost"))
+        .map_err(git_error)?;
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit().map_err(git_error)?),
+        Err(err) if err.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(err) => return Err(git_error(err)),
+    };
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let id = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .map_err(git_error)?;
+    Ok(id.to_string())
+}
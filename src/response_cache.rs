@@ -0,0 +1,160 @@
+use crate::cache::LruCache;
+use crate::recording::{load_response, recording_path, save_response};
+use crate::*;
+use std::fmt;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// A response stored by a `CacheBackend`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CachedResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Headers, in order
+    pub headers: Vec<(String, String)>,
+    /// Decompressed body
+    pub body: Vec<u8>,
+}
+
+impl CachedResponse {
+    /// Reads the whole body of `page`.
+    pub(crate) fn read(mut page: reqwest::blocking::Response) -> Result<Self> {
+        let mut body = Vec::new();
+        page.read_to_end(&mut body).map_err(|err| {
+            PbInfoError::NetworkError(format!("Could not read the response body: {}", err))
+        })?;
+        Ok(CachedResponse {
+            status: page.status().as_u16(),
+            headers: page
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_owned()))
+                })
+                .collect(),
+            body,
+        })
+    }
+
+    /// Converts the stored response into one that can be read like a fresh one.
+    pub(crate) fn to_response(&self) -> Result<reqwest::blocking::Response> {
+        let mut response = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            // The body is stored decompressed, so its encoding and length may differ
+            if name.eq_ignore_ascii_case("content-encoding")
+                || name.eq_ignore_ascii_case("content-length")
+            {
+                continue;
+            }
+            response = response.header(name, value);
+        }
+        let response = response
+            .body(self.body.clone())
+            .map_err(|err| PbInfoError::Error(format!("Invalid cached response: {}", err)))?;
+        Ok(reqwest::blocking::Response::from(response))
+    }
+}
+
+/// Storage for the responses to the GET requests of a client, keyed by url (see
+/// `PbInfoClientBuilder::cache_backend`). Implement it to keep the responses in a shared store
+/// (e.g. Redis or S3) when several processes fetch the same pages.
+///
+/// The backend decides how long responses are kept; they are served without being revalidated.
+/// Backends should not fail the request they are used for, so errors are theirs to handle
+/// (e.g. by treating them as misses).
+pub trait CacheBackend: Send + Sync {
+    /// Returns the response stored for `url`, if any.
+    fn get(&self, url: &str) -> Option<CachedResponse>;
+
+    /// Stores `response` as the response to `url`.
+    fn put(&self, url: &str, response: CachedResponse);
+}
+
+impl fmt::Debug for dyn CacheBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CacheBackend")
+    }
+}
+
+/// A `CacheBackend` that keeps up to a number of responses in memory, evicting the least
+/// recently used one first.
+#[derive(Debug)]
+pub struct MemoryCache {
+    entries: Mutex<LruCache<String, CachedResponse>>,
+}
+
+impl MemoryCache {
+    /// Creates a cache holding at most `capacity` responses.
+    pub fn new(capacity: usize) -> Self {
+        MemoryCache {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns the hit and miss counts.
+    pub fn stats(&self) -> CacheStats {
+        self.entries.lock().unwrap().stats()
+    }
+}
+
+impl CacheBackend for MemoryCache {
+    fn get(&self, url: &str) -> Option<CachedResponse> {
+        self.entries.lock().unwrap().get(&url.to_owned())
+    }
+
+    fn put(&self, url: &str, response: CachedResponse) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(url.to_owned(), response);
+    }
+}
+
+/// A `CacheBackend` that stores every response in a file of a directory, in the format used by
+/// `RecordMode`. Responses are kept until the files are deleted.
+#[derive(Debug, Clone)]
+pub struct FileCache {
+    dir: PathBuf,
+}
+
+impl FileCache {
+    /// Stores the responses in `dir`, which is created if it does not exist.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileCache { dir: dir.into() }
+    }
+}
+
+impl CacheBackend for FileCache {
+    fn get(&self, url: &str) -> Option<CachedResponse> {
+        let path = recording_path(&self.dir, "GET", url);
+        if !path.exists() {
+            return None;
+        }
+        load_response(&path).and_then(CachedResponse::read).ok()
+    }
+
+    fn put(&self, url: &str, response: CachedResponse) {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in &response.headers {
+            if let (Ok(name), Ok(value)) = (
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()),
+                reqwest::header::HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        let status = match reqwest::StatusCode::from_u16(response.status) {
+            Ok(status) => status,
+            Err(_) => return,
+        };
+        // A response that can not be stored is fetched again next time
+        let _ = save_response(
+            &recording_path(&self.dir, "GET", url),
+            url,
+            status,
+            &headers,
+            &response.body,
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+/// A rule of a robots.txt group: a path pattern, and whether it allows or disallows the paths
+/// that match it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Rule {
+    pattern: String,
+    allow: bool,
+}
+
+/// The rules of a robots.txt file that apply to one user agent.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct RobotsRules {
+    rules: Vec<Rule>,
+    /// Delay between two requests asked for by the site
+    crawl_delay: Option<Duration>,
+}
+
+/// Returns the product token of a User-Agent (e.g. `pbinfo-rs` for `pbinfo-rs/0.0.1 (...)`),
+/// lowercase, which is what the `User-agent` lines of robots.txt name.
+pub(crate) fn robots_agent(user_agent: &str) -> String {
+    user_agent
+        .split(['/', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// Returns `true` if `pattern` (which may contain `*` wildcards and end with `$`) matches the
+/// beginning of `path` (or all of it, for patterns ending with `$`).
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match path.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let parts = parts.collect::<Vec<_>>();
+    for (i, part) in parts.iter().enumerate() {
+        // The last part of an anchored pattern has to match the end of the path
+        if anchored && i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+impl RobotsRules {
+    /// Parses `text` (the contents of robots.txt), keeping the group of `agent` (a lowercase
+    /// product token), or the `*` group if there is none for it.
+    pub fn parse(text: &str, agent: &str) -> Self {
+        // (agents, rules) of every group, in order
+        let mut groups: Vec<(Vec<String>, RobotsRules)> = Vec::new();
+        let mut in_agents = false;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim().to_lowercase(), value.trim()),
+                None => continue,
+            };
+            match key.as_str() {
+                "user-agent" => {
+                    if !in_agents {
+                        groups.push((Vec::new(), RobotsRules::default()));
+                    }
+                    groups.last_mut().unwrap().0.push(value.to_lowercase());
+                    in_agents = true;
+                }
+                "allow" | "disallow" | "crawl-delay" if !groups.is_empty() => {
+                    in_agents = false;
+                    let rules = &mut groups.last_mut().unwrap().1;
+                    match key.as_str() {
+                        "crawl-delay" => {
+                            rules.crawl_delay = value
+                                .parse::<f64>()
+                                .ok()
+                                .and_then(|delay| Duration::try_from_secs_f64(delay).ok())
+                        }
+                        // An empty Disallow allows everything
+                        _ if value.is_empty() => {}
+                        allow => rules.rules.push(Rule {
+                            pattern: value.to_owned(),
+                            allow: allow == "allow",
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let find = |agent: &str| {
+            groups
+                .iter()
+                .find(|(agents, _)| agents.iter().any(|name| name == agent))
+                .map(|(_, rules)| rules.clone())
+        };
+        find(agent).or_else(|| find("*")).unwrap_or_default()
+    }
+
+    /// Returns `true` if `path` (with its query) may be requested. The longest matching rule
+    /// wins, and Allow wins over Disallow between rules of the same length.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+
+    /// Returns the delay between two requests asked for by the site, if any.
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+}
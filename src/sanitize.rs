@@ -0,0 +1,193 @@
+use crate::render::{absolute_url, remove_hidden};
+use crate::*;
+
+/// Elements kept by the default `Sanitizer`, with the attributes they may keep.
+const DEFAULT_ELEMENTS: &[(&str, &[&str])] = &[
+    ("p", &[]),
+    ("br", &[]),
+    ("ul", &[]),
+    ("ol", &[]),
+    ("li", &[]),
+    ("strong", &[]),
+    ("b", &[]),
+    ("em", &[]),
+    ("i", &[]),
+    ("code", &[]),
+    ("pre", &[]),
+    ("sup", &[]),
+    ("sub", &[]),
+    ("table", &[]),
+    ("thead", &[]),
+    ("tbody", &[]),
+    ("tr", &[]),
+    ("th", &["colspan", "rowspan"]),
+    ("td", &["colspan", "rowspan"]),
+    ("a", &["href"]),
+    ("img", &["src", "alt"]),
+];
+
+/// Cleans fragments of PbInfo html so they can be embedded in other pages.
+///
+/// Scripts, styles, embedded frames and comments are always removed, together with their
+/// contents. Other elements are kept only if they are in the allowlist, and only with the
+/// attributes allowed for them; the tags of the other elements are dropped but their text is
+/// kept. Event handlers (`onclick` etc.) are never kept, and `href`/`src` attributes are made
+/// absolute, dropping those that are not http(s) urls (e.g. `javascript:`).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Sanitizer {
+    /// Allowed elements, with their allowed attributes (all lowercase)
+    elements: Vec<(String, Vec<String>)>,
+    /// Class added to some of the allowed elements
+    classes: Vec<(String, String)>,
+}
+
+impl Default for Sanitizer {
+    /// Allows basic formatting: paragraphs, lists, emphasis, code, tables, links and images.
+    fn default() -> Self {
+        Sanitizer {
+            elements: DEFAULT_ELEMENTS
+                .iter()
+                .map(|(element, attributes)| {
+                    (
+                        element.to_string(),
+                        attributes.iter().map(|attr| attr.to_string()).collect(),
+                    )
+                })
+                .collect(),
+            classes: Vec::new(),
+        }
+    }
+}
+
+impl Sanitizer {
+    /// Creates a sanitizer with the default allowlist.
+    pub fn new() -> Self {
+        Sanitizer::default()
+    }
+
+    /// Creates a sanitizer that allows no element, keeping only the text.
+    pub fn empty() -> Self {
+        Sanitizer {
+            elements: Vec::new(),
+            classes: Vec::new(),
+        }
+    }
+
+    /// Allows `element` with `attributes`, replacing the attributes allowed for it before.
+    /// Attributes starting with `on` are ignored.
+    pub fn allow(mut self, element: &str, attributes: &[&str]) -> Self {
+        let element = element.to_lowercase();
+        let attributes = attributes
+            .iter()
+            .map(|attr| attr.to_lowercase())
+            .filter(|attr| !attr.starts_with("on"))
+            .collect();
+        match self.elements.iter_mut().find(|(name, _)| *name == element) {
+            Some((_, allowed)) => *allowed = attributes,
+            None => self.elements.push((element, attributes)),
+        }
+        self
+    }
+
+    /// Removes `element` from the allowlist.
+    pub fn deny(mut self, element: &str) -> Self {
+        let element = element.to_lowercase();
+        self.elements.retain(|(name, _)| *name != element);
+        self
+    }
+
+    /// Adds `class` to every `element` that is kept, so it can be styled without affecting the
+    /// rest of the page.
+    pub fn with_class(mut self, element: &str, class: &str) -> Self {
+        let element = element.to_lowercase();
+        self.classes.retain(|(name, _)| *name != element);
+        self.classes.push((element, class.to_owned()));
+        self
+    }
+
+    /// Returns `true` if `element` is kept.
+    pub fn allows(&self, element: &str) -> bool {
+        let element = element.to_lowercase();
+        self.elements.iter().any(|(name, _)| *name == element)
+    }
+
+    /// Sanitizes a fragment of html. The `<` and `>` that are not part of a tag (such as the
+    /// ones of an unclosed tag) are escaped, so they can not start a tag in the page the fragment
+    /// is embedded in.
+    pub fn sanitize(&self, html: &str) -> String {
+        let tag = &selectors::HTML_TAG;
+        let escape = |text: &str| text.replace('<', "&lt;").replace('>', "&gt;");
+
+        let html = remove_hidden(html);
+        let mut sanitized = String::new();
+        let mut last = 0;
+        for caps in tag.captures_iter(&html) {
+            let found = caps.get(0).unwrap();
+            sanitized.push_str(&escape(&html[last..found.start()]));
+            sanitized.push_str(&self.sanitize_tag(&caps));
+            last = found.end();
+        }
+        sanitized.push_str(&escape(&html[last..]));
+        sanitized
+    }
+
+    /// Returns the tag matched by `selectors::HTML_TAG` with only the allowed attributes, or
+    /// nothing if the element is not allowed.
+    fn sanitize_tag(&self, caps: &regex::Captures) -> String {
+        let attribute = &selectors::HTML_ATTRIBUTE;
+
+        let name = caps[2].to_lowercase();
+        let allowed = match self.elements.iter().find(|(element, _)| *element == name) {
+            Some((_, allowed)) => allowed,
+            None => return String::new(),
+        };
+        if &caps[1] == "/" {
+            return format!("</{}>", name);
+        }
+
+        let mut cleaned = format!("<{}", name);
+        if let Some((_, class)) = self.classes.iter().find(|(element, _)| *element == name) {
+            cleaned.push_str(&format!(" class=\"{}\"", class.replace('"', "&quot;")));
+        }
+        for attr in attribute.captures_iter(&caps[3]) {
+            let attr_name = attr[1].to_lowercase();
+            if attr_name.starts_with("on") || !allowed.contains(&attr_name) {
+                continue;
+            }
+            let value = attr
+                .get(2)
+                .or_else(|| attr.get(3))
+                .or_else(|| attr.get(4))
+                .map(|value| value.as_str().trim())
+                .unwrap_or_default();
+            let value = match attr_name.as_str() {
+                "href" | "src" => match absolute_url(value) {
+                    Some(url) => url,
+                    None => continue,
+                },
+                _ => value.to_owned(),
+            };
+            cleaned.push_str(&format!(
+                " {}=\"{}\"",
+                attr_name,
+                value.replace('"', "&quot;")
+            ));
+        }
+        cleaned.push('>');
+        cleaned
+    }
+}
+
+impl PbInfoProblem {
+    /// Returns the sections of the statement (see `sections`) with their html cleaned by
+    /// `sanitizer`.
+    pub fn sanitized_sections(&self, sanitizer: &Sanitizer) -> Vec<Section> {
+        self.sections()
+            .into_iter()
+            .map(|section| Section {
+                html: sanitizer.sanitize(section.html.trim()),
+                ..section
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,14 @@
+use crate::*;
+use std::collections::HashMap;
+
+/// Returns the problems whose names (partially) match `query`.
+pub fn search(query: &str) -> Result<Vec<SearchResult>> {
+    PbInfoClient::shared().search(query)
+}
+
+/// Searches for every query in `queries`, returning a map from each query to its results.
+/// Queries that only differ in case, diacritics or surrounding whitespace are searched once, and
+/// all of the requests share the connections of the shared client.
+pub fn search_many(queries: &[&str]) -> Result<HashMap<String, Vec<SearchResult>>> {
+    PbInfoClient::shared().search_many(queries)
+}
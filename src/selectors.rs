@@ -0,0 +1,473 @@
+//! The regular expressions that parse PbInfo's pages, statements and sources, each compiled
+//! once, the first time it is used.
+//!
+//! Nothing here panics: a selector whose pattern does not compile matches nothing (and
+//! `Selector::regex` returns `PbInfoError::RegexError`), and `Selector::captures_or` turns a
+//! page that does not match into a `PbInfoError::RegexError` instead of an `unwrap`. Every
+//! pattern is compiled by the tests.
+
+// Some selectors are only used by the client
+#![cfg_attr(not(feature = "blocking"), allow(dead_code))]
+
+use crate::*;
+use regex::{Captures, Match, Regex, Replacer};
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// A regular expression, compiled the first time it is used.
+pub(crate) struct Selector {
+    pattern: &'static str,
+    regex: OnceLock<std::result::Result<Regex, String>>,
+}
+
+impl Selector {
+    pub(crate) const fn new(pattern: &'static str) -> Self {
+        Selector {
+            pattern,
+            regex: OnceLock::new(),
+        }
+    }
+
+    /// Returns the compiled regular expression, or `PbInfoError::RegexError` if the pattern is
+    /// invalid.
+    pub(crate) fn regex(&self) -> Result<&Regex> {
+        self.regex
+            .get_or_init(|| Regex::new(self.pattern).map_err(|err| err.to_string()))
+            .as_ref()
+            .map_err(|err| PbInfoError::RegexError(format!("`{}`: {}", self.pattern, err)))
+    }
+
+    /// Returns the captures of the first match in `haystack`, failing with
+    /// `PbInfoError::RegexError` if there is none. `what` names what is looked for in the
+    /// error (e.g. "the task").
+    pub(crate) fn captures_or<'h>(&self, haystack: &'h str, what: &str) -> Result<Captures<'h>> {
+        self.regex()?.captures(haystack).ok_or_else(|| {
+            PbInfoError::RegexError(format!("Failed to locate {} in the HTML", what))
+        })
+    }
+
+    /// See `Regex::is_match`.
+    pub(crate) fn is_match(&self, haystack: &str) -> bool {
+        self.regex().is_ok_and(|regex| regex.is_match(haystack))
+    }
+
+    /// See `Regex::find`.
+    pub(crate) fn find<'h>(&self, haystack: &'h str) -> Option<Match<'h>> {
+        self.regex().ok()?.find(haystack)
+    }
+
+    /// See `Regex::find_iter`.
+    pub(crate) fn find_iter<'r, 'h: 'r>(
+        &'r self,
+        haystack: &'h str,
+    ) -> impl Iterator<Item = Match<'h>> + 'r {
+        self.regex()
+            .ok()
+            .into_iter()
+            .flat_map(move |regex| regex.find_iter(haystack))
+    }
+
+    /// See `Regex::captures`.
+    pub(crate) fn captures<'h>(&self, haystack: &'h str) -> Option<Captures<'h>> {
+        self.regex().ok()?.captures(haystack)
+    }
+
+    /// See `Regex::captures_iter`.
+    pub(crate) fn captures_iter<'r, 'h: 'r>(
+        &'r self,
+        haystack: &'h str,
+    ) -> impl Iterator<Item = Captures<'h>> + 'r {
+        self.regex()
+            .ok()
+            .into_iter()
+            .flat_map(move |regex| regex.captures_iter(haystack))
+    }
+
+    /// See `Regex::replace_all`. The text is left as it is if the pattern is invalid.
+    pub(crate) fn replace_all<'h>(
+        &self,
+        haystack: &'h str,
+        replacement: impl Replacer,
+    ) -> Cow<'h, str> {
+        match self.regex() {
+            Ok(regex) => regex.replace_all(haystack, replacement),
+            Err(_) => Cow::Borrowed(haystack),
+        }
+    }
+}
+
+/// Declares the selectors, and `ALL`, the list of every one of them.
+macro_rules! selectors {
+    ($($(#[$attr:meta])* $name:ident = $pattern:expr;)*) => {
+        $($(#[$attr])* pub(crate) static $name: Selector = Selector::new($pattern);)*
+
+        /// Every selector declared with `selectors!`
+        #[cfg(test)]
+        pub(crate) static ALL: &[&Selector] = &[$(&$name),*];
+    };
+}
+
+selectors! {
+    // Articles
+    /// Body of an `<article>`
+    ARTICLE = r"<article[^>]*>([\s\S]*?)</article>";
+    /// Contents of an `<h1>` heading
+    H1 = r"<h1[^>]*>([\s\S]*?)</h1>";
+    /// Text of the `<title>`, up to the site name
+    TITLE_TEXT = r"<title>([^|<]*)";
+    /// Contents of a `<pre>` block
+    PRE = r"<pre[^>]*>([\s\S]*?)</pre>";
+
+    // Code blocks
+    /// Language named by the class of a code element (`language-cpp`, `brush: c`...)
+    CODE_LANGUAGE_CLASS = r#"(?i)class=["'][^"']*\b(?:language|lang|brush)[-:\s]+\s*(c\+\+|cpp|c|python|py)\b"#;
+    /// Opening tag and body of a `<pre>` block or of an element with the `cod-sursa` class
+    CODE_BLOCK = r#"(?i)(<pre[^>]*>)([\s\S]*?)</pre>|(<(?:div|code)[^>]*class=["'][^"']*\bcod-sursa\b[^"']*["'][^>]*>)([\s\S]*?)</(?:div|code)>"#;
+    /// Any tag
+    TAG = r"<[^>]*>";
+    /// A line break
+    BR = r"(?i)<br\s*/?>";
+
+    // Export
+    /// A link to a profile, with its text
+    PROFILE_LINK = r#"(?is)<a[^>]*href=["'][^"']*/profil/[^"']*["'][^>]*>.*?</a>"#;
+    /// An email address
+    EMAIL = r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+";
+
+    // Problem pages
+    /// Contents of a `<table>`
+    TABLE = r"<table[^>]*>([\s\S]*?)</table>";
+    /// Input and output sources (file names, `tastatură` or `ecran`) of the metadata table
+    IO_SOURCES = r#"<span style="background: url\(.*?>\s*([\w\.ă]+) / ([\w\.ă]+)\s*</span>"#;
+    /// Text between two tags in the memory limit cell
+    MEMORY_PART = r">([\w -]*)<";
+    /// The task, input and output sections of a statement
+    TASK = r"<h1.*>Cerința</h1>[\s\S]*<p>(?P<task>[\s\S]+)</p>[\s\S]*<h1.*>Date de intrare</h1>[\s\S]*<p>(?P<input>[\s\S]+)</p>[\s\S]*<h1.*>Date de ieșire</h1>[\s\S]*<p>(?P<output>[\s\S]+)</p>[\s\S]*<h1.*>Restricții și precizări</h1>";
+    /// A named or numeric html entity
+    ENTITY = r"&(#x[0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);";
+    /// A line break or the end of a block element
+    TEXT_BREAK = r"(?i)<br\s*/?>|</(p|div|li|pre|tr|h\d)>";
+    /// Two or more consecutive blank lines
+    BLANK_LINES = r"\n[ \t]*\n(\s*\n)+";
+    /// Contents of a table row, which may not be closed
+    LOOSE_TABLE_ROW = r"(?i)<tr[^>]*>([\s\S]*?)(?:</tr>|$)";
+    /// Kind (`h` or `d`), attributes and contents of a table cell
+    LOOSE_TABLE_CELL = r"(?i)<t([hd])([^>]*)>([\s\S]*?)</t[hd]>";
+    /// The `colspan` of a table cell
+    COLSPAN = r#"(?i)\bcolspan=["']?(\d+)"#;
+    /// Contents of a `<table>`, in any case
+    LOOSE_TABLE = r"(?i)<table[^>]*>([\s\S]*?)</table\s*>";
+    /// A subtask stated as `pentru teste în valoare de N puncte, ...`
+    SUBTASK_POINTS = r"(?i)pentru\s+(?:(?:teste|testele)\s+(?:în\s+)?valoare\s+de\s+)?(\d+)\s*(?:de\s+)?(?:puncte|%)(?:\s+din\s+teste)?\s*[,:\-–]?\s*(.+)";
+    /// A subtask stated as `Subtask #N (P puncte): ...`
+    SUBTASK_HEADING = r"(?i)subtask(?:-ul)?\s*#?\d+\s*\(\s*(\d+)\s*(?:de\s+)?(?:puncte|p)\s*\)\s*[:\-–]?\s*(.+)";
+    /// Title of the section describing the interaction protocol
+    INTERACTION_TITLE = r"(?i)^\s*(?:protocol(?:ul)? de\s+)?interac[țţt]iun|^\s*interaction";
+    /// A statement saying that the problem is interactive
+    INTERACTION_MENTION = r"(?i)problem[ăa] interactiv[ăa]|\binteractiv[ăa]\b|\binteractor(?:ul)?\b|interactive problem";
+    /// A statement asking to flush the output
+    FLUSH_MENTION = r"(?i)flush|\bendl\b|gole[șşs]te";
+    /// Id of the problem of a url or path
+    PROBLEM_URL = r"^(?:(?:https?://)?(?:www\.)?pbinfo\.ro)?/?probleme/(\d+)(?:/[^?#]*)?(?:[?#].*)?$";
+    /// Contents of a header cell
+    TH = r"<th(?:\s[^>]*)?>([\s\S]*?)</th>";
+    /// Contents of a data cell
+    TD = r"<td(?:\s[^>]*)?>([\s\S]*?)</td>";
+    /// Source of a captcha image
+    CAPTCHA_IMAGE = r#"(?i)<img[^>]*src=["']([^"']*captcha[^"']*)["']"#;
+    /// Site key of a reCAPTCHA
+    RECAPTCHA_SITE_KEY = r#"data-sitekey=["']([^"']+)["']"#;
+    /// Token of a login challenge form
+    CHALLENGE_TOKEN = r#"name=["'](?:form_token|token|challenge_token)["'][^>]*value=["']([^"']*)["']"#;
+    /// A request for a verification code
+    VERIFICATION_CODE = r"(?i)cod(?:ul)? de (?:verificare|confirmare)";
+    /// Source of a solution, in a `<textarea>`
+    SOURCE_TEXTAREA = r#"<textarea[^>]*(?:id|name)=["']?(?:sursa|source)[^>]*>([\s\S]*?)</textarea>"#;
+    /// Source of a solution, in a `<pre>` block
+    SOURCE_PRE = r#"<pre[^>]*class=["'][^"']*(?:sursa|source|cod)[^"']*["'][^>]*>([\s\S]*?)</pre>"#;
+    /// A compilation error notice
+    COMPILATION_ERROR = r"(?i)eroare de compilare";
+    /// Messages of the compiler
+    COMPILER_MESSAGES = r#"<pre[^>]*class=["'][^"']*compil[^"']*["'][^>]*>([\s\S]*?)</pre>"#;
+    /// A submission waiting to be evaluated
+    QUEUED = r"(?i)[îi]n a[șşs]teptare|[îi]n coad[ăa]|waiting";
+    /// A submission being evaluated
+    EVALUATING = r"(?i)se evalueaz[ăa]|[îi]n curs de evaluare|evaluating";
+    /// The total score of a submission
+    TOTAL_SCORE = r"(?i)(?:punctaj|scor)\s*(?:total)?\s*:?\s*(\d+)";
+    /// Contents of a table row
+    TABLE_ROW = r"<tr[^>]*>([\s\S]*?)</tr>";
+    /// Verdict of a test
+    TEST_VERDICT = r"(?i)\b(?:ok|corect|r[ăa]spuns gre[șşs]it|gre[șşs]it|dep[ăa][șşs]it|eroare)\b";
+    /// Username of a link to a profile on PbInfo
+    PROFILE_HREF = r#"href=["'](?:https://www\.pbinfo\.ro)?/profil/([^"'/?]+)"#;
+    /// A date (`dd.mm.yyyy` or `yyyy-mm-dd`), optionally followed by a time
+    DATETIME = r"^(?:(\d{1,2})\.(\d{1,2})\.(\d{4})|(\d{4})-(\d{1,2})-(\d{1,2}))(?:[T ]+(\d{1,2}):(\d{2})(?::(\d{2}))?)?$";
+    /// Id of a link to an evaluation
+    EVALUATION_LINK_ID = r#"href=["'](?:https?://[^/"']+)?/detalii-evaluare/(\d+)"#;
+    /// Id of a link to a problem
+    PROBLEM_LINK_ID = r#"href=["'](?:https?://[^/"']+)?/probleme/(\d+)"#;
+    /// Username of a link to a profile
+    PROFILE_LINK_USERNAME = r#"href=["'](?:https?://[^/"']+)?/profil/([^"'/?]+)"#;
+    /// A date and time within text
+    DATETIME_TEXT = r"\d{1,2}\.\d{1,2}\.\d{4}(?:\s+\d{1,2}:\d{2}(?::\d{2})?)?|\d{4}-\d{2}-\d{2}[T ]\d{1,2}:\d{2}(?::\d{2})?";
+    /// Contents of a table row or of a list item
+    ROW_OR_ITEM = r"<(tr|li)[^>]*>([\s\S]*?)</(?:tr|li)>";
+    /// Id and text of a link to a problem
+    PROBLEM_LINK = r#"<a[^>]*href=["'](?:https?://[^/"']+)?/probleme/(\d+)[^"']*["'][^>]*>([\s\S]*?)</a>"#;
+    /// A score, from 0 to 100
+    SCORE = r"\b(\d{1,3})\b";
+    /// An http date (`Wed, 21 Oct 2015 07:28:00 GMT`)
+    HTTP_DATE = r"^\w{3}, (\d{2}) (\w{3}) (\d{4}) (\d{2}):(\d{2}):(\d{2}) GMT$";
+    /// The label of the languages
+    LANGUAGE_LABEL = r"(?i)limbaj";
+    /// A language and its count
+    LANGUAGE_COUNT = r"(?i)(?:^|[\s|(,;])(c\+\+|c#|c|pascal|python\s*3?|java)\s*[:|\-]?\s*(\d[\d.]*)\b";
+    /// Contents of a breadcrumb
+    BREADCRUMB = r#"<(ol|ul|nav|div)[^>]*class=["'][^"']*breadcrumb[^"']*["'][^>]*>([\s\S]*?)</(?:ol|ul|nav|div)>"#;
+    /// Text of a link
+    LINK_TEXT = r"<a[^>]*>([\s\S]*?)</a>";
+    /// The heading of the attachments
+    ATTACHMENTS_HEADING = r"(?i)<h[1-4][^>]*>\s*Ata[șş]amente\s*</h[1-4]>";
+    /// The end of the attachments
+    ATTACHMENTS_END = r"(?i)<h[1-4][\s>]|</div>|</section>";
+    /// Target and text of a link
+    LINK = r#"<a[^>]*href=["']([^"']+)["'][^>]*>([\s\S]*?)</a>"#;
+    /// A file size (`12 KB`)
+    FILE_SIZE = r"(?i)\d+(?:[.,]\d+)?\s*[KMG]?B\b";
+    /// A year
+    YEAR = r"\b(?:19|20)\d{2}\b";
+    /// A notice that the site is unavailable
+    MAINTENANCE = r"(?i)mentenan[țţt][ăa]|[îi]ntre[țţt]inere|temporar indisponibil|maintenance|[îi]n vacan[țţt][ăa]";
+    /// Contents of the `<h1>` or `<title>` of a page
+    NOTICE_TITLE = r"(?i)<(?:h1|title)[^>]*>([\s\S]*?)</(?:h1|title)>";
+    /// The charset of a `Content-Type`
+    CONTENT_TYPE_CHARSET = r#"(?i)charset\s*=\s*["']?([\w:.-]+)"#;
+    /// The charset of a `<meta>` tag
+    META_CHARSET = r#"(?i)<meta[^>]*charset\s*=\s*["']?([\w:.-]+)"#;
+    /// Name of the problem in the `<title>`
+    NAME_TITLE = r"<title>Problema ([\w]+) \| www.pbinfo.ro</title>";
+    /// Name of the problem in the `og:title` meta tag
+    NAME_OG_TITLE = r#"<meta[^>]*property=["']og:title["'][^>]*content=["']\s*Problema\s+#?\d*\s*:?\s*([\w]+)"#;
+    /// Name of the problem in the `<title>` of older pages
+    NAME_LEGACY_TITLE = r"(?i)<title>\s*(?:Problema\s+)?#?\d*\s*:?\s*([\w]+)\s*[|-]";
+    /// Problem text, from the "Cerința" heading to the end of the `<article>`
+    TEXT_ARTICLE = r"(<h1>Cerința</h1>[\s\S]*)</article>";
+    /// Problem text, from a "Cerința" heading with attributes to the end of the `<article>`
+    TEXT_ARTICLE_ATTRIBUTES = r"(<h1[^>]*>\s*Cerin[țţ]a\s*</h1>[\s\S]*)</article>";
+    /// Problem text, from the "Cerința" heading to the end of the `<main>` or `<body>`
+    TEXT_MAIN = r"(<h[12][^>]*>\s*Cerin[țţ]a\s*</h[12]>[\s\S]*?)</(?:main|body)>";
+    /// Contents of the metadata table
+    METADATA_TABLE = r#"<table class="table table-bordered">([\s\S]*?)</table>"#;
+    /// The eight cells of the metadata table
+    METADATA_ROW = r#"<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>\s*?<td[ \S]*?>([\s\S]*?)</td>"#;
+    /// Number of solutions submitted to a problem
+    STATS_ATTEMPTS = r"(?i)(?:(?:soluții|solutii|surse) trimise)\s*:?\s*([\d.,]+)";
+    /// Number of correct solutions of a problem
+    STATS_SOLVED = r"(?i)(?:(?:soluții|solutii|surse) corecte)\s*:?\s*([\d.,]+)";
+    /// Success rate of a problem
+    STATS_SUCCESS_RATE = r"(?i)(?:rat[aă] de succes|procent de succes)\s*:?\s*([\d.,]+)";
+    /// Average score of a problem
+    STATS_AVERAGE_SCORE = r"(?i)(?:scor mediu|punctaj mediu)\s*:?\s*([\d.,]+)";
+
+    // Groups
+    /// Id and text of a link to a group on PbInfo
+    GROUP_LINK = r#"<a[^>]*href=["'](?:https://www\.pbinfo\.ro)?/grupuri/(\d+)[^"']*["'][^>]*>([\s\S]*?)</a>"#;
+    /// Id and text of a link to a problem on PbInfo
+    PBINFO_PROBLEM_LINK = r#"href=["'](?:https://www\.pbinfo\.ro)?/probleme/(\d+)[^"']*["'][^>]*>([\s\S]*?)</a>"#;
+    /// A deadline (`dd.mm.yyyy hh:mm`)
+    DEADLINE = r"\d{1,2}\.\d{1,2}\.\d{4}(?:\s+\d{1,2}:\d{2})?";
+    /// Contents of a table cell
+    TABLE_CELL = r"<t[dh](?:\s[^>]*)?>([\s\S]*?)</t[dh]>";
+    /// Id of a link to a problem on PbInfo
+    PBINFO_PROBLEM_HREF = r#"href=["'](?:https://www\.pbinfo\.ro)?/probleme/(\d+)"#;
+
+    // Judging
+    /// A decimal number, with `.` or `,` as the decimal point
+    DECIMAL = r"(\d+(?:[.,]\d+)?)";
+    /// A memory size (`64 MB`)
+    SIZE = r"(?i)(\d+(?:[.,]\d+)?)\s*([KMG]?B)";
+
+    // Stress tests
+    /// The bounds of a variable (`1 ≤ n ≤ 10^5`)
+    BOUNDS = r"(-?\d+(?:\^\d+)?)\s*(?:≤|<=|&le;)\s*([A-Za-z_]\w*)(?:\[i\]|\(i\)|i)?\s*(?:≤|<=|&le;)\s*(-?[\d.]+(?:\^\d+)?)";
+
+    // Links
+    /// Path of an url on PbInfo
+    PBINFO_URL = r"(?i)^https?://(?:www\.)?pbinfo\.ro(/[^?#]*)?";
+    /// Kind and id of the path of a problem or of an article
+    PBINFO_PATH = r"^/(probleme|articole)/(\d+)(?:[/?#]|$)";
+    /// Target of a link
+    HREF = r#"(?i)<a\s[^>]*href\s*=\s*["']([^"']*)["']"#;
+
+    // Mirrors
+    /// Source of an image, between the rest of the tag
+    IMAGE_SOURCE = r#"(<img[^>]*\ssrc=")([^"]+)(")"#;
+
+    // Sources
+    /// A C or C++ comment
+    C_COMMENT = r"//[^\n]*|/\*[\s\S]*?\*/";
+    /// Reading from stdin in C or C++
+    C_READS_STDIN = r"\bcin\b|\bscanf\s*\(|\bgetchar\s*\(|\bgets\s*\(|\bstdin\b";
+    /// Writing to stdout in C or C++
+    C_WRITES_STDOUT = r"\bcout\b|\bprintf\s*\(|\bputs\s*\(|\bputchar\s*\(|\bstdout\b";
+    /// A Python comment
+    PYTHON_COMMENT = r"#[^\n]*";
+    /// Reading from stdin in Python
+    PYTHON_READS_STDIN = r"\binput\s*\(|\bsys\.stdin\b";
+    /// Writing to stdout in Python
+    PYTHON_PRINT = r"\bprint\s*\(";
+    /// A string literal
+    STRING_LITERAL = r#"["']([^"'\n]*)["']"#;
+    /// File name and mode of an `fopen` or `freopen`
+    C_FOPEN = r#"\bf(?:re)?open\s*\(\s*"([^"]*)"\s*,\s*"([rwa+]*)""#;
+    /// File name of an `ifstream`
+    CPP_IFSTREAM = r#"\bifstream\s+\w+\s*\(\s*"([^"]*)""#;
+    /// File name of an `ofstream`
+    CPP_OFSTREAM = r#"\bofstream\s+\w+\s*\(\s*"([^"]*)""#;
+    /// stdin redirected by `freopen`
+    FREOPEN_STDIN = r#"\bfreopen\s*\([^;]*\bstdin\s*\)"#;
+    /// stdout redirected by `freopen`
+    FREOPEN_STDOUT = r#"\bfreopen\s*\([^;]*\bstdout\s*\)"#;
+    /// File name and mode of an `open`
+    PYTHON_OPEN = r#"\bopen\s*\(\s*["']([^"']*)["'](?:\s*,\s*(?:mode\s*=\s*)?["']([rwa+]*)["'])?"#;
+
+    // Quizzes
+    /// Opening tag of a quiz question
+    QUIZ_QUESTION = r#"<(?:div|li|fieldset)[^>]*class=["'][^"']*\bintrebare\b[^"']*["'][^>]*>"#;
+    /// Kind of the input of a quiz answer
+    QUIZ_INPUT = r#"(?i)<input[^>]*type=["']?(radio|checkbox)["']?[^>]*>"#;
+    /// An answer marked as correct
+    QUIZ_CORRECT = r"(?i)\bcorect[ăa]?\b";
+
+    // Rendering
+    /// An html comment
+    HTML_COMMENT = r"<!--[\s\S]*?-->";
+    /// A MathML tag: whether it closes, its name and whether it is self-closing
+    MATHML_TAG = r"^<(/?)([\w:]+)[^>]*?(/?)>$";
+    /// A MathML tag or text
+    MATHML_TOKEN = r"<[^>]*>|[^<]+";
+    /// An image
+    IMG = r"(?i)<img[^>]*>";
+    /// The `alt` of an image
+    ALT = r#"(?i)\balt=["']([^"']*)["']"#;
+    /// The `src` of an image
+    SRC = r#"(?i)\bsrc=["']([^"']*)["']"#;
+    /// The formula of an image rendered by a LaTeX service
+    LATEX_URL = r"(?i)(?:latex|codecogs|mathtex|tex)[^?]*\?(.+)$";
+    /// A MathJax script and whether it is displayed
+    MATH_SCRIPT = r#"(?i)<script[^>]*type=["']math/tex(; *mode=display)?["'][^>]*>([\s\S]*?)</script>"#;
+    /// An inline formula, `\(...\)`
+    INLINE_LATEX = r"\\\(([\s\S]*?)\\\)";
+    /// A displayed formula, `\[...\]`
+    DISPLAY_LATEX = r"\\\[([\s\S]*?)\\\]";
+    /// Attributes and contents of a `<math>` element
+    MATHML = r"(?i)<math([^>]*)>([\s\S]*?)</math>";
+    /// The `alttext` of a `<math>` element
+    ALTTEXT = r#"(?i)\balttext=["']([^"']*)["']"#;
+    /// Contents of a `<sup>`
+    SUP = r"(?i)<sup>([^<]*)</sup>";
+    /// Contents of a `<sub>`
+    SUB = r"(?i)<sub>([^<]*)</sub>";
+    /// Contents of a `<pre>` block, in any case
+    MARKUP_PRE = r"(?i)<pre[^>]*>([\s\S]*?)</pre>";
+    /// Level and contents of a heading
+    MARKUP_HEADING = r"(?i)<h([1-6])[^>]*>([\s\S]*?)</h[1-6]>";
+    /// Contents of a `<strong>` or `<b>`
+    MARKUP_STRONG = r"(?i)<(?:strong|b)>([\s\S]*?)</(?:strong|b)>";
+    /// Contents of an `<em>` or `<i>`
+    MARKUP_EMPHASIS = r"(?i)<(?:em|i)>([\s\S]*?)</(?:em|i)>";
+    /// Contents of a `<code>`
+    MARKUP_CODE = r"(?i)<code[^>]*>([\s\S]*?)</code>";
+    /// Target and text of a link, in any case
+    MARKUP_LINK = r#"(?i)<a[^>]*href=["']([^"']*)["'][^>]*>([\s\S]*?)</a>"#;
+    /// Start of a list item
+    MARKUP_ITEM = r"(?i)<li[^>]*>";
+    /// A tag of a block element
+    MARKUP_BLOCK = r"(?i)</?(?:p|div|ul|ol|table|tr)[^>]*>|</li>";
+    /// Contents of a heading
+    MARKUP_HEADING_TEXT = r"(?i)<h[1-6][^>]*>([\s\S]*?)</h[1-6]>";
+    /// A displayed formula, `$$...$$`
+    DISPLAY_DOLLARS = r"\$\$([^$]+)\$\$";
+    /// An inline formula, `$...$`
+    INLINE_DOLLARS = r"\$([^$\n]+)\$";
+    /// A tag of a block element or a line break
+    MARKUP_BLOCK_OR_BREAK = r"(?i)</?(?:p|div|ul|ol|table|tr)[^>]*>|</li>|<br\s*/?>";
+
+    // Sanitizer
+    /// Whether a tag closes, its name and its attributes
+    HTML_TAG = r"<(/?)([a-zA-Z][a-zA-Z0-9]*)([^>]*)>";
+    /// Name and value of an attribute
+    HTML_ATTRIBUTE = r#"([a-zA-Z-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>"']+))"#;
+
+    // Submissions
+    /// Id of an evaluation
+    EVALUATION_ID = r"detalii-evaluare/(\d+)";
+
+    // Templates
+    /// A template placeholder, `{{...}}`
+    PLACEHOLDER = r"\{\{([^{}]*)\}\}";
+}
+
+/// Features of C++ counted by `detect_language`
+pub(crate) static CPP_FEATURES: [Selector; 7] = [
+    Selector::new(
+        r"#include\s*<(?:iostream|fstream|bits/stdc\+\+\.h|vector|algorithm|string|cstring|cmath)>",
+    ),
+    Selector::new(r"\busing\s+namespace\s+std\b"),
+    Selector::new(r"\b(?:cin|fin)\s*>>"),
+    Selector::new(r"\b(?:cout|fout)\s*<<"),
+    Selector::new(r"\bstd::"),
+    Selector::new(r"\b[io]fstream\b"),
+    Selector::new(r"\b(?:vector|pair|map|set)\s*<"),
+];
+
+/// Features of C counted by `detect_language`
+pub(crate) static C_FEATURES: [Selector; 3] = [
+    Selector::new(r"#include\s*<(?:stdio|stdlib|string|math)\.h>"),
+    Selector::new(r"\b(?:printf|scanf|fprintf|fscanf)\s*\("),
+    Selector::new(r"\bFILE\s*\*"),
+];
+
+/// Features shared by C and C++ counted by `detect_language`
+pub(crate) static C_FAMILY_FEATURES: [Selector; 3] = [
+    Selector::new(r"\bint\s+main\s*\("),
+    Selector::new(r"(?m);\s*$"),
+    Selector::new(r"[{}]"),
+];
+
+/// Features of Python counted by `detect_language`
+pub(crate) static PYTHON_FEATURES: [Selector; 6] = [
+    Selector::new(r"(?m)^\s*def\s+\w+\s*\(.*\)\s*:"),
+    Selector::new(r"\bprint\s*\("),
+    Selector::new(r"(?m)^\s*(?:import|from)\s+\w+"),
+    Selector::new(r"\bin\s+range\s*\("),
+    Selector::new(r"(?m)^\s*(?:elif|for|while|if)\b.*:\s*$"),
+    Selector::new(r"\binput\s*\(\s*\)"),
+];
+
+/// Elements whose contents are not displayed, removed by `remove_hidden`
+pub(crate) static HIDDEN_ELEMENTS: [Selector; 6] = [
+    Selector::new(r"(?i)<script[^>]*>[\s\S]*?</script\s*>"),
+    Selector::new(r"(?i)<style[^>]*>[\s\S]*?</style\s*>"),
+    Selector::new(r"(?i)<iframe[^>]*>[\s\S]*?</iframe\s*>"),
+    Selector::new(r"(?i)<object[^>]*>[\s\S]*?</object\s*>"),
+    Selector::new(r"(?i)<embed[^>]*>[\s\S]*?</embed\s*>"),
+    Selector::new(r"(?i)<noscript[^>]*>[\s\S]*?</noscript\s*>"),
+];
+
+/// Every selector, including the lists
+#[cfg(test)]
+pub(crate) fn all() -> impl Iterator<Item = &'static Selector> {
+    ALL.iter().copied().chain(
+        [
+            &CPP_FEATURES[..],
+            &C_FEATURES[..],
+            &C_FAMILY_FEATURES[..],
+            &PYTHON_FEATURES[..],
+            &HIDDEN_ELEMENTS[..],
+        ]
+        .into_iter()
+        .flatten(),
+    )
+}
@@ -0,0 +1,308 @@
+//! A small HTTP JSON API over the client, so that web frontends can use PbInfo data without
+//! embedding Rust:
+//!
+//! - `GET /problems/{id}`: the problem, in the public JSON schema (see `to_json_v1`)
+//! - `GET /problems/{id}/examples`: the examples, as `[{"input": ..., "output": ...}]`
+//! - `GET /search?q={query}`: the problems found, as `[{"id": ..., "name": ...}]`
+//!
+//! Errors are returned as `{"error": ...}` with a matching status code. Responses from PbInfo are
+//! cached in memory, and every client address can only make a limited number of requests per
+//! minute.
+
+use crate::*;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How the server runs.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ServerConfig {
+    /// Address to listen on (e.g. `127.0.0.1:8080`)
+    pub address: String,
+    /// Requests allowed per minute from one client address (0 for no limit)
+    pub requests_per_minute: u32,
+    /// Responses from PbInfo kept in memory
+    pub cache_capacity: usize,
+    /// Minimum delay between two requests to PbInfo
+    pub upstream_interval: Duration,
+    /// Requests answered at the same time; the others wait for a free worker
+    pub workers: usize,
+}
+
+impl Default for ServerConfig {
+    /// Listens on `127.0.0.1:8080`, allows 60 requests per minute from every address, caches
+    /// 1000 responses, waits 200 ms between requests to PbInfo and answers 8 requests at a time.
+    fn default() -> Self {
+        ServerConfig {
+            address: "127.0.0.1:8080".to_owned(),
+            requests_per_minute: 60,
+            cache_capacity: 1000,
+            upstream_interval: Duration::from_millis(200),
+            workers: 8,
+        }
+    }
+}
+
+/// A response of the API.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ApiResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// JSON body
+    pub body: serde_json::Value,
+    /// Seconds after which a throttled client may try again
+    pub retry_after: Option<u64>,
+}
+
+impl ApiResponse {
+    fn ok(body: serde_json::Value) -> Self {
+        ApiResponse {
+            status: 200,
+            body,
+            retry_after: None,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        ApiResponse {
+            status,
+            body: serde_json::json!({ "error": message }),
+            retry_after: None,
+        }
+    }
+
+    /// Returns the response for `err`.
+    fn from_error(err: PbInfoError) -> Self {
+        let status = match &err {
+            PbInfoError::UnknownId(_) | PbInfoError::UnknownName(..) => 404,
+            PbInfoError::RateLimited { .. }
+            | PbInfoError::CircuitOpen { .. }
+            | PbInfoError::SiteUnavailable(_) => 503,
+            PbInfoError::NetworkError(_) => 502,
+            _ => 500,
+        };
+        let mut response = ApiResponse::error(status, &format!("{:?}", err));
+        if let PbInfoError::UnknownName(_, suggestions) = &err {
+            response.body["suggestions"] = suggestions
+                .iter()
+                .map(|suggestion| {
+                    serde_json::json!({
+                        "id": suggestion.id.0,
+                        "name": suggestion.name,
+                        "score": suggestion.score,
+                    })
+                })
+                .collect();
+        }
+        let retry_after = match err {
+            PbInfoError::RateLimited { retry_after } => retry_after,
+            PbInfoError::CircuitOpen { retry_after } => Some(retry_after),
+            _ => None,
+        };
+        response.retry_after = retry_after.map(|retry_after| retry_after.as_secs().max(1));
+        response
+    }
+}
+
+/// A token bucket per client address: every address may make `capacity` requests at once,
+/// and gets them back at a steady rate over a minute.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    capacity: f64,
+    buckets: Mutex<Buckets>,
+}
+
+/// The buckets of the addresses seen recently.
+#[derive(Debug)]
+struct Buckets {
+    /// Tokens left and time of the last request of every address
+    by_address: HashMap<IpAddr, (f64, Instant)>,
+    /// When the idle buckets were last removed
+    swept: Instant,
+}
+
+/// How long a bucket takes to fill up again, after which it can be forgotten.
+const BUCKET_REFILL: Duration = Duration::from_secs(60);
+
+impl RateLimiter {
+    pub(crate) fn new(requests_per_minute: u32) -> Self {
+        RateLimiter {
+            capacity: requests_per_minute as f64,
+            buckets: Mutex::new(Buckets {
+                by_address: HashMap::new(),
+                swept: Instant::now(),
+            }),
+        }
+    }
+
+    /// Returns how many addresses have a bucket.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.buckets.lock().unwrap().by_address.len()
+    }
+
+    /// Takes a request from the bucket of `address` at `now`. Returns the time to wait until
+    /// the next request is allowed if the bucket is empty.
+    pub(crate) fn check(&self, address: IpAddr, now: Instant) -> std::result::Result<(), Duration> {
+        if self.capacity == 0.0 {
+            return Ok(());
+        }
+        let per_second = self.capacity / 60.0;
+        let mut buckets = self.buckets.lock().unwrap();
+        // A bucket left alone for a minute is full, the same as a new one, so it is dropped to
+        // keep the map from growing with every address ever seen
+        if now.saturating_duration_since(buckets.swept) >= BUCKET_REFILL {
+            buckets
+                .by_address
+                .retain(|_, (_, last)| now.saturating_duration_since(*last) < BUCKET_REFILL);
+            buckets.swept = now;
+        }
+        let (tokens, last) = buckets
+            .by_address
+            .entry(address)
+            .or_insert((self.capacity, now));
+        let refilled = now.saturating_duration_since(*last).as_secs_f64() * per_second;
+        *tokens = (*tokens + refilled).min(self.capacity);
+        *last = now;
+        if *tokens < 1.0 {
+            return Err(Duration::from_secs_f64((1.0 - *tokens) / per_second));
+        }
+        *tokens -= 1.0;
+        Ok(())
+    }
+}
+
+/// The API, answering requests with `client`.
+#[derive(Debug)]
+pub struct Api {
+    client: PbInfoClient,
+    limiter: RateLimiter,
+}
+
+impl Api {
+    /// Creates the API, limiting every client address to `requests_per_minute` (0 for no
+    /// limit). `client` should cache its responses and throttle its requests.
+    pub fn new(client: PbInfoClient, requests_per_minute: u32) -> Self {
+        Api {
+            client,
+            limiter: RateLimiter::new(requests_per_minute),
+        }
+    }
+
+    /// Answers a request for `url` (path and query) made with `method` by `address`.
+    pub fn handle(&self, method: &str, url: &str, address: IpAddr) -> ApiResponse {
+        if let Err(wait) = self.limiter.check(address, Instant::now()) {
+            let mut response = ApiResponse::error(429, "Too many requests");
+            response.retry_after = Some(wait.as_secs().max(1));
+            return response;
+        }
+        if method != "GET" {
+            return ApiResponse::error(405, "Only GET requests are supported");
+        }
+        let url = match reqwest::Url::parse("http://localhost").and_then(|base| base.join(url)) {
+            Ok(url) => url,
+            Err(_) => return ApiResponse::error(400, "Invalid url"),
+        };
+        let segments = url.path().trim_matches('/').split('/').collect::<Vec<_>>();
+
+        match segments.as_slice() {
+            ["search"] => {
+                let query = url
+                    .query_pairs()
+                    .find(|(key, _)| key == "q")
+                    .map(|(_, value)| value.into_owned())
+                    .unwrap_or_default();
+                if query.trim().is_empty() {
+                    return ApiResponse::error(400, "Missing query parameter `q`");
+                }
+                match self.client.search(&query) {
+                    Ok(results) => ApiResponse::ok(
+                        results
+                            .iter()
+                            .map(|result| {
+                                serde_json::json!({ "id": result.id.0, "name": result.name })
+                            })
+                            .collect(),
+                    ),
+                    Err(err) => ApiResponse::from_error(err),
+                }
+            }
+            ["problems", id, rest @ ..] if rest.is_empty() || rest == ["examples"] => {
+                let id = match id.parse::<usize>() {
+                    Ok(id) => ProblemId(id),
+                    Err(_) => return ApiResponse::error(400, "Invalid problem id"),
+                };
+                let problem = match self.client.fetch_problem_by_id(id) {
+                    Ok(problem) => problem,
+                    Err(err) => return ApiResponse::from_error(err),
+                };
+                match rest.is_empty() {
+                    true => ApiResponse::ok(problem.to_json_v1()),
+                    false => ApiResponse::ok(
+                        problem
+                            .examples()
+                            .iter()
+                            .map(|example| {
+                                serde_json::json!({
+                                    "input": example.input,
+                                    "output": example.output,
+                                })
+                            })
+                            .collect(),
+                    ),
+                }
+            }
+            _ => ApiResponse::error(404, "Not found"),
+        }
+    }
+
+    /// Answers the requests of `server` until it is closed, on `workers` threads.
+    pub fn serve(&self, server: &tiny_http::Server, workers: usize) {
+        std::thread::scope(|scope| {
+            for _ in 0..workers.max(1) {
+                scope.spawn(|| {
+                    for request in server.incoming_requests() {
+                        self.respond(request);
+                    }
+                });
+            }
+        });
+    }
+
+    /// Answers `request`.
+    fn respond(&self, request: tiny_http::Request) {
+        let address = request
+            .remote_addr()
+            .map(|address| address.ip())
+            .unwrap_or(IpAddr::from([0, 0, 0, 0]));
+        let response = self.handle(request.method().as_str(), request.url(), address);
+
+        let mut http_response = tiny_http::Response::from_string(response.body.to_string())
+            .with_status_code(response.status)
+            .with_header(
+                tiny_http::Header::from_bytes("Content-Type", "application/json; charset=utf-8")
+                    .unwrap(),
+            );
+        if let Some(retry_after) = response.retry_after {
+            http_response.add_header(
+                tiny_http::Header::from_bytes("Retry-After", retry_after.to_string()).unwrap(),
+            );
+        }
+        // The client may have gone away; there is nobody to report it to
+        let _ = request.respond(http_response);
+    }
+}
+
+/// Runs the API as configured by `config`, until the process is stopped.
+pub fn run(config: &ServerConfig) -> Result<()> {
+    let client = PbInfoClient::builder()
+        .cache_backend(MemoryCache::new(config.cache_capacity))
+        .min_request_interval(config.upstream_interval)
+        .build()?;
+    let server = tiny_http::Server::http(&config.address).map_err(|err| {
+        PbInfoError::IOError(format!("Could not listen on `{}`: {}", config.address, err))
+    })?;
+    Api::new(client, config.requests_per_minute).serve(&server, config.workers);
+    Ok(())
+}
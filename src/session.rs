@@ -0,0 +1,121 @@
+use crate::*;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// A cookie jar that can be written to and read from disk, so a login session outlives the
+/// process that created it. Only the name and value of each cookie are kept, grouped by the
+/// host that set them.
+#[derive(Debug, Default)]
+pub(crate) struct SessionJar {
+    /// host -> cookie name -> cookie value
+    cookies: RwLock<BTreeMap<String, BTreeMap<String, String>>>,
+}
+
+impl SessionJar {
+    /// Serializes the cookies, one `host\tname\tvalue` line per cookie.
+    pub fn to_text(&self) -> String {
+        let cookies = self.cookies.read().unwrap();
+        let mut text = String::new();
+        for (host, values) in cookies.iter() {
+            for (name, value) in values.iter() {
+                text.push_str(&format!("{}\t{}\t{}\n", host, name, value));
+            }
+        }
+        text
+    }
+
+    /// Replaces the cookies with the ones serialized in `text` by `to_text`.
+    pub fn load_text(&self, text: &str) -> Result<()> {
+        let mut loaded: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let mut parts = line.splitn(3, '\t');
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(host), Some(name), Some(value)) => {
+                    loaded
+                        .entry(host.to_owned())
+                        .or_default()
+                        .insert(name.to_owned(), value.to_owned());
+                }
+                _ => {
+                    return Err(PbInfoError::IOError(format!(
+                        "Invalid line in the session file: `{}`",
+                        line
+                    )))
+                }
+            }
+        }
+        *self.cookies.write().unwrap() = loaded;
+        Ok(())
+    }
+
+    /// Removes every cookie.
+    pub fn clear(&self) {
+        self.cookies.write().unwrap().clear();
+    }
+
+    /// Returns `true` if there are no cookies.
+    pub fn is_empty(&self) -> bool {
+        self.cookies
+            .read()
+            .unwrap()
+            .values()
+            .all(|values| values.is_empty())
+    }
+}
+
+impl reqwest::cookie::CookieStore for SessionJar {
+    fn set_cookies(
+        &self,
+        cookie_headers: &mut dyn Iterator<Item = &reqwest::header::HeaderValue>,
+        url: &reqwest::Url,
+    ) {
+        let host = match url.host_str() {
+            Some(host) => host.to_owned(),
+            None => return,
+        };
+
+        let mut cookies = self.cookies.write().unwrap();
+        let values = cookies.entry(host).or_default();
+        for header in cookie_headers {
+            let header = match header.to_str() {
+                Ok(header) => header,
+                Err(_) => continue,
+            };
+            let mut attributes = header.split(';');
+            let (name, value) = match attributes.next().and_then(|pair| pair.split_once('=')) {
+                Some((name, value)) => (name.trim(), value.trim()),
+                None => continue,
+            };
+
+            // Servers delete cookies by expiring them immediately
+            let deleted = value.is_empty()
+                || value == "deleted"
+                || attributes.any(|attribute| attribute.trim().eq_ignore_ascii_case("max-age=0"));
+            if deleted {
+                values.remove(name);
+            } else {
+                values.insert(name.to_owned(), value.to_owned());
+            }
+        }
+    }
+
+    fn cookies(&self, url: &reqwest::Url) -> Option<reqwest::header::HeaderValue> {
+        let host = url.host_str()?;
+        let cookies = self.cookies.read().unwrap();
+
+        let header = cookies
+            .iter()
+            .filter(|(domain, _)| {
+                host == domain.as_str() || host.ends_with(&format!(".{}", domain))
+            })
+            .flat_map(|(_, values)| values.iter())
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        match header.is_empty() {
+            true => None,
+            false => reqwest::header::HeaderValue::from_str(&header).ok(),
+        }
+    }
+}
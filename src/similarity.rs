@@ -0,0 +1,79 @@
+use crate::*;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+/// Number of consecutive words in a shingle.
+const SHINGLE_SIZE: usize = 3;
+
+/// Returns the words of the statement of `problem`: plain text, lowercase, without diacritics
+/// or punctuation, so that formatting changes do not matter.
+fn statement_words(problem: &PbInfoProblem) -> Vec<String> {
+    normalize_text(&html_to_text(&problem.problem_text))
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_owned())
+        .collect()
+}
+
+/// Returns the shingles (runs of `SHINGLE_SIZE` consecutive words) of `words`. Texts shorter
+/// than a shingle are a single shingle.
+fn shingles(words: &[String]) -> HashSet<String> {
+    if words.len() < SHINGLE_SIZE {
+        return HashSet::from([words.join(" ")]);
+    }
+    words
+        .windows(SHINGLE_SIZE)
+        .map(|window| window.join(" "))
+        .collect()
+}
+
+/// Returns the Jaccard similarity of two sets of shingles.
+fn jaccard(ours: &HashSet<String>, theirs: &HashSet<String>) -> f32 {
+    match ours.union(theirs).count() {
+        0 => 1.0,
+        union => ours.intersection(theirs).count() as f32 / union as f32,
+    }
+}
+
+impl PbInfoProblem {
+    /// Returns the SHA-256 (in hex) of the normalized statement text. Problems reposted under
+    /// another name, grade or contest have the same hash as long as their statements match
+    /// word for word.
+    pub fn content_hash(&self) -> String {
+        let hash = Sha256::digest(statement_words(self).join(" ").as_bytes());
+        hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Returns how similar the statements of the two problems are, from 0 (nothing in common)
+    /// to 1 (same text): the Jaccard similarity of their sets of three-word shingles.
+    pub fn similar_to(&self, other: &PbInfoProblem) -> f32 {
+        jaccard(
+            &shingles(&statement_words(self)),
+            &shingles(&statement_words(other)),
+        )
+    }
+}
+
+/// Returns the pairs of problems whose statements are at least `threshold` similar (see
+/// `PbInfoProblem::similar_to`), together with their similarity, most similar first.
+pub fn find_duplicates(
+    problems: &[PbInfoProblem],
+    threshold: f32,
+) -> Vec<(ProblemId, ProblemId, f32)> {
+    let shingled = problems
+        .iter()
+        .map(|problem| (problem.id, shingles(&statement_words(problem))))
+        .collect::<Vec<_>>();
+
+    let mut duplicates = Vec::new();
+    for (i, (first, ours)) in shingled.iter().enumerate() {
+        for (second, theirs) in &shingled[i + 1..] {
+            let similarity = jaccard(ours, theirs);
+            if similarity >= threshold {
+                duplicates.push((*first, *second, similarity));
+            }
+        }
+    }
+    duplicates.sort_by(|a, b| b.2.total_cmp(&a.2));
+    duplicates
+}
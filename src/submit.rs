@@ -0,0 +1,131 @@
+use crate::judge::Language;
+use crate::*;
+use sha2::{Digest, Sha256};
+
+/// Returns the SHA-256 (in hex) of `source` with normalized line endings and without trailing
+/// whitespace, so that a source re-read from PbInfo has the same digest as the one submitted.
+pub fn source_digest(source: &str) -> String {
+    let normalized = source
+        .replace("\r\n", "\n")
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let hash = Sha256::digest(normalized.trim_end().as_bytes());
+    hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The result of `PbInfoClient::submit_solution_once`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Submitted {
+    /// Id of the submission (evaluation)
+    pub id: usize,
+    /// Whether an identical source had already been submitted, in which case `id` is the id of
+    /// that submission and nothing was sent
+    pub duplicate: bool,
+}
+
+/// Number of recent submissions compared by `PbInfoClient::submit_solution_once`.
+pub const DEDUPE_LOOKBACK: usize = 5;
+
+impl PbInfoClient {
+    /// Submits `source` as a solution to the problem `id`. The client has to be logged in.
+    /// Returns the id of the submission, which can be followed with `watch_submission`.
+    ///
+    /// A request that times out may still have been received by PbInfo, so scripts that retry
+    /// should use `submit_solution_once` instead.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, source), err(Debug))
+    )]
+    pub fn submit_solution(
+        &self,
+        id: ProblemId,
+        language: Language,
+        source: &str,
+    ) -> Result<usize> {
+        if !self.has_session() {
+            return Err(PbInfoError::LoginError(
+                "Submitting a solution is only possible after logging in".to_owned(),
+            ));
+        }
+        let url = self.url("/ajx-module/php-solutie.php");
+        let form = [
+            ("id", id.to_string()),
+            ("limbaj", language.extension().to_owned()),
+            ("sursa", source.to_owned()),
+        ];
+        let page = self.send(self.http().post(url).form(&form))?;
+        if !page.status().is_success() {
+            return Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to submit the solution. HTTP status code {}",
+                page.status()
+            )));
+        }
+
+        let text = self.read_text(page)?;
+        let regex = &selectors::EVALUATION_ID;
+        match regex.captures(&text) {
+            Some(caps) => caps[1].parse().map_err(|_| {
+                PbInfoError::RegexError(format!("Invalid evaluation id `{}`", &caps[1]))
+            }),
+            None => {
+                check_site_available(&text)?;
+                Err(PbInfoError::Error(format!(
+                    "The solution was not accepted: {}",
+                    html_to_text(&text).trim()
+                )))
+            }
+        }
+    }
+
+    /// Returns the id of one of the last `DEDUPE_LOOKBACK` submissions of `username` to the
+    /// problem `id` whose source is `source` (see `source_digest`), if there is one.
+    pub fn find_duplicate_submission(
+        &self,
+        username: &str,
+        id: ProblemId,
+        source: &str,
+    ) -> Result<Option<usize>> {
+        let digest = source_digest(source);
+        let recent = self.fetch_submissions(&SubmissionFilter {
+            username: Some(username.to_owned()),
+            problem: Some(id),
+            max_entries: Some(DEDUPE_LOOKBACK),
+            ..SubmissionFilter::default()
+        })?;
+        for submission in recent {
+            if source_digest(&self.fetch_submission_source(submission.id)?) == digest {
+                return Ok(Some(submission.id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Submits `source` like `submit_solution`, unless one of the recent submissions of
+    /// `username` (the logged in user) to the problem has the same source: then its id is
+    /// returned and nothing is submitted. Retrying after a timeout is then safe, and does not
+    /// count as another attempt.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, source), err(Debug))
+    )]
+    pub fn submit_solution_once(
+        &self,
+        username: &str,
+        id: ProblemId,
+        language: Language,
+        source: &str,
+    ) -> Result<Submitted> {
+        if let Some(existing) = self.find_duplicate_submission(username, id, source)? {
+            return Ok(Submitted {
+                id: existing,
+                duplicate: true,
+            });
+        }
+        Ok(Submitted {
+            id: self.submit_solution(id, language, source)?,
+            duplicate: false,
+        })
+    }
+}
@@ -0,0 +1,183 @@
+//! Snapshots of the catalog, as written by `export_catalog_jsonl` after every sync, and the
+//! differences between two of them: the problems that were added, removed or modified, with the
+//! fields that changed. Mirror maintainers can turn them into a changelog with
+//! `CatalogDiff::to_markdown`.
+
+use crate::*;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::path::Path;
+
+/// The problems of a catalog snapshot, by id, as the JSON objects of `problem_to_json`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Catalog {
+    /// The problems, by id
+    pub problems: BTreeMap<ProblemId, serde_json::Value>,
+}
+
+impl Catalog {
+    /// Creates a snapshot of `problems`, as `export_catalog_jsonl` would write it.
+    pub fn from_problems<'a>(
+        problems: impl IntoIterator<Item = &'a PbInfoProblem>,
+        options: &ExportOptions,
+    ) -> Self {
+        Catalog {
+            problems: problems
+                .into_iter()
+                .map(|problem| (problem.id, problem_to_json(problem, options)))
+                .collect(),
+        }
+    }
+
+    /// Reads a snapshot written by `export_catalog_jsonl`. Empty lines are skipped; if a problem
+    /// appears twice, the last line wins.
+    pub fn read_jsonl(reader: impl BufRead) -> Result<Self> {
+        let mut problems = BTreeMap::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line.map_err(|err| {
+                PbInfoError::IOError(format!("Could not read the catalog: {}", err))
+            })?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let problem: serde_json::Value = serde_json::from_str(&line).map_err(|err| {
+                PbInfoError::JSONError(format!("Line {} of the catalog: {}", number + 1, err))
+            })?;
+            let id = problem["id"].as_u64().ok_or_else(|| {
+                PbInfoError::JSONError(format!("Line {} of the catalog has no id", number + 1))
+            })?;
+            problems.insert(ProblemId(id as usize), problem);
+        }
+        Ok(Catalog { problems })
+    }
+
+    /// Reads the snapshot `path` (see `read_jsonl`).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).map_err(|err| {
+            PbInfoError::IOError(format!("Could not open `{}`: {}", path.display(), err))
+        })?;
+        Catalog::read_jsonl(std::io::BufReader::new(file))
+    }
+}
+
+/// A field of a problem that changed between two snapshots. A field that is missing from one of
+/// them is `null` there.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FieldChange {
+    /// Name of the field (e.g. `time_limit`)
+    pub field: String,
+    /// Value in the old snapshot
+    pub old: serde_json::Value,
+    /// Value in the new snapshot
+    pub new: serde_json::Value,
+}
+
+/// A problem that is in both snapshots, but differs between them.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProblemChange {
+    /// Id of the problem
+    pub id: ProblemId,
+    /// The changed fields, ordered by name
+    pub changes: Vec<FieldChange>,
+}
+
+/// The differences between two catalog snapshots, ordered by problem id.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CatalogDiff {
+    /// Problems that are only in the new snapshot
+    pub added: Vec<ProblemId>,
+    /// Problems that are only in the old snapshot
+    pub removed: Vec<ProblemId>,
+    /// Problems that are in both, but differ
+    pub modified: Vec<ProblemChange>,
+    /// Names of the problems, from the new snapshot (or the old one, for removed problems)
+    names: BTreeMap<ProblemId, String>,
+}
+
+impl CatalogDiff {
+    /// Returns `true` if the snapshots have the same problems.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Writes the differences as a Markdown changelog, with a section for the added, removed and
+    /// modified problems (sections without problems are left out).
+    pub fn to_markdown(&self) -> String {
+        let title = |id: &ProblemId| match self.names.get(id) {
+            Some(name) => format!("#{} {}", id, name),
+            None => format!("#{}", id),
+        };
+        let mut output = String::new();
+        for (heading, ids) in [("Added", &self.added), ("Removed", &self.removed)] {
+            if !ids.is_empty() {
+                output.push_str(&format!("## {}\n\n", heading));
+                for id in ids {
+                    output.push_str(&format!("- {}\n", title(id)));
+                }
+                output.push('\n');
+            }
+        }
+        if !self.modified.is_empty() {
+            output.push_str("## Modified\n\n");
+            for problem in &self.modified {
+                output.push_str(&format!("- {}\n", title(&problem.id)));
+                for change in &problem.changes {
+                    output.push_str(&format!(
+                        "  - `{}`: {} → {}\n",
+                        change.field, change.old, change.new
+                    ));
+                }
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// Compares two catalog snapshots, field by field.
+pub fn diff(old: &Catalog, new: &Catalog) -> CatalogDiff {
+    let mut diff = CatalogDiff::default();
+    let name = |problem: &serde_json::Value| problem["name"].as_str().map(str::to_owned);
+
+    for (id, problem) in &old.problems {
+        if !new.problems.contains_key(id) {
+            diff.removed.push(*id);
+            diff.names.extend(name(problem).map(|name| (*id, name)));
+        }
+    }
+    for (id, problem) in &new.problems {
+        diff.names.extend(name(problem).map(|name| (*id, name)));
+        let old = match old.problems.get(id) {
+            Some(old) => old,
+            None => {
+                diff.added.push(*id);
+                continue;
+            }
+        };
+        let fields = |value: &serde_json::Value| {
+            value
+                .as_object()
+                .map(|object| object.keys().cloned().collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+        let mut keys = fields(old);
+        keys.extend(fields(problem));
+        keys.sort();
+        keys.dedup();
+
+        let changes = keys
+            .into_iter()
+            .filter(|key| old[key] != problem[key])
+            .map(|key| FieldChange {
+                old: old[&key].clone(),
+                new: problem[&key].clone(),
+                field: key,
+            })
+            .collect::<Vec<_>>();
+        if !changes.is_empty() {
+            diff.modified.push(ProblemChange { id: *id, changes });
+        }
+    }
+    diff
+}
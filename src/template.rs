@@ -0,0 +1,323 @@
+//! Solution templates: skeleton source files generated from a problem, with placeholders such
+//! as `{{problem.name}}`, `{{input_file}}` or `{{time_limit_ms}}` and conditional blocks
+//! (`{{#if uses_files}}...{{else}}...{{/if}}`, `{{#unless ...}}`).
+
+use crate::codegen::{indent, io_boilerplate};
+use crate::judge::{Language, Limits};
+use crate::*;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// A part of a parsed template.
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Node {
+    /// Text copied as is
+    Text(String),
+    /// A placeholder, replaced by the value of the variable
+    Variable(String),
+    /// A block rendered if the variable is set (or unset, for `#unless`), with an optional
+    /// `{{else}}` block
+    Condition {
+        variable: String,
+        negated: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+/// A parsed template.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+/// Returns the error for a malformed template.
+fn syntax_error(message: &str) -> PbInfoError {
+    PbInfoError::Error(format!("Invalid template: {}", message))
+}
+
+/// Parses the nodes of `tokens` up to the end of the current block, returning them and the tag
+/// that ended the block (`else`, `/if`, `/unless` or `None` at the end of the template).
+fn parse_nodes<'a>(
+    tokens: &mut impl Iterator<Item = (&'a str, bool)>,
+) -> Result<(Vec<Node>, Option<String>)> {
+    let mut nodes = Vec::new();
+    while let Some((token, is_tag)) = tokens.next() {
+        if !is_tag {
+            nodes.push(Node::Text(token.to_owned()));
+            continue;
+        }
+
+        let tag = token.trim();
+        if tag == "else" || tag == "/if" || tag == "/unless" {
+            return Ok((nodes, Some(tag.to_owned())));
+        }
+        let (negated, variable) = match tag.split_once(char::is_whitespace) {
+            Some(("#if", variable)) => (false, variable.trim()),
+            Some(("#unless", variable)) => (true, variable.trim()),
+            _ if tag.starts_with(['#', '/']) => {
+                return Err(syntax_error(&format!("unknown tag `{{{{{}}}}}`", tag)))
+            }
+            _ => {
+                nodes.push(Node::Variable(tag.to_owned()));
+                continue;
+            }
+        };
+
+        let closing = if negated { "/unless" } else { "/if" };
+        let (then, end) = parse_nodes(tokens)?;
+        let (otherwise, end) = match end.as_deref() {
+            Some("else") => parse_nodes(tokens)?,
+            _ => (Vec::new(), end),
+        };
+        if end.as_deref() != Some(closing) {
+            return Err(syntax_error(&format!("`{}` is not closed", tag)));
+        }
+        nodes.push(Node::Condition {
+            variable: variable.to_owned(),
+            negated,
+            then,
+            otherwise,
+        });
+    }
+    Ok((nodes, None))
+}
+
+impl Template {
+    /// Parses `source`, checking that every block is closed.
+    pub fn new(source: &str) -> Result<Self> {
+        let regex = &selectors::PLACEHOLDER;
+
+        let mut tokens = Vec::new();
+        let mut last = 0;
+        for caps in regex.captures_iter(source) {
+            let whole = caps.get(0).unwrap();
+            tokens.push((&source[last..whole.start()], false));
+            tokens.push((caps.get(1).unwrap().as_str(), true));
+            last = whole.end();
+        }
+        tokens.push((&source[last..], false));
+
+        match parse_nodes(&mut tokens.into_iter())? {
+            (nodes, None) => Ok(Template { nodes }),
+            (_, Some(tag)) => Err(syntax_error(&format!("unexpected `{{{{{}}}}}`", tag))),
+        }
+    }
+
+    /// Renders the template, replacing the placeholders with the values in `context`. A
+    /// variable is set if it has a non-empty value. Unknown variables are an error, so typos in
+    /// user templates are noticed.
+    pub fn render(&self, context: &HashMap<String, String>) -> Result<String> {
+        let mut output = String::new();
+        render_nodes(&self.nodes, context, &mut output)?;
+        Ok(output)
+    }
+}
+
+/// Appends the rendering of `nodes` to `output`.
+fn render_nodes(
+    nodes: &[Node],
+    context: &HashMap<String, String>,
+    output: &mut String,
+) -> Result<()> {
+    let value = |variable: &str| {
+        context
+            .get(variable)
+            .ok_or_else(|| PbInfoError::Error(format!("Unknown template variable `{}`", variable)))
+    };
+
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Variable(variable) => output.push_str(value(variable)?),
+            Node::Condition {
+                variable,
+                negated,
+                then,
+                otherwise,
+            } => {
+                let set = !value(variable)?.is_empty();
+                match set != *negated {
+                    true => render_nodes(then, context, output)?,
+                    false => render_nodes(otherwise, context, output)?,
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the variables available to templates for `problem`:
+///
+/// - `problem.id`, `problem.name`, `problem.grade`, `problem.author`, `problem.source`,
+///   `problem.url`
+/// - `input_file`, `output_file` (empty for stdin/stdout) and `uses_files`
+/// - `io_header`, `io_setup`, `io_cleanup`, `io_input` and `io_output` (see
+///   `codegen::io_boilerplate`), with the setup and cleanup indented for the body of `main`
+///   in C and C++
+/// - `time_limit_ms`, `memory_limit_kb`, `stack_limit_kb` (empty if unknown)
+/// - `language` (the file extension, e.g. `cpp`)
+pub fn template_context(problem: &PbInfoProblem, language: Language) -> HashMap<String, String> {
+    let file = |source: &IOSource| match source {
+        IOSource::File(name) => name.clone(),
+        IOSource::Std => String::new(),
+    };
+    let limits = Limits::from_problem(problem);
+    let kilobytes = |bytes: Option<u64>| bytes.map(|bytes| (bytes / 1024).to_string());
+    let io = io_boilerplate(problem, language);
+    let body_indent = match language {
+        Language::C | Language::Cpp => "    ",
+        Language::Python => "",
+    };
+
+    let variables = [
+        ("problem.id", problem.id.to_string()),
+        ("problem.name", problem.name.to_string()),
+        ("problem.grade", problem.grade.to_string()),
+        ("problem.author", problem.author.clone().unwrap_or_default()),
+        ("problem.source", problem.source.clone().unwrap_or_default()),
+        (
+            "problem.url",
+            format!("{}/probleme/{}", DEFAULT_BASE_URL, problem.id),
+        ),
+        ("input_file", file(&problem.input_source)),
+        ("output_file", file(&problem.output_source)),
+        (
+            "uses_files",
+            match problem.input_source {
+                IOSource::File(_) => "true".to_owned(),
+                IOSource::Std => String::new(),
+            },
+        ),
+        (
+            "time_limit_ms",
+            limits
+                .time
+                .map(|time| time.as_millis().to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "memory_limit_kb",
+            kilobytes(limits.memory).unwrap_or_default(),
+        ),
+        (
+            "stack_limit_kb",
+            kilobytes(limits.stack).unwrap_or_default(),
+        ),
+        ("language", language.extension().to_owned()),
+        ("io_header", io.header),
+        ("io_setup", indent(&io.setup, body_indent)),
+        ("io_cleanup", indent(&io.cleanup, body_indent)),
+        ("io_input", io.input),
+        ("io_output", io.output),
+    ];
+    variables
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value))
+        .collect()
+}
+
+/// The templates used for each language. Starts with simple built-in templates, which can be
+/// replaced by the user's own.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Templates {
+    templates: BTreeMap<&'static str, Template>,
+}
+
+/// Built-in C++ template.
+const CPP_TEMPLATE: &str = "// {{problem.name}} - {{problem.url}}
+#include <bits/stdc++.h>
+using namespace std;
+
+int main() {
+{{io_setup}}
+    return 0;
+}
+";
+
+/// Built-in C template.
+const C_TEMPLATE: &str = "// {{problem.name}} - {{problem.url}}
+#include <stdio.h>
+
+int main(void) {
+{{io_setup}}{{#if io_cleanup}}
+{{io_cleanup}}{{/if}}    return 0;
+}
+";
+
+/// Built-in Python template.
+const PYTHON_TEMPLATE: &str = "# {{problem.name}} - {{problem.url}}
+{{io_header}}
+{{io_setup}}{{#if io_cleanup}}
+{{io_cleanup}}{{/if}}";
+
+impl Default for Templates {
+    fn default() -> Self {
+        let templates = [
+            (Language::Cpp, CPP_TEMPLATE),
+            (Language::C, C_TEMPLATE),
+            (Language::Python, PYTHON_TEMPLATE),
+        ]
+        .into_iter()
+        .map(|(language, source)| (language.extension(), Template::new(source).unwrap()))
+        .collect();
+        Templates { templates }
+    }
+}
+
+impl Templates {
+    /// Returns the built-in templates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the template of `language`.
+    pub fn set(&mut self, language: Language, template: Template) {
+        self.templates.insert(language.extension(), template);
+    }
+
+    /// Replaces the built-in templates with the files `template.cpp`, `template.c` and
+    /// `template.py` from `dir`, for those that exist.
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        for language in [Language::Cpp, Language::C, Language::Python] {
+            let path = dir.join(format!("template.{}", language.extension()));
+            if !path.exists() {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).map_err(|err| {
+                PbInfoError::IOError(format!("Could not read `{}`: {}", path.display(), err))
+            })?;
+            self.set(language, Template::new(&source)?);
+        }
+        Ok(())
+    }
+
+    /// Renders the template of `language` for `problem`.
+    pub fn render(&self, problem: &PbInfoProblem, language: Language) -> Result<String> {
+        self.templates[language.extension()].render(&template_context(problem, language))
+    }
+}
+
+/// Writes a solution skeleton for `problem` to `dir/{name}.{extension}`, unless that file
+/// already exists. Returns the path of the solution.
+pub fn scaffold(
+    problem: &PbInfoProblem,
+    language: Language,
+    templates: &Templates,
+    dir: impl AsRef<Path>,
+) -> Result<PathBuf> {
+    let dir = dir.as_ref();
+    let path = dir.join(format!("{}.{}", problem.name, language.extension()));
+    if path.exists() {
+        return Ok(path);
+    }
+
+    let source = templates.render(problem, language)?;
+    std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&path, source))
+        .map_err(|err| {
+            PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+        })?;
+    Ok(path)
+}
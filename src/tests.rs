@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::*;
     #[test]
@@ -28,11 +29,11 @@ mod tests {
 					</td>
 		<td cass="center""#;
         assert_eq!(
-            extract_input_source(&metadata_file),
+            extract_input_source(metadata_file),
             Ok(IOSource::File(String::from("numere8.in")))
         );
         assert_eq!(
-            extract_output_source(&metadata_file),
+            extract_output_source(metadata_file),
             Ok(IOSource::File(String::from("numere8.out")))
         );
 
@@ -44,8 +45,8 @@ mod tests {
 			0.1 secunde
 		</td>
 		<td>"#;
-        assert_eq!(extract_input_source(&metadata_std), Ok(IOSource::Std));
-        assert_eq!(extract_output_source(&metadata_std), Ok(IOSource::Std));
+        assert_eq!(extract_input_source(metadata_std), Ok(IOSource::Std));
+        assert_eq!(extract_output_source(metadata_std), Ok(IOSource::Std));
     }
 
     const IO_TEXT_1: &str = r#"<table class="table table-bordered">
@@ -185,4 +186,4204 @@ mod tests {
         assert_eq!(extract_difficulty(IO_TEXT_1), Ok(Some(Difficulty::Contest)));
         assert_eq!(extract_difficulty(IO_TEXT_2), Ok(Some(Difficulty::Easy)));
     }
+
+    #[test]
+    fn test_extract_page_parts() {
+        let page = r#"<head><title>Problema Numere8 | www.pbinfo.ro</title></head>
+<article><h1>Cerința</h1><p>Se dă un număr n.</p></article>
+<table class="table table-bordered"><tr><th>Clasa</th></tr></table>"#;
+        assert_eq!(extract_name(page), Ok("numere8".to_owned()));
+        assert_eq!(
+            extract_problem_text(page),
+            Ok("<h1>Cerința</h1><p>Se dă un număr n.</p>".to_owned())
+        );
+        assert_eq!(
+            extract_metadata(page),
+            Ok("<tr><th>Clasa</th></tr>".to_owned())
+        );
+        assert!(extract_name("<title>Not found</title>").is_err());
+    }
+
+    const STATEMENT: &str = r#"<h1>Cerința</h1><p>Se dă un număr n.</p>
+<h1>Date de intrare</h1><p>Fișierul conține n.</p>
+<h1>Date de ieșire</h1><p>Fișierul va conține n.</p>
+<h1>Restricții și precizări</h1><ul><li>1 ≤ n ≤ 100</li></ul>
+<h1>Exemplu</h1><pre>5</pre>"#;
+
+    #[test]
+    fn test_extract_sections() {
+        let sections = extract_sections(STATEMENT);
+        assert_eq!(sections.len(), 5);
+        assert_eq!(
+            sections[0],
+            ("Cerința".to_owned(), "<p>Se dă un număr n.</p>".to_owned())
+        );
+        assert_eq!(
+            sections[4],
+            ("Exemplu".to_owned(), "<pre>5</pre>".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_diff_statements() {
+        let old = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_2.to_owned(),
+        )
+        .unwrap();
+        assert!(diff_statements(&old, &old).is_empty());
+
+        let mut new = old.clone();
+        new.problem_text = new.problem_text.replace("100", "1000");
+        new.time_limit = Some("0.2 secunde".to_owned());
+        let diff = diff_statements(&old, &new);
+        assert_eq!(diff.changes.len(), 2);
+        assert!(diff.changed(StatementSection::Restrictions));
+        assert!(diff.changed(StatementSection::TimeLimit));
+        assert!(!diff.changed(StatementSection::Task));
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        assert_eq!(
+            html_to_text("<p>Se dă <strong>n</strong> &lt; 10.</p><p>Afișați&nbsp;suma.</p>"),
+            "Se dă n < 10.\nAfișați suma."
+        );
+        assert_eq!(decode_entities("1 &le; n &#8804; 10&#x21;"), "1 ≤ n ≤ 10!");
+        assert_eq!(decode_entities("&unknown;"), "&unknown;");
+    }
+
+    #[test]
+    fn test_sections() {
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            format!(
+                "{}\n<h1>Interacțiune</h1><p>Comunicați prin stdout.</p>",
+                STATEMENT
+            ),
+            IO_TEXT_2.to_owned(),
+        )
+        .unwrap();
+
+        let sections = problem.sections();
+        assert_eq!(sections.len(), 6);
+        assert_eq!(sections[3].title, "Restricții și precizări");
+        assert_eq!(sections[3].text, "1 ≤ n ≤ 100");
+        assert_eq!(
+            problem.section("interac").map(|section| section.text),
+            Some("Comunicați prin stdout.".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_interaction_protocol() {
+        let problem = |text: &str| {
+            PbInfoProblem::from_parts(
+                ProblemId(1),
+                "ghicit".to_owned(),
+                format!("{}{}", STATEMENT, text),
+                IO_TEXT_2.to_owned(),
+            )
+            .unwrap()
+        };
+
+        assert!(!problem("").is_interactive());
+        assert_eq!(problem("").interaction_protocol(), None);
+
+        let protocol = problem(
+            "<h1>Protocol de interacțiune</h1><p>Afișați <code>? x</code>, apoi apelați fflush(stdout).</p>",
+        )
+        .interaction_protocol()
+        .unwrap();
+        assert_eq!(protocol.text, "Afișați ? x, apoi apelați fflush(stdout).");
+        assert!(protocol.requires_flush);
+
+        let mentioned = problem("<h1>Observații</h1><p>Aceasta este o problemă interactivă.</p>");
+        assert!(mentioned.is_interactive());
+        assert!(!mentioned.interaction_protocol().unwrap().requires_flush);
+    }
+
+    #[test]
+    fn test_extract_subtasks() {
+        let text = "1 ≤ n ≤ 100.000
+Pentru 30 de puncte, n ≤ 1000.
+Pentru teste în valoare de 20 de puncte: toate numerele sunt pare;
+Pentru 10% din teste, n = 1
+Subtask 4 (40 de puncte): fără restricții suplimentare";
+        assert_eq!(
+            extract_subtasks(text),
+            vec![
+                Subtask {
+                    points: 30,
+                    constraints: "n ≤ 1000".to_owned()
+                },
+                Subtask {
+                    points: 20,
+                    constraints: "toate numerele sunt pare".to_owned()
+                },
+                Subtask {
+                    points: 10,
+                    constraints: "n = 1".to_owned()
+                },
+                Subtask {
+                    points: 40,
+                    constraints: "fără restricții suplimentare".to_owned()
+                },
+            ]
+        );
+        assert!(extract_subtasks("1 ≤ n ≤ 100").is_empty());
+    }
+
+    #[test]
+    fn test_problem_name() {
+        let name = ProblemName::new("  Șir Țintă ").unwrap();
+        assert_eq!(name, "sir tinta");
+        assert_eq!(name.to_string(), "sir tinta");
+        assert_eq!(name, ProblemName::new("sir TINTA").unwrap());
+        assert!(ProblemName::new("   ").is_err());
+
+        assert_eq!(ProblemId(1691).to_string(), "1691");
+        assert_eq!(usize::from(ProblemId(1691)), 1691);
+    }
+
+    #[test]
+    fn test_from_str_conversions() {
+        assert_eq!("tastatură".parse::<IOSource>(), Ok(IOSource::Std));
+        assert_eq!(
+            " numere8.in".parse::<IOSource>(),
+            Ok(IOSource::File("numere8.in".to_owned()))
+        );
+        assert_eq!(
+            IOSource::parse_pair("numere8.in / ecran"),
+            Ok((IOSource::File("numere8.in".to_owned()), IOSource::Std))
+        );
+        assert!(IOSource::parse_pair("numere8.in").is_err());
+
+        assert_eq!("Ușor".parse::<Difficulty>(), Ok(Difficulty::Easy));
+        assert_eq!("dificila".parse::<Difficulty>(), Ok(Difficulty::Difficult));
+        assert!("foarte greu".parse::<Difficulty>().is_err());
+
+        assert_eq!("9".parse::<Grade>(), Ok(Grade(9)));
+        assert_eq!("Clasa a XI-a".parse::<Grade>(), Ok(Grade(11)));
+        assert_eq!("a IX-a".parse::<Grade>(), Ok(Grade(9)));
+        assert!("13".parse::<Grade>().is_err());
+
+        assert_eq!(ProblemId::try_from("#1691"), Ok(ProblemId(1691)));
+        assert_eq!(ProblemId::try_from(" 1691 "), Ok(ProblemId(1691)));
+        assert_eq!(
+            ProblemId::try_from("https://www.pbinfo.ro/probleme/1691/arbore1"),
+            Ok(ProblemId(1691))
+        );
+        assert!("arbore1".parse::<ProblemId>().is_err());
+    }
+
+    #[test]
+    fn test_display_localized() {
+        use crate::judge::Limits;
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1691),
+            "arbore1".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        assert_eq!(
+            problem.localized(Locale::English).to_string(),
+            format!(
+                "#1691 arbore1 (grade 11, contest, arbore1.in / arbore1.out, time 0.5 s, memory 64 MB, stack 32 MB)"
+            )
+        );
+        assert!(problem
+            .localized(Locale::Romanian)
+            .to_string()
+            .contains("timp 0.5 secunde, memorie 64 MB, stivă 32 MB"));
+
+        assert_eq!(
+            IOSource::Std.localized(Locale::Romanian).to_string(),
+            "tastatură/ecran"
+        );
+        assert_eq!(
+            Difficulty::Medium.localized(Locale::Romanian).to_string(),
+            "mediu"
+        );
+        assert_eq!(
+            Limits::default().localized(Locale::English).to_string(),
+            "no limits"
+        );
+        assert_eq!(IOSource::File("a.in".to_owned()).to_string(), "a.in");
+    }
+
+    #[test]
+    fn test_content_hash_and_similarity() {
+        let problem = |id: usize, text: &str| {
+            PbInfoProblem::from_parts(
+                ProblemId(id),
+                format!("p{}", id),
+                format!("<h1>Cerința</h1><p>{}</p>", text),
+                IO_TEXT_1.to_owned(),
+            )
+            .unwrap()
+        };
+        let original = problem(
+            1,
+            "Se dă un șir cu n numere naturale. Determinați suma lor.",
+        );
+        let reposted = problem(
+            2,
+            "Se  dă un <b>sir</b> cu n numere naturale.\nDeterminati suma lor!",
+        );
+        let changed = problem(
+            3,
+            "Se dă un șir cu n numere naturale. Determinați produsul lor.",
+        );
+        let other = problem(4, "Afișați cel mai mare divizor comun al lui a și b.");
+
+        assert_eq!(original.content_hash(), reposted.content_hash());
+        assert_ne!(original.content_hash(), changed.content_hash());
+        assert_eq!(original.content_hash().len(), 64);
+
+        assert_eq!(original.similar_to(&reposted), 1.0);
+        let similarity = original.similar_to(&changed);
+        assert!(similarity > 0.5 && similarity < 1.0);
+        assert_eq!(original.similar_to(&other), 0.0);
+
+        let duplicates = find_duplicates(&[original, reposted, changed, other], 0.5);
+        assert_eq!(duplicates.len(), 3);
+        assert_eq!(
+            (duplicates[0].0, duplicates[0].1),
+            (ProblemId(1), ProblemId(2))
+        );
+    }
+
+    #[test]
+    fn test_extract_id_from_url() {
+        assert_eq!(
+            extract_id_from_url("https://www.pbinfo.ro/probleme/1691"),
+            Ok(1691)
+        );
+        assert_eq!(
+            extract_id_from_url("https://www.pbinfo.ro/probleme/1691/arbore1"),
+            Ok(1691)
+        );
+        assert_eq!(
+            extract_id_from_url("http://pbinfo.ro/probleme/877/numere8?tab=2#top"),
+            Ok(877)
+        );
+        assert_eq!(extract_id_from_url("www.pbinfo.ro/probleme/877/"), Ok(877));
+        assert_eq!(extract_id_from_url("/probleme/877"), Ok(877));
+        assert!(extract_id_from_url("https://www.pbinfo.ro/articole/877").is_err());
+        assert!(extract_id_from_url("https://example.com/probleme/877").is_err());
+    }
+
+    #[test]
+    fn test_extract_metadata_map() {
+        let map = extract_metadata_map(IO_TEXT_1);
+        assert_eq!(map.len(), 9);
+        assert_eq!(map["Postată de"], "Candale Silviu (silviu)");
+        assert_eq!(map["Clasa"], "11");
+        assert_eq!(map["Intrare/ieșire"], "arbore1.in / arbore1.out");
+        assert_eq!(map["Limită memorie"], "64 MB / 32 MB");
+        assert_eq!(map["Dificultate"], "concurs");
+        assert_eq!(map["Scorul tău"], "100");
+
+        assert_eq!(extract_metadata_map(IO_TEXT_2)["Autor"], "-");
+    }
+
+    #[test]
+    fn test_extract_search_results() {
+        use std::collections::HashMap;
+
+        let json = vec![
+            HashMap::from([
+                ("value".to_owned(), "Arbore1".to_owned()),
+                (
+                    "label".to_owned(),
+                    "Problema #1691: <strong>Arbore1</strong>".to_owned(),
+                ),
+            ]),
+            HashMap::from([
+                ("value".to_owned(), "Arbore2".to_owned()),
+                (
+                    "label".to_owned(),
+                    "Problema #1692: <strong>Arbore2</strong>".to_owned(),
+                ),
+            ]),
+        ];
+        assert_eq!(
+            extract_search_results(&json),
+            Ok(vec![
+                SearchResult {
+                    id: ProblemId(1691),
+                    name: "Arbore1".to_owned()
+                },
+                SearchResult {
+                    id: ProblemId(1692),
+                    name: "Arbore2".to_owned()
+                },
+            ])
+        );
+
+        let json = vec![HashMap::from([("value".to_owned(), "Arbore1".to_owned())])];
+        assert!(extract_search_results(&json).is_err());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_session_jar() {
+        use crate::session::SessionJar;
+        use reqwest::cookie::CookieStore;
+        use reqwest::header::HeaderValue;
+
+        let jar = SessionJar::default();
+        let url = reqwest::Url::parse("https://www.pbinfo.ro/").unwrap();
+        let headers = [
+            HeaderValue::from_static("PHPSESSID=abc123; path=/; HttpOnly"),
+            HeaderValue::from_static("remember=yes; Max-Age=3600"),
+        ];
+        jar.set_cookies(&mut headers.iter(), &url);
+        assert_eq!(
+            jar.cookies(&url),
+            Some(HeaderValue::from_static("PHPSESSID=abc123; remember=yes"))
+        );
+
+        let deleted = [HeaderValue::from_static("remember=x; Max-Age=0")];
+        jar.set_cookies(&mut deleted.iter(), &url);
+        assert_eq!(jar.to_text(), "www.pbinfo.ro\tPHPSESSID\tabc123\n");
+
+        let restored = SessionJar::default();
+        restored.load_text(&jar.to_text()).unwrap();
+        assert_eq!(
+            restored.cookies(&url),
+            Some(HeaderValue::from_static("PHPSESSID=abc123"))
+        );
+        assert!(restored.load_text("not a cookie").is_err());
+
+        let other = reqwest::Url::parse("https://example.com/").unwrap();
+        assert_eq!(restored.cookies(&other), None);
+    }
+
+    #[test]
+    fn test_extract_login_challenge() {
+        let captcha = r#"<form><input type="hidden" name="form_token" value="f00d">
+<img src="/php/captcha.php?r=12" alt="cod"></form>"#;
+        assert_eq!(
+            extract_login_challenge(captcha),
+            Some(LoginChallenge {
+                kind: ChallengeKind::Captcha,
+                image_url: Some("https://www.pbinfo.ro/php/captcha.php?r=12".to_owned()),
+                token: "f00d".to_owned(),
+            })
+        );
+
+        let recaptcha = r#"<div class="g-recaptcha" data-sitekey="6Lc-key"></div>"#;
+        assert_eq!(
+            extract_login_challenge(recaptcha).map(|challenge| challenge.kind),
+            Some(ChallengeKind::ReCaptcha("6Lc-key".to_owned()))
+        );
+
+        let verification = "Introduceți codul de verificare primit pe email.";
+        assert_eq!(
+            extract_login_challenge(verification).map(|challenge| challenge.kind),
+            Some(ChallengeKind::Verification)
+        );
+
+        assert_eq!(extract_login_challenge(r#"{"raspuns":"ok"}"#), None);
+    }
+
+    #[test]
+    fn test_extract_submission_source() {
+        let page = r#"<h1>Detalii evaluare #35494272</h1>
+<textarea id="sursa" readonly>#include &lt;iostream&gt;
+int main() { return 0; }</textarea>"#;
+        assert_eq!(
+            extract_submission_source(page),
+            Ok("#include <iostream>\nint main() { return 0; }".to_owned())
+        );
+
+        let highlighted = r#"<pre class="cod-sursa"><span class="kw">int</span> x;</pre>"#;
+        assert_eq!(
+            extract_submission_source(highlighted),
+            Ok("int x;".to_owned())
+        );
+
+        assert!(extract_submission_source("<p>Nu aveți acces.</p>").is_err());
+    }
+
+    #[test]
+    fn test_extract_submission_status() {
+        assert_eq!(
+            extract_submission_status("<p>Soluția este în așteptare.</p>"),
+            Ok(SubmissionStatus::Queued)
+        );
+        let evaluating = r#"<p>Se evaluează...</p><table>
+<tr><th>Test</th><th>Rezultat</th></tr>
+<tr><td>1</td><td>OK</td></tr>
+<tr><td>2</td><td>Răspuns greșit</td></tr>
+<tr><td>3</td><td></td></tr></table>"#;
+        assert_eq!(
+            extract_submission_status(evaluating),
+            Ok(SubmissionStatus::Evaluating(2))
+        );
+        assert_eq!(
+            extract_submission_status("<h2>Punctaj total: 85</h2>"),
+            Ok(SubmissionStatus::Finished(85))
+        );
+        let compilation = r#"<div>Eroare de compilare</div>
+<pre class="mesaje-compilare">main.cpp:1: error: &lt;x&gt;</pre>"#;
+        assert_eq!(
+            extract_submission_status(compilation),
+            Ok(SubmissionStatus::CompilationError(
+                "main.cpp:1: error: <x>".to_owned()
+            ))
+        );
+        assert!(extract_submission_status("<p>Nimic</p>").is_err());
+        assert!(SubmissionStatus::Finished(0).is_final());
+        assert!(!SubmissionStatus::Evaluating(3).is_final());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_next_poll_interval() {
+        use crate::watch::next_poll_interval;
+        use std::time::Duration;
+
+        let interval = next_poll_interval(Duration::from_secs(4), true);
+        assert_eq!(interval, Duration::from_millis(500));
+        let interval = next_poll_interval(interval, false);
+        assert_eq!(interval, Duration::from_millis(750));
+        assert_eq!(
+            next_poll_interval(Duration::from_secs(4), false),
+            Duration::from_secs(5)
+        );
+
+        // A submission that stays queued is given up on after `max_wait`
+        use crate::recording::{recording_path, save_response};
+        let dir = std::env::temp_dir().join(format!("pbinfo-watch-{}", std::process::id()));
+        let url = "https://www.pbinfo.ro/detalii-evaluare/7";
+        save_response(
+            &recording_path(&dir, "GET", url),
+            url,
+            reqwest::StatusCode::OK,
+            &reqwest::header::HeaderMap::new(),
+            "<p>Soluția este în așteptare.</p>".as_bytes(),
+        )
+        .unwrap();
+        let client = PbInfoClient::builder()
+            .record_mode(RecordMode::Replay(dir.clone()))
+            .build()
+            .unwrap();
+        let mut watch = client.watch_submission(7).max_wait(Duration::ZERO);
+        assert_eq!(watch.next(), Some(Ok(SubmissionStatus::Queued)));
+        assert!(matches!(
+            watch.next(),
+            Some(Err(PbInfoError::DeadlineExceeded(_)))
+        ));
+        assert_eq!(watch.next(), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_rankings() {
+        let page = r#"<table class="table">
+<tr><th>Loc</th><th>Utilizator</th><th>Probleme rezolvate</th><th>Scor</th></tr>
+<tr><td>1.</td><td><a href="/profil/silviu">Candale Silviu</a></td><td>2.345</td><td>234500</td></tr>
+<tr><td class="center">2</td><td><img src="x.png"><a href="https://www.pbinfo.ro/profil/ana_m">Ana</a></td><td>1000</td><td>99950</td></tr>
+</table>"#;
+        assert_eq!(
+            extract_rankings(page),
+            vec![
+                RankEntry {
+                    rank: 1,
+                    username: "silviu".to_owned(),
+                    solved: 2345,
+                    score: 234500
+                },
+                RankEntry {
+                    rank: 2,
+                    username: "ana_m".to_owned(),
+                    solved: 1000,
+                    score: 99950
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_public_solves() {
+        let page = r#"<ul class="solved">
+<li><a href="/probleme/1691/arbore1">arbore1</a> <span class="badge">100</span></li>
+<li><a href="https://www.pbinfo.ro/probleme/4/sum">sum</a></li>
+</ul>
+<table><tr><td><a href="/probleme/1691/arbore1">arbore1</a></td><td>40</td></tr>
+<tr><td><a href="/probleme/2000/x2000">x2000</a></td><td>2023</td><td>35</td></tr></table>"#;
+        assert_eq!(
+            extract_public_solves(page),
+            vec![
+                PublicSolve {
+                    id: ProblemId(1691),
+                    name: "arbore1".to_owned(),
+                    score: Some(100)
+                },
+                PublicSolve {
+                    id: ProblemId(4),
+                    name: "sum".to_owned(),
+                    score: None
+                },
+                PublicSolve {
+                    id: ProblemId(2000),
+                    name: "x2000".to_owned(),
+                    score: Some(35)
+                },
+            ]
+        );
+        assert_eq!(extract_public_solves("<p>Niciun rezultat</p>"), vec![]);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_extract_questions() {
+        use crate::quiz::*;
+
+        let page = r#"<h1>Test <b>tablouri</b></h1>
+<div class="intrebare panel"><p>Câte elemente are <code>int v[10]</code>?</p>
+<label><input type="radio" name="q1" value="1"> 9</label>
+<label><input type="radio" name="q1" value="2" class="corect"> 10</label>
+<label><input type="radio" name="q1" value="3"> 11</label></div>
+<div class="intrebare"><p>Care sunt tipuri întregi?</p><ul>
+<li><input type="checkbox" name="q2"> <code>int</code></li>
+<li><input type="checkbox" name="q2"> <code>double</code></li></ul></div>
+<div class="intrebare"><p>Fără variante</p></div>"#;
+        let questions = extract_questions(page);
+        assert_eq!(
+            questions,
+            vec![
+                Question {
+                    prompt: "Câte elemente are int v[10]?".to_owned(),
+                    choices: vec!["9".to_owned(), "10".to_owned(), "11".to_owned()],
+                    multiple: false,
+                    answers: Some(vec![1]),
+                },
+                Question {
+                    prompt: "Care sunt tipuri întregi?".to_owned(),
+                    choices: vec!["int".to_owned(), "double".to_owned()],
+                    multiple: true,
+                    answers: None,
+                },
+            ]
+        );
+        assert_eq!(questions[0].check(&[1]), Some(true));
+        assert_eq!(questions[0].check(&[0, 1]), Some(false));
+        assert_eq!(questions[1].check(&[0]), None);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_extract_groups() {
+        let groups = r#"<ul><li><a href="/grupuri/12/clasa-a-ix-a">Clasa a IX-a</a></li>
+<li><a class="btn" href="/grupuri/15">Lot <b>juniori</b></a></li>
+<li><a href="/grupuri/12/teme">Teme</a></li>
+<li><a href="/grupuri/99999999999999999999999">Invalid</a></li></ul>"#;
+        assert_eq!(
+            extract_groups(groups),
+            vec![
+                Group {
+                    id: 12,
+                    name: "Clasa a IX-a".to_owned()
+                },
+                Group {
+                    id: 15,
+                    name: "Lot juniori".to_owned()
+                },
+            ]
+        );
+
+        let homework = r#"<table>
+<tr><th>Problemă</th><th>Termen</th></tr>
+<tr><td><a href="/probleme/877/numere8">Numere8</a></td><td>20.10.2026 23:59</td></tr>
+<tr><td><a href="/probleme/1691/arbore1">Arbore1</a></td><td>-</td></tr>
+</table>"#;
+        assert_eq!(
+            extract_assigned_problems(homework),
+            vec![
+                AssignedProblem {
+                    id: ProblemId(877),
+                    name: "Numere8".to_owned(),
+                    deadline: Some("20.10.2026 23:59".to_owned())
+                },
+                AssignedProblem {
+                    id: ProblemId(1691),
+                    name: "Arbore1".to_owned(),
+                    deadline: None
+                },
+            ]
+        );
+
+        let progress = r#"<table>
+<tr><th>Elev</th><th><a href="/probleme/877">877</a></th><th><a href="/probleme/1691">1691</a></th></tr>
+<tr><td><a href="/profil/ana">Ana</a></td><td>100</td><td>40</td></tr>
+<tr><td><a href="/profil/ion">Ion</a></td><td>-</td><td>100</td></tr>
+</table>"#;
+        let progress = extract_group_progress(progress);
+        assert_eq!(progress.len(), 2);
+        assert_eq!(progress[0].username, "ana");
+        assert_eq!(progress[0].scores[&ProblemId(1691)], Some(40));
+        assert_eq!(progress[0].solved(), vec![ProblemId(877)]);
+        assert_eq!(progress[1].scores[&ProblemId(877)], None);
+        assert_eq!(progress[1].solved(), vec![ProblemId(1691)]);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_verify_zip() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("pbinfo-tests-{}.zip", std::process::id()));
+        {
+            let file = std::fs::File::create(&path).unwrap();
+            let mut writer = zip::ZipWriter::new(file);
+            let options = zip::write::FileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored);
+            writer.start_file("1-numere8.in", options).unwrap();
+            writer.write_all(b"1 2 3 4 5 6 7 8 9\n").unwrap();
+            writer.finish().unwrap();
+        }
+        assert_eq!(verify_zip(&path), Ok(()));
+
+        // Corrupt the contents of the entry, so its CRC no longer matches
+        let mut bytes = std::fs::read(&path).unwrap();
+        let pos = bytes.windows(3).position(|w| w == b"1 2").unwrap();
+        bytes[pos] = b'9';
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(verify_zip(&path).is_err());
+
+        // Truncated archives lack the central directory
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(verify_zip(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    const EXAMPLES: &str = r#"<h1>Exemplu</h1>
+<p><code>numere8.in</code></p>
+<pre>3
+1 2 3
+</pre>
+<p><code>numere8.out</code></p>
+<pre>6</pre>
+<h1>Explicație</h1><p>1 + 2 + 3 = 6.</p>"#;
+
+    #[test]
+    fn test_extract_examples() {
+        assert_eq!(
+            extract_examples(EXAMPLES),
+            vec![Example {
+                input: "3\n1 2 3\n".to_owned(),
+                output: "6\n".to_owned()
+            }]
+        );
+        assert_eq!(
+            extract_examples("<pre>&lt;3</pre><pre>a &amp; b</pre><pre>unpaired</pre>"),
+            vec![Example {
+                input: "<3\n".to_owned(),
+                output: "a & b\n".to_owned()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_judge_run_examples() {
+        use crate::judge::*;
+
+        assert!(outputs_match("6 \n", "6\n"));
+        assert!(outputs_match("1  2\n3", "1 2 3\n"));
+        assert!(!outputs_match("1 2", "1 2 3"));
+
+        // The judge itself can only be tested where python is installed
+        if std::process::Command::new("python3")
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            return;
+        }
+
+        let mut problem = PbInfoProblem::from_parts(
+            ProblemId(877),
+            "numere8".to_owned(),
+            EXAMPLES.to_owned(),
+            IO_TEXT_2.to_owned(),
+        )
+        .unwrap();
+        // Starting the interpreter alone might take longer than the original 0.1 seconds
+        problem.time_limit = Some("2 secunde".to_owned());
+        let correct = "with open('numere8.in') as fin, open('numere8.out', 'w') as fout:
+    fin.readline()
+    fout.write(str(sum(map(int, fin.readline().split()))))
+";
+        let results = check_examples(&problem, correct, Language::Python).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].verdict, Verdict::Accepted);
+
+        let wrong = "open('numere8.out', 'w').write('7')";
+        let results = check_examples(&problem, wrong, Language::Python).unwrap();
+        assert_eq!(results[0].verdict, Verdict::WrongAnswer);
+
+        let crashing = "raise SystemExit(3)";
+        let results = check_examples(&problem, crashing, Language::Python).unwrap();
+        assert_eq!(results[0].verdict, Verdict::RuntimeError(Some(3)));
+
+        assert!(matches!(
+            compile("def f(:", Language::Python),
+            Err(PbInfoError::CompilationError(_))
+        ));
+
+        let looping = compile("while True: pass", Language::Python).unwrap();
+        let limits = Limits {
+            time: Some(std::time::Duration::from_millis(300)),
+            ..Limits::default()
+        };
+        let results =
+            run_tests_with_limits(&looping, &problem, &problem.examples(), &limits).unwrap();
+        assert_eq!(results[0].verdict, Verdict::TimeLimitExceeded);
+
+        // A program that never reads a large input from stdin still times out
+        let mut from_stdin = problem.clone();
+        from_stdin.input_source = IOSource::Std;
+        let large = Example {
+            input: "1 ".repeat(4 << 20),
+            output: String::new(),
+        };
+        let start = std::time::Instant::now();
+        let results = run_tests_with_limits(&looping, &from_stdin, &[large], &limits).unwrap();
+        assert_eq!(results[0].verdict, Verdict::TimeLimitExceeded);
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_judge_parallel() {
+        use crate::judge::*;
+        if std::process::Command::new("python3")
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            return;
+        }
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(877),
+            "numere8".to_owned(),
+            EXAMPLES.to_owned(),
+            IO_TEXT_2.to_owned(),
+        )
+        .unwrap();
+        // Every test writes the same output file, which would clash without separate directories
+        let program = compile(
+            "import time
+with open('numere8.in') as fin, open('numere8.out', 'w') as fout:
+    n = int(fin.readline())
+    time.sleep(0.5)
+    fout.write(str(n * 2))
+",
+            Language::Python,
+        )
+        .unwrap();
+        let tests = (1..=8)
+            .map(|n| Example {
+                input: n.to_string(),
+                output: (n * 2 + n % 2).to_string(),
+            })
+            .collect::<Vec<_>>();
+        let limits = Limits {
+            time: Some(std::time::Duration::from_secs(5)),
+            ..Limits::default()
+        };
+
+        let start = std::time::Instant::now();
+        let results =
+            run_tests_parallel(&program, &problem, &tests, &limits, &TokenChecker, 8).unwrap();
+        assert!(start.elapsed() < std::time::Duration::from_secs(3));
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| result.output.as_str())
+                .collect::<Vec<_>>(),
+            vec!["2", "4", "6", "8", "10", "12", "14", "16"]
+        );
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| result.verdict == Verdict::Accepted)
+                .collect::<Vec<_>>(),
+            vec![false, true, false, true, false, true, false, true]
+        );
+    }
+
+    #[test]
+    fn test_judge_limits() {
+        use crate::judge::*;
+        use std::time::Duration;
+
+        assert_eq!(parse_time("0.5 secunde"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_time("1,5 secunde"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_size("64 MB"), Some(64 << 20));
+        assert_eq!(parse_size("512 KB"), Some(512 << 10));
+        assert_eq!(parse_size("-"), None);
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1691),
+            "arbore1".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        assert_eq!(
+            Limits::from_problem(&problem),
+            Limits {
+                time: Some(Duration::from_millis(500)),
+                memory: Some(64 << 20),
+                stack: Some(32 << 20),
+            }
+        );
+    }
+
+    #[test]
+    fn test_checkers() {
+        use crate::judge::*;
+
+        let test = Example {
+            input: "2\n".to_owned(),
+            output: "0.333333 1\n".to_owned(),
+        };
+        assert_eq!(ExactChecker.check(&test, "0.333333 1 \n\n"), Ok(true));
+        assert_eq!(ExactChecker.check(&test, "0.333333\n1\n"), Ok(false));
+        assert_eq!(TokenChecker.check(&test, "0.333333\n1\n"), Ok(true));
+        assert_eq!(TokenChecker.check(&test, "0.3333333 1"), Ok(false));
+
+        let float = FloatChecker { epsilon: 1e-4 };
+        assert_eq!(float.check(&test, "0.3333333 1.00001"), Ok(true));
+        assert_eq!(float.check(&test, "0.34 1"), Ok(false));
+        assert_eq!(float.check(&test, "0.333333"), Ok(false));
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(877),
+            "numere8".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_2.to_owned(),
+        )
+        .unwrap();
+        let mut checkers = Checkers::new();
+        assert_eq!(
+            checkers.for_problem(&problem).check(&test, "0.3333333 1"),
+            Ok(false)
+        );
+        checkers.register(ProblemId(877), float);
+        assert_eq!(
+            checkers.for_problem(&problem).check(&test, "0.3333333 1"),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_stress() {
+        use crate::judge::*;
+
+        assert_eq!(
+            extract_bounds("1 ≤ n ≤ 100.000\n-10^9 <= a[i] <= 10^9"),
+            vec![
+                ("n".to_owned(), 1, 100_000),
+                ("a".to_owned(), -1_000_000_000, 1_000_000_000)
+            ]
+        );
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(877),
+            "numere8".to_owned(),
+            STATEMENT.replace("1 ≤ n ≤ 100", "1 ≤ n ≤ 100, 1 ≤ x ≤ 9"),
+            IO_TEXT_2.to_owned(),
+        )
+        .unwrap();
+        let generator = ArrayGenerator::from_problem(&problem);
+        assert_eq!(
+            generator,
+            ArrayGenerator {
+                min_value: 1,
+                max_value: 9
+            }
+        );
+        let input = generator.generate(&mut Rng::new(1), 5);
+        assert_eq!(input, generator.generate(&mut Rng::new(1), 5));
+        let mut rng = Rng::new(2);
+        assert!((1..=5).contains(&rng.range(5, 1)));
+        assert_eq!(rng.range(7, 7), 7);
+        rng.range(i64::MIN, i64::MAX);
+        rng.range(-1, i64::MAX);
+        let lines = input.lines().collect::<Vec<_>>();
+        let n = lines[0].parse::<usize>().unwrap();
+        assert!((1..=5).contains(&n));
+        assert_eq!(lines[1].split_whitespace().count(), n);
+
+        if std::process::Command::new("python3")
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            return;
+        }
+
+        let read = "fin = open('numere8.in'); n = int(fin.readline()); a = list(map(int, fin.readline().split()))\n";
+        let reference = compile(
+            &format!("{}open('numere8.out', 'w').write(str(sum(a)))", read),
+            Language::Python,
+        )
+        .unwrap();
+        // Forgets the last number once there are more than 3 of them
+        let candidate = compile(
+            &format!(
+                "{}open('numere8.out', 'w').write(str(sum(a if n <= 3 else a[:-1])))",
+                read
+            ),
+            Language::Python,
+        )
+        .unwrap();
+
+        let options = StressOptions {
+            iterations: 8,
+            max_size: 8,
+            ..StressOptions::default()
+        };
+        let counterexample = stress(&problem, &reference, &candidate, &generator, &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(counterexample.result.verdict, Verdict::WrongAnswer);
+        assert!(counterexample.size >= 4);
+        let lines = counterexample.test.input.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "4");
+        assert_eq!(lines[1].split_whitespace().count(), 4);
+
+        // The failing input is shrunk to the smallest array on which the candidate still fails
+        let large = |_: &mut Rng, _: usize| "8\n1 2 3 4 5 6 7 8\n".to_owned();
+        let counterexample = stress(&problem, &reference, &candidate, &large, &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(counterexample.test.input, "4\n5 6 7 8\n");
+        assert_eq!(counterexample.test.output, "26");
+
+        let options = StressOptions {
+            iterations: 3,
+            ..options
+        };
+        assert_eq!(
+            stress(&problem, &reference, &reference, &generator, &options),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_write_examples() {
+        let dir = std::env::temp_dir().join(format!("pbinfo-examples-{}", std::process::id()));
+        let mut problem = PbInfoProblem::from_parts(
+            ProblemId(877),
+            "numere8".to_owned(),
+            format!("{}{}", EXAMPLES, EXAMPLES.replace("1 2 3", "4 5 6")),
+            IO_TEXT_2.to_owned(),
+        )
+        .unwrap();
+
+        let paths = problem.write_examples(&dir).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                dir.join("numere8.1.in"),
+                dir.join("numere8.1.ok"),
+                dir.join("numere8.2.in"),
+                dir.join("numere8.2.ok"),
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("numere8.2.in")).unwrap(),
+            "3\n4 5 6\n"
+        );
+
+        problem.input_source = IOSource::Std;
+        problem.name = ProblemName::new("sum").unwrap();
+        assert_eq!(
+            problem.write_examples(&dir).unwrap()[0],
+            dir.join("sum.1.in")
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_catalog_jsonl() {
+        let first = PbInfoProblem::from_parts(
+            ProblemId(1691),
+            "arbore1".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        let mut second = first.clone();
+        second.id = ProblemId(877);
+        second.input_source = IOSource::Std;
+
+        let mut output = Vec::new();
+        let options = ExportOptions {
+            include_statement: true,
+            ..ExportOptions::default()
+        };
+        assert_eq!(
+            export_catalog_jsonl(&mut output, [&first, &second], &options),
+            Ok(2)
+        );
+
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        let json: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(json["id"], 1691);
+        assert_eq!(json["input"], "arbore1.in");
+        assert_eq!(json["difficulty"], "contest");
+        assert_eq!(json["source"], "ONI 2016, clasele XI-XII");
+        assert!(json["statement"].as_str().unwrap().starts_with("Cerința"));
+        assert!(json.get("statement_html").is_none());
+
+        let json: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(json["input"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_export_research_dataset() {
+        let mut problem = PbInfoProblem::from_parts(
+            ProblemId(1691),
+            "arbore1".to_owned(),
+            format!(
+                "{}<p>Propusă de Ion Popescu (<a href=\"https://www.pbinfo.ro/profil/ion_p\">ion_p</a>, ion.popescu@example.com).</p>",
+                STATEMENT
+            ),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        problem.author = Some("Ion Popescu".to_owned());
+        problem.time_limit = Some("0.5 secunde".to_owned());
+        problem.memory_limit = Some("64 MB / 8 MB".to_owned());
+        let stats = ProblemStats {
+            attempts: 10,
+            solved: 4,
+            average_score: Some(62.5),
+            success_rate: 40.0,
+            ..ProblemStats::default()
+        };
+
+        let mut output = Vec::new();
+        assert_eq!(
+            export_research_dataset(&mut output, [(&problem, Some(&stats)), (&problem, None)]),
+            Ok(2)
+        );
+        let output = String::from_utf8(output).unwrap();
+        assert!(!output.contains("Ion Popescu"));
+        assert!(!output.contains("ion_p"));
+        assert!(!output.contains("example.com"));
+
+        let json: serde_json::Value = serde_json::from_str(output.lines().next().unwrap()).unwrap();
+        assert_eq!(json["schema"], RESEARCH_SCHEMA_V1);
+        assert_eq!(json["io"], "file");
+        assert_eq!(json["time_limit_ms"], 500);
+        assert_eq!(json["memory_limit_bytes"], 64 << 20);
+        assert!(json.get("author").is_none());
+        assert!(json["statement"]
+            .as_str()
+            .unwrap()
+            .contains("Propusă de [author] ([user], [email])."));
+        assert_eq!(json["stats"]["solved"], 4);
+        let json: serde_json::Value = serde_json::from_str(output.lines().nth(1).unwrap()).unwrap();
+        assert_eq!(json["stats"], serde_json::Value::Null);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_client_builder() {
+        assert!(DEFAULT_USER_AGENT.starts_with("pbinfo-rs/"));
+        assert!(PbInfoClient::builder()
+            .user_agent("school-tool/1.0")
+            .header("X-Contact", "teacher@example.com")
+            .build()
+            .is_ok());
+        let client = PbInfoClient::builder()
+            .base_url("http://localhost:8080/")
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url(), "http://localhost:8080");
+        assert_eq!(
+            client.url("/probleme/1"),
+            "http://localhost:8080/probleme/1"
+        );
+        assert!(PbInfoClient::builder()
+            .header("Invalid Header", "x")
+            .build()
+            .is_err());
+        assert!(PbInfoClient::builder()
+            .header("X-Contact", "line\nbreak")
+            .build()
+            .is_err());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_client_pool() {
+        let mut pool = ClientPool::new();
+        assert!(pool.client_for(Operation::Fetch).is_err());
+
+        for name in ["teacher", "student1", "student2"] {
+            let client = PbInfoClient::builder()
+                .min_request_interval(std::time::Duration::from_millis(200))
+                .build()
+                .unwrap();
+            pool.add(name, client);
+        }
+        assert_eq!(pool.names(), vec!["teacher", "student1", "student2"]);
+        assert!(pool.route(Operation::Groups, "admin").is_err());
+        pool.route(Operation::Groups, "teacher").unwrap();
+
+        assert_eq!(pool.account_for(Operation::Groups), Ok("teacher"));
+        assert_eq!(pool.account_for(Operation::Groups), Ok("teacher"));
+        let spread = (0..3)
+            .map(|_| pool.account_for(Operation::Fetch).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(spread, vec!["teacher", "student1", "student2"]);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_robots_rules() {
+        let text = "# PbInfo
+User-agent: *
+Disallow: /php/
+Allow: /php/ajax-search.php
+Disallow: /*?print=$
+Crawl-delay: 2
+
+User-agent: pbinfo-rs
+User-agent: other
+Disallow: /profil/
+Crawl-delay: 0.5
+";
+        let rules = RobotsRules::parse(text, "pbinfo-rs");
+        assert!(!rules.is_allowed("/profil/silviu"));
+        assert!(rules.is_allowed("/php/descarca-teste.php?id=1"));
+        assert_eq!(
+            rules.crawl_delay(),
+            Some(std::time::Duration::from_millis(500))
+        );
+
+        let rules = RobotsRules::parse(text, "curl");
+        assert!(rules.is_allowed("/probleme/1691"));
+        assert!(!rules.is_allowed("/php/descarca-teste.php?id=1"));
+        assert!(rules.is_allowed("/php/ajax-search.php?term=suma"));
+        assert!(!rules.is_allowed("/probleme/1691?print="));
+        assert!(rules.is_allowed("/probleme/1691?print=1"));
+        assert_eq!(rules.crawl_delay(), Some(std::time::Duration::from_secs(2)));
+
+        assert!(RobotsRules::parse("", "pbinfo-rs").is_allowed("/"));
+        // A delay too long for a Duration is ignored instead of panicking
+        let rules = RobotsRules::parse("User-agent: *\nCrawl-delay: 1e300\n", "pbinfo-rs");
+        assert_eq!(rules.crawl_delay(), None);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let now = UNIX_EPOCH + Duration::from_secs(1445412470);
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            parse_retry_after("Tue, 20 Oct 2015 07:28:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_extract_problem_stats() {
+        let html = r#"<table class="statistici">
+            <tr><td>Soluții trimise</td><td>1.250</td></tr>
+            <tr><td>Soluții corecte</td><td>500</td></tr>
+            <tr><td>Scor mediu</td><td>61,5</td></tr>
+        </table>"#;
+        assert_eq!(
+            extract_problem_stats(html),
+            Ok(ProblemStats {
+                attempts: 1250,
+                solved: 500,
+                average_score: Some(61.5),
+                success_rate: 40.0,
+                ..ProblemStats::default()
+            })
+        );
+
+        let html = r#"<p>Soluții trimise: 40</p><p>Soluții corecte: 20</p>
+            <h3>Soluții corecte pe limbaje</h3>
+            <table><tr><th>Limbaj</th><th>Soluții</th></tr>
+            <tr><td>C++</td><td>1.012</td></tr><tr><td>C</td><td>30</td></tr>
+            <tr><td>Python 3</td><td>8</td></tr></table>"#;
+        let stats = extract_problem_stats(html).unwrap();
+        assert_eq!(
+            stats.language_breakdown,
+            [("C", 30), ("C++", 1012), ("Python", 8)]
+                .into_iter()
+                .map(|(language, solved)| (language.to_owned(), solved))
+                .collect()
+        );
+        assert_eq!(stats.language_share("python"), Some(8.0 / 1050.0));
+        assert_eq!(stats.language_share("Java"), Some(0.0));
+        assert_eq!(ProblemStats::default().language_share("Python"), None);
+
+        let html = "<p>Soluții trimise: 0</p><p>Soluții corecte: 0</p>";
+        assert_eq!(extract_problem_stats(html), Ok(ProblemStats::default()));
+        assert!(extract_problem_stats("<p>nimic</p>").is_err());
+    }
+
+    #[test]
+    fn test_extract_attachments() {
+        let page = r#"<div class="enunt"><h1>Cerința</h1><p>...</p>
+<h3>Atașamente</h3>
+<ul><li><a href="/resurse/enunt-1691.pdf">enunt.pdf</a> (120 KB)</li>
+<li><a href="https://cdn.pbinfo.ro/date.zip?a=1&amp;b=2">date.zip</a></li></ul>
+</div><a href="/probleme/2">alta</a>"#;
+        assert_eq!(
+            extract_attachments(page),
+            vec![
+                Attachment {
+                    name: "enunt.pdf".to_owned(),
+                    url: "https://www.pbinfo.ro/resurse/enunt-1691.pdf".to_owned(),
+                    size: Some(120 << 10),
+                },
+                Attachment {
+                    name: "date.zip".to_owned(),
+                    url: "https://cdn.pbinfo.ro/date.zip?a=1&b=2".to_owned(),
+                    size: None,
+                },
+            ]
+        );
+        assert_eq!(extract_attachments("<h1>Cerința</h1>"), vec![]);
+    }
+
+    #[test]
+    fn test_extract_topics() {
+        let html = r#"<ol class="breadcrumb">
+            <li><a href="/">Acasă</a></li>
+            <li><a href="/probleme">Probleme</a></li>
+            <li><a href="/probleme/categorii/9">Clasa a IX-a</a></li>
+            <li><a href="/probleme/categorii/9/tablouri">Tablouri unidimensionale</a></li>
+            <li class="active">numere8</li>
+        </ol>"#;
+        assert_eq!(
+            extract_topics(html),
+            vec!["Clasa a IX-a", "Tablouri unidimensionale"]
+        );
+        assert!(extract_topics("<p>Fără categorii</p>").is_empty());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_find_last_id() {
+        // Ids 1..=1000 exist, except for a few gaps
+        let exists = |id: usize| id <= 1000 && !(100..110).contains(&id) && !id.is_multiple_of(7);
+
+        let mut probes = 0;
+        let last = find_last_id(
+            |id| {
+                probes += 1;
+                Ok(exists(id))
+            },
+            16,
+        );
+        assert_eq!(last, Ok(1000));
+        assert!(probes < 500);
+
+        assert_eq!(find_last_id(|_| Ok(false), 16), Ok(0));
+        assert_eq!(find_last_id(|id| Ok(id == 1), 16), Ok(1));
+    }
+
+    #[test]
+    fn test_extract_contest_source() {
+        let parse = |source: &str| {
+            extract_contest_source(source)
+                .map(|source| (source.contest, source.year, source.division))
+        };
+        let some = |contest: &str, year: Option<u16>, division: Option<&str>| {
+            Some((contest.to_owned(), year, division.map(|d| d.to_owned())))
+        };
+
+        assert_eq!(
+            parse("ONI 2016, clasele XI-XII"),
+            some("ONI", Some(2016), Some("clasele XI-XII"))
+        );
+        assert_eq!(parse("OJI 2019"), some("OJI", Some(2019), None));
+        assert_eq!(
+            parse("Lot 2010 juniori"),
+            some("Lot", Some(2010), Some("juniori"))
+        );
+        assert_eq!(
+            parse("ONI 2008 (clasa a 9-a)"),
+            some("ONI", Some(2008), Some("clasa a 9-a"))
+        );
+        assert_eq!(parse("infoarena"), some("infoarena", None, None));
+        assert_eq!(parse("  "), None);
+    }
+
+    #[test]
+    fn test_author_matches() {
+        assert_eq!(
+            normalize_author("  prof. Ștefan  Popescu "),
+            "prof stefan popescu"
+        );
+        assert!(author_matches("prof. Popescu Ștefan", "stefan popescu"));
+        assert!(author_matches("Popescu Ion, Ionescu Ana", "Ana Ionescu"));
+        assert!(!author_matches("Popescu Ion", "Popescu Ana"));
+        assert!(!author_matches("Popescu Ion", " "));
+
+        let mut first = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "suma".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        first.author = Some("Popescu Ion".to_owned());
+        let mut second = first.clone();
+        second.id = ProblemId(2);
+        let mut third = first.clone();
+        third.id = ProblemId(3);
+        third.author = None;
+
+        let index = author_index(&[first, second, third]);
+        assert_eq!(index.len(), 1);
+        assert_eq!(index["Popescu Ion"], vec![ProblemId(1), ProblemId(2)]);
+    }
+
+    #[test]
+    fn test_check_site_available() {
+        let html = r#"<html><head><title>PbInfo - Mentenanță</title></head>
+            <body><h1>Site-ul este în mentenanță</h1><p>Revenim în curând.</p></body></html>"#;
+        assert_eq!(
+            check_site_available(html),
+            Err(PbInfoError::SiteUnavailable(
+                "PbInfo - Mentenanță".to_owned()
+            ))
+        );
+        assert_eq!(
+            PbInfoProblem::from_html(ProblemId(1), html).unwrap_err(),
+            PbInfoError::SiteUnavailable("PbInfo - Mentenanță".to_owned())
+        );
+        assert_eq!(check_site_available("<h1>numere8</h1>"), Ok(()));
+    }
+
+    #[test]
+    fn test_decode_html() {
+        // "Cerinţa" in Windows-1250 ("ţ" is 0xFE)
+        let bytes = b"<h1>Cerin\xfea</h1>";
+        assert_eq!(decode_html(bytes, None), "<h1>Cerinţa</h1>");
+        assert_eq!(
+            decode_html(bytes, Some("text/html; charset=windows-1250")),
+            "<h1>Cerinţa</h1>"
+        );
+        assert_eq!(
+            decode_html(bytes, Some("text/html; charset=UTF-8")),
+            "<h1>Cerin\u{fffd}a</h1>"
+        );
+        assert_eq!(
+            decode_html("<h1>Cerința</h1>".as_bytes(), None),
+            "<h1>Cerința</h1>"
+        );
+
+        let page = b"<meta charset=\"iso-8859-2\"><p>\xe2</p>";
+        assert_eq!(
+            decode_html(page, None),
+            "<meta charset=\"iso-8859-2\"><p>â</p>"
+        );
+    }
+
+    #[test]
+    fn test_lru_cache() {
+        let mut cache = crate::cache::LruCache::new(2);
+        cache.insert(1, "a");
+        cache.insert(2, "b");
+        assert_eq!(cache.get(&1), Some("a"));
+        // 2 is now the least recently used
+        cache.insert(3, "c");
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some("c"));
+        cache.insert(1, "d");
+        assert_eq!(cache.get(&1), Some("d"));
+
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 3,
+                misses: 1,
+                len: 2,
+                capacity: 2,
+            }
+        );
+
+        let mut disabled = crate::cache::LruCache::new(0);
+        disabled.insert(1, "a");
+        assert_eq!(disabled.get(&1), None);
+    }
+
+    #[test]
+    fn test_metrics_average_latency() {
+        use std::time::Duration;
+
+        assert_eq!(Metrics::default().average_latency(), Duration::ZERO);
+        let metrics = Metrics {
+            requests: 4,
+            total_latency: Duration::from_millis(200),
+            ..Metrics::default()
+        };
+        assert_eq!(metrics.average_latency(), Duration::from_millis(50));
+        let metrics = Metrics {
+            requests: 1 << 32,
+            total_latency: Duration::from_secs(1 << 32),
+            ..Metrics::default()
+        };
+        assert_eq!(metrics.average_latency(), Duration::from_secs(1));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_record_and_replay() {
+        use crate::recording::*;
+        use std::io::Read;
+
+        let dir = std::env::temp_dir().join(format!("pbinfo-recording-{}", std::process::id()));
+        let url = "https://www.pbinfo.ro/probleme/1";
+        let path = recording_path(&dir, "GET", url);
+        assert_eq!(path, recording_path(&dir, "GET", url));
+        assert_ne!(path, recording_path(&dir, "HEAD", url));
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            "text/html; charset=utf-8".parse().unwrap(),
+        );
+        headers.insert(reqwest::header::CONTENT_ENCODING, "gzip".parse().unwrap());
+        save_response(
+            &path,
+            url,
+            reqwest::StatusCode::OK,
+            &headers,
+            STATEMENT.as_bytes(),
+        )
+        .unwrap();
+
+        let mut page = load_response(&path).unwrap();
+        assert_eq!(page.status(), reqwest::StatusCode::OK);
+        assert_eq!(
+            page.headers()[reqwest::header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        assert!(page
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .is_none());
+        let mut body = String::new();
+        page.read_to_string(&mut body).unwrap();
+        assert_eq!(body, STATEMENT);
+
+        let client = PbInfoClient::builder()
+            .record_mode(RecordMode::Replay(dir.clone()))
+            .build()
+            .unwrap();
+        assert!(client.get_page(url).is_ok());
+        assert!(matches!(
+            client.get_page("https://www.pbinfo.ro/probleme/2"),
+            Err(PbInfoError::NetworkError(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_cache_backends() {
+        let response = CachedResponse {
+            status: 200,
+            headers: vec![(
+                "content-type".to_owned(),
+                "text/html; charset=utf-8".to_owned(),
+            )],
+            body: "<p>Se dă n.</p>".as_bytes().to_vec(),
+        };
+        let url = "http://127.0.0.1:9/probleme/1";
+
+        let memory = MemoryCache::new(1);
+        assert_eq!(memory.get(url), None);
+        memory.put(url, response.clone());
+        assert_eq!(memory.get(url), Some(response.clone()));
+        memory.put("http://127.0.0.1:9/probleme/2", response.clone());
+        assert_eq!(memory.get(url), None);
+
+        let dir = std::env::temp_dir().join(format!("pbinfo-file-cache-{}", std::process::id()));
+        let files = FileCache::new(&dir);
+        assert_eq!(files.get(url), None);
+        files.put(url, response.clone());
+        let cached = files.get(url).unwrap();
+        assert_eq!(cached.status, 200);
+        assert_eq!(cached.body, response.body);
+
+        // Nothing listens on the port, so the page can only come from the cache
+        let client = PbInfoClient::builder()
+            .base_url("http://127.0.0.1:9")
+            .max_retries(0)
+            .cache_backend(files)
+            .build()
+            .unwrap();
+        let page = client.get_page(url).unwrap();
+        assert_eq!(client.read_text(page).unwrap(), "<p>Se dă n.</p>");
+        assert!(client.get_page("http://127.0.0.1:9/probleme/2").is_err());
+        assert_eq!(client.metrics().requests, 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_adaptive_limit() {
+        use crate::batch::AdaptiveLimit;
+        use std::time::Duration;
+
+        let fast = Duration::from_millis(100);
+        let ok: Result<()> = Ok(());
+        let mut limit = AdaptiveLimit::new(&FetchOptions::default());
+        assert_eq!(limit.current(), 2);
+        // About one more request after a full round of fast requests
+        limit.record(fast, &ok);
+        limit.record(fast, &ok);
+        assert_eq!(limit.current(), 2);
+        limit.record(fast, &ok);
+        assert_eq!(limit.current(), 3);
+        for _ in 0..100 {
+            limit.record(fast, &ok);
+        }
+        assert_eq!(limit.current(), 8);
+
+        limit.record(Duration::from_secs(3), &ok);
+        assert_eq!(limit.current(), 4);
+        limit.record(
+            fast,
+            &Err::<(), _>(PbInfoError::RateLimited { retry_after: None }),
+        );
+        assert_eq!(limit.current(), 2);
+        limit.record(fast, &Err::<(), _>(PbInfoError::UnknownId(ProblemId(1))));
+        assert_eq!(limit.current(), 2);
+        for _ in 0..3 {
+            limit.record(
+                fast,
+                &Err::<(), _>(PbInfoError::NetworkError(String::new())),
+            );
+        }
+        assert_eq!(limit.current(), 1);
+
+        let sequential = AdaptiveLimit::new(&FetchOptions::sequential());
+        assert_eq!(sequential.current(), 1);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_fetch_many_report() {
+        use crate::recording::{recording_path, save_response};
+
+        let dir = std::env::temp_dir().join(format!("pbinfo-batch-{}", std::process::id()));
+        let record = |id: usize, status: reqwest::StatusCode| {
+            let url = format!("https://www.pbinfo.ro/probleme/{}", id);
+            let body = format!(
+                "<title>Problema numere{} | www.pbinfo.ro</title><article>{}</article>{}",
+                id, STATEMENT, IO_TEXT_1
+            );
+            save_response(
+                &recording_path(&dir, "GET", &url),
+                &url,
+                status,
+                &reqwest::header::HeaderMap::new(),
+                body.as_bytes(),
+            )
+            .unwrap();
+        };
+        record(1, reqwest::StatusCode::OK);
+        record(3, reqwest::StatusCode::NOT_FOUND);
+        record(4, reqwest::StatusCode::OK);
+
+        let client = PbInfoClient::builder()
+            .record_mode(RecordMode::Replay(dir.clone()))
+            .build()
+            .unwrap();
+        let ids = (1..=4).map(ProblemId).collect::<Vec<_>>();
+        let mut report = client.fetch_many(&ids);
+        assert_eq!(report.succeeded.len(), 2);
+        assert_eq!(report.succeeded[0].name, "numere1");
+        assert_eq!(report.failed_ids(), vec![ProblemId(2)]);
+        assert!(matches!(report.failed[0].1, PbInfoError::NetworkError(_)));
+        assert_eq!(report.skipped, vec![ProblemId(3)]);
+        assert!(!report.is_complete());
+
+        record(2, reqwest::StatusCode::OK);
+        assert_eq!(report.retry_failed(&client), 0);
+        assert!(report.is_complete());
+        let names = report
+            .succeeded
+            .iter()
+            .map(|problem| problem.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["numere1", "numere2", "numere4"]);
+
+        let ids = (1..=3).chain(1..=3).map(ProblemId).collect::<Vec<_>>();
+        let report = client.fetch_many_with(&ids, &FetchOptions::default());
+        let names = report
+            .succeeded
+            .iter()
+            .map(|problem| problem.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["numere1", "numere2", "numere1", "numere2"]);
+        assert_eq!(report.skipped, vec![ProblemId(3), ProblemId(3)]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_mirror_resumes() {
+        use crate::mirror::*;
+        use crate::recording::{recording_path, save_response};
+
+        let dir = std::env::temp_dir().join(format!("pbinfo-mirror-{}", std::process::id()));
+        let recordings = dir.join("recordings");
+        let output = dir.join("mirror");
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            "text/html; charset=utf-8".parse().unwrap(),
+        );
+        let record = |url: &str, body: &str| {
+            save_response(
+                &recording_path(&recordings, "GET", url),
+                url,
+                reqwest::StatusCode::OK,
+                &headers,
+                body.as_bytes(),
+            )
+            .unwrap();
+        };
+        record(
+            "https://www.pbinfo.ro/probleme/1",
+            &format!(
+                "<title>Problema numere8 | www.pbinfo.ro</title><article>{}<p>Vezi <a href=\"/articole/18\">lecția</a>.<img src=\"/images/desen.png\" alt=\"desen\"></p></article>{}",
+                STATEMENT, IO_TEXT_1
+            ),
+        );
+        record(
+            "https://www.pbinfo.ro/articole/18",
+            "<article><h1>Sortare &amp; căutare</h1><p>Text<script>x()</script><img src=\"/images/desen.png\"></p></article>",
+        );
+        record("https://www.pbinfo.ro/images/desen.png", "PNG");
+
+        let client = PbInfoClient::builder()
+            .record_mode(RecordMode::Replay(recordings.clone()))
+            .build()
+            .unwrap();
+        let mut config = MirrorConfig::new(&output);
+        config.problems = Some(vec![ProblemId(1), ProblemId(2)]);
+        let report = run_with(&client, &config).unwrap();
+        assert_eq!(
+            (report.problems, report.articles, report.skipped),
+            (1, 1, 0)
+        );
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "problem 2");
+
+        let problem = std::fs::read_to_string(output.join("problems/1.html")).unwrap();
+        assert!(problem.contains("<title>numere8</title>"));
+        assert!(problem.contains("Se dă un număr n."));
+        // The image shared by the problem and the article is stored once
+        let store = AssetStore::open(output.join("assets")).unwrap();
+        assert_eq!(report.images, 2);
+        assert_eq!(store.assets().len(), 1);
+        let image = store.assets().values().next().unwrap();
+        assert!(image.file.ends_with(".png"));
+        assert_eq!(image.refs.len(), 2);
+        assert!(problem.contains(&format!("src=\"../assets/{}\"", image.file)));
+        let article = std::fs::read_to_string(output.join("articles/18.html")).unwrap();
+        assert!(article.contains("<h1>Sortare &amp; căutare</h1>"));
+        assert!(!article.contains("script"));
+        let index = std::fs::read_to_string(output.join("index.html")).unwrap();
+        assert!(index.contains("<a href=\"problems/1.html\">#1 numere8</a>"));
+        assert!(index.contains("<a href=\"articles/18.html\">Sortare &amp; căutare</a>"));
+
+        config.problems = Some(vec![ProblemId(1)]);
+        let report = run_with(&client, &config).unwrap();
+        assert_eq!(
+            (report.problems, report.articles, report.skipped),
+            (0, 0, 1)
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extraction_fallbacks() {
+        let current = r#"<head><title>Problema Numere8 | www.pbinfo.ro</title></head>
+<article><h1>Cerința</h1><p>Se dă un număr n.</p></article>
+<table class="table table-bordered"><tr><th>Clasa</th></tr></table>"#;
+        assert_eq!(
+            extraction_report(current),
+            ExtractionReport {
+                name: Some("title"),
+                problem_text: Some("article"),
+                metadata: Some("table.table-bordered"),
+            }
+        );
+
+        let redesigned = r#"<head><title>#1234 Numere8 - PbInfo</title></head>
+<main><h2 class="titlu">Cerinţa</h2><p>Se dă un număr n.</p></main>
+<table class="table table-striped"><tr><th>Clasa</th><td>9</td></tr></table>"#;
+        assert_eq!(
+            extraction_report(redesigned),
+            ExtractionReport {
+                name: Some("title (legacy)"),
+                problem_text: Some("main"),
+                metadata: Some("table with Clasa"),
+            }
+        );
+        assert_eq!(extract_name(redesigned), Ok("numere8".to_owned()));
+        assert_eq!(
+            extract_problem_text(redesigned),
+            Ok(r#"<h2 class="titlu">Cerinţa</h2><p>Se dă un număr n.</p>"#.to_owned())
+        );
+
+        assert_eq!(
+            extraction_report("<p>Pagina nu există</p>"),
+            ExtractionReport {
+                name: None,
+                problem_text: None,
+                metadata: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_math_to_latex() {
+        assert_eq!(
+            math_to_latex(r#"<img src="https://latex.codecogs.com/gif.latex?n%20%5Cle%2010%5E5">"#),
+            r"$n \le 10^5$"
+        );
+        assert_eq!(
+            math_to_latex(r#"<img class="latex" src="/formule/12.png" alt="1 \le n">"#),
+            r"$1 \le n$"
+        );
+        assert_eq!(
+            math_to_latex(r#"<img src="/images/desen.png" alt="desen">"#),
+            r#"<img src="/images/desen.png" alt="desen">"#
+        );
+        assert_eq!(math_to_latex(r"\(a_i \le 10^9\)"), r"$a_i \le 10^9$");
+        assert_eq!(math_to_latex(r"\[x^2\]"), r"$$x^2$$");
+        assert_eq!(
+            math_to_latex(r#"<script type="math/tex">n \le 100</script>"#),
+            r"$n \le 100$"
+        );
+        assert_eq!(
+            math_to_latex(
+                "<math><mn>1</mn><mo>&le;</mo><mi>n</mi><mo>&le;</mo>\
+                 <msup><mn>10</mn><mn>5</mn></msup></math>"
+            ),
+            r"$1\le n\le 10^5$"
+        );
+        assert_eq!(
+            math_to_latex(
+                "<math display=\"block\"><mfrac><mrow><mi>n</mi><mo>+</mo><mn>1</mn></mrow>\
+                 <mn>2</mn></mfrac></math>"
+            ),
+            r"$$\frac{n+1}{2}$$"
+        );
+        assert_eq!(math_to_latex("10<sup>9</sup>, a<sub>i</sub>"), "10^9, a_i");
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            r#"<h1>Cerința</h1><p>Se dă un <strong>număr</strong> \(n\).</p>
+<h1>Restricții și precizări</h1><ul><li>1 &le; n &le; 10<sup>5</sup></li></ul>
+<h1>Exemplu</h1><pre>3
+1 2 3</pre>"#
+                .to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            problem.to_markdown(),
+            "# numere8\n\n## Cerința\n\nSe dă un **număr** $n$.\n\n\
+             ## Restricții și precizări\n\n- 1 ≤ n ≤ 10^5\n\n\
+             ## Exemplu\n\n```\n3\n1 2 3\n```\n"
+        );
+        assert!(problem.to_plain_text().contains("Se dă un număr $n$."));
+    }
+
+    #[test]
+    fn test_to_clean_html_and_rst() {
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            r#"<h1>Cerința</h1><p class="x" onclick="alert(1)">Se dă <a href="/probleme/2">n</a>.<script>alert(1)</script></p>
+<p><img src="javascript:alert(1)" alt="x"><img src="/images/desen.png" alt="desen"></p>
+<h1>Exemplu</h1><pre>3
+1 2 3</pre><p>Deci \(n \le 3\).</p>"#
+                .to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+
+        let html = problem.to_clean_html();
+        assert!(html.starts_with("<div class=\"pbinfo-problem\">"));
+        assert!(html.contains("<p>Se dă <a href=\"https://www.pbinfo.ro/probleme/2\">n</a>.</p>"));
+        assert!(html.contains(
+            "<img class=\"pbinfo-figure\" alt=\"x\"><img class=\"pbinfo-figure\" src=\"https://www.pbinfo.ro/images/desen.png\" alt=\"desen\">"
+        ));
+        assert!(html.contains("<pre class=\"pbinfo-example\">3\n1 2 3</pre>"));
+        assert!(!html.contains("alert"));
+
+        // Titles are text, so the markup they contain once decoded is escaped
+        let hostile = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            "<h1>&lt;img src=x onerror=alert(1)&gt;</h1><p>n</p>".to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        assert!(hostile.to_clean_html().contains(
+            "<h3 class=\"pbinfo-section-title\">&lt;img src=x onerror=alert(1)&gt;</h3>"
+        ));
+
+        assert_eq!(
+            problem.to_rst(),
+            "numere8\n=======\n\n\
+             Cerința\n-------\n\n\
+             Se dă `n <https://www.pbinfo.ro/probleme/2>`__.\n\n\
+             Exemplu\n-------\n\n\
+             ::\n\n    3\n    1 2 3\n\nDeci :math:`n \\le 3`.\n"
+        );
+    }
+
+    #[test]
+    fn test_article_from_html() {
+        let page = r#"<html><head><title>Sortarea prin selecție | www.pbinfo.ro</title></head><body>
+<article><h1>Sortarea prin <em>selecție</em></h1>
+<p>Vezi <a href="/probleme/1691/arbore1">arbore1</a> și <a href="https://www.pbinfo.ro/probleme/4/sum">sum</a>.</p>
+<pre class="cpp">for (int i = 0; i &lt; n; i++)
+    cin &gt;&gt; v[i];</pre>
+<p>Din nou <a href="/probleme/1691">arbore1</a>.</p><pre>int main() {}</pre></article></body></html>"#;
+        let article = Article::from_html(18, page).unwrap();
+        assert_eq!(article.id, 18);
+        assert_eq!(article.title, "Sortarea prin selecție");
+        assert!(article.html.starts_with("<p>Vezi"));
+        assert_eq!(
+            article.code_samples,
+            vec![
+                "for (int i = 0; i < n; i++)\n    cin >> v[i];".to_owned(),
+                "int main() {}".to_owned(),
+            ]
+        );
+        assert_eq!(article.problems, vec![ProblemId(1691), ProblemId(4)]);
+
+        let untitled = Article::from_html(
+            1,
+            "<title>Lecție | www.pbinfo.ro</title><article><p>x</p></article>",
+        );
+        assert_eq!(untitled.unwrap().title, "Lecție");
+        assert!(matches!(
+            Article::from_html(1, "<p>Pagina nu există</p>"),
+            Err(PbInfoError::RegexError(_))
+        ));
+    }
+
+    #[test]
+    fn test_links() {
+        assert_eq!(
+            LinkTarget::parse("/probleme/1691/arbore1?tab=1"),
+            Some(LinkTarget::Problem(ProblemId(1691)))
+        );
+        assert_eq!(
+            LinkTarget::parse("https://pbinfo.ro/articole/18/sortare"),
+            Some(LinkTarget::Article(18))
+        );
+        assert_eq!(
+            LinkTarget::parse("/probleme/categorii"),
+            Some(LinkTarget::External(
+                "https://www.pbinfo.ro/probleme/categorii".to_owned()
+            ))
+        );
+        assert_eq!(LinkTarget::parse("#exemplu"), None);
+        assert_eq!(LinkTarget::parse("javascript:void(0)"), None);
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            r#"<h1>Cerința</h1><p>Ca la <a href="/probleme/4/sum">sum</a>, vezi
+<a class="x" href="https://www.pbinfo.ro/articole/18">articolul</a>, <a href="https://cppreference.com/?a=1&amp;b=2">aici</a>
+și <a href="/probleme/4">sum</a>.</p>"#
+                .to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        assert_eq!(
+            problem.links(),
+            vec![
+                LinkTarget::Problem(ProblemId(4)),
+                LinkTarget::Article(18),
+                LinkTarget::External("https://cppreference.com/?a=1&b=2".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_problem_filter() {
+        let problem = |id: usize, grade: usize, difficulty: Option<Difficulty>, text: &str| {
+            let mut problem = PbInfoProblem::from_parts(
+                ProblemId(id),
+                format!("numere{}", id),
+                text.to_owned(),
+                IO_TEXT_1.to_owned(),
+            )
+            .unwrap();
+            problem.grade = Grade(grade);
+            problem.difficulty = difficulty;
+            problem
+        };
+        let mut first = problem(1, 9, Some(Difficulty::Easy), STATEMENT);
+        first.topics = vec!["Recursivitate".to_owned()];
+        first.author = Some("prof. Popescu Ion".to_owned());
+        let second = problem(
+            2,
+            10,
+            Some(Difficulty::Medium),
+            &format!("{}{}", STATEMENT, EXAMPLES),
+        );
+        let third = problem(3, 11, None, STATEMENT);
+        let problems = vec![first, second, third];
+        let ids = |filter: &ProblemFilter| {
+            filter
+                .apply(&problems)
+                .iter()
+                .map(|problem| problem.id.0)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(&ProblemFilter::new()), vec![1, 2, 3]);
+        assert_eq!(
+            ids(&ProblemFilter::new().grades(Grade(10), Grade(11))),
+            vec![2, 3]
+        );
+        assert_eq!(ids(&ProblemFilter::new().grade(Grade(9))), vec![1]);
+        assert_eq!(
+            ids(&ProblemFilter::new()
+                .difficulty(Difficulty::Easy)
+                .difficulty(Difficulty::Medium)),
+            vec![1, 2]
+        );
+        assert_eq!(ids(&ProblemFilter::new().has_tests(true)), vec![2]);
+        assert_eq!(ids(&ProblemFilter::new().has_tests(false)), vec![1, 3]);
+        assert_eq!(ids(&ProblemFilter::new().solved([ProblemId(3)])), vec![3]);
+        assert_eq!(
+            ids(&ProblemFilter::new().unsolved([ProblemId(3)])),
+            vec![1, 2]
+        );
+        assert_eq!(ids(&ProblemFilter::new().topic("recursivitate")), vec![1]);
+        assert_eq!(ids(&ProblemFilter::new().author("Ion Popescu")), vec![1]);
+        assert_eq!(
+            ids(&ProblemFilter::new().grade(Grade(9)).has_tests(true)),
+            Vec::<usize>::new()
+        );
+
+        let filter = ProblemFilter::new().grades(Grade(9), Grade(10));
+        let picked = filter.pick(&problems, 7).unwrap();
+        assert_eq!(filter.pick(&problems, 7).unwrap().id, picked.id);
+        assert!(picked.id.0 <= 2);
+        assert!(filter.pick_random(&problems).is_some());
+        assert!(ProblemFilter::new()
+            .grade(Grade(5))
+            .pick_random(&problems)
+            .is_none());
+    }
+
+    #[test]
+    fn test_progress_tracking() {
+        use crate::progress::*;
+
+        const DAY: u64 = 24 * 60 * 60;
+        let mut progress = Progress::new("ana");
+        progress.record(ProblemId(1), 40, 10 * DAY);
+        progress.record(ProblemId(1), 100, 11 * DAY + 5);
+        progress.record(ProblemId(2), 20, 12 * DAY);
+        progress.record(ProblemId(3), 100, 20 * DAY);
+        assert_eq!(
+            progress.problems[&ProblemId(1)],
+            ProblemProgress {
+                best_score: Some(100),
+                attempts: 2,
+                solved_at: Some(11 * DAY + 5),
+                last_attempt: Some(11 * DAY + 5),
+                history: vec![
+                    Attempt {
+                        at: 10 * DAY,
+                        score: Some(40)
+                    },
+                    Attempt {
+                        at: 11 * DAY + 5,
+                        score: Some(100)
+                    },
+                ],
+            }
+        );
+        assert_eq!(progress.solved(), vec![ProblemId(1), ProblemId(3)]);
+        assert_eq!(progress.unsolved(), vec![ProblemId(2)]);
+        assert_eq!(progress.longest_streak(), 3);
+        assert_eq!(progress.current_streak(20 * DAY + 100), 1);
+        assert_eq!(progress.current_streak(21 * DAY), 1);
+        assert_eq!(progress.current_streak(22 * DAY), 0);
+
+        let solves = [
+            PublicSolve {
+                id: ProblemId(2),
+                name: "b".to_owned(),
+                score: Some(100),
+            },
+            PublicSolve {
+                id: ProblemId(3),
+                name: "c".to_owned(),
+                score: Some(100),
+            },
+            PublicSolve {
+                id: ProblemId(4),
+                name: "d".to_owned(),
+                score: None,
+            },
+        ];
+        assert_eq!(progress.merge_solves(&solves, 21 * DAY), 2);
+        assert_eq!(progress.problems[&ProblemId(2)].solved_at, Some(21 * DAY));
+        assert_eq!(progress.problems[&ProblemId(4)].best_score, None);
+        assert_eq!(progress.current_streak(21 * DAY), 2);
+        assert_eq!(progress.merge_solves(&solves, 22 * DAY), 0);
+
+        let mut first = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "a".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        first.topics = vec!["Grafuri".to_owned(), "Clasa a XI-a".to_owned()];
+        let mut fifth = first.clone();
+        fifth.id = ProblemId(5);
+        fifth.topics = vec!["Grafuri".to_owned()];
+        let coverage = progress.topic_coverage(&[first, fifth]);
+        assert_eq!(
+            coverage["Grafuri"],
+            TopicCoverage {
+                solved: 1,
+                total: 2
+            }
+        );
+        assert_eq!(coverage["Grafuri"].ratio(), 0.5);
+        assert_eq!(coverage["Clasa a XI-a"].ratio(), 1.0);
+
+        let path =
+            std::env::temp_dir().join(format!("pbinfo-progress-{}.json", std::process::id()));
+        assert_eq!(
+            Progress::load_or_new(&path, "ana").unwrap(),
+            Progress::new("ana")
+        );
+        progress.save(&path).unwrap();
+        assert_eq!(Progress::load(&path).unwrap(), progress);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_progress_trend() {
+        use crate::progress::*;
+
+        const DAY: u64 = 24 * 60 * 60;
+        let mut progress = Progress::new("ana");
+        // Day 4 of the Unix epoch was a Monday
+        progress.record(ProblemId(1), 40, 4 * DAY);
+        progress.record(ProblemId(1), 100, 5 * DAY);
+        progress.record(ProblemId(2), 50, 12 * DAY);
+        let solves = [PublicSolve {
+            id: ProblemId(2),
+            name: "b".to_owned(),
+            score: Some(100),
+        }];
+        progress.merge_solves(&solves, 19 * DAY);
+        assert_eq!(
+            progress.syncs,
+            vec![ScoreSync {
+                at: 19 * DAY,
+                solved: 2,
+                total_score: 200,
+                changed: 1,
+            }]
+        );
+
+        let week = |start: u64, solved: usize, score_delta: i64, total_score: usize| WeeklyTrend {
+            week_start: start * DAY,
+            solved,
+            score_delta,
+            total_score,
+        };
+        assert_eq!(
+            progress.trend(5 * DAY..19 * DAY + 1),
+            vec![
+                week(4, 1, 100, 100),
+                week(11, 0, 50, 150),
+                week(18, 1, 50, 200)
+            ]
+        );
+        assert!(progress.trend(5 * DAY..5 * DAY).is_empty());
+        // The whole history, from the epoch
+        assert_eq!(
+            progress.trend(0..12 * DAY),
+            vec![week(0, 0, 0, 0), week(4, 1, 100, 100), week(11, 0, 50, 150)]
+        );
+        assert_eq!(progress.trend(u64::MAX - DAY..u64::MAX).len(), 1);
+        assert_eq!(
+            Progress::from_json(&progress.to_json()).unwrap().syncs,
+            progress.syncs
+        );
+    }
+
+    #[test]
+    fn test_progress_reviews() {
+        use crate::progress::*;
+
+        const DAY: u64 = 24 * 60 * 60;
+        let parameters = ReviewParameters::default();
+        let mut progress = Progress::new("ana");
+        progress.record(ProblemId(1), 100, 0);
+        assert_eq!(
+            progress.problems[&ProblemId(1)].next_review(&parameters),
+            Some((DAY, 1.0))
+        );
+        progress.record(ProblemId(1), 100, DAY);
+        assert_eq!(
+            progress.problems[&ProblemId(1)].next_review(&parameters),
+            Some((7 * DAY, 6.0))
+        );
+        progress.record(ProblemId(1), 100, 7 * DAY);
+        // The ease grew to 2.7 after three perfect attempts
+        let (due, interval) = progress.problems[&ProblemId(1)]
+            .next_review(&parameters)
+            .unwrap();
+        assert!((interval - 6.0 * 2.7).abs() < 1e-9);
+        assert_eq!(due, 7 * DAY + (interval * DAY as f64).round() as u64);
+
+        // A failed attempt starts over
+        progress.record(ProblemId(2), 100, 0);
+        progress.record(ProblemId(2), 100, DAY);
+        progress.record(ProblemId(2), 40, 7 * DAY);
+        assert_eq!(
+            progress.problems[&ProblemId(2)].next_review(&parameters),
+            Some((8 * DAY, 1.0))
+        );
+
+        let reviews = progress.reviews(10 * DAY, &parameters);
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].id, ProblemId(2));
+        assert_eq!(reviews[0].best_score, Some(100));
+        let reviews = progress.reviews(60 * DAY, &parameters);
+        let ids = reviews.iter().map(|review| review.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![ProblemId(2), ProblemId(1)]);
+
+        let strict = ReviewParameters {
+            passing_quality: 5,
+            ..ReviewParameters::default()
+        };
+        progress.record(ProblemId(3), 80, 0);
+        progress.record(ProblemId(3), 80, DAY);
+        assert_eq!(
+            progress.problems[&ProblemId(3)].next_review(&strict),
+            Some((2 * DAY, 1.0))
+        );
+    }
+
+    #[test]
+    fn test_progress_csv() {
+        let solve = |id: usize, name: &str, score: Option<usize>| PublicSolve {
+            id: ProblemId(id),
+            name: name.to_owned(),
+            score,
+        };
+        let ana = [solve(1691, "arbore1", Some(100)), solve(4, "sum", Some(40))];
+        let ion = [solve(4, "sum", None), solve(7, "a,b", Some(100))];
+
+        let mut output = Vec::new();
+        progress::write_csv(&mut output, &[("ana", &ana), ("ion \"x\"", &ion)]).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Username,sum (#4),\"a,b (#7)\",arbore1 (#1691)\r\n\
+             ana,40,,100\r\n\
+             \"ion \"\"x\"\"\",,100,\r\n"
+        );
+    }
+
+    #[test]
+    fn test_submission_history() {
+        assert_eq!(parse_datetime("01.01.1970"), Some(0));
+        assert_eq!(parse_datetime("20.10.2026 23:59"), Some(1792540740));
+        assert_eq!(parse_datetime("2026-10-20T23:59:05"), Some(1792540745));
+        assert_eq!(parse_datetime(" 2024-02-29 "), Some(1709164800));
+        assert_eq!(parse_datetime("31.13.2026"), None);
+        assert_eq!(parse_datetime("ieri"), None);
+
+        let page = r#"<table><tr><th>Id</th><th>Problema</th><th>Utilizator</th><th>Data</th><th>Scor</th></tr>
+<tr><td><a href="/detalii-evaluare/501">#501</a></td><td><a href="https://www.pbinfo.ro/probleme/1691/arbore1">arbore1</a></td>
+<td><a href="/profil/ana">Ana</a></td><td>20.10.2026 23:59:05</td><td><span class="badge">100</span></td></tr>
+<tr><td><a href="/detalii-evaluare/500">#500</a></td><td><a href="/probleme/4/sum">sum</a></td>
+<td><a href="/profil/ana">Ana</a></td><td>19.10.2026 08:00</td><td>în evaluare</td></tr>
+<tr><td><a href="/probleme/4/sum">sum</a></td><td>fără evaluare</td></tr></table>"#;
+        let submissions = extract_submissions(page);
+        assert_eq!(
+            submissions,
+            vec![
+                Submission {
+                    id: 501,
+                    problem: ProblemId(1691),
+                    username: "ana".to_owned(),
+                    score: Some(100),
+                    submitted_at: Some(1792540745),
+                },
+                Submission {
+                    id: 500,
+                    problem: ProblemId(4),
+                    username: "ana".to_owned(),
+                    score: None,
+                    submitted_at: parse_datetime("19.10.2026 08:00"),
+                },
+            ]
+        );
+
+        let filter = SubmissionFilter {
+            since: parse_datetime("20.10.2026"),
+            ..SubmissionFilter::default()
+        };
+        assert!(filter.matches_time(&submissions[0]));
+        assert!(!filter.matches_time(&submissions[1]));
+    }
+
+    #[cfg(feature = "homework")]
+    #[test]
+    fn test_homework_check() {
+        use crate::homework::*;
+
+        let set = HomeworkSet::from_toml(
+            r#"
+name = "Tema 3"
+problems = [1691, 4, 7]
+deadline = 2026-10-20T23:59:00
+students = ["ana"]
+
+[pins]
+1691 = "abc123"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            set.problems,
+            vec![ProblemId(1691), ProblemId(4), ProblemId(7)]
+        );
+        assert_eq!(
+            set.pins,
+            std::collections::BTreeMap::from([(ProblemId(1691), "abc123".to_owned())])
+        );
+        assert!(HomeworkSet::from_toml(
+            "problems = [1]\ndeadline = 2026-10-20T23:59:00\npins = { x = \"a\" }"
+        )
+        .is_err());
+        assert_eq!(set.deadline, parse_datetime("20.10.2026 23:59").unwrap());
+        assert!(HomeworkSet::from_toml("problems = [1]\ndeadline = \"mâine\"").is_err());
+
+        let submission = |id: usize, problem: usize, score: usize, at: &str| Submission {
+            id,
+            problem: ProblemId(problem),
+            username: "ana".to_owned(),
+            score: Some(score),
+            submitted_at: parse_datetime(at),
+        };
+        let submissions = [
+            submission(1, 1691, 40, "18.10.2026 10:00"),
+            submission(2, 1691, 100, "19.10.2026 10:00"),
+            submission(3, 4, 60, "20.10.2026 12:00"),
+            submission(4, 4, 100, "21.10.2026 09:00"),
+            Submission {
+                username: "ion".to_owned(),
+                ..submission(5, 7, 100, "19.10.2026 10:00")
+            },
+        ];
+        let result = check_submissions(&set, "ana", &submissions);
+        assert_eq!(result.solved(), 1);
+        assert!(!result.is_complete());
+        assert_eq!(
+            result.problems[0].solved_at,
+            parse_datetime("19.10.2026 10:00")
+        );
+        assert_eq!(result.problems[1].best_score, Some(60));
+        assert!(result.problems[1].solved_late);
+        assert_eq!(result.problems[2].best_score, None);
+
+        let event = set.calendar_event();
+        assert_eq!(event.uid, "homework-tema-3@pbinfo.ro");
+        assert_eq!(event.summary, "Tema 3");
+        assert_eq!(event.description, "Probleme: #1691, #4, #7");
+        // Moving the deadline keeps the event
+        let mut moved = set.clone();
+        moved.deadline += 24 * 60 * 60;
+        assert_eq!(moved.calendar_event().uid, event.uid);
+        moved.name.clear();
+        assert_eq!(moved.calendar_event().uid, "homework-1691-4-7@pbinfo.ro");
+    }
+
+    #[test]
+    fn test_estimate_difficulty() {
+        let stats = |attempts: usize, solved: usize, average_score: Option<f64>| ProblemStats {
+            attempts,
+            solved,
+            average_score,
+            success_rate: 0.0,
+            ..ProblemStats::default()
+        };
+
+        let easy = estimate_difficulty(&stats(2000, 1800, Some(95.0)), Grade(9));
+        let hard = estimate_difficulty(&stats(2000, 100, Some(30.0)), Grade(9));
+        assert!(easy < 2.0, "{}", easy);
+        assert!(hard > 7.0, "{}", hard);
+        assert!(
+            estimate_difficulty(&stats(2000, 100, Some(30.0)), Grade(11)) > hard,
+            "higher grades are harder"
+        );
+        // Without submissions, the rating only depends on the prior and the grade
+        assert_eq!(
+            estimate_difficulty(&stats(0, 0, None), Grade(5)),
+            estimate_difficulty(&stats(0, 0, Some(12.0)), Grade(5))
+        );
+        // A couple of failed submissions do not make a problem the hardest one
+        assert!(estimate_difficulty(&stats(2, 0, Some(0.0)), Grade(9)) < hard);
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        assert_eq!(
+            problem.estimated_difficulty(&stats(10, 5, None)),
+            estimate_difficulty(&stats(10, 5, None), problem.grade)
+        );
+    }
+
+    #[test]
+    fn test_catalog_diff() {
+        let old =
+            "{\"id\":1,\"name\":\"numere8\",\"time_limit\":\"0.1 secunde\",\"topics\":[]}\n\n\
+                   {\"id\":2,\"name\":\"sum\"}\n\
+                   {\"id\":3,\"name\":\"max\"}\n";
+        let new = "{\"id\":1,\"name\":\"numere8\",\"time_limit\":\"0.2 secunde\",\"topics\":[],\"author\":\"Ana\"}\n\
+                   {\"id\":3,\"name\":\"max\"}\n\
+                   {\"id\":4,\"name\":\"min\"}\n";
+        let old = sync::Catalog::read_jsonl(old.as_bytes()).unwrap();
+        let new = sync::Catalog::read_jsonl(new.as_bytes()).unwrap();
+
+        let diff = sync::diff(&old, &new);
+        assert_eq!(diff.added, vec![ProblemId(4)]);
+        assert_eq!(diff.removed, vec![ProblemId(2)]);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(
+            diff.modified[0].changes,
+            vec![
+                sync::FieldChange {
+                    field: "author".to_owned(),
+                    old: serde_json::Value::Null,
+                    new: serde_json::json!("Ana"),
+                },
+                sync::FieldChange {
+                    field: "time_limit".to_owned(),
+                    old: serde_json::json!("0.1 secunde"),
+                    new: serde_json::json!("0.2 secunde"),
+                },
+            ]
+        );
+        assert_eq!(
+            diff.to_markdown(),
+            "## Added\n\n- #4 min\n\n## Removed\n\n- #2 sum\n\n## Modified\n\n- #1 numere8\n  \
+             - `author`: null → \"Ana\"\n  - `time_limit`: \"0.1 secunde\" → \"0.2 secunde\"\n\n"
+        );
+        assert!(sync::diff(&new, &new).is_empty());
+        assert!(sync::Catalog::read_jsonl("{\"name\":\"x\"}".as_bytes()).is_err());
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        let catalog = sync::Catalog::from_problems([&problem], &ExportOptions::default());
+        assert_eq!(catalog.problems[&ProblemId(1)]["name"], "numere8");
+    }
+
+    #[test]
+    fn test_json_v1_round_trip() {
+        let mut problem = PbInfoProblem::from_parts(
+            ProblemId(1691),
+            "arbore1".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        problem.output_source = IOSource::Std;
+        problem.topics = vec!["Clasa a XI-a".to_owned(), "Grafuri".to_owned()];
+        problem.attachments = vec![Attachment {
+            name: "desen.pdf".to_owned(),
+            url: "https://www.pbinfo.ro/files/desen.pdf".to_owned(),
+            size: None,
+        }];
+        problem.etag = Some("\"abc\"".to_owned());
+
+        let json = problem.to_json_v1();
+        assert_eq!(json["schema"], "pbinfo/problem/v1");
+        assert_eq!(json["input"], "arbore1.in");
+        assert_eq!(json["output"], serde_json::Value::Null);
+        assert_eq!(json["difficulty"], "contest");
+        assert_eq!(json["attachments"][0]["size"], serde_json::Value::Null);
+
+        let read = PbInfoProblem::from_json_v1(&json).unwrap();
+        assert_eq!(read.id, problem.id);
+        assert_eq!(read.name, problem.name);
+        assert_eq!(read.grade, problem.grade);
+        assert_eq!(read.input_source, problem.input_source);
+        assert_eq!(read.output_source, IOSource::Std);
+        assert_eq!(read.time_limit, problem.time_limit);
+        assert_eq!(read.source, problem.source);
+        assert_eq!(read.difficulty, problem.difficulty);
+        assert_eq!(read.topics, problem.topics);
+        assert_eq!(read.attachments, problem.attachments);
+        assert_eq!(read.problem_text, problem.problem_text);
+        assert_eq!(read.meta_text, problem.meta_text);
+        assert_eq!(read.etag, problem.etag);
+        assert_eq!(read.to_json_v1(), json);
+
+        let mut other = json.clone();
+        other["schema"] = serde_json::json!("pbinfo/problem/v2");
+        assert!(PbInfoProblem::from_json_v1(&other).is_err());
+        let mut other = json;
+        other.as_object_mut().unwrap().remove("grade");
+        assert!(PbInfoProblem::from_json_v1(&other).is_err());
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_ffi_problem() {
+        use crate::ffi::*;
+        use std::ffi::CStr;
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1691),
+            "arbore1".to_owned(),
+            format!("{}{}", STATEMENT, EXAMPLES),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        let mut c_problem = problem_to_c(&problem);
+        unsafe {
+            assert_eq!(c_problem.id, 1691);
+            assert_eq!(CStr::from_ptr(c_problem.name).to_str(), Ok("arbore1"));
+            assert_eq!(
+                CStr::from_ptr(c_problem.input_file).to_str(),
+                Ok("arbore1.in")
+            );
+            assert_eq!(c_problem.examples_len, 1);
+            let example = &*c_problem.examples;
+            assert_eq!(
+                CStr::from_ptr(example.output).to_str(),
+                Ok(problem.examples()[0].output.as_str())
+            );
+
+            pbinfo_free_problem(&mut c_problem);
+            assert!(c_problem.name.is_null() && c_problem.examples.is_null());
+            pbinfo_free_problem(&mut c_problem);
+            pbinfo_free_problem(std::ptr::null_mut());
+
+            assert_eq!(
+                pbinfo_fetch_problem(1, std::ptr::null_mut()),
+                PBINFO_ERR_INVALID_ARGUMENT
+            );
+            assert_eq!(
+                CStr::from_ptr(pbinfo_last_error()).to_str(),
+                Ok("`out` is null")
+            );
+        }
+        assert_eq!(
+            error_code(&PbInfoError::UnknownId(ProblemId(1))),
+            PBINFO_ERR_UNKNOWN_ID
+        );
+        assert_eq!(
+            error_code(&PbInfoError::RateLimited { retry_after: None }),
+            PBINFO_ERR_RATE_LIMITED
+        );
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_module() {
+        use pyo3::prelude::*;
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1691),
+            "arbore1".to_owned(),
+            format!("{}{}", STATEMENT, EXAMPLES),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "pbinfo").unwrap();
+            python::pbinfo(&module).unwrap();
+            let problem = Py::new(py, python::PyProblem { problem }).unwrap();
+
+            let globals = pyo3::types::PyDict::new(py);
+            globals.set_item("pbinfo", &module).unwrap();
+            globals.set_item("problem", problem).unwrap();
+            let run = |code: &str| {
+                py.eval(&std::ffi::CString::new(code).unwrap(), Some(&globals), None)
+                    .unwrap()
+            };
+            assert_eq!(run("problem.id").extract::<usize>().unwrap(), 1691);
+            assert_eq!(
+                run("problem.input_file").extract::<String>().unwrap(),
+                "arbore1.in"
+            );
+            assert_eq!(
+                run("len(problem.examples())").extract::<usize>().unwrap(),
+                1
+            );
+            assert!(run("problem.examples()[0].output")
+                .extract::<String>()
+                .is_ok());
+            assert_eq!(
+                run("repr(problem)").extract::<String>().unwrap(),
+                "Problem(id=1691, name=\"arbore1\")"
+            );
+            assert!(run("issubclass(pbinfo.UnknownProblem, pbinfo.Error)")
+                .extract::<bool>()
+                .unwrap());
+        });
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_server_api() {
+        use crate::recording::{recording_path, save_response};
+        use crate::server::*;
+        use std::net::IpAddr;
+
+        let dir = std::env::temp_dir().join(format!("pbinfo-server-{}", std::process::id()));
+        let url = "https://www.pbinfo.ro/probleme/1";
+        let body = format!(
+            "<title>Problema numere8 | www.pbinfo.ro</title><article>{}{}</article>{}",
+            STATEMENT, EXAMPLES, IO_TEXT_1
+        );
+        save_response(
+            &recording_path(&dir, "GET", url),
+            url,
+            reqwest::StatusCode::OK,
+            &reqwest::header::HeaderMap::new(),
+            body.as_bytes(),
+        )
+        .unwrap();
+        let client = PbInfoClient::builder()
+            .record_mode(RecordMode::Replay(dir.clone()))
+            .build()
+            .unwrap();
+        let api = Api::new(client, 3);
+        let address = IpAddr::from([10, 0, 0, 1]);
+
+        let response = api.handle("GET", "/problems/1", address);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body["name"], "numere8");
+        let response = api.handle("GET", "/problems/1/examples", address);
+        assert_eq!(response.body.as_array().unwrap().len(), 1);
+        assert_eq!(api.handle("POST", "/problems/1", address).status, 405);
+        let response = api.handle("GET", "/problems/1", address);
+        assert_eq!(response.status, 429);
+        assert!(response.retry_after.is_some());
+
+        let other = IpAddr::from([10, 0, 0, 2]);
+        assert_eq!(api.handle("GET", "/problems/x", other).status, 400);
+        assert_eq!(api.handle("GET", "/search?q=", other).status, 400);
+        assert_eq!(api.handle("POST", "/problems/1", other).status, 405);
+
+        let api = Api::new(PbInfoClient::new(), 0);
+        assert_eq!(api.handle("GET", "/problems/1/tests", address).status, 404);
+
+        let limiter = RateLimiter::new(60);
+        let now = std::time::Instant::now();
+        for _ in 0..60 {
+            assert!(limiter.check(address, now).is_ok());
+        }
+        assert_eq!(
+            limiter.check(address, now),
+            Err(std::time::Duration::from_secs(1))
+        );
+        assert!(limiter
+            .check(address, now + std::time::Duration::from_secs(1))
+            .is_ok());
+        // Buckets idle for a minute are forgotten
+        let later = now + std::time::Duration::from_secs(61);
+        assert!(limiter.check(other, later).is_ok());
+        assert_eq!(limiter.len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "graphql")]
+    #[test]
+    fn test_graphql_schema() {
+        use crate::recording::{recording_path, save_response};
+
+        let dir = std::env::temp_dir().join(format!("pbinfo-graphql-{}", std::process::id()));
+        let url = "https://www.pbinfo.ro/probleme/1";
+        let body = format!(
+            "<title>Problema numere8 | www.pbinfo.ro</title><article>{}{}</article>{}",
+            STATEMENT, EXAMPLES, IO_TEXT_1
+        );
+        save_response(
+            &recording_path(&dir, "GET", url),
+            url,
+            reqwest::StatusCode::OK,
+            &reqwest::header::HeaderMap::new(),
+            body.as_bytes(),
+        )
+        .unwrap();
+        let client = PbInfoClient::builder()
+            .record_mode(RecordMode::Replay(dir.clone()))
+            .build()
+            .unwrap();
+        let schema = graphql::schema(client);
+
+        let response = graphql::execute_blocking(
+            &schema,
+            "{ problem(id: 1) { name inputFile examples { output } } user(username: \"ana\") { username } }",
+        );
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["problem"]["name"], "numere8");
+        assert_eq!(data["problem"]["inputFile"], "arbore1.in");
+        assert_eq!(data["problem"]["examples"].as_array().unwrap().len(), 1);
+        assert_eq!(data["user"]["username"], "ana");
+
+        let response = graphql::execute_blocking(&schema, "{ problem(id: 1) { nope } }");
+        assert_eq!(response.errors.len(), 1);
+
+        // Queries can not walk the whole history of submissions
+        assert_eq!(graphql::submissions_limit(None), 20);
+        assert_eq!(graphql::submissions_limit(Some(5)), 5);
+        assert_eq!(graphql::submissions_limit(Some(1_000_000)), 100);
+        let nested = format!(
+            "{{ problem(id: 1) {{ {}name{} }} }}",
+            "submissions { problem { ".repeat(6),
+            " } }".repeat(6)
+        );
+        let response = graphql::execute_blocking(&schema, nested);
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("too deep"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_notifications() {
+        use crate::notify::*;
+        use std::sync::Mutex;
+
+        let verdict = Notification::Verdict {
+            submission_id: 501,
+            status: SubmissionStatus::Finished(100),
+        };
+        assert_eq!(
+            verdict.message(),
+            "Evaluarea #501: 100 puncte\nhttps://www.pbinfo.ro/detalii-evaluare/501"
+        );
+        assert_eq!(verdict.to_json()["type"], "verdict");
+        assert_eq!(verdict.to_json()["score"], 100);
+        let new = Notification::NewProblem {
+            id: ProblemId(1691),
+            name: "arbore1".to_owned(),
+        };
+        assert_eq!(new.title(), "Problemă nouă: #1691 arbore1");
+        assert_eq!(
+            DiscordSink::payload(&new)["content"],
+            "Problemă nouă: #1691 arbore1\nhttps://www.pbinfo.ro/probleme/1691/arbore1"
+        );
+
+        let sent = Mutex::new(Vec::new());
+        let record = |notification: &Notification| {
+            sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        };
+        let fail = |_: &Notification| Err(PbInfoError::Error("down".to_owned()));
+        assert_eq!(
+            notify_all(&[&fail, &record], &verdict),
+            Err(PbInfoError::Error("down".to_owned()))
+        );
+        assert_eq!(notify_all(&[&record], &new), Ok(()));
+        assert_eq!(*sent.lock().unwrap(), vec![verdict, new]);
+
+        // A failing sink or fetch does not lose the problems that were already announced
+        use crate::recording::{recording_path, save_response};
+        let dir = std::env::temp_dir().join(format!("pbinfo-announce-{}", std::process::id()));
+        for id in [5, 6] {
+            let url = format!("https://www.pbinfo.ro/probleme/{}", id);
+            let body = format!(
+                "<title>Problema numere{} | www.pbinfo.ro</title><article>{}</article>{}",
+                id, STATEMENT, IO_TEXT_1
+            );
+            save_response(
+                &recording_path(&dir, "GET", &url),
+                &url,
+                reqwest::StatusCode::OK,
+                &reqwest::header::HeaderMap::new(),
+                body.as_bytes(),
+            )
+            .unwrap();
+        }
+        let client = PbInfoClient::builder()
+            .record_mode(RecordMode::Replay(dir.clone()))
+            .build()
+            .unwrap();
+        sent.lock().unwrap().clear();
+        let announcement = client.announce_new_problems(ProblemId(4), &[&record, &fail]);
+        let ids = announcement
+            .problems
+            .iter()
+            .map(|problem| problem.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![ProblemId(5), ProblemId(6)]);
+        assert_eq!(sent.lock().unwrap().len(), 2);
+        assert_eq!(
+            announcement.failed,
+            vec![
+                (ProblemId(5), 1, PbInfoError::Error("down".to_owned())),
+                (ProblemId(6), 1, PbInfoError::Error("down".to_owned()))
+            ]
+        );
+        // Problem 7 was not recorded
+        assert!(matches!(
+            announcement.error,
+            Some(PbInfoError::NetworkError(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_atom_feed() {
+        let problem = |id: usize, name: &str, grade: usize| {
+            let mut problem = PbInfoProblem::from_parts(
+                ProblemId(id),
+                name.to_owned(),
+                STATEMENT.to_owned(),
+                IO_TEXT_1.to_owned(),
+            )
+            .unwrap();
+            problem.grade = Grade(grade);
+            problem.topics = vec!["Grafuri & arbori".to_owned()];
+            problem
+        };
+        let problems = [
+            problem(4, "sum", 9),
+            problem(1691, "arbore1", 11),
+            problem(900, "max", 11),
+        ];
+
+        let mut options = feed::FeedOptions::new(
+            "PbInfo <clasa a XI-a>",
+            "https://example.com/feed.xml",
+            parse_datetime("2026-10-20 12:00").unwrap(),
+        );
+        options.filter = ProblemFilter::new().grade(Grade(11));
+        let feed = feed::generate(&problems, &options);
+
+        assert!(feed.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+        assert!(feed.contains("<title>PbInfo &lt;clasa a XI-a&gt;</title>"));
+        assert!(feed.contains("<updated>2026-10-20T12:00:00Z</updated>"));
+        assert!(feed.contains("<category term=\"Grafuri &amp; arbori\"/>"));
+        assert!(feed.contains("<summary>Cerința Se dă un număr n."));
+        assert!(!feed.contains("#4 sum"));
+        let first = feed.find("#1691 arbore1").unwrap();
+        assert!(first < feed.find("#900 max").unwrap());
+        assert_eq!(feed.matches("<entry>").count(), 2);
+        assert!(feed.ends_with("</entry>\n</feed>\n"));
+
+        options.max_entries = 1;
+        assert_eq!(
+            feed::generate(&problems, &options)
+                .matches("<entry>")
+                .count(),
+            1
+        );
+        assert_eq!(
+            format_rfc3339(parse_datetime("29.02.2024 23:59:59").unwrap()),
+            "2024-02-29T23:59:59Z"
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_calendar_ics() {
+        use crate::calendar::*;
+
+        let group = Group {
+            id: 12,
+            name: "IX B".to_owned(),
+        };
+        let assigned = |deadline: Option<&str>| AssignedProblem {
+            id: ProblemId(4),
+            name: "sum".to_owned(),
+            deadline: deadline.map(str::to_owned),
+        };
+        assert_eq!(
+            CalendarEvent::from_assigned_problem(&group, &assigned(None)),
+            None
+        );
+        let mut event =
+            CalendarEvent::from_assigned_problem(&group, &assigned(Some("20.10.2026 23:59")))
+                .unwrap();
+        assert_eq!(event.uid, "group-12-problem-4@pbinfo.ro");
+        event.description =
+            "Citiți enunțul; apoi, rezolvați problema \"sum\" din capitolul despre sume și produse"
+                .to_owned();
+
+        let mut calendar = Calendar::new("Teme");
+        calendar.events.push(event);
+        let ics = calendar.to_ics(parse_datetime("2026-10-16 08:00").unwrap());
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(ics.contains("TZID:Europe/Bucharest\r\n"));
+        assert!(ics.contains("DTSTAMP:20261016T080000Z\r\n"));
+        assert!(ics.contains("DTSTART;TZID=Europe/Bucharest:20261020T235900\r\n"));
+        assert!(ics.contains("SUMMARY:IX B: sum\r\n"));
+        assert!(ics.contains("DESCRIPTION:Citiți enunțul\\; apoi\\, rezolvați"));
+        assert!(ics.contains("TRIGGER:-PT1440M\r\n"));
+        // Long lines are folded into lines of at most 75 bytes
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        assert!(ics.contains("\r\n "));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_submission_dedupe() {
+        use crate::recording::{recording_path, save_response};
+
+        assert_eq!(
+            source_digest("int main() {\r\n  return 0;  \r\n}\r\n\r\n"),
+            source_digest("int main() {\n  return 0;\n}")
+        );
+        assert_ne!(source_digest("a"), source_digest("b"));
+
+        let dir = std::env::temp_dir().join(format!("pbinfo-dedupe-{}", std::process::id()));
+        let record = |url: &str, body: &str| {
+            save_response(
+                &recording_path(&dir, "GET", url),
+                url,
+                reqwest::StatusCode::OK,
+                &reqwest::header::HeaderMap::new(),
+                body.as_bytes(),
+            )
+            .unwrap();
+        };
+        let row = |id: usize| {
+            format!(
+                "<tr><td><a href=\"/detalii-evaluare/{}\">#{}</a></td><td><a href=\"/probleme/4/sum\">sum</a></td>\
+                 <td><a href=\"/profil/ana\">Ana</a></td><td>20.10.2026 10:0{}</td><td>100</td></tr>",
+                id, id, id
+            )
+        };
+        record(
+            "https://www.pbinfo.ro/solutii?start=0&user=ana&problema=4",
+            &format!("<table>{}{}</table>", row(2), row(1)),
+        );
+        record(
+            "https://www.pbinfo.ro/detalii-evaluare/2",
+            "<textarea id=\"sursa\">int main() { return 1; }</textarea>",
+        );
+        record(
+            "https://www.pbinfo.ro/detalii-evaluare/1",
+            "<textarea id=\"sursa\">int main() {\r\n    return 0;\r\n}</textarea>",
+        );
+        record(
+            "https://www.pbinfo.ro/solutii?start=2&user=ana&problema=4",
+            "",
+        );
+
+        let client = PbInfoClient::builder()
+            .record_mode(RecordMode::Replay(dir.clone()))
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.find_duplicate_submission(
+                "ana",
+                ProblemId(4),
+                "int main() {\n    return 0;\n}\n"
+            ),
+            Ok(Some(1))
+        );
+        assert_eq!(
+            client.submit_solution_once(
+                "ana",
+                ProblemId(4),
+                judge::Language::Cpp,
+                "int main() {\n    return 0;\n}"
+            ),
+            Ok(Submitted {
+                id: 1,
+                duplicate: true
+            })
+        );
+        assert!(matches!(
+            client.submit_solution_once("ana", ProblemId(4), judge::Language::Cpp, "int x;"),
+            Err(PbInfoError::LoginError(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate_source() {
+        use crate::judge::Language;
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "arbore".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        let correct = "#include <fstream>\nstd::ifstream fin(\"arbore1.in\");\nstd::ofstream fout(\"arbore1.out\");\nint main() { int n; fin >> n; fout << n; }";
+        assert!(validate_source(&problem, correct, Language::Cpp).is_empty());
+
+        let stdio = "#include <iostream>\n// reads arbore.in\nint main() { int n; std::cin >> n; std::cout << n; }";
+        assert_eq!(
+            validate_source(&problem, stdio, Language::Cpp),
+            vec![
+                SourceIssue::ReadsStdin("arbore1.in".to_owned()),
+                SourceIssue::WritesStdout("arbore1.out".to_owned()),
+            ]
+        );
+
+        // Opening the input file does not help if the numbers are read from stdin
+        let mixed = "#include <fstream>\n#include <iostream>\nstd::ifstream fin(\"arbore1.in\");\nstd::ofstream fout(\"arbore1.out\");\nint main() { int n; std::cin >> n; fout << n; }";
+        assert_eq!(
+            validate_source(&problem, mixed, Language::Cpp),
+            vec![SourceIssue::ReadsStdin("arbore1.in".to_owned())]
+        );
+
+        let redirected = "#include <cstdio>\nint main() { freopen(\"arbore1.in\", \"r\", stdin); freopen(\"arbore.out\", \"w\", stdout); int n; scanf(\"%d\", &n); printf(\"%d\", n); }";
+        assert_eq!(
+            validate_source(&problem, redirected, Language::C),
+            vec![SourceIssue::WrongOutputFile {
+                expected: "arbore1.out".to_owned(),
+                found: "arbore.out".to_owned(),
+            }]
+        );
+
+        let python = "with open('arbore1.in') as f:\n    n = int(f.read())\nprint(n, file=open('arbore1.out', 'w'))\n";
+        assert!(validate_source(&problem, python, Language::Python).is_empty());
+        let python = "n = int(input())\n";
+        assert_eq!(
+            validate_source(&problem, python, Language::Python),
+            vec![
+                SourceIssue::ReadsStdin("arbore1.in".to_owned()),
+                SourceIssue::MissingOutputFile("arbore1.out".to_owned()),
+            ]
+        );
+
+        let mut problem = problem;
+        problem.input_source = IOSource::Std;
+        problem.output_source = IOSource::Std;
+        assert_eq!(
+            validate_source(&problem, correct, Language::Cpp),
+            vec![
+                SourceIssue::UnexpectedFile("arbore1.in".to_owned()),
+                SourceIssue::UnexpectedFile("arbore1.out".to_owned()),
+            ]
+        );
+        assert!(validate_source(&problem, stdio, Language::Cpp).is_empty());
+    }
+
+    #[test]
+    fn test_io_boilerplate() {
+        use crate::codegen::*;
+        use crate::judge::Language;
+        use crate::template::Templates;
+
+        let mut problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "arbore1".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        let code = io_boilerplate(&problem, Language::Cpp);
+        assert_eq!(code.header, "#include <fstream>\n");
+        assert_eq!(
+            code.setup,
+            "ifstream fin(\"arbore1.in\");\nofstream fout(\"arbore1.out\");\n"
+        );
+        assert_eq!((code.input.as_str(), code.output.as_str()), ("fin", "fout"));
+        assert_eq!(
+            indent(&io_boilerplate(&problem, Language::C).cleanup, "    "),
+            "    fclose(fin);\n    fclose(fout);\n"
+        );
+
+        problem.output_source = IOSource::Std;
+        let code = io_boilerplate(&problem, Language::Python);
+        assert_eq!(code.header, "import sys\n");
+        assert_eq!(code.setup, "fin = open(\"arbore1.in\")\n");
+        assert_eq!(code.output, "sys.stdout");
+
+        // The generated skeletons use the files of the problem
+        let templates = Templates::new();
+        for input in [IOSource::File("arbore1.in".to_owned()), IOSource::Std] {
+            for output in [IOSource::File("arbore1.out".to_owned()), IOSource::Std] {
+                problem.input_source = input.clone();
+                problem.output_source = output.clone();
+                for language in [Language::C, Language::Cpp, Language::Python] {
+                    let source = templates.render(&problem, language).unwrap();
+                    assert_eq!(
+                        validate_source(&problem, &source, language),
+                        vec![],
+                        "{:?}\n{}",
+                        language,
+                        source
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_daemon() {
+        use crate::daemon::Daemon;
+
+        let messages = [
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "typo"}"#,
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "search", "params": {}}"#,
+            r#"{"jsonrpc": "2.0", "id": 3, "method": "judge", "params": {"id": 1, "source": "", "language": "rust"}}"#,
+            r#"{"jsonrpc": "2.0", "method": "search", "params": {}}"#,
+            r#"{"jsonrpc": "2.0", "id": 4, "method": "shutdown"}"#,
+            r#"{"jsonrpc": "2.0", "id": 5, "method": "fetch", "params": {"id": 1}}"#,
+            "not json",
+            r#"{"jsonrpc": "2.0", "method": "exit"}"#,
+            r#"{"jsonrpc": "2.0", "id": 6, "method": "shutdown"}"#,
+        ];
+        let input = messages
+            .iter()
+            .map(|body| format!("Content-Length: {}\r\n\r\n{}", body.len(), body))
+            .collect::<String>();
+        let mut output = Vec::new();
+        let mut daemon = Daemon::new(PbInfoClient::new());
+        daemon.serve(input.as_bytes(), &mut output).unwrap();
+
+        let mut output = output.as_slice();
+        let mut responses = Vec::new();
+        while let Some(body) = crate::daemon::read_message(&mut output).unwrap() {
+            responses.push(serde_json::from_str::<serde_json::Value>(&body).unwrap());
+        }
+        let errors = responses
+            .iter()
+            .map(|response| (response["id"].clone(), response["error"]["code"].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                (1.into(), (-32601).into()),
+                (2.into(), (-32602).into()),
+                (3.into(), (-32602).into()),
+                (4.into(), serde_json::Value::Null),
+                (5.into(), (-32600).into()),
+                (serde_json::Value::Null, (-32700).into()),
+            ]
+        );
+        assert_eq!(responses[3]["result"], serde_json::Value::Null);
+
+        // A huge Content-Length is rejected before allocating the body
+        let input = "Content-Length: 99999999999\r\n\r\n{}";
+        assert!(crate::daemon::read_message(&mut input.as_bytes()).is_err());
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_config_layers() {
+        use crate::config::Config;
+        use crate::judge::Language;
+        use std::time::Duration;
+
+        let file = Config::from_toml(
+            r#"
+            username = "ana"
+            password_env = "PBINFO_PASSWORD"
+            cache_dir = "/tmp/pbinfo"
+            language = "c++"
+
+            [rate_limit]
+            min_interval_ms = 500
+            "#,
+        )
+        .unwrap();
+        assert_eq!(file.language, Some(Language::Cpp));
+        assert_eq!(file.min_request_interval, Some(Duration::from_millis(500)));
+        assert_eq!(file.max_retries, None);
+        assert!(Config::from_toml("language = \"rust\"").is_err());
+        assert!(Config::from_toml("[rate_limit]\nmax_retries = \"3\"").is_err());
+
+        let env = Config::from_vars(|name| match name {
+            "PBINFO_USERNAME" => Some("mihai".to_owned()),
+            "PBINFO_MAX_RETRIES" => Some("0".to_owned()),
+            "PBINFO_LANGUAGE" => Some(" ".to_owned()),
+            _ => None,
+        })
+        .unwrap();
+        let flags = Config {
+            cache_dir: Some("/var/cache/pbinfo".into()),
+            ..Config::default()
+        };
+        let config = file.merge(env).merge(flags);
+        assert_eq!(config.username.as_deref(), Some("mihai"));
+        assert_eq!(config.password_env.as_deref(), Some("PBINFO_PASSWORD"));
+        assert_eq!(config.language, Some(Language::Cpp));
+        assert_eq!(config.max_retries, Some(0));
+        assert_eq!(config.cache_dir, Some("/var/cache/pbinfo".into()));
+        assert!(Config::from_vars(|_| Some("many".to_owned())).is_err());
+    }
+
+    #[cfg(feature = "keyring")]
+    #[test]
+    fn test_client_auth_secret() {
+        let auth = ClientAuth::new("ana", "parola");
+        let debug = format!("{:?}", auth);
+        assert!(debug.contains("ana"));
+        assert!(!debug.contains("parola"));
+
+        assert_eq!(ClientAuth::from_secret(&auth.to_secret()).unwrap(), auth);
+        let session_only = auth.without_password();
+        assert_eq!(
+            ClientAuth::from_secret(&session_only.to_secret()).unwrap(),
+            session_only
+        );
+        assert!(ClientAuth::from_secret("{}").is_err());
+        assert!(ClientAuth::from_secret("parola").is_err());
+    }
+
+    #[test]
+    fn test_statement_tables() {
+        let html = r#"<h1>Punctare</h1>
+<table class="table">
+<thead><tr><th>Grupa</th><th>Puncte</th><th>Restricții</th></tr></thead>
+<tbody>
+<tr><td>1</td><td>30</td><td>n &le; 10<sup>3</sup></td></tr>
+<tr><td>2</td><td colspan="2">restul | testelor</td></tr>
+</tbody>
+</table>"#;
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "arbore".to_owned(),
+            html.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        let table = Table {
+            headers: vec!["Grupa".into(), "Puncte".into(), "Restricții".into()],
+            rows: vec![
+                vec!["1".into(), "30".into(), "n ≤ 10^3".into()],
+                vec!["2".into(), "restul | testelor".into(), "".into()],
+            ],
+        };
+        assert_eq!(problem.tables(), vec![table.clone()]);
+        assert_eq!(problem.sections()[0].tables, vec![table]);
+
+        assert_eq!(
+            problem.sections()[0].text,
+            "Grupa  Puncte             Restricții\n\
+             -----  -----------------  ----------\n\
+             1      30                 n ≤ 10^3\n\
+             2      restul | testelor"
+        );
+        assert!(problem.to_markdown().contains(
+            "| Grupa | Puncte | Restricții |\n\
+             | --- | --- | --- |\n\
+             | 1 | 30 | n ≤ 10^3 |\n\
+             | 2 | restul \\| testelor |  |"
+        ));
+        assert_eq!(
+            extract_tables("<table><tr><td>a</td><td>b</td></tr></table>"),
+            vec![Table {
+                headers: vec![],
+                rows: vec![vec!["a".into(), "b".into()]],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        use crate::judge::Language;
+
+        assert_eq!(
+            detect_language(
+                "#include <iostream>\nusing namespace std;\nint main() { int n; cin >> n; }"
+            ),
+            Some(Language::Cpp)
+        );
+        assert_eq!(
+            detect_language("#include <stdio.h>\nint main(void) {\n    printf(\"%d\", 1);\n}"),
+            Some(Language::C)
+        );
+        assert_eq!(
+            detect_language("n = int(input())\nfor i in range(n):\n    print(i)"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            detect_language("int cmmdc(int a, int b) {\n    return b ? cmmdc(b, a % b) : a;\n}"),
+            Some(Language::Cpp)
+        );
+        assert_eq!(detect_language("5\n1 2 3 4 5"), None);
+        assert_eq!(detect_language("citește n\ncât timp n > 0 execută"), None);
+
+        let html = r#"<h1>Cerința</h1><p>Se dă funcția:</p>
+<pre class="language-c">int f(int x) { return x &lt; 0 ? -x : x; }</pre>
+<div class="cod-sursa">def f(x):<br>    return abs(x)<br>print(f(-1))</div>
+<h1>Exemplu</h1><pre>3</pre><pre>6</pre>"#;
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "abs".to_owned(),
+            html.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        let blocks = problem.code_blocks();
+        assert_eq!(
+            blocks,
+            vec![
+                CodeBlock {
+                    language: Some(Language::C),
+                    text: "int f(int x) { return x < 0 ? -x : x; }".to_owned(),
+                },
+                CodeBlock {
+                    language: Some(Language::Python),
+                    text: "def f(x):\n    return abs(x)\nprint(f(-1))".to_owned(),
+                },
+            ]
+        );
+        assert_eq!(
+            blocks[1].to_markdown(),
+            "```py\ndef f(x):\n    return abs(x)\nprint(f(-1))\n```"
+        );
+        assert!(
+            html_to_markdown("<pre>#include &lt;iostream&gt;\nint main() {}</pre>")
+                .starts_with("```cpp\n#include <iostream>")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "blocking")]
+    fn test_fetch_overview_deadline() {
+        // A server that accepts connections but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = PbInfoClient::builder()
+            .base_url(&format!("http://{}", listener.local_addr().unwrap()))
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        let deadline = Deadline::after(
+            std::time::Duration::from_millis(200),
+            PartialPolicy::ReturnPartial,
+        );
+        let overview = client.fetch_overview(ProblemId(1), None, deadline).unwrap();
+        assert!(deadline.is_expired());
+        assert!(overview.problem.is_none() && overview.stats.is_none());
+        assert!(!overview.is_complete());
+        assert_eq!(
+            overview
+                .missing
+                .iter()
+                .map(|(part, _)| *part)
+                .collect::<Vec<_>>(),
+            vec![OverviewPart::Problem, OverviewPart::Stats]
+        );
+        assert_eq!(
+            overview.missing[0].1,
+            PbInfoError::DeadlineExceeded("problem, stats".to_owned())
+        );
+
+        let deadline = Deadline::after(std::time::Duration::from_millis(50), PartialPolicy::Fail);
+        assert!(matches!(
+            client.fetch_overview(ProblemId(1), None, deadline),
+            Err(PbInfoError::DeadlineExceeded(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "blocking")]
+    fn test_circuit_breaker() {
+        use crate::breaker::CircuitBreaker;
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        let mut breaker = CircuitBreaker::new(2, Duration::from_secs(30));
+        breaker.record(false, start);
+        assert_eq!(breaker.check(start), Ok(()));
+        breaker.record(false, start);
+        assert_eq!(
+            breaker.check(start + Duration::from_secs(10)),
+            Err(PbInfoError::CircuitOpen {
+                retry_after: Duration::from_secs(20)
+            })
+        );
+        // Half-open after the cooldown: a single probe is let through, and its failure opens the
+        // circuit again
+        let later = start + Duration::from_secs(30);
+        assert_eq!(breaker.check(later), Ok(()));
+        assert!(matches!(
+            breaker.check(later),
+            Err(PbInfoError::CircuitOpen { .. })
+        ));
+        breaker.record(false, later);
+        assert!(breaker.check(later + Duration::from_secs(10)).is_err());
+        // The success of the next probe closes it
+        let later = later + Duration::from_secs(30);
+        assert_eq!(breaker.check(later), Ok(()));
+        assert!(breaker.check(later).is_err());
+        breaker.record(true, later);
+        breaker.record(false, later);
+        assert_eq!(breaker.check(later), Ok(()));
+        assert_eq!(breaker.check(later), Ok(()));
+
+        // Connections to a closed port are refused
+        let address = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let client = PbInfoClient::builder()
+            .base_url(&format!("http://{}", address))
+            .circuit_breaker(2, Duration::from_secs(60))
+            .build()
+            .unwrap();
+        for _ in 0..2 {
+            assert!(matches!(
+                client.fetch_problem_by_id(ProblemId(1)),
+                Err(PbInfoError::NetworkError(_))
+            ));
+        }
+        assert!(matches!(
+            client.clone().fetch_problem_by_id(ProblemId(1)),
+            Err(PbInfoError::CircuitOpen { .. })
+        ));
+        assert_eq!(client.metrics().requests, 2);
+
+        // Once the circuit is open, a batch stops with one shared error
+        let client = PbInfoClient::builder()
+            .base_url(&format!("http://{}", address))
+            .circuit_breaker(2, Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let ids = (1..=5).map(ProblemId).collect::<Vec<_>>();
+        let report = client.fetch_many(&ids);
+        assert_eq!(report.failed_ids(), ids);
+        assert!(report.failed[..2]
+            .iter()
+            .all(|(_, err)| matches!(err, PbInfoError::NetworkError(_))));
+        assert!(report.failed[2..]
+            .iter()
+            .all(|(_, err)| err == &report.failed[2].1
+                && matches!(err, PbInfoError::CircuitOpen { .. })));
+        assert_eq!(client.metrics().requests, 2);
+    }
+
+    #[test]
+    fn test_asset_store() {
+        let dir = std::env::temp_dir().join(format!("pbinfo-assets-{}", std::process::id()));
+        let mut store = AssetStore::open(&dir).unwrap();
+        let first = store.add(b"imagine", "desen.PNG", "problem 1").unwrap();
+        assert!(first.ends_with(".png"));
+        assert_eq!(
+            store.add(b"imagine", "figura.png", "problem 2").unwrap(),
+            first
+        );
+        let other = store.add(b"teste", "teste.zip", "problem 2").unwrap();
+        assert_eq!(store.assets().len(), 2);
+        assert_eq!(store.disk_usage(), 12);
+        let mut assets = store.assets_of("problem 2");
+        assets.sort();
+        assert_eq!(
+            assets,
+            vec![
+                ("figura.png".to_owned(), first.clone()),
+                ("teste.zip".to_owned(), other.clone())
+            ]
+        );
+        store.save().unwrap();
+
+        // The references survive reopening the store, and files are only removed with the last one
+        let mut store = AssetStore::open(&dir).unwrap();
+        assert_eq!(
+            store
+                .release_except("problem 2", std::slice::from_ref(&other))
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            store.assets_of("problem 2"),
+            vec![("teste.zip".to_owned(), other.clone())]
+        );
+        assert_eq!(store.release("problem 2").unwrap(), 5);
+        assert!(dir.join(&first).exists());
+        assert!(!dir.join(&other).exists());
+        assert_eq!(store.release("problem 1").unwrap(), 7);
+        assert!(!dir.join(&first).exists());
+        assert!(store.assets().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_name_normalization() {
+        assert_eq!(normalize_text(" Șir Maxim "), "sir maxim");
+        assert_eq!(fold_diacritics("ŞIR ţintă"), "SIR tinta");
+        for name in [
+            "SumCif", "sum_cif", "sumcif", "Sum-Cif", "sum cif", "SUMCIF ",
+        ] {
+            assert_eq!(name_key(name), "sumcif");
+        }
+        assert_eq!(name_key("Țăruș"), name_key("tarus"));
+
+        let search_json = [("sumcifpare", 1000), ("SumCif", 1001)]
+            .iter()
+            .map(|(name, id)| {
+                std::collections::HashMap::from([
+                    ("value".to_owned(), name.to_string()),
+                    (
+                        "label".to_owned(),
+                        format!("Problema #{}: <strong>{}</strong>", id, name),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(extract_id_from_search("sum_cif", &search_json), Ok(1001));
+        assert!(matches!(
+            extract_id_from_search("sum", &search_json),
+            Err(PbInfoError::UnknownName(..))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_name_suggestions() {
+        assert_eq!(name_similarity("Sum_Cif", "sumcif"), 1.0);
+        assert_eq!(name_similarity("abcd", "abce"), 0.75);
+        assert_eq!(name_similarity("abc", "xyz"), 0.0);
+
+        let search_json = [("sumcifpare", "1000"), ("SumCif", "1001"), ("fara id", "x")]
+            .iter()
+            .map(|(name, id)| {
+                std::collections::HashMap::from([
+                    ("value".to_owned(), name.to_string()),
+                    (
+                        "label".to_owned(),
+                        format!("Problema #{}: <strong>{}</strong>", id, name),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let suggestions = match extract_id_from_search("sumcf", &search_json) {
+            Err(PbInfoError::UnknownName(name, suggestions)) => {
+                assert_eq!(name, "sumcf");
+                suggestions
+            }
+            other => panic!("expected UnknownName, got {:?}", other),
+        };
+        let ranked = suggestions
+            .iter()
+            .map(|suggestion| (suggestion.id, suggestion.name.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranked,
+            [(ProblemId(1001), "SumCif"), (ProblemId(1000), "sumcifpare")]
+        );
+        assert!(suggestions[0].score > suggestions[1].score);
+    }
+
+    #[test]
+    fn test_statement_pin() {
+        let problem = |text: &str| {
+            PbInfoProblem::from_parts(
+                ProblemId(7),
+                "p".to_owned(),
+                format!("<h1>Cerința</h1><p>{}</p>", text),
+                IO_TEXT_1.to_owned(),
+            )
+            .unwrap()
+        };
+        let original = problem("Se dă un șir cu n numere. Determinați suma lor.");
+        let hash = original.pin_hash();
+        assert_eq!(original.check_pin(&hash), None);
+        assert_eq!(
+            original.check_pin(&format!(" {} ", hash.to_uppercase())),
+            None
+        );
+        assert_eq!(
+            problem("Se dă un <b>șir</b> cu n numere.\nDeterminați suma lor.").check_pin(&hash),
+            None
+        );
+
+        let edited = problem("Se dă un șir cu n numere. Determinați produsul lor.");
+        assert_eq!(
+            edited.check_pin(&hash),
+            Some(StatementDrift {
+                id: ProblemId(7),
+                expected: hash.clone(),
+                actual: edited.pin_hash(),
+            })
+        );
+
+        // Changes that keep the words are edits too
+        let bounded = problem("Se dă un șir cu n ≤ 100 numere. Determinați suma lor.");
+        let changed = problem("Se dă un șir cu n < 100 numere. Determinați suma lor.");
+        assert_eq!(bounded.content_hash(), changed.content_hash());
+        assert!(changed.check_pin(&bounded.pin_hash()).is_some());
+        let mut slower = original.clone();
+        slower.time_limit = Some("1 secunde".to_owned());
+        assert!(slower.check_pin(&hash).is_some());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_contest_scoreboard() {
+        use crate::contest::*;
+        use crate::judge::Verdict;
+        use std::time::Duration;
+
+        let submission = |problem: usize, minutes: u64, passed: usize, failed: usize| {
+            let mut verdicts = vec![Verdict::Accepted; passed];
+            verdicts.extend(vec![Verdict::WrongAnswer; failed]);
+            ContestSubmission {
+                problem: ProblemId(problem),
+                at: Duration::from_secs(minutes * 60),
+                score: passed * 100 / (passed + failed),
+                verdicts,
+                compilation_error: None,
+            }
+        };
+        let mut compilation_error = submission(1, 5, 0, 1);
+        compilation_error.verdicts.clear();
+        compilation_error.compilation_error = Some("error".to_owned());
+        let submissions = [
+            compilation_error,
+            submission(1, 10, 1, 1),
+            submission(1, 30, 2, 0),
+            submission(1, 40, 1, 1),
+            submission(2, 50, 1, 3),
+        ];
+        let problems = [
+            (ProblemId(1), "unu".to_owned()),
+            (ProblemId(2), "doi".to_owned()),
+            (ProblemId(3), "trei".to_owned()),
+        ];
+
+        let icpc = Scoreboard::new(ScoringStyle::Icpc, &problems, &submissions);
+        assert_eq!(
+            icpc.rows[0],
+            ScoreboardRow {
+                id: ProblemId(1),
+                name: "unu".to_owned(),
+                score: 100,
+                attempts: 2,
+                solved_at: Some(Duration::from_secs(30 * 60)),
+                error: None,
+            }
+        );
+        assert_eq!((icpc.rows[1].score, icpc.rows[1].attempts), (0, 1));
+        assert_eq!(icpc.solved(), 1);
+        assert_eq!(icpc.penalty(), Duration::from_secs(50 * 60));
+        assert_eq!(
+            icpc.to_string(),
+            "Problem  Score  Attempts    Solved
+#1 unu     100         2   0:30:00
+#2 doi       0         1         -
+#3 trei      0         0         -
+Total        1             0:50:00
+"
+        );
+
+        let oji = Scoreboard::new(ScoringStyle::Oji, &problems, &submissions);
+        let scores = oji.rows.iter().map(|row| row.score).collect::<Vec<_>>();
+        assert_eq!(scores, [50, 25, 0]);
+        assert_eq!(oji.total_score(), 75);
+        assert_eq!(
+            oji.to_string(),
+            "Problem  Score  Attempts    Solved
+#1 unu      50         4   0:30:00
+#2 doi      25         1         -
+#3 trei      0         0         -
+Total       75
+"
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_contest_judging() {
+        use crate::contest::*;
+        use crate::judge::Language;
+        use std::time::Duration;
+
+        if std::process::Command::new("python3")
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            return;
+        }
+
+        let mut problem = PbInfoProblem::from_parts(
+            ProblemId(877),
+            "numere8".to_owned(),
+            EXAMPLES.to_owned(),
+            IO_TEXT_2.to_owned(),
+        )
+        .unwrap();
+        problem.time_limit = Some("2 secunde".to_owned());
+        let dir = std::env::temp_dir().join(format!("pbinfo-contest-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let options = ContestOptions {
+            dir: dir.clone(),
+            language: Language::Python,
+            style: ScoringStyle::Icpc,
+            ..ContestOptions::default()
+        };
+
+        let mut contest =
+            Contest::start(vec![problem.clone()], Duration::from_secs(60), &options).unwrap();
+        let solution = dir.join("numere8.py");
+        assert_eq!(contest.problems[0].solution, solution);
+        assert!(std::fs::read_to_string(&solution)
+            .unwrap()
+            .contains("numere8"));
+
+        std::fs::write(&solution, "open('numere8.out', 'w').write('7')").unwrap();
+        assert!(!contest.submit(ProblemId(877)).unwrap().is_accepted());
+        std::fs::write(
+            &solution,
+            "with open('numere8.in') as fin, open('numere8.out', 'w') as fout:
+    fin.readline()
+    fout.write(str(sum(map(int, fin.readline().split()))))
+",
+        )
+        .unwrap();
+        assert!(contest.submit(ProblemId(877)).unwrap().is_accepted());
+        assert!(contest.submit(ProblemId(1)).is_err());
+        let scoreboard = contest.finish();
+        assert_eq!(scoreboard.solved(), 1);
+        assert_eq!(scoreboard.rows[0].attempts, 2);
+        assert!(scoreboard.penalty() >= Duration::from_secs(20 * 60));
+
+        // OJI contests judge the last version of the solutions at the end, which is kept
+        let options = ContestOptions {
+            style: ScoringStyle::Oji,
+            ..options
+        };
+        let contest = Contest::start(vec![problem.clone()], Duration::ZERO, &options).unwrap();
+        assert!(contest.is_over());
+        assert_eq!(contest.finish().total_score(), 100);
+
+        // A solution that can not be judged scores 0 without losing the scoreboard
+        let contest = Contest::start(vec![problem], Duration::ZERO, &options).unwrap();
+        std::fs::remove_file(&solution).unwrap();
+        let scoreboard = contest.finish();
+        assert_eq!(scoreboard.total_score(), 0);
+        assert!(scoreboard.rows[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("IOError"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_contest_archive() {
+        let problem = |id: usize, name: &str, source: Option<&str>| {
+            let mut problem = PbInfoProblem::from_parts(
+                ProblemId(id),
+                name.to_owned(),
+                STATEMENT.to_owned(),
+                IO_TEXT_1.to_owned(),
+            )
+            .unwrap();
+            problem.source = source.map(str::to_owned);
+            problem
+        };
+        let catalog = [
+            problem(30, "trei", Some("ONI 2016, clasa a 9-a")),
+            problem(10, "unu", Some("ONI 2016, clasa a 9-a")),
+            problem(20, "doi", Some("oni 2016 (clasele XI-XII)")),
+            problem(40, "patru", Some("ONI 2016")),
+            problem(50, "cinci", Some("ONI 2017, clasa a 9-a")),
+            problem(60, "sase", Some("OJI 2016, clasa a 9-a")),
+            problem(70, "sapte", None),
+        ];
+
+        let archive = contest_archive(&catalog, "ONI", 2016);
+        let ids = archive
+            .problems
+            .iter()
+            .map(|problem| problem.id.0)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [10, 20, 30, 40]);
+        assert_eq!(
+            archive.divisions(),
+            std::collections::BTreeMap::from([
+                (None, vec![ProblemId(40)]),
+                (
+                    Some("clasa a 9-a".to_owned()),
+                    vec![ProblemId(10), ProblemId(30)]
+                ),
+                (Some("clasele XI-XII".to_owned()), vec![ProblemId(20)]),
+            ])
+        );
+
+        let dir = std::env::temp_dir().join(format!("pbinfo-archive-{}", std::process::id()));
+        let paths = archive.save(&dir).unwrap();
+        assert_eq!(paths[0], dir.join("clasa-a-9-a").join("unu.pbinfo"));
+        assert_eq!(paths[1], dir.join("clasele-xi-xii").join("doi.pbinfo"));
+        assert_eq!(paths[3], dir.join("toate").join("patru.pbinfo"));
+        assert_eq!(bundle::load(&paths[1]).unwrap().problem.id, ProblemId(20));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_golden_fixtures() {
+        golden::assert_fixtures(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/problems"));
+    }
+
+    #[test]
+    fn test_golden_mismatches() {
+        use crate::golden::*;
+
+        let dir = std::env::temp_dir().join(format!("pbinfo-golden-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let page = format!(
+            "<title>Problema numere8 | www.pbinfo.ro</title>{}<article>{}</article>",
+            IO_TEXT_2, EXAMPLES
+        )
+        .replace(
+            "<h1>Exemplu</h1>",
+            "<h1>Cerința</h1><p>Suma.</p><h1>Exemplu</h1>",
+        );
+        std::fs::write(dir.join("877.html"), &page).unwrap();
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let fixtures = fixtures(&dir).unwrap();
+        assert_eq!(fixtures.len(), 1);
+        assert_eq!(fixtures[0].golden, dir.join("877.json"));
+        assert!(fixtures[0].check().is_err());
+        assert_eq!(update_fixtures(&dir).unwrap(), 1);
+        assert_eq!(check_fixtures(&dir).unwrap(), []);
+
+        let record = golden_record(ProblemId(877), &page);
+        assert_eq!(record["problem"]["name"], "numere8");
+        assert_eq!(record["examples"][0]["output"], "6\n");
+        assert_eq!(record["extraction"]["problem_text"], "article");
+
+        // A parser change that loses the time limit and an example
+        std::fs::write(
+            dir.join("877.html"),
+            page.replace("0.1 secunde", "").replace("<pre>6</pre>", ""),
+        )
+        .unwrap();
+        let mismatches = check_fixtures(&dir).unwrap();
+        let pointers = mismatches
+            .iter()
+            .map(|mismatch| mismatch.pointer.as_str())
+            .collect::<Vec<_>>();
+        assert!(pointers.contains(&"/problem/time_limit"));
+        assert!(pointers.contains(&"/examples/0"));
+        let time_limit = mismatches
+            .iter()
+            .find(|mismatch| mismatch.pointer == "/problem/time_limit")
+            .unwrap();
+        assert_eq!(time_limit.expected, "0.1 secunde");
+        assert_eq!(
+            time_limit.to_string(),
+            format!(
+                "#877 /problem/time_limit: expected \"0.1 secunde\", got {}",
+                time_limit.actual
+            )
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_selectors() {
+        for selector in selectors::all() {
+            selector.regex().unwrap();
+        }
+
+        static INVALID: selectors::Selector = selectors::Selector::new(r"(unclosed");
+        assert!(matches!(INVALID.regex(), Err(PbInfoError::RegexError(_))));
+        assert!(!INVALID.is_match("(unclosed"));
+        assert!(INVALID.captures("(unclosed").is_none());
+        assert_eq!(INVALID.find_iter("(unclosed").count(), 0);
+        assert_eq!(INVALID.replace_all("(unclosed", ""), "(unclosed");
+
+        assert!(matches!(
+            selectors::H1.captures_or("<p>no heading</p>", "the heading"),
+            Err(PbInfoError::RegexError(_))
+        ));
+        assert_eq!(
+            &selectors::H1
+                .captures_or("<h1>Cerința</h1>", "the heading")
+                .unwrap()[1],
+            "Cerința"
+        );
+    }
+
+    #[test]
+    fn test_malformed_pages() {
+        assert!(matches!(
+            get_task("<h1>Cerința</h1><p>incomplete"),
+            Err(PbInfoError::RegexError(_))
+        ));
+        assert!(matches!(
+            extract_submission_status("<p>Punctaj total: 99999999999999999999999</p>"),
+            Err(PbInfoError::RegexError(_))
+        ));
+    }
+
+    #[test]
+    fn test_translation() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "x".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        assert_eq!(problem.locale(), Some(Locale::Romanian));
+        assert_eq!(
+            detect_locale("Read the numbers from the input file and print their sum."),
+            Some(Locale::English)
+        );
+        assert_eq!(detect_locale("3 5\n1 2 3"), None);
+
+        let calls = AtomicUsize::new(0);
+        let translator = |html: &str, from: Locale, to: Locale| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            assert_eq!((from, to), (Locale::Romanian, Locale::English));
+            Ok(html.replace("Cerința", "Task"))
+        };
+        let dir = std::env::temp_dir().join(format!("pbinfo-translations-{}", std::process::id()));
+        let cache = TranslationCache::in_dir(&dir);
+        let translated = problem
+            .translate(&translator, &cache, Locale::English)
+            .unwrap();
+        assert!(translated.problem_text.contains("Task"));
+        assert_eq!(translated.id, problem.id);
+        problem
+            .translate(&translator, &cache, Locale::English)
+            .unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        // Translations stored in a directory outlive the cache
+        let cache = TranslationCache::in_dir(&dir);
+        assert_eq!(
+            cache.get(&problem.statement_hash(), Locale::English),
+            Some(translated.problem_text.clone())
+        );
+        problem
+            .translate(&translator, &cache, Locale::English)
+            .unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        // A statement whose words are the same but whose symbols changed is translated again
+        let edited = PbInfoProblem {
+            problem_text: problem.problem_text.replace('≤', "&lt;"),
+            ..problem.clone()
+        };
+        assert_eq!(edited.content_hash(), problem.content_hash());
+        let retranslated = edited
+            .translate(&translator, &cache, Locale::English)
+            .unwrap();
+        assert!(retranslated.problem_text.contains("1 &lt; n &lt; 100"));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Nothing to translate
+        problem
+            .translate(&translator, &TranslationCache::new(), Locale::Romanian)
+            .unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_accessible_text() {
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            r#"<h1>Cerința</h1><p>Se dă un număr \(n\) și un șir cu \(n\) elemente. Să se afișeze suma lor.</p>
+<p><img src="/images/desen.png" alt="desenul șirului"><img src="/images/x.png"></p>
+<table><tr><th>n</th><th>suma</th></tr><tr><td>3</td><td>6</td></tr><tr><td>1</td><td></td></tr></table>
+<h1>Restricții și precizări</h1><ul><li>1 &le; n &le; 10<sup>5</sup></li><li>\(a_i \ne \frac{n+1}{2}\), \(x \geq \sqrt{y}\)</li></ul>
+<h1>Exemplu</h1><pre>3
+1 2 3</pre>"#
+                .to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            problem.to_accessible_text(),
+            "numere8.\n\nCerința.\n\n\
+             Se dă un număr n și un șir cu n elemente. Să se afișeze suma lor.\n\n\
+             [figură: desenul șirului] [figură]\n\n\
+             Tabel cu 2 rânduri, 2 coloane.\nRândul 1: n: 3, suma: 6.\nRândul 2: n: 1, suma: gol.\n\n\
+             Restricții și precizări.\n\n\
+             1 mai mic sau egal cu n mai mic sau egal cu 10 la puterea 5\n\
+             a indice i diferit de n plus 1 supra 2, x mai mare sau egal cu radical din y\n\n\
+             Exemplu.\n\n3\n1 2 3\n"
+        );
+
+        assert_eq!(
+            html_to_accessible_text(
+                r#"<p>\(1 \le n \le 10^{5}\), a<sub>i</sub> &lt; 3.</p><img src="/a.png" alt="">"#,
+                Locale::English
+            ),
+            "1 less than or equal to n less than or equal to 10 to the power of 5, a sub i < 3.\n[figure]"
+        );
+        assert_eq!(
+            spell_latex(r"\frac{a_1 + \dots}{2} \times \alpha", Locale::English),
+            "a sub 1 plus and so on over 2 times alpha"
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_external_cookies_session() {
+        assert!(!PbInfoClient::new().has_session());
+        let client = PbInfoClient::with_http_client(reqwest::blocking::Client::new());
+        assert!(client.has_session());
+    }
+
+    #[test]
+    fn test_name_search_queries() {
+        assert_eq!(name_search_queries("sum_cif"), vec!["sumcif", "sum cif"]);
+        assert_eq!(name_search_queries("sumcif"), vec!["sumcif"]);
+
+        let suggestion = |id, score| Suggestion {
+            id: ProblemId(id),
+            name: format!("p{}", id),
+            score,
+        };
+        let mut suggestions = vec![suggestion(1, 0.5)];
+        merge_suggestions(
+            &mut suggestions,
+            vec![suggestion(1, 0.5), suggestion(2, 0.8)],
+        );
+        assert_eq!(suggestions, vec![suggestion(2, 0.8), suggestion(1, 0.5)]);
+    }
+
+    #[test]
+    fn test_sanitizer_allowlist() {
+        let html = r#"<div style="color:red"><p onclick="alert(1)">Se dă <b>n</b> și <span data-x="1">m</span>.</p><style>p{}</style>
+<a href="javascript:alert(1)" onmouseover="x()">aici</a><iframe src="/x"></iframe></div>"#;
+
+        assert_eq!(
+            Sanitizer::new().sanitize(html),
+            "<p>Se dă <b>n</b> și m.</p>\n<a>aici</a>"
+        );
+        let sanitizer = Sanitizer::new()
+            .deny("b")
+            .allow("span", &["data-x", "onclick"])
+            .allow("div", &["style"]);
+        assert!(!sanitizer.allows("b"));
+        assert_eq!(
+            sanitizer.sanitize(html),
+            "<div style=\"color:red\"><p>Se dă n și <span data-x=\"1\">m</span>.</p>\n<a>aici</a></div>"
+        );
+        assert_eq!(Sanitizer::empty().sanitize(html), "Se dă n și m.\naici");
+        // An unclosed tag would be closed by the next `>` of the page
+        assert_eq!(
+            Sanitizer::new().sanitize("<p>1 < 2</p><img src=x onerror=alert(1)"),
+            "<p>1 &lt; 2</p>&lt;img src=x onerror=alert(1)"
+        );
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            format!("<h1>Cerința</h1>{}", html),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        let sections = problem.sanitized_sections(&Sanitizer::empty());
+        assert_eq!(sections[0].title, "Cerința");
+        assert_eq!(sections[0].html, "Se dă n și m.\naici");
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let mut problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            format!("{}{}", STATEMENT, EXAMPLES),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        problem.topics = vec!["Clasa a IX-a".to_owned()];
+        problem.attachments = vec![Attachment {
+            name: "enunt.pdf".to_owned(),
+            url: "https://www.pbinfo.ro/resurse/enunt.pdf".to_owned(),
+            size: Some(1024),
+        }];
+        let tests = vec![Example {
+            input: "1\n5\n".to_owned(),
+            output: "5\n".to_owned(),
+        }];
+
+        let path =
+            std::env::temp_dir().join(format!("pbinfo-bundle-{}.pbinfo", std::process::id()));
+        bundle::save_with_tests(&problem, &tests, &path).unwrap();
+        let loaded = bundle::load(&path).unwrap();
+
+        assert_eq!(loaded.problem.name, "numere8");
+        assert_eq!(loaded.problem.problem_text, problem.problem_text);
+        assert_eq!(loaded.problem.grade, problem.grade);
+        assert_eq!(loaded.problem.topics, problem.topics);
+        assert_eq!(loaded.problem.attachments, problem.attachments);
+        assert_eq!(loaded.problem.examples(), problem.examples());
+        assert_eq!(loaded.tests, tests);
+
+        std::fs::write(&path, "not a zip").unwrap();
+        assert!(matches!(bundle::load(&path), Err(PbInfoError::IOError(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_templates() {
+        use crate::judge::Language;
+        use crate::template::*;
+
+        let template = Template::new(
+            "// {{problem.name}}{{#if uses_files}} {{input_file}}{{else}} stdin{{/if}}\
+             {{#unless uses_files}}!{{/unless}}",
+        )
+        .unwrap();
+        let mut context = std::collections::HashMap::new();
+        context.insert("problem.name".to_owned(), "suma".to_owned());
+        context.insert("input_file".to_owned(), "suma.in".to_owned());
+        context.insert("uses_files".to_owned(), "true".to_owned());
+        assert_eq!(template.render(&context).unwrap(), "// suma suma.in");
+        context.insert("uses_files".to_owned(), String::new());
+        assert_eq!(template.render(&context).unwrap(), "// suma stdin!");
+
+        assert!(Template::new("{{#if uses_files}}").is_err());
+        assert!(Template::new("{{/if}}").is_err());
+        assert!(Template::new("{{#each tests}}{{/each}}").is_err());
+        assert!(Template::new("{{typo}}").unwrap().render(&context).is_err());
+
+        let problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "arbore1".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        let context = template_context(&problem, Language::Cpp);
+        assert_eq!(context["input_file"], "arbore1.in");
+        assert_eq!(context["memory_limit_kb"], "65536");
+
+        let mut templates = Templates::new();
+        let source = templates.render(&problem, Language::Cpp).unwrap();
+        assert!(source.contains("ifstream fin(\"arbore1.in\");"));
+        templates.set(
+            Language::Cpp,
+            Template::new("// {{problem.id}}, {{time_limit_ms}} ms\n").unwrap(),
+        );
+        assert_eq!(
+            templates.render(&problem, Language::Cpp).unwrap(),
+            format!("// 1, {} ms\n", context["time_limit_ms"])
+        );
+    }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn test_commit_solution() {
+        let mut problem = PbInfoProblem::from_parts(
+            ProblemId(1),
+            "numere8".to_owned(),
+            STATEMENT.to_owned(),
+            IO_TEXT_1.to_owned(),
+        )
+        .unwrap();
+        problem.topics = vec!["Clasa a IX-a".to_owned(), "Tablouri/vectori".to_owned()];
+        assert_eq!(
+            repo::solution_dir(&problem),
+            std::path::Path::new(&problem.grade.to_string())
+                .join("Tablouri-vectori")
+                .join("numere8")
+        );
+        let message = repo::commit_message(&problem, Some(100));
+        assert_eq!(message, "numere8 (#1): 100 points");
+
+        let dir = std::env::temp_dir().join(format!("pbinfo-repo-{}", std::process::id()));
+        let first = repo::commit_solution(
+            &dir,
+            &problem,
+            &[("numere8.cpp", "int main() {}\n")],
+            &message,
+        )
+        .unwrap();
+        let second = repo::commit_solution(
+            &dir,
+            &problem,
+            &[("numere8.cpp", "int main() { return 0; }\n")],
+            &repo::commit_message(&problem, None),
+        )
+        .unwrap();
+
+        let git = git2::Repository::open(&dir).unwrap();
+        let head = git.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.id().to_string(), second);
+        assert_eq!(head.message(), Some("numere8 (#1)"));
+        assert_eq!(head.parent(0).unwrap().id().to_string(), first);
+        assert!(dir
+            .join(repo::solution_dir(&problem))
+            .join("numere8.cpp")
+            .exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
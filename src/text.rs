@@ -0,0 +1,59 @@
+//! Normalization of names and text, shared by every comparison that should not care about case,
+//! Romanian diacritics or separators (searching problems by name, filtering by topic, comparing
+//! statements).
+
+/// Replaces the Romanian letters with diacritics (including the cedilla variants of `ș` and `ț`)
+/// by their base letters, keeping the case.
+pub fn fold_diacritics(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'ă' | 'â' => 'a',
+            'Ă' | 'Â' => 'A',
+            'î' => 'i',
+            'Î' => 'I',
+            'ș' | 'ş' => 's',
+            'Ș' | 'Ş' => 'S',
+            'ț' | 'ţ' => 't',
+            'Ț' | 'Ţ' => 'T',
+            c => c,
+        })
+        .collect()
+}
+
+/// Lowercases `text`, without diacritics or surrounding whitespace (e.g. `Șir Maxim ` becomes
+/// `sir maxim`).
+pub fn normalize_text(text: &str) -> String {
+    fold_diacritics(&text.trim().to_lowercase())
+}
+
+/// Returns the key under which problem names are compared: `normalize_text` without any
+/// separators, so that `SumCif`, `sum_cif`, `sum-cif`, `Sum Cif` and `sumcif` are the same name.
+pub fn name_key(name: &str) -> String {
+    normalize_text(name)
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// Returns how similar two problem names are, from 0 to 1 (same `name_key`): one minus the edit
+/// distance between their keys, relative to the longer one.
+pub fn name_similarity(first: &str, second: &str) -> f32 {
+    let first = name_key(first).chars().collect::<Vec<_>>();
+    let second = name_key(second).chars().collect::<Vec<_>>();
+    let longest = first.len().max(second.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    // Edit distances between the prefixes of `first` and the prefixes of `second`, a row at a time
+    let mut previous = (0..=second.len()).collect::<Vec<_>>();
+    for (i, a) in first.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in second.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    1.0 - previous[second.len()] as f32 / longest as f32
+}
@@ -0,0 +1,202 @@
+//! Detection of the language statements are written in, and translation of statements through
+//! a `Translator` supplied by the application (e.g. a client of DeepL or LibreTranslate).
+//! Translations are cached by `statement_hash`, so each version of a statement is translated
+//! once.
+
+use crate::*;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Words that are frequent in Romanian statements and do not occur in English ones, without
+/// diacritics.
+const ROMANIAN_WORDS: &[&str] = &[
+    "si",
+    "care",
+    "este",
+    "sunt",
+    "un",
+    "o",
+    "se",
+    "cu",
+    "pentru",
+    "din",
+    "sa",
+    "pe",
+    "la",
+    "numarul",
+    "numere",
+    "fisierul",
+    "afiseaza",
+    "cerinta",
+    "iesire",
+    "intrare",
+    "restrictii",
+    "precizari",
+    "exemplu",
+    "citeste",
+    "valoarea",
+];
+
+/// Words that are frequent in English statements and do not occur in Romanian ones.
+const ENGLISH_WORDS: &[&str] = &[
+    "the", "and", "is", "of", "to", "which", "are", "for", "with", "number", "numbers", "file",
+    "output", "input", "print", "each", "that", "given", "task", "example", "read", "value",
+];
+
+/// Minimum number of known words for the language of a text to be detected.
+const MIN_DETECTED_WORDS: usize = 3;
+
+impl Locale {
+    /// Returns the ISO 639-1 code of the language (`en`, `ro`), as expected by translation
+    /// services.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Romanian => "ro",
+        }
+    }
+}
+
+/// Detects whether plain `text` is written in Romanian or in English, from its frequent words
+/// and its diacritics. Returns `None` if it has too few words to tell (e.g. only numbers).
+pub fn detect_locale(text: &str) -> Option<Locale> {
+    let (mut romanian, mut english) = (0, 0);
+    for word in text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let folded = fold_diacritics(word);
+        if folded != word || ROMANIAN_WORDS.contains(&folded.as_str()) {
+            romanian += 1;
+        } else if ENGLISH_WORDS.contains(&word) {
+            english += 1;
+        }
+    }
+
+    if romanian + english < MIN_DETECTED_WORDS {
+        return None;
+    }
+    match romanian.cmp(&english) {
+        std::cmp::Ordering::Greater => Some(Locale::Romanian),
+        std::cmp::Ordering::Less => Some(Locale::English),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// A translation service. Implement it to translate statements with DeepL, LibreTranslate etc.
+pub trait Translator: Send + Sync {
+    /// Translates the html of a statement from `from` to `to`. The tags should be kept as they
+    /// are (DeepL's `tag_handling=html`, LibreTranslate's `format=html`).
+    fn translate(&self, html: &str, from: Locale, to: Locale) -> Result<String>;
+}
+
+impl<F: Fn(&str, Locale, Locale) -> Result<String> + Send + Sync> Translator for F {
+    fn translate(&self, html: &str, from: Locale, to: Locale) -> Result<String> {
+        self(html, from, to)
+    }
+}
+
+/// Translated statements, keyed by the `statement_hash` of the original statement and by the
+/// language of the translation. A statement that is edited (even only its markup) has a new
+/// hash, so it is translated again.
+#[derive(Debug, Default)]
+pub struct TranslationCache {
+    dir: Option<PathBuf>,
+    entries: Mutex<HashMap<(String, Locale), String>>,
+}
+
+impl TranslationCache {
+    /// Creates a cache that keeps the translations in memory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a cache that also stores the translations in `dir` (as `{hash}.{code}.html`),
+    /// which is created when the first one is stored, so that they are kept across runs.
+    pub fn in_dir(dir: impl Into<PathBuf>) -> Self {
+        TranslationCache {
+            dir: Some(dir.into()),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Returns the path of the translation of the statement with `hash` to `to`.
+    fn path(&self, hash: &str, to: Locale) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        Some(dir.join(format!("{}.{}.html", hash, to.code())))
+    }
+
+    /// Returns the translation of the statement with `hash` to `to`, if it is cached.
+    pub fn get(&self, hash: &str, to: Locale) -> Option<String> {
+        let key = (hash.to_owned(), to);
+        if let Some(html) = self.entries.lock().unwrap().get(&key) {
+            return Some(html.clone());
+        }
+        let html = std::fs::read_to_string(self.path(hash, to)?).ok()?;
+        self.entries.lock().unwrap().insert(key, html.clone());
+        Some(html)
+    }
+
+    /// Stores `html` as the translation of the statement with `hash` to `to`.
+    pub fn insert(&self, hash: &str, to: Locale, html: &str) -> Result<()> {
+        if let (Some(dir), Some(path)) = (&self.dir, self.path(hash, to)) {
+            std::fs::create_dir_all(dir).map_err(|err| {
+                PbInfoError::IOError(format!("Could not create `{}`: {}", dir.display(), err))
+            })?;
+            std::fs::write(&path, html).map_err(|err| {
+                PbInfoError::IOError(format!("Could not write `{}`: {}", path.display(), err))
+            })?;
+        }
+        self.entries
+            .lock()
+            .unwrap()
+            .insert((hash.to_owned(), to), html.to_owned());
+        Ok(())
+    }
+}
+
+impl PbInfoProblem {
+    /// Returns the SHA-256 (in hex) of the exact html of the statement, under which its
+    /// translations are cached.
+    pub fn statement_hash(&self) -> String {
+        let hash = Sha256::digest(self.problem_text.as_bytes());
+        hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Returns the language the statement is written in (see `detect_locale`).
+    pub fn locale(&self) -> Option<Locale> {
+        detect_locale(&html_to_text(&self.problem_text))
+    }
+
+    /// Returns the problem with its statement translated to `to` by `translator`, using the
+    /// translation in `cache` if there is one. Statements whose language is not detected are
+    /// taken to be Romanian; statements already in `to` are returned as they are.
+    pub fn translate(
+        &self,
+        translator: &dyn Translator,
+        cache: &TranslationCache,
+        to: Locale,
+    ) -> Result<PbInfoProblem> {
+        let from = self.locale().unwrap_or(Locale::Romanian);
+        if from == to {
+            return Ok(self.clone());
+        }
+
+        let hash = self.statement_hash();
+        let problem_text = match cache.get(&hash, to) {
+            Some(html) => html,
+            None => {
+                let html = translator.translate(&self.problem_text, from, to)?;
+                cache.insert(&hash, to, &html)?;
+                html
+            }
+        };
+        Ok(PbInfoProblem {
+            problem_text,
+            ..self.clone()
+        })
+    }
+}
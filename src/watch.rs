@@ -0,0 +1,136 @@
+use crate::*;
+use std::time::{Duration, Instant};
+
+/// Delay before the first poll after a change of status.
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Longest delay between two polls.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// How long a submission is watched by default before giving up.
+const DEFAULT_MAX_WAIT: Duration = Duration::from_secs(10 * 60);
+
+/// Returns the delay before the next poll: back to the minimum after a change of status,
+/// otherwise one and a half times the previous delay, up to the maximum.
+pub(crate) fn next_poll_interval(interval: Duration, changed: bool) -> Duration {
+    if changed {
+        MIN_POLL_INTERVAL
+    } else {
+        (interval * 3 / 2).clamp(MIN_POLL_INTERVAL, MAX_POLL_INTERVAL)
+    }
+}
+
+/// An iterator over the status updates of a submission, created by
+/// `PbInfoClient::watch_submission`. Each status is yielded once, when it is first seen, and
+/// the iterator ends after yielding a final status (or an error). A submission whose evaluation
+/// does not finish within `max_wait` (10 minutes by default) ends the iterator with
+/// `PbInfoError::DeadlineExceeded`.
+#[derive(Debug)]
+pub struct SubmissionWatch<'a> {
+    client: &'a PbInfoClient,
+    submission_id: usize,
+    /// The last status that was yielded
+    last: Option<SubmissionStatus>,
+    /// Delay before the next poll
+    interval: Duration,
+    /// When the watch was created
+    started: Instant,
+    /// How long the evaluation is waited for
+    max_wait: Duration,
+    /// Whether the iterator ended
+    done: bool,
+}
+
+impl SubmissionWatch<'_> {
+    /// Gives up on the evaluation `wait` after the watch was created.
+    pub fn max_wait(mut self, wait: Duration) -> Self {
+        self.max_wait = wait;
+        self
+    }
+}
+
+impl Iterator for SubmissionWatch<'_> {
+    type Item = Result<SubmissionStatus>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        loop {
+            if self.last.is_some() {
+                let remaining = self.max_wait.saturating_sub(self.started.elapsed());
+                if remaining.is_zero() {
+                    self.done = true;
+                    return Some(Err(PbInfoError::DeadlineExceeded(format!(
+                        "evaluation of submission {}",
+                        self.submission_id
+                    ))));
+                }
+                std::thread::sleep(self.interval.min(remaining));
+            }
+            let status = match self.client.fetch_submission_status(self.submission_id) {
+                Ok(status) => status,
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            };
+
+            let changed = self.last.as_ref() != Some(&status);
+            self.interval = next_poll_interval(self.interval, changed);
+            if changed {
+                self.done = status.is_final();
+                self.last = Some(status.clone());
+                return Some(Ok(status));
+            }
+        }
+    }
+}
+
+impl PbInfoClient {
+    /// Returns the current evaluation status of a submission.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err(Debug)))]
+    pub fn fetch_submission_status(&self, submission_id: usize) -> Result<SubmissionStatus> {
+        let page = self.get_page(&self.url(&format!("/detalii-evaluare/{}", submission_id)))?;
+
+        match page.status() {
+            reqwest::StatusCode::OK => {
+                let text = self.read_text(page)?;
+                match extract_submission_status(&text) {
+                    Ok(status) => Ok(status),
+                    Err(err) => {
+                        check_site_available(&text)?;
+                        Err(err)
+                    }
+                }
+            }
+            reqwest::StatusCode::NOT_FOUND => Err(PbInfoError::Error(format!(
+                "Unknown submission {}",
+                submission_id
+            ))),
+            s => Err(PbInfoError::NetworkError(format!(
+                "Encountered an error when trying to fetch the submission. HTTP status code {}",
+                s
+            ))),
+        }
+    }
+
+    /// Polls the page of a submission until its evaluation is complete, yielding every new
+    /// status (queued, evaluating test k, final verdict). Polls are frequent while the status
+    /// changes and slow down while it does not, for up to 10 minutes (see
+    /// `SubmissionWatch::max_wait`).
+    pub fn watch_submission(&self, submission_id: usize) -> SubmissionWatch<'_> {
+        SubmissionWatch {
+            client: self,
+            submission_id,
+            last: None,
+            interval: MIN_POLL_INTERVAL,
+            started: Instant::now(),
+            max_wait: DEFAULT_MAX_WAIT,
+            done: false,
+        }
+    }
+}
+
+/// Polls the page of a submission until its evaluation is complete, yielding every new status.
+pub fn watch_submission(submission_id: usize) -> SubmissionWatch<'static> {
+    PbInfoClient::shared().watch_submission(submission_id)
+}